@@ -33,7 +33,8 @@ typedef enum {
 typedef enum {
     OB_TIF_GTC = 0,
     OB_TIF_IOC = 1,
-    OB_TIF_FOK = 2
+    OB_TIF_FOK = 2,
+    OB_TIF_GTD = 3  /* rests like GTC; the caller cancels it at its expiry */
 } ob_time_in_force_t;
 
 typedef enum {
@@ -44,6 +45,25 @@ typedef enum {
     OB_STP_DECREMENT_AND_CANCEL = 4
 } ob_stp_mode_t;
 
+/* Why an order or amendment was rejected; OB_REJECT_NONE if accepted */
+typedef enum {
+    OB_REJECT_NONE                     = 0,
+    OB_REJECT_DUPLICATE_ID             = 1,
+    OB_REJECT_INVALID_QUANTITY         = 2,
+    OB_REJECT_MISSING_PRICE            = 3,
+    OB_REJECT_INVALID_PRICE            = 4,
+    OB_REJECT_MARKET_ORDER_GTC         = 5,
+    OB_REJECT_INVALID_ALL_OR_NONE      = 6,
+    OB_REJECT_INVALID_POST_ONLY        = 7,
+    OB_REJECT_FOK_UNFILLABLE           = 8,
+    OB_REJECT_NO_LIQUIDITY             = 9,
+    OB_REJECT_POST_ONLY_WOULD_MATCH    = 10,
+    OB_REJECT_AON_UNFILLABLE           = 11,
+    OB_REJECT_NOT_FOUND                = 12,
+    OB_REJECT_CROSSED_SPREAD           = 13,
+    OB_REJECT_REDUCE_EXCEEDS_REMAINING = 14
+} ob_reject_code_t;
+
 /* ======================================================================
    Opaque handle
    ====================================================================== */
@@ -62,6 +82,8 @@ typedef struct {
     ob_time_in_force_t  time_in_force;
     ob_stp_mode_t       stp_mode;
     bool                has_price;      /* false for market orders */
+    bool                all_or_none;    /* GTC limit only: execute in full or not at all */
+    bool                post_only;      /* GTC limit only: rejected if it would trade on arrival */
 } ob_order_t;
 
 /* ======================================================================
@@ -86,6 +108,7 @@ typedef struct {
 typedef struct {
     bool                accepted;
     char*               reject_reason;          /* heap-allocated, NULL if accepted */
+    ob_reject_code_t    reject_code;
     ob_trade_t*         trades;                 /* heap-allocated array, may be NULL */
     size_t              trades_len;
     ob_quantity_t       remaining_quantity;
@@ -95,6 +118,7 @@ typedef struct {
 typedef struct {
     bool                accepted;
     char*               reject_reason;          /* heap-allocated, NULL if accepted */
+    ob_reject_code_t    reject_code;
     ob_price_t          old_price;
     ob_price_t          new_price;
     ob_quantity_t       old_quantity;
@@ -111,6 +135,41 @@ typedef struct {
     ob_quantity_t   last_trade_qty;
 } ob_price_data_t;
 
+typedef struct {
+    bool                found;                  /* false if the order is not resting */
+    size_t              orders_ahead;
+    ob_quantity_t       quantity_ahead;
+} ob_queue_position_t;
+
+typedef struct {
+    bool                found;                  /* false if the order is not resting */
+    ob_side_t           side;
+    ob_price_t          price;
+    ob_quantity_t       quantity;               /* remaining on the book */
+    ob_quantity_t       original_quantity;      /* as submitted */
+    ob_quantity_t       filled_quantity;
+} ob_order_info_t;
+
+typedef struct {
+    ob_price_t          price;
+    ob_quantity_t       quantity;
+    size_t              order_count;
+} ob_depth_level_t;
+
+typedef struct {
+    size_t              bid_orders;
+    size_t              ask_orders;
+    ob_quantity_t       bid_quantity;
+    ob_quantity_t       ask_quantity;
+} ob_book_stats_t;
+
+typedef struct {
+    ob_quantity_t       filled_quantity;
+    int64_t             cost;                   /* sum of price * quantity */
+    ob_price_t          worst_price;            /* 0 if nothing would fill */
+    size_t              levels;
+} ob_fill_estimate_t;
+
 /* ======================================================================
    Lifecycle
    ====================================================================== */
@@ -122,10 +181,18 @@ void                ob_orderbook_destroy(ob_orderbook_t* book);
    ====================================================================== */
 ob_order_result_t*  ob_orderbook_add_order(ob_orderbook_t* book, const ob_order_t* order);
 bool                ob_orderbook_cancel_order(ob_orderbook_t* book, ob_order_id_t id);
+size_t              ob_orderbook_clear(ob_orderbook_t* book);  /* returns orders removed */
 ob_modify_result_t* ob_orderbook_modify_order(ob_orderbook_t* book,
                                                ob_order_id_t id,
                                                ob_price_t new_price,
                                                ob_quantity_t new_quantity);
+ob_modify_result_t* ob_orderbook_reduce_order(ob_orderbook_t* book,
+                                               ob_order_id_t id,
+                                               ob_quantity_t reduce_by);
+/* Count quantity filled off the book (an auction uncross) toward a resting
+   order. Returns false if the order isn't resting. */
+bool                ob_orderbook_credit_fill(ob_orderbook_t* book, ob_order_id_t id,
+                                             ob_quantity_t filled);
 
 /* ======================================================================
    Market data queries (returned by value, no heap allocation)
@@ -137,6 +204,28 @@ ob_price_t          ob_orderbook_get_spread(const ob_orderbook_t* book);
 ob_price_t          ob_orderbook_get_mid_price(const ob_orderbook_t* book);
 ob_price_t          ob_orderbook_get_last_trade_price(const ob_orderbook_t* book);
 ob_quantity_t       ob_orderbook_get_last_trade_qty(const ob_orderbook_t* book);
+ob_queue_position_t ob_orderbook_get_queue_position(const ob_orderbook_t* book, ob_order_id_t id);
+ob_order_info_t     ob_orderbook_get_order(const ob_orderbook_t* book, ob_order_id_t id);
+ob_book_stats_t     ob_orderbook_get_book_stats(const ob_orderbook_t* book);
+
+/* Copies up to max_levels levels of one side, best price first, into the
+   caller-provided buffer. Returns the number of levels written. */
+size_t              ob_orderbook_get_depth(const ob_orderbook_t* book, ob_side_t side,
+                                           ob_depth_level_t* out, size_t max_levels);
+
+/* Quantity an order of quantity could take from the first max_orders
+   resting orders on one side in matching order, ignoring levels beyond
+   limit_price (0 for no limit). All-or-none orders too large to take are
+   passed over and not counted. */
+ob_quantity_t       ob_orderbook_get_sweepable_quantity(const ob_orderbook_t* book,
+                                                        ob_side_t side, size_t max_orders,
+                                                        ob_price_t limit_price,
+                                                        ob_quantity_t quantity);
+
+/* What an order of quantity on side would take from the opposite side,
+   ignoring price limits and self-trade prevention. */
+ob_fill_estimate_t  ob_orderbook_estimate_fill(const ob_orderbook_t* book, ob_side_t side,
+                                               ob_quantity_t quantity);
 
 /* ======================================================================
    Memory cleanup
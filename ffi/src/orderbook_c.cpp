@@ -31,6 +31,7 @@ static TimeInForce to_tif(ob_time_in_force_t t) {
     switch (t) {
         case OB_TIF_IOC: return TimeInForce::IOC;
         case OB_TIF_FOK: return TimeInForce::FOK;
+        case OB_TIF_GTD: return TimeInForce::GTC;
         default:         return TimeInForce::GTC;
     }
 }
@@ -94,6 +95,9 @@ ob_order_result_t* ob_orderbook_add_order(ob_orderbook_t* handle, const ob_order
         );
     }
 
+    order.allOrNone = c_order->all_or_none;
+    order.postOnly = c_order->post_only;
+
     OrderResult cpp_result = book->addOrderToBook(order);
 
     // Allocate C result
@@ -102,6 +106,7 @@ ob_order_result_t* ob_orderbook_add_order(ob_orderbook_t* handle, const ob_order
 
     result->accepted = cpp_result.accepted;
     result->reject_reason = dup_string(cpp_result.rejectReason);
+    result->reject_code = static_cast<ob_reject_code_t>(cpp_result.rejectCode);
     result->remaining_quantity = cpp_result.remainingQuantity;
 
     // Convert trades vector
@@ -146,6 +151,10 @@ bool ob_orderbook_cancel_order(ob_orderbook_t* handle, ob_order_id_t id) {
     return as_book(handle)->cancelOrder(id);
 }
 
+size_t ob_orderbook_clear(ob_orderbook_t* handle) {
+    return as_book(handle)->clear();
+}
+
 ob_modify_result_t* ob_orderbook_modify_order(ob_orderbook_t* handle,
                                                ob_order_id_t id,
                                                ob_price_t new_price,
@@ -157,6 +166,7 @@ ob_modify_result_t* ob_orderbook_modify_order(ob_orderbook_t* handle,
 
     result->accepted = cpp_result.accepted;
     result->reject_reason = dup_string(cpp_result.rejectReason);
+    result->reject_code = static_cast<ob_reject_code_t>(cpp_result.rejectCode);
     result->old_price = cpp_result.oldPrice;
     result->new_price = cpp_result.newPrice;
     result->old_quantity = cpp_result.oldQuantity;
@@ -165,6 +175,29 @@ ob_modify_result_t* ob_orderbook_modify_order(ob_orderbook_t* handle,
     return result;
 }
 
+ob_modify_result_t* ob_orderbook_reduce_order(ob_orderbook_t* handle,
+                                               ob_order_id_t id,
+                                               ob_quantity_t reduce_by) {
+    ModifyResult cpp_result = as_book(handle)->reduceOrder(id, reduce_by);
+
+    auto* result = static_cast<ob_modify_result_t*>(malloc(sizeof(ob_modify_result_t)));
+    if (!result) return nullptr;
+
+    result->accepted = cpp_result.accepted;
+    result->reject_reason = dup_string(cpp_result.rejectReason);
+    result->reject_code = static_cast<ob_reject_code_t>(cpp_result.rejectCode);
+    result->old_price = cpp_result.oldPrice;
+    result->new_price = cpp_result.newPrice;
+    result->old_quantity = cpp_result.oldQuantity;
+    result->new_quantity = cpp_result.newQuantity;
+
+    return result;
+}
+
+bool ob_orderbook_credit_fill(ob_orderbook_t* handle, ob_order_id_t id, ob_quantity_t filled) {
+    return as_book(handle)->creditFill(id, filled);
+}
+
 // ======================================================================
 // Market data queries
 // ======================================================================
@@ -206,6 +239,64 @@ ob_quantity_t ob_orderbook_get_last_trade_qty(const ob_orderbook_t* handle) {
     return as_book(handle)->getLastTradeQty();
 }
 
+ob_queue_position_t ob_orderbook_get_queue_position(const ob_orderbook_t* handle, ob_order_id_t id) {
+    QueuePosition pos = as_book(handle)->getQueuePosition(id);
+    return {
+        pos.found,
+        pos.ordersAhead,
+        pos.quantityAhead
+    };
+}
+
+ob_order_info_t ob_orderbook_get_order(const ob_orderbook_t* handle, ob_order_id_t id) {
+    OrderInfo info = as_book(handle)->getOrder(id);
+    return {
+        info.found,
+        info.side == Side::BUY ? OB_SIDE_BUY : OB_SIDE_SELL,
+        info.price,
+        info.quantity,
+        info.originalQuantity,
+        info.filledQuantity
+    };
+}
+
+ob_book_stats_t ob_orderbook_get_book_stats(const ob_orderbook_t* handle) {
+    BookStats stats = as_book(handle)->getBookStats();
+    return {
+        stats.bidOrders,
+        stats.askOrders,
+        stats.bidQuantity,
+        stats.askQuantity
+    };
+}
+
+size_t ob_orderbook_get_depth(const ob_orderbook_t* handle, ob_side_t side,
+                              ob_depth_level_t* out, size_t max_levels) {
+    if (out == nullptr || max_levels == 0) return 0;
+    auto levels = as_book(handle)->getDepth(to_side(side), max_levels);
+    for (size_t i = 0; i < levels.size(); i++) {
+        out[i] = {levels[i].price, levels[i].quantity, levels[i].orderCount};
+    }
+    return levels.size();
+}
+
+ob_quantity_t ob_orderbook_get_sweepable_quantity(const ob_orderbook_t* handle, ob_side_t side,
+                                                  size_t max_orders, ob_price_t limit_price,
+                                                  ob_quantity_t quantity) {
+    return as_book(handle)->getSweepableQuantity(to_side(side), max_orders, limit_price, quantity);
+}
+
+ob_fill_estimate_t ob_orderbook_estimate_fill(const ob_orderbook_t* handle, ob_side_t side,
+                                              ob_quantity_t quantity) {
+    FillEstimate estimate = as_book(handle)->estimateFill(to_side(side), quantity);
+    return {
+        estimate.filledQuantity,
+        estimate.cost,
+        estimate.worstPrice,
+        estimate.levels
+    };
+}
+
 // ======================================================================
 // Memory cleanup
 // ======================================================================
@@ -17,6 +17,10 @@ namespace orderbook {
         TimeInForce timeInForce = TimeInForce::GTC;
         Timestamp timestamp;
         STPMode stpMode = STPMode::ALLOW; 
+        bool allOrNone = false;  // Only ever executes its full quantity, in one fill
+        bool postOnly = false;   // Rejected rather than matched if it would cross
+        Quantity originalQuantity = 0;  // as submitted
+        Quantity filledQuantity = 0;
         
 
         Order() = default;
@@ -25,7 +29,8 @@ namespace orderbook {
         Order(OrderId id, std::optional<Price> price, Quantity quantity, Side side,
               OrderType type, TimeInForce tif, std::string traderID, STPMode stp)
             : traderId(std::move(traderID)), id(id), price(price), quantity(quantity), side(side),
-              orderType(type), timeInForce(tif), timestamp(now()), stpMode(stp) {}
+              orderType(type), timeInForce(tif), timestamp(now()), stpMode(stp),
+              originalQuantity(quantity) {}
 
     public:
         static Order Limit(OrderId id, Price price, Quantity quantity, Side side,
@@ -42,10 +42,30 @@ namespace orderbook {
     constexpr Quantity API_MAX_ORDER_SIZE = 100000;
     constexpr Price API_PRICE_BAND_PERCENT = 10;
 
+    // Why the book refused an order or amendment, alongside the message
+    enum class RejectCode : uint8_t {
+        NONE,
+        DUPLICATE_ID,
+        INVALID_QUANTITY,
+        MISSING_PRICE,
+        INVALID_PRICE,
+        MARKET_ORDER_GTC,
+        INVALID_ALL_OR_NONE,
+        INVALID_POST_ONLY,
+        FOK_UNFILLABLE,
+        NO_LIQUIDITY,
+        POST_ONLY_WOULD_MATCH,
+        AON_UNFILLABLE,
+        NOT_FOUND,
+        CROSSED_SPREAD,
+        REDUCE_EXCEEDS_REMAINING
+    };
+
     // Result of adding an order
     struct OrderResult {
         bool accepted = false;              // Was order accepted?
         std::string rejectReason;           // If rejected, why?
+        RejectCode rejectCode = RejectCode::NONE;
         std::vector<Trade> trades;          // Trades generated
         Quantity remainingQuantity = 0;     // Unfilled quantity
         STPResult stpResult;
@@ -55,18 +75,62 @@ namespace orderbook {
     struct ModifyResult {
         bool accepted = false;
         std::string rejectReason;
+        RejectCode rejectCode = RejectCode::NONE;
         Price oldPrice = 0;
         Price newPrice = 0;
         Quantity oldQuantity = 0;
         Quantity newQuantity = 0;
     };
 
+    // Position of a resting order within its price level's FIFO queue
+    struct QueuePosition {
+        bool found = false;
+        size_t ordersAhead = 0;
+        Quantity quantityAhead = 0;
+    };
+
+    // Current state of a resting order
+    struct OrderInfo {
+        bool found = false;
+        Side side = Side::BUY;
+        Price price = 0;
+        Quantity quantity = 0;  // remaining on the book
+        Quantity originalQuantity = 0;
+        Quantity filledQuantity = 0;
+    };
+
+    // Aggregated resting quantity at one price
+    struct DepthLevel {
+        Price price = 0;
+        Quantity quantity = 0;
+        size_t orderCount = 0;
+    };
+
+    // Resting order counts and quantity per side
+    struct BookStats {
+        size_t bidOrders = 0;
+        size_t askOrders = 0;
+        Quantity bidQuantity = 0;
+        Quantity askQuantity = 0;
+    };
+
+    // What an incoming order would take from the book
+    struct FillEstimate {
+        Quantity filledQuantity = 0;
+        int64_t cost = 0;               // sum of price * quantity
+        Price worstPrice = 0;           // last level reached, 0 if nothing fills
+        size_t levels = 0;
+    };
+
     class OrderBook {
         public:
             OrderBook();
             void print() const;
             OrderResult addOrderToBook(Order order);  // Changed return type
             bool cancelOrder(OrderId id);
+            // Remove every resting order and forget the last trade. Trade
+            // ids keep counting. Returns how many orders were removed.
+            size_t clear();
 
             // Price getters
             Price getBestBid() const { return bids.empty() ? 0 : bids.begin()->first; }
@@ -93,6 +157,16 @@ namespace orderbook {
             }
 
             ModifyResult modifyOrder(OrderId id, Price newPrice, Quantity newQuantity);
+            ModifyResult reduceOrder(OrderId id, Quantity reduceBy);
+            // Count quantity an order already filled off the book (an
+            // auction uncross) toward it. Returns false if it isn't resting.
+            bool creditFill(OrderId id, Quantity filled);
+            QueuePosition getQueuePosition(OrderId id) const;
+            OrderInfo getOrder(OrderId id) const;
+            std::vector<DepthLevel> getDepth(Side side, size_t maxLevels) const;
+            BookStats getBookStats() const;
+            Quantity getSweepableQuantity(Side side, size_t maxOrders, Price limitPrice, Quantity quantity) const;
+            FillEstimate estimateFill(Side side, Quantity quantity) const;
 
         private:
             std::map<Price, PriceLevel, std::greater<Price>> bids;
@@ -102,11 +176,20 @@ namespace orderbook {
             Quantity lastTradeQty = 0;
             TradeId nextTradeId = 1;
 
-            std::vector<Trade> fillLimitOrder(Order& order);
-            std::vector<Trade> fillMarketOrder(Order& order);
+            std::vector<Trade> fillLimitOrder(Order& order, STPResult& stpResult);
+            std::vector<Trade> fillMarketOrder(Order& order, STPResult& stpResult);
 
-            void fillOrderAtPriceLevel(Order& order, PriceLevel& level, std::vector<Trade>& trades);
+            void fillOrderAtPriceLevel(Order& order, PriceLevel& level, std::vector<Trade>& trades, STPResult& stpResult);
             Quantity getAvailableLiquidity(Side side, std::optional<Price> limitPrice);
+            // How matching would go for an order, without changing the book
+            struct MatchPreview {
+                Quantity unfilled = 0;
+                bool passedAllOrNone = false;       // skipped an AON order too large to fill
+                bool cancelledBySelfTrade = false;  // CANCEL_NEWEST or CANCEL_BOTH would hit
+            };
+            MatchPreview previewMatch(const Order& order) const;
+            bool canFillInFull(const Order& order) const;
+            bool crossesBook(const Order& order) const;
             bool validateOrder(const Order& order, OrderResult& result);
             OrderResult handleMarketOrder(Order order);
             OrderResult handleLimitOrder(Order order); 
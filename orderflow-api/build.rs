@@ -1,4 +1,9 @@
 fn main() {
+    println!("cargo:rerun-if-changed=../ffi/src");
+    println!("cargo:rerun-if-changed=../ffi/include");
+    println!("cargo:rerun-if-changed=../src/OrderBook.cpp");
+    println!("cargo:rerun-if-changed=../include");
+
     cc::Build::new()
         .cpp(true)
         .std("c++17")
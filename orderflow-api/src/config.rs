@@ -1,23 +1,136 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Debug, Clone, Deserialize)]
+use crate::models::order::StpMode;
+use crate::services::trading_hours::TradingHours;
+
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
 pub struct Config {
     #[serde(default)]
     pub server: ServerConfig,
     #[serde(default)]
     pub risk: RiskConfig,
+    #[serde(default)]
+    pub engine: EngineConfig,
+    #[serde(default)]
+    pub audit: AuditConfig,
+    #[serde(default)]
+    pub fees: FeeConfig,
+    #[serde(default)]
+    pub trading_hours: TradingHoursConfig,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
 pub struct ServerConfig {
     #[serde(default = "default_host")]
     pub host: String,
     #[serde(default = "default_port")]
     pub port: u16,
+    /// Trades returned inline in an order response before it is summarized
+    #[serde(default = "default_max_inline_trades")]
+    pub max_inline_trades: usize,
+    /// Most levels per side `GET /api/v1/market/depth` returns; asking for
+    /// more is a 400
+    #[serde(default = "default_max_depth_levels")]
+    pub max_depth_levels: usize,
+    /// Most orders one `POST /api/v1/orders/batch` may carry
+    #[serde(default = "default_max_batch_orders")]
+    pub max_batch_orders: usize,
+    /// How long an Idempotency-Key can be used to address its order
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub idempotency_ttl_secs: u64,
+    /// Treat a submit that reuses a completed Idempotency-Key as a modify of
+    /// the order it created (upsert), instead of rejecting the duplicate
+    #[serde(default)]
+    pub idempotency_key_upsert: bool,
+    /// Batch WebSocket messages into one array frame per interval; 0 sends
+    /// each message as its own frame
+    #[serde(default)]
+    pub ws_batch_interval_ms: u64,
+    /// Largest WebSocket message or frame accepted from a client; larger
+    /// ones close the connection
+    #[serde(default = "default_ws_max_message_bytes")]
+    pub ws_max_message_bytes: usize,
+    /// How long a dropped WebSocket session can be resumed with its token
+    /// before its cancel-on-disconnect fires
+    #[serde(default = "default_ws_resume_grace_ms")]
+    pub ws_resume_grace_ms: u64,
+    /// Reject order submissions for this long after startup; 0 disables
+    #[serde(default)]
+    pub warmup_ms: u64,
+    /// Open the session with a call auction: for this long after warmup,
+    /// orders are collected without matching, then uncrossed at the price
+    /// that matches the most volume. 0 opens straight into continuous trading
+    #[serde(default)]
+    pub opening_auction_ms: u64,
+    /// Delay trade broadcasts by this long; order responses still report
+    /// fills immediately. 0 publishes trades as they happen
+    #[serde(default)]
+    pub trade_print_delay_ms: u64,
+    /// Serve `/api/v1/admin/debug/*` endpoints that expose internal state.
+    /// They take an admin key and listen only on `debug_bind_addr`, never on
+    /// the trading port.
+    #[serde(default)]
+    pub debug_endpoints: bool,
+    /// Address of the listener for debug endpoints; keep it off public
+    /// interfaces
+    #[serde(default = "default_debug_bind_addr")]
+    pub debug_bind_addr: String,
+    /// Send quantities and ids in JSON responses as strings, for clients
+    /// that parse numbers as doubles. Clients can also opt in per request
+    /// with `Accept: application/json; numbers=string`.
+    #[serde(default)]
+    pub numbers_as_strings: bool,
+    /// Wrap successful JSON responses as `{data, meta: {seq, timestampNs}}`.
+    /// Clients can choose per request with `?envelope=true` or `false`.
+    #[serde(default)]
+    pub envelope_responses: bool,
+    /// Broadcast an `imbalanceAlert` when the top-of-book imbalance,
+    /// (bid - ask) / (bid + ask), reaches this magnitude; unset disables
+    #[serde(default)]
+    pub imbalance_alert_threshold: Option<f64>,
+    /// How far below the threshold the imbalance must fall before the book
+    /// counts as balanced again
+    #[serde(default = "default_imbalance_alert_hysteresis")]
+    pub imbalance_alert_hysteresis: f64,
+    /// Broadcast a `marketQualityAlert` when the spread stays wider than
+    /// this many dollars, or a side of the book stays empty, for
+    /// `market_quality_grace_ms`; unset disables
+    #[serde(default)]
+    pub market_quality_max_spread: Option<f64>,
+    #[serde(default = "default_market_quality_grace_ms")]
+    pub market_quality_grace_ms: u64,
+    /// Broadcast a `snapshot` of the top of book this often, when it has
+    /// changed since the last one; 0 disables
+    #[serde(default)]
+    pub snapshot_interval_ms: u64,
+    /// Serve reads only: every mutating request is refused with 503, for
+    /// running replicas behind a load balancer
+    #[serde(default)]
+    pub read_only: bool,
+    /// Refuse order bodies not sent as `Content-Type: application/json`
+    /// with 415; when off, any body is parsed as JSON regardless of header
+    #[serde(default = "default_true")]
+    pub strict_content_type: bool,
+    /// Exit at startup if the Prometheus recorder can't be installed;
+    /// otherwise the server runs without `/metrics`
+    #[serde(default)]
+    pub metrics_required: bool,
+    /// Route → requests per second allowed from one client IP, e.g.
+    /// `"/api/v1/market" = 20`. Routes use their pattern (`/api/v1/orders/:id`);
+    /// unlisted routes are not limited
+    #[serde(default)]
+    pub http_rate_limits: HashMap<String, u32>,
+    /// `X-Admin-Key` value → principal name for the admin endpoints. Empty
+    /// disables them: every admin request is refused. Never logged.
+    #[serde(default, skip_serializing)]
+    pub admin_api_keys: HashMap<String, String>,
 }
 
-#[derive(Debug, Clone, Deserialize)]
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
 pub struct RiskConfig {
     #[serde(default = "default_min_order_size")]
     pub min_order_size: i64,
@@ -25,10 +138,318 @@ pub struct RiskConfig {
     pub max_order_size: i64,
     #[serde(default = "default_price_band_percent")]
     pub price_band_percent: f64,
+    /// Band around the best price on the only populated side, used when the
+    /// book is one-sided and has no other reference; 0 skips the band check
+    /// until a mid or trade exists
+    #[serde(default = "default_one_sided_price_band_percent")]
+    pub one_sided_price_band_percent: f64,
+    /// Minimum price increment in dollars; prices must be a whole multiple
+    #[serde(default = "default_tick_size")]
+    pub tick_size: f64,
+    /// Quantities must be a whole multiple of this many units
+    #[serde(default = "default_lot_size")]
+    pub lot_size: i64,
+    /// Net position limit for each trader, applied to every symbol
+    /// separately
     #[serde(default = "default_max_position_per_trader")]
     pub max_position_per_trader: i64,
+    /// Whether a position of exactly `max_position_per_trader` is allowed;
+    /// when off, only positions strictly inside the limit are
+    #[serde(default = "default_true")]
+    pub position_limit_inclusive: bool,
+    /// Count resting order quantity toward the position limit, as if every
+    /// open order on the same side filled
+    #[serde(default)]
+    pub include_resting_in_position_limit: bool,
     #[serde(default = "default_max_orders_per_second")]
     pub max_orders_per_second: u32,
+    /// Rate-limit each (trader, symbol) pair separately
+    #[serde(default)]
+    pub rate_limit_per_symbol: bool,
+    #[serde(default = "default_max_in_flight_per_trader")]
+    pub max_in_flight_per_trader: u32,
+    /// Trader ids permitted to submit orders; empty allows everyone
+    #[serde(default)]
+    pub allowed_traders: Vec<String>,
+    /// Trader ids whose orders may only reduce their position
+    #[serde(default)]
+    pub reduce_only_traders: Vec<String>,
+    /// Distinct traders tracked at once, bounding the per-trader risk and
+    /// rate-limit state; 0 is unlimited
+    #[serde(default)]
+    pub max_traders: usize,
+    /// What happens to a new trader once `max_traders` is reached
+    #[serde(default)]
+    pub trader_cap_policy: TraderCapPolicy,
+    /// Symbol → price band reference for the session open, used until that
+    /// symbol's book has a mid price or a trade
+    #[serde(default)]
+    pub opening_prices: HashMap<String, f64>,
+    /// Largest order value in dollars (price × quantity). Market orders are
+    /// valued at the best opposite price.
+    #[serde(default)]
+    pub max_order_notional: Option<f64>,
+    /// Cache the market snapshot used by risk checks for this long; 0 reads
+    /// the book on every order
+    #[serde(default)]
+    pub snapshot_cache_ttl_ms: u64,
+    /// Hard ceiling on a cached snapshot's age. Past the TTL a stale snapshot
+    /// is only used while the book is locked by a writer, and never past this
+    #[serde(default = "default_snapshot_max_age_ms")]
+    pub snapshot_max_age_ms: u64,
+    /// Read the book's snapshot on a background task this often and publish
+    /// it for risk checks, so submissions never take the book's read lock
+    /// themselves; 0 disables polling. Must be under `snapshot_max_age_ms`.
+    #[serde(default)]
+    pub snapshot_poll_interval_ms: u64,
+    /// Halt order entry on a symbol when its last-trade price moves more than
+    /// this percent within `circuit_breaker_window_ms`; unset disables the
+    /// breaker
+    #[serde(default)]
+    pub circuit_breaker_percent: Option<f64>,
+    #[serde(default = "default_circuit_breaker_window_ms")]
+    pub circuit_breaker_window_ms: u64,
+    /// How long order entry on the symbol stays halted after the breaker
+    /// trips
+    #[serde(default = "default_circuit_breaker_cooldown_ms")]
+    pub circuit_breaker_cooldown_ms: u64,
+    /// Rejections within `rejection_penalty_window_ms` that put a trader in
+    /// the penalty box, refusing their orders for a cool-down; 0 disables
+    #[serde(default)]
+    pub rejection_penalty_threshold: u32,
+    #[serde(default = "default_rejection_penalty_window_ms")]
+    pub rejection_penalty_window_ms: u64,
+    /// First cool-down; each repeat offence doubles it, up to
+    /// `rejection_penalty_max_ms`
+    #[serde(default = "default_rejection_penalty_base_ms")]
+    pub rejection_penalty_base_ms: u64,
+    #[serde(default = "default_rejection_penalty_max_ms")]
+    pub rejection_penalty_max_ms: u64,
+    /// A trader who stays out of the box this long after a cool-down ends
+    /// starts again from the base cool-down
+    #[serde(default = "default_rejection_penalty_reset_ms")]
+    pub rejection_penalty_reset_ms: u64,
+    /// Modifies allowed per order id before it must be cancelled and
+    /// resubmitted, to discourage flickering quotes; 0 is unlimited
+    #[serde(default)]
+    pub max_modifies_per_order: u32,
+    /// Persist trader positions to this file and restore them at startup,
+    /// so position limits hold across a restart; unset keeps them in memory
+    #[serde(default)]
+    pub state_file: Option<String>,
+    /// How often positions are written to `state_file`; they are also
+    /// written on shutdown
+    #[serde(default = "default_state_persist_interval_secs")]
+    pub state_persist_interval_secs: u64,
+    /// JSON map of trader → symbol → position to seed at startup, e.g. overnight
+    /// positions from another system. Only applied when no `state_file`
+    /// was restored, as saved state is the more recent.
+    #[serde(default)]
+    pub position_import_file: Option<String>,
+    /// Self-trade prevention for orders that don't set `stpMode`; unset
+    /// leaves them at `ALLOW`
+    #[serde(default)]
+    pub default_stp_mode: Option<StpMode>,
+    /// Trader id → STP mode for that trader's orders that don't set one,
+    /// overriding `default_stp_mode`
+    #[serde(default)]
+    pub trader_stp_modes: HashMap<String, StpMode>,
+    /// Trader id → limits replacing the ones above for that trader, e.g.
+    /// a higher position limit for an institutional account
+    #[serde(default)]
+    pub overrides: HashMap<String, RiskOverride>,
+}
+
+/// Per-trader order limits. Unset fields keep the global `risk` value.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct RiskOverride {
+    #[serde(default)]
+    pub min_order_size: Option<i64>,
+    #[serde(default)]
+    pub max_order_size: Option<i64>,
+    #[serde(default)]
+    pub max_position_per_trader: Option<i64>,
+    #[serde(default)]
+    pub max_order_notional: Option<f64>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct EngineConfig {
+    /// Instrument traded by orders that don't name a symbol. Its book always
+    /// exists; other symbols get a book on their first order.
+    #[serde(default = "default_symbol")]
+    pub symbol: String,
+    /// Most books the engine will hold, the default symbol's included.
+    /// Orders naming a new symbol past this are rejected.
+    #[serde(default = "default_max_symbols")]
+    pub max_symbols: usize,
+    /// When set, market orders are converted to IOC limit orders priced at the
+    /// best opposite price ± this many dollars, capping the worst fill price.
+    #[serde(default)]
+    pub market_protection_offset: Option<f64>,
+    /// Halt order entry if the integrity check finds a crossed book
+    #[serde(default)]
+    pub halt_on_crossed_book: bool,
+    /// Lifetime cap on orders the engine accepts, for bounding load tests;
+    /// 0 is unlimited
+    #[serde(default)]
+    pub max_total_orders: u64,
+    /// Let a modify raise an order's quantity above what is still resting.
+    /// Off forces clients to send a new order for more size, so a modify
+    /// can't grow an order while keeping its place in the queue.
+    #[serde(default = "default_true")]
+    pub allow_modify_quantity_increase: bool,
+    /// Reject GTC market orders as invalid requests (400). Off passes them
+    /// to the engine, which refuses them as a 409 engine rejection.
+    #[serde(default = "default_true")]
+    pub strict_market_time_in_force: bool,
+    /// Resting GTC orders expire this long after entry, for venues without
+    /// perpetual orders; 0 keeps them until cancelled. IOC and FOK never
+    /// rest, so need no expiry.
+    #[serde(default)]
+    pub gtc_default_expiry_secs: u64,
+    /// Reject GTC limit orders priced exactly at the best opposite price,
+    /// which would leave a locked market (bid == ask). Orders priced
+    /// through the touch still cross and trade as usual.
+    #[serde(default)]
+    pub reject_locking_orders: bool,
+    /// Start in quote-only mode: orders that would trade on arrival are
+    /// refused. Toggled at runtime via the admin API, for every book or one.
+    #[serde(default)]
+    pub quote_only: bool,
+    /// Most resting orders a single market, IOC or FOK order may trade
+    /// against; 0 is unlimited
+    #[serde(default)]
+    pub max_sweep_orders: usize,
+    /// What to do with an order that would sweep past `max_sweep_orders`
+    #[serde(default)]
+    pub sweep_cap_policy: SweepCapPolicy,
+    /// Which clock stamps trades; see `TradeResponse`
+    #[serde(default)]
+    pub trade_timestamp_source: TradeTimestampSource,
+    /// Tag trades up/down/zero against the previous trade price, for
+    /// charting clients
+    #[serde(default)]
+    pub trade_tick_direction: bool,
+    /// Mid price reported while only one side of the book is quoted. The
+    /// risk price band takes the mid as its reference first, so a one-sided
+    /// mid also bands orders with `price_band_percent` around it.
+    #[serde(default)]
+    pub one_sided_mid: OneSidedMid,
+    /// Record the duration of order and snapshot calls into the C++ book as
+    /// `orderflow_ffi_call_seconds`, labelled by operation
+    #[serde(default)]
+    pub ffi_call_timing: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TraderCapPolicy {
+    /// Refuse orders from traders not already tracked
+    #[default]
+    RejectNew,
+    /// Make room by dropping the least recently active trader with no
+    /// resting orders and a flat position; refuse if there is none
+    EvictIdle,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SweepCapPolicy {
+    /// Refuse the whole order
+    Reject,
+    /// Fill against the allowed orders and cancel the remainder
+    #[default]
+    AcceptPartial,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TradeTimestampSource {
+    /// Wall-clock time taken by the server when the order executed
+    #[default]
+    Server,
+    /// The matching engine's monotonic clock, with `serverTs` alongside
+    Engine,
+    /// Server time in `timestampNs`, plus both `engineTs` and `serverTs`
+    Both,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OneSidedMid {
+    /// No mid until both sides are quoted
+    #[default]
+    None,
+    /// The price of the side that is quoted
+    BestPrice,
+    /// The last trade price, if there has been a trade
+    LastTrade,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct AuditConfig {
+    /// Log only every Nth order rejection per (trader, source); 1 logs all
+    #[serde(default = "default_rejection_log_every")]
+    pub rejection_log_every: u32,
+    /// How often to log a summary of suppressed rejections
+    #[serde(default = "default_rejection_summary_secs")]
+    pub rejection_summary_secs: u64,
+    /// Admin audit entries kept in memory for `GET /api/v1/admin/audit-log`
+    #[serde(default = "default_admin_log_capacity")]
+    pub admin_log_capacity: usize,
+    /// Orders whose lifecycle is kept for `GET /api/v1/orders/:id/history`
+    #[serde(default = "default_order_history_capacity")]
+    pub order_history_capacity: usize,
+    /// Log an `OrderFilled` summary for each order that trades on arrival,
+    /// alongside its per-trade `TradeExecuted` events
+    #[serde(default = "default_true")]
+    pub log_order_fills: bool,
+}
+
+/// Trading fees in basis points of trade notional. The maker is the resting
+/// side of a trade, the taker the incoming order; a negative fee is a rebate.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FeeConfig {
+    #[serde(default)]
+    pub maker_fee_bps: f64,
+    #[serde(default)]
+    pub taker_fee_bps: f64,
+    /// Named rate tiers, e.g. rebates for market makers
+    #[serde(default)]
+    pub tiers: HashMap<String, FeeTier>,
+    /// Trader id → tier name; traders not listed pay the default rates
+    #[serde(default)]
+    pub trader_tiers: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct FeeTier {
+    pub maker_fee_bps: f64,
+    pub taker_fee_bps: f64,
+}
+
+/// Daily window in which new orders are accepted. With `open` and `close`
+/// unset, order entry is open around the clock. Cancels, modifies and
+/// reductions are allowed at any time.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all(serialize = "camelCase"))]
+pub struct TradingHoursConfig {
+    /// Local time order entry opens, "HH:MM"
+    #[serde(default)]
+    pub open: Option<String>,
+    /// Local time order entry closes, "HH:MM"; earlier than `open` for a
+    /// session that runs past midnight
+    #[serde(default)]
+    pub close: Option<String>,
+    /// Local time's offset from UTC, "+HH:MM" or "-HH:MM"
+    #[serde(default = "default_utc_offset")]
+    pub utc_offset: String,
 }
 
 fn default_host() -> String {
@@ -37,6 +458,36 @@ fn default_host() -> String {
 fn default_port() -> u16 {
     8080
 }
+fn default_debug_bind_addr() -> String {
+    "127.0.0.1:9091".into()
+}
+fn default_max_inline_trades() -> usize {
+    100
+}
+fn default_max_depth_levels() -> usize {
+    50
+}
+fn default_max_batch_orders() -> usize {
+    50
+}
+fn default_utc_offset() -> String {
+    "+00:00".into()
+}
+fn default_idempotency_ttl_secs() -> u64 {
+    86_400
+}
+fn default_ws_max_message_bytes() -> usize {
+    64 * 1024
+}
+fn default_ws_resume_grace_ms() -> u64 {
+    10_000
+}
+fn default_imbalance_alert_hysteresis() -> f64 {
+    0.1
+}
+fn default_market_quality_grace_ms() -> u64 {
+    10_000
+}
 fn default_min_order_size() -> i64 {
     1
 }
@@ -46,18 +497,100 @@ fn default_max_order_size() -> i64 {
 fn default_price_band_percent() -> f64 {
     10.0
 }
+fn default_one_sided_price_band_percent() -> f64 {
+    20.0
+}
+fn default_tick_size() -> f64 {
+    0.01
+}
+fn default_lot_size() -> i64 {
+    1
+}
 fn default_max_position_per_trader() -> i64 {
     1_000_000
 }
 fn default_max_orders_per_second() -> u32 {
     100
 }
+fn default_max_in_flight_per_trader() -> u32 {
+    16
+}
+fn default_true() -> bool {
+    true
+}
+fn default_snapshot_max_age_ms() -> u64 {
+    100
+}
+fn default_circuit_breaker_window_ms() -> u64 {
+    5_000
+}
+fn default_circuit_breaker_cooldown_ms() -> u64 {
+    60_000
+}
+fn default_rejection_penalty_window_ms() -> u64 {
+    1_000
+}
+fn default_rejection_penalty_base_ms() -> u64 {
+    1_000
+}
+fn default_rejection_penalty_max_ms() -> u64 {
+    60_000
+}
+fn default_rejection_penalty_reset_ms() -> u64 {
+    60_000
+}
+fn default_state_persist_interval_secs() -> u64 {
+    5
+}
+fn default_symbol() -> String {
+    "DEFAULT".into()
+}
+fn default_max_symbols() -> usize {
+    64
+}
+fn default_rejection_log_every() -> u32 {
+    1
+}
+fn default_rejection_summary_secs() -> u64 {
+    60
+}
+fn default_admin_log_capacity() -> usize {
+    1_000
+}
+fn default_order_history_capacity() -> usize {
+    10_000
+}
 
 impl Default for ServerConfig {
     fn default() -> Self {
         Self {
             host: default_host(),
             port: default_port(),
+            max_inline_trades: default_max_inline_trades(),
+            max_depth_levels: default_max_depth_levels(),
+            max_batch_orders: default_max_batch_orders(),
+            idempotency_ttl_secs: default_idempotency_ttl_secs(),
+            idempotency_key_upsert: false,
+            ws_batch_interval_ms: 0,
+            ws_max_message_bytes: default_ws_max_message_bytes(),
+            ws_resume_grace_ms: default_ws_resume_grace_ms(),
+            warmup_ms: 0,
+            opening_auction_ms: 0,
+            trade_print_delay_ms: 0,
+            debug_endpoints: false,
+            debug_bind_addr: default_debug_bind_addr(),
+            numbers_as_strings: false,
+            envelope_responses: false,
+            imbalance_alert_threshold: None,
+            imbalance_alert_hysteresis: default_imbalance_alert_hysteresis(),
+            market_quality_max_spread: None,
+            market_quality_grace_ms: default_market_quality_grace_ms(),
+            snapshot_interval_ms: 0,
+            read_only: false,
+            strict_content_type: true,
+            metrics_required: false,
+            http_rate_limits: HashMap::new(),
+            admin_api_keys: HashMap::new(),
         }
     }
 }
@@ -68,17 +601,84 @@ impl Default for RiskConfig {
             min_order_size: default_min_order_size(),
             max_order_size: default_max_order_size(),
             price_band_percent: default_price_band_percent(),
+            one_sided_price_band_percent: default_one_sided_price_band_percent(),
+            tick_size: default_tick_size(),
+            lot_size: default_lot_size(),
             max_position_per_trader: default_max_position_per_trader(),
+            position_limit_inclusive: true,
+            include_resting_in_position_limit: false,
             max_orders_per_second: default_max_orders_per_second(),
+            rate_limit_per_symbol: false,
+            max_in_flight_per_trader: default_max_in_flight_per_trader(),
+            allowed_traders: Vec::new(),
+            reduce_only_traders: Vec::new(),
+            max_traders: 0,
+            trader_cap_policy: TraderCapPolicy::default(),
+            opening_prices: HashMap::new(),
+            max_order_notional: None,
+            snapshot_cache_ttl_ms: 0,
+            snapshot_max_age_ms: default_snapshot_max_age_ms(),
+            snapshot_poll_interval_ms: 0,
+            circuit_breaker_percent: None,
+            circuit_breaker_window_ms: default_circuit_breaker_window_ms(),
+            circuit_breaker_cooldown_ms: default_circuit_breaker_cooldown_ms(),
+            rejection_penalty_threshold: 0,
+            rejection_penalty_window_ms: default_rejection_penalty_window_ms(),
+            rejection_penalty_base_ms: default_rejection_penalty_base_ms(),
+            rejection_penalty_max_ms: default_rejection_penalty_max_ms(),
+            rejection_penalty_reset_ms: default_rejection_penalty_reset_ms(),
+            max_modifies_per_order: 0,
+            state_file: None,
+            state_persist_interval_secs: default_state_persist_interval_secs(),
+            position_import_file: None,
+            default_stp_mode: None,
+            trader_stp_modes: HashMap::new(),
+            overrides: HashMap::new(),
+        }
+    }
+}
+
+impl Default for EngineConfig {
+    fn default() -> Self {
+        Self {
+            symbol: default_symbol(),
+            max_symbols: default_max_symbols(),
+            market_protection_offset: None,
+            halt_on_crossed_book: false,
+            max_total_orders: 0,
+            allow_modify_quantity_increase: true,
+            strict_market_time_in_force: true,
+            gtc_default_expiry_secs: 0,
+            reject_locking_orders: false,
+            quote_only: false,
+            max_sweep_orders: 0,
+            sweep_cap_policy: SweepCapPolicy::default(),
+            trade_timestamp_source: TradeTimestampSource::default(),
+            trade_tick_direction: false,
+            one_sided_mid: OneSidedMid::default(),
+            ffi_call_timing: false,
+        }
+    }
+}
+
+impl Default for TradingHoursConfig {
+    fn default() -> Self {
+        Self {
+            open: None,
+            close: None,
+            utc_offset: default_utc_offset(),
         }
     }
 }
 
-impl Default for Config {
+impl Default for AuditConfig {
     fn default() -> Self {
         Self {
-            server: ServerConfig::default(),
-            risk: RiskConfig::default(),
+            rejection_log_every: default_rejection_log_every(),
+            rejection_summary_secs: default_rejection_summary_secs(),
+            admin_log_capacity: default_admin_log_capacity(),
+            order_history_capacity: default_order_history_capacity(),
+            log_order_fills: true,
         }
     }
 }
@@ -106,4 +706,368 @@ impl Config {
         }
         Self::default()
     }
+
+    /// Check settings that would otherwise silently break order entry, such
+    /// as a zero price band rejecting every order. Returns the first problem.
+    pub fn validate(&self) -> Result<(), String> {
+        let risk = &self.risk;
+        if !(0.1..=100.0).contains(&risk.price_band_percent) {
+            return Err(format!(
+                "risk.price_band_percent must be between 0.1 and 100, got {}",
+                risk.price_band_percent
+            ));
+        }
+        let one_sided = risk.one_sided_price_band_percent;
+        if one_sided != 0.0 && !(0.1..=100.0).contains(&one_sided) {
+            return Err(format!(
+                "risk.one_sided_price_band_percent must be 0 or between 0.1 and 100, got {}",
+                one_sided
+            ));
+        }
+        if risk.min_order_size <= 0 {
+            return Err(format!(
+                "risk.min_order_size must be positive, got {}",
+                risk.min_order_size
+            ));
+        }
+        if risk.max_order_size < risk.min_order_size {
+            return Err(format!(
+                "risk.max_order_size ({}) is below risk.min_order_size ({})",
+                risk.max_order_size, risk.min_order_size
+            ));
+        }
+        if risk.lot_size <= 0 {
+            return Err(format!(
+                "risk.lot_size must be positive, got {}",
+                risk.lot_size
+            ));
+        }
+        if !is_whole_cents(risk.tick_size) {
+            return Err(format!(
+                "risk.tick_size must be a positive whole number of cents, got {}",
+                risk.tick_size
+            ));
+        }
+        if risk.max_position_per_trader <= 0 {
+            return Err(format!(
+                "risk.max_position_per_trader must be positive, got {}",
+                risk.max_position_per_trader
+            ));
+        }
+        for (trader_id, limits) in &risk.overrides {
+            let min = limits.min_order_size.unwrap_or(risk.min_order_size);
+            let max = limits.max_order_size.unwrap_or(risk.max_order_size);
+            let position = limits
+                .max_position_per_trader
+                .unwrap_or(risk.max_position_per_trader);
+            if min <= 0 || max < min || position <= 0 {
+                return Err(format!(
+                    "risk.overrides.{}: sizes and position limit must be positive, \
+                     with max_order_size at least min_order_size",
+                    trader_id
+                ));
+            }
+        }
+        let poll = risk.snapshot_poll_interval_ms;
+        if poll > 0 && poll >= risk.snapshot_max_age_ms {
+            return Err(format!(
+                "risk.snapshot_poll_interval_ms ({}) must be below risk.snapshot_max_age_ms ({})",
+                poll, risk.snapshot_max_age_ms
+            ));
+        }
+        if risk.max_orders_per_second == 0 {
+            return Err("risk.max_orders_per_second must be positive".into());
+        }
+        if risk.max_in_flight_per_trader == 0 {
+            return Err("risk.max_in_flight_per_trader must be positive".into());
+        }
+        if let Some(threshold) = self.server.imbalance_alert_threshold {
+            if !(threshold > 0.0 && threshold <= 1.0) {
+                return Err(format!(
+                    "server.imbalance_alert_threshold must be in (0, 1], got {}",
+                    threshold
+                ));
+            }
+            let hysteresis = self.server.imbalance_alert_hysteresis;
+            if !(0.0..threshold).contains(&hysteresis) {
+                return Err(format!(
+                    "server.imbalance_alert_hysteresis must be in [0, threshold), got {}",
+                    hysteresis
+                ));
+            }
+        }
+        if let Some(max_spread) = self.server.market_quality_max_spread {
+            if !(max_spread > 0.0 && max_spread.is_finite()) {
+                return Err(format!(
+                    "server.market_quality_max_spread must be positive, got {}",
+                    max_spread
+                ));
+            }
+        }
+        if let Some(offset) = self.engine.market_protection_offset {
+            if !is_whole_cents(offset) {
+                return Err(format!(
+                    "engine.market_protection_offset must be a positive whole number of cents, got {}",
+                    offset
+                ));
+            }
+        }
+        if let Some((symbol, price)) = self
+            .risk
+            .opening_prices
+            .iter()
+            .find(|(_, price)| !(**price > 0.0 && price.is_finite()))
+        {
+            return Err(format!(
+                "risk.opening_prices.{} must be positive, got {}",
+                symbol, price
+            ));
+        }
+        if self.engine.max_symbols == 0 {
+            return Err("engine.max_symbols must be positive".into());
+        }
+        if self.server.max_depth_levels == 0 {
+            return Err("server.max_depth_levels must be positive".into());
+        }
+        if self.server.max_batch_orders == 0 {
+            return Err("server.max_batch_orders must be positive".into());
+        }
+        if let Some((route, _)) = self.server.http_rate_limits.iter().find(|(_, &n)| n == 0) {
+            return Err(format!(
+                "server.http_rate_limits for {} must be positive",
+                route
+            ));
+        }
+        TradingHours::from_config(&self.trading_hours)?;
+        for (trader, tier) in &self.fees.trader_tiers {
+            if !self.fees.tiers.contains_key(tier) {
+                return Err(format!(
+                    "fees.trader_tiers assigns {} to unknown tier {}",
+                    trader, tier
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Log the effective configuration as a single structured `ServiceStarted`
+    /// event, one JSON field per section.
+    pub fn log_effective(&self) {
+        tracing::info!(
+            event = "ServiceStarted",
+            version = env!("CARGO_PKG_VERSION"),
+            server = %to_json(&self.server),
+            risk = %to_json(&self.risk),
+            engine = %to_json(&self.engine),
+            audit = %to_json(&self.audit),
+        );
+    }
+}
+
+fn to_json<T: Serialize>(section: &T) -> String {
+    serde_json::to_string(section).unwrap_or_default()
+}
+
+/// True for a finite dollar amount of at least one cent with no sub-cent part.
+fn is_whole_cents(dollars: f64) -> bool {
+    let cents = (dollars * 100.0).round();
+    dollars.is_finite() && cents >= 1.0 && (cents / 100.0 - dollars).abs() <= 0.001
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_config_is_valid() {
+        assert!(Config::default().validate().is_ok());
+    }
+
+    #[test]
+    fn test_price_band_out_of_range_rejected() {
+        for band in [0.0, 0.05, -5.0, 100.5, 1000.0, f64::NAN] {
+            let mut config = Config::default();
+            config.risk.price_band_percent = band;
+            let err = config.validate().unwrap_err();
+            assert!(err.contains("price_band_percent"), "band {}: {}", band, err);
+        }
+        for band in [0.1, 10.0, 100.0] {
+            let mut config = Config::default();
+            config.risk.price_band_percent = band;
+            assert!(config.validate().is_ok(), "band {} should be valid", band);
+        }
+
+        let mut config = Config::default();
+        config.risk.one_sided_price_band_percent = 0.0;
+        assert!(config.validate().is_ok());
+        config.risk.one_sided_price_band_percent = 150.0;
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("one_sided_price_band_percent"));
+    }
+
+    #[test]
+    fn test_non_positive_limits_rejected() {
+        let cases: [fn(&mut RiskConfig); 6] = [
+            |r| r.min_order_size = 0,
+            |r| r.lot_size = 0,
+            |r| r.max_order_size = -1,
+            |r| r.max_position_per_trader = 0,
+            |r| r.max_orders_per_second = 0,
+            |r| r.max_in_flight_per_trader = 0,
+        ];
+        for case in cases {
+            let mut config = Config::default();
+            case(&mut config.risk);
+            assert!(config.validate().is_err());
+        }
+    }
+
+    #[test]
+    fn test_tick_size_must_be_whole_cents() {
+        for tick in [0.0, -0.01, 0.005, 0.015, f64::NAN, f64::INFINITY] {
+            let mut config = Config::default();
+            config.risk.tick_size = tick;
+            let err = config.validate().unwrap_err();
+            assert!(err.contains("tick_size"), "tick {}: {}", tick, err);
+        }
+        for tick in [0.01, 0.05, 1.0] {
+            let mut config = Config::default();
+            config.risk.tick_size = tick;
+            assert!(config.validate().is_ok(), "tick {} should be valid", tick);
+        }
+    }
+
+    #[test]
+    fn test_market_protection_offset_must_be_positive() {
+        for offset in [0.0, -1.0, 0.005, f64::NAN] {
+            let mut config = Config::default();
+            config.engine.market_protection_offset = Some(offset);
+            let err = config.validate().unwrap_err();
+            assert!(
+                err.contains("market_protection_offset"),
+                "offset {}: {}",
+                offset,
+                err
+            );
+        }
+        let mut config = Config::default();
+        config.engine.market_protection_offset = Some(0.50);
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_opening_prices_must_be_positive() {
+        let mut config = Config::default();
+        config.risk.opening_prices.insert("ETH-USD".into(), 2_500.0);
+        assert!(config.validate().is_ok());
+        config.risk.opening_prices.insert("BTC-USD".into(), 0.0);
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("opening_prices.BTC-USD"));
+    }
+
+    #[test]
+    fn test_max_symbols_must_be_positive() {
+        let mut config = Config::default();
+        config.engine.max_symbols = 0;
+        assert!(config.validate().unwrap_err().contains("max_symbols"));
+    }
+
+    #[test]
+    fn test_snapshot_poll_must_beat_max_age() {
+        let mut config = Config::default();
+        config.risk.snapshot_poll_interval_ms = 10;
+        assert!(config.validate().is_ok());
+        config.risk.snapshot_poll_interval_ms = config.risk.snapshot_max_age_ms;
+        assert!(config
+            .validate()
+            .unwrap_err()
+            .contains("snapshot_poll_interval_ms"));
+    }
+
+    #[test]
+    fn test_unknown_fee_tier_rejected() {
+        let mut config = Config::default();
+        config
+            .fees
+            .trader_tiers
+            .insert("mm1".into(), "market_maker".into());
+        assert!(config.validate().unwrap_err().contains("market_maker"));
+
+        config.fees.tiers.insert(
+            "market_maker".into(),
+            FeeTier {
+                maker_fee_bps: -0.5,
+                taker_fee_bps: 2.0,
+            },
+        );
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_risk_overrides_parsed_and_validated() {
+        let toml = "[risk.overrides]\nwhale = { max_position_per_trader = 1_000_000 }\n";
+        let mut config: Config = toml::from_str(toml).unwrap();
+        let whale = &config.risk.overrides["whale"];
+        assert_eq!(whale.max_position_per_trader, Some(1_000_000));
+        assert!(whale.max_order_size.is_none());
+        assert!(config.validate().is_ok());
+
+        // Checked against the global value it leaves in place
+        config.risk.overrides.insert(
+            "minnow".into(),
+            RiskOverride {
+                min_order_size: Some(config.risk.max_order_size + 1),
+                ..RiskOverride::default()
+            },
+        );
+        assert!(config.validate().unwrap_err().contains("minnow"));
+    }
+
+    /// `effective` JSON keys back in config-file form, so it can be parsed
+    /// as a `Config` again.
+    fn snake_case_keys(value: serde_json::Value) -> serde_json::Value {
+        use serde_json::Value;
+        match value {
+            Value::Object(map) => Value::Object(
+                map.into_iter()
+                    .map(|(key, value)| {
+                        let mut snake = String::new();
+                        for c in key.chars() {
+                            if c.is_ascii_uppercase() {
+                                snake.push('_');
+                            }
+                            snake.push(c.to_ascii_lowercase());
+                        }
+                        (snake, snake_case_keys(value))
+                    })
+                    .collect(),
+            ),
+            other => other,
+        }
+    }
+
+    #[test]
+    fn test_effective_config_round_trips() {
+        let mut config: Config = toml::from_str(include_str!("../config.toml")).unwrap();
+        config
+            .server
+            .admin_api_keys
+            .insert("secret".into(), "ops".into());
+        let effective = serde_json::to_value(&config).unwrap();
+
+        // Every logged field reads back as the setting it came from
+        let reparsed: Config = serde_json::from_value(snake_case_keys(effective.clone())).unwrap();
+        assert_eq!(serde_json::to_value(&reparsed).unwrap(), effective);
+
+        for section in ["server", "risk", "engine", "audit", "fees", "trading_hours"] {
+            assert!(effective[section].is_object(), "{} missing", section);
+        }
+        assert_eq!(effective["risk"]["maxOrderSize"], 100_000);
+        // Admin keys are credentials and never leave the process
+        assert!(effective["server"].get("adminApiKeys").is_none());
+    }
 }
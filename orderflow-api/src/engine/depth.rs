@@ -0,0 +1,184 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::models::order::Side;
+
+/// Resting quantity per price level, prices in integer cents.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DepthBook {
+    pub bids: BTreeMap<i64, i64>,
+    pub asks: BTreeMap<i64, i64>,
+}
+
+/// One binary depth message. `snapshot` frames carry every level; delta frames
+/// carry only levels whose quantity changed, with 0 meaning the level is gone
+/// and `OUT_OF_VIEW` meaning it was pushed past the levels the feed carries.
+///
+/// Encoded with MessagePack as a positional array
+/// `[snapshot, [[price, qty], ...], [[price, qty], ...]]`, prices in cents.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DepthFrame {
+    pub snapshot: bool,
+    pub bids: Vec<(i64, i64)>,
+    pub asks: Vec<(i64, i64)>,
+}
+
+impl DepthFrame {
+    pub fn is_empty(&self) -> bool {
+        self.bids.is_empty() && self.asks.is_empty()
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        // Plain tuples and vecs always serialize
+        rmp_serde::to_vec(self).expect("depth frame encodes")
+    }
+}
+
+/// Quantity sent for a level that is still on the book but no longer among
+/// the best levels the feed carries. Clients drop it as they would a deleted
+/// level, without treating it as a cancel.
+pub const OUT_OF_VIEW: i64 = -1;
+
+/// Levels of one side that changed between two views of at most `levels`
+/// levels each. `side` tells which end of `next` is its worst level.
+fn diff_side(
+    prev: &BTreeMap<i64, i64>,
+    next: &BTreeMap<i64, i64>,
+    levels: usize,
+    side: Side,
+) -> Vec<(i64, i64)> {
+    // Past the worst level of a full view, a missing price may still rest
+    let worst = match side {
+        Side::Buy => next.keys().next(),
+        Side::Sell => next.keys().next_back(),
+    };
+    let out_of_view = |price: i64| match (worst, side) {
+        (Some(&worst), Side::Buy) => next.len() >= levels && price < worst,
+        (Some(&worst), Side::Sell) => next.len() >= levels && price > worst,
+        (None, _) => false,
+    };
+    let mut changes: Vec<(i64, i64)> = next
+        .iter()
+        .filter(|(price, qty)| prev.get(price) != Some(qty))
+        .map(|(&price, &qty)| (price, qty))
+        .collect();
+    changes.extend(
+        prev.keys()
+            .filter(|price| !next.contains_key(price))
+            .map(|&price| (price, if out_of_view(price) { OUT_OF_VIEW } else { 0 })),
+    );
+    changes.sort_unstable_by_key(|&(price, _)| price);
+    changes
+}
+
+/// Per-connection depth state: the first frame is a full snapshot, later
+/// frames are diffs against whatever was last sent.
+pub struct DepthFeed {
+    /// Levels per side in each view passed to `next_frame`
+    levels: usize,
+    last_sent: Option<DepthBook>,
+}
+
+impl DepthFeed {
+    pub fn new(levels: usize) -> Self {
+        Self {
+            levels,
+            last_sent: None,
+        }
+    }
+
+    /// Frame to send for the current book, or None if nothing changed.
+    pub fn next_frame(&mut self, current: DepthBook) -> Option<DepthFrame> {
+        let frame = match &self.last_sent {
+            None => DepthFrame {
+                snapshot: true,
+                bids: current.bids.iter().map(|(&p, &q)| (p, q)).collect(),
+                asks: current.asks.iter().map(|(&p, &q)| (p, q)).collect(),
+            },
+            Some(prev) => {
+                let frame = DepthFrame {
+                    snapshot: false,
+                    bids: diff_side(&prev.bids, &current.bids, self.levels, Side::Buy),
+                    asks: diff_side(&prev.asks, &current.asks, self.levels, Side::Sell),
+                };
+                if frame.is_empty() {
+                    return None;
+                }
+                frame
+            }
+        };
+        self.last_sent = Some(current);
+        Some(frame)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn book(bids: &[(i64, i64)], asks: &[(i64, i64)]) -> DepthBook {
+        DepthBook {
+            bids: bids.iter().copied().collect(),
+            asks: asks.iter().copied().collect(),
+        }
+    }
+
+    #[test]
+    fn test_first_frame_is_full_snapshot() {
+        let mut feed = DepthFeed::new(20);
+        let frame = feed
+            .next_frame(book(&[(9900, 10), (10000, 5)], &[(10100, 7)]))
+            .unwrap();
+        assert!(frame.snapshot);
+        assert_eq!(frame.bids.len(), 2);
+        assert_eq!(frame.asks, vec![(10100, 7)]);
+    }
+
+    #[test]
+    fn test_unchanged_book_sends_nothing() {
+        let mut feed = DepthFeed::new(20);
+        let b = book(&[(10000, 5)], &[(10100, 7)]);
+        feed.next_frame(b.clone());
+        assert!(feed.next_frame(b).is_none());
+    }
+
+    #[test]
+    fn test_removed_level_sent_as_zero() {
+        let mut feed = DepthFeed::new(20);
+        feed.next_frame(book(&[(9900, 10), (10000, 5)], &[]));
+        let frame = feed.next_frame(book(&[(9900, 10)], &[])).unwrap();
+        assert!(!frame.snapshot);
+        assert_eq!(frame.bids, vec![(10000, 0)]);
+    }
+
+    #[test]
+    fn test_level_pushed_out_of_view_is_not_a_deletion() {
+        let mut feed = DepthFeed::new(2);
+        feed.next_frame(book(&[(9800, 10), (9900, 10)], &[(10100, 5), (10200, 5)]));
+
+        // Better prices push the worst level on each side past the view
+        let frame = feed
+            .next_frame(book(&[(9900, 10), (9950, 3)], &[(10050, 4), (10100, 5)]))
+            .unwrap();
+        assert_eq!(frame.bids, vec![(9800, OUT_OF_VIEW), (9950, 3)]);
+        assert_eq!(frame.asks, vec![(10050, 4), (10200, OUT_OF_VIEW)]);
+
+        // A level cancelled from inside a full view is still a deletion
+        let frame = feed
+            .next_frame(book(&[(9800, 10), (9950, 3)], &[(10050, 4), (10100, 5)]))
+            .unwrap();
+        assert_eq!(frame.bids, vec![(9800, 10), (9900, 0)]);
+    }
+
+    #[test]
+    fn test_frame_round_trips_through_msgpack() {
+        let frame = DepthFrame {
+            snapshot: false,
+            bids: vec![(10000, 25)],
+            asks: vec![(10100, 0)],
+        };
+        let decoded: DepthFrame = rmp_serde::from_slice(&frame.encode()).unwrap();
+        assert_eq!(decoded, frame);
+    }
+}
@@ -0,0 +1,50 @@
+use crate::models::market::MarketSnapshot;
+
+/// A book is crossed when both sides are present and the best bid is at or
+/// above the best ask. Matching should make this impossible, so seeing it means
+/// the engine state is corrupt.
+pub fn is_crossed(snapshot: &MarketSnapshot) -> bool {
+    match (snapshot.best_bid, snapshot.best_ask) {
+        (Some(bid), Some(ask)) => bid >= ask,
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(best_bid: Option<f64>, best_ask: Option<f64>) -> MarketSnapshot {
+        MarketSnapshot {
+            best_bid,
+            best_ask,
+            spread: None,
+            mid_price: None,
+            last_trade_price: None,
+            last_trade_qty: None,
+            seq: 0,
+        }
+    }
+
+    #[test]
+    fn test_normal_book_not_crossed() {
+        assert!(!is_crossed(&snapshot(Some(99.00), Some(101.00))));
+    }
+
+    #[test]
+    fn test_crossed_book_detected() {
+        assert!(is_crossed(&snapshot(Some(101.00), Some(99.00))));
+    }
+
+    #[test]
+    fn test_locked_book_detected() {
+        assert!(is_crossed(&snapshot(Some(100.00), Some(100.00))));
+    }
+
+    #[test]
+    fn test_one_sided_book_not_crossed() {
+        assert!(!is_crossed(&snapshot(Some(100.00), None)));
+        assert!(!is_crossed(&snapshot(None, Some(100.00))));
+        assert!(!is_crossed(&snapshot(None, None)));
+    }
+}
@@ -1 +1,4 @@
+pub mod depth;
+pub mod integrity;
 pub mod orderbook;
+pub mod reject;
@@ -1,30 +1,182 @@
-use std::sync::atomic::{AtomicU64, Ordering};
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use dashmap::DashMap;
 use tokio::sync::RwLock;
 
-use crate::ffi::safe_wrapper::OwnedOrderBook;
+use crate::config::{EngineConfig, OneSidedMid, SweepCapPolicy, TradeTimestampSource};
+use crate::ffi::safe_wrapper::{OrderResult, OwnedOrderBook, PriceData};
 use crate::ffi::types;
+use crate::middleware::metrics as m;
 use crate::models::error::ApiError;
-use crate::models::market::MarketSnapshot;
+use crate::models::market::{
+    BookLevel, BookStats, ExecutionEstimate, IntegrityReport, MarketDepth, MarketSnapshot,
+    SymbolStatus, Ticker,
+};
 use crate::models::order::*;
-use crate::models::trade::TradeResponse;
+use crate::models::trade::{TickDirection, TradeResponse};
+
+use super::depth::DepthBook;
+use super::integrity;
+use super::reject::RejectReason;
+
+/// An order validated and translated to FFI values, ready to match.
+struct PreparedOrder {
+    req: OrderRequest,
+    order_id: u64,
+    price_cents: Option<i64>,
+    side: u32,
+    order_type: u32,
+    tif: u32,
+    stp: u32,
+    /// Set for GTC limit orders when a default horizon is configured
+    expires_at_ns: Option<u64>,
+    /// Quantity cut off by the sweep cap, reported back as unfilled
+    sweep_trimmed: i64,
+}
+
+/// One collected order's part in the opening uncross.
+pub struct UncrossLeg<'a> {
+    pub order_id: u64,
+    pub req: &'a OrderRequest,
+    /// Quantity that trades at the uncross price
+    pub matched: i64,
+}
+
+pub struct UncrossExecution {
+    pub trades: Vec<TradeResponse>,
+    /// (order_id, quantity left on the book) for every leg
+    pub resting: Vec<(u64, i64)>,
+    pub seq: u64,
+}
+
+struct ExecutedOrder {
+    result: OrderResult,
+    /// Book's last trade price before matching, with `trade_tick_direction`
+    last_trade_cents: Option<i64>,
+    first_exec_seq: u64,
+    seq: u64,
+    timestamp_ns: u64,
+    remainder_cancelled: bool,
+}
 
 pub struct Engine {
-    book: RwLock<OwnedOrderBook>,
+    /// Symbol of orders that don't name one
+    symbol: String,
+    /// The default symbol's book, also held in `books`
+    book: Arc<RwLock<OwnedOrderBook>>,
+    /// One book per symbol, created on the symbol's first order
+    books: DashMap<String, Arc<RwLock<OwnedOrderBook>>>,
+    /// Cap on `books`, so order entry can't create books without bound
+    max_symbols: usize,
+    /// Symbol of every order resting on a book
+    order_symbols: DashMap<u64, String>,
+    ffi_call_timing: bool,
     next_order_id: AtomicU64,
     total_orders: AtomicU64,
     total_trades: AtomicU64,
+    /// Next execution sequence number; only advanced under the book write lock
+    next_exec_seq: AtomicU64,
+    /// Sequence of the last change to any book; only advanced under a book
+    /// write lock, so a reader holding that book's read lock sees a
+    /// consistent value
+    book_seq: AtomicU64,
+    /// Wall-clock nanoseconds of each book's last change, by symbol; absent
+    /// before the book's first
+    last_update_ns: DashMap<String, u64>,
+    tick_size_cents: i64,
+    lot_size: i64,
+    market_protection_cents: Option<i64>,
+    halt_on_crossed_book: bool,
+    halted: AtomicBool,
+    /// Only accept orders that rest without crossing the touch; the mode of
+    /// every book without its own in `quote_only_books`
+    quote_only: AtomicBool,
+    /// Quote-only mode switched for one symbol's book
+    quote_only_books: DashMap<String, bool>,
+    max_total_orders: u64,
+    allow_modify_quantity_increase: bool,
+    strict_market_time_in_force: bool,
+    reject_locking_orders: bool,
+    max_sweep_orders: usize,
+    sweep_cap_policy: SweepCapPolicy,
+    trade_timestamp_source: TradeTimestampSource,
+    trade_tick_direction: bool,
+    one_sided_mid: OneSidedMid,
+    /// Horizon given to GTC orders, which then expire like GTD orders
+    gtc_expiry_ns: Option<u64>,
+    /// (expires_at_ns, order_id) for resting orders that carry an expiry
+    expiries: Mutex<BTreeSet<(u64, u64)>>,
 }
 
 impl Engine {
+    #[cfg(test)]
+    #[allow(clippy::new_without_default)]
     pub fn new() -> Self {
+        Self::with_config(EngineConfig::default())
+    }
+
+    pub fn with_config(config: EngineConfig) -> Self {
+        let market_protection_cents = config
+            .market_protection_offset
+            .map(|offset| dollars_to_cents(offset).unwrap_or(0));
+        let book = Arc::new(RwLock::new(
+            OwnedOrderBook::new().with_call_timing(config.ffi_call_timing),
+        ));
+        let books = DashMap::new();
+        books.insert(config.symbol.clone(), book.clone());
         Self {
-            book: RwLock::new(OwnedOrderBook::new()),
+            symbol: config.symbol,
+            book,
+            books,
+            max_symbols: config.max_symbols,
+            order_symbols: DashMap::new(),
+            ffi_call_timing: config.ffi_call_timing,
             next_order_id: AtomicU64::new(1),
             total_orders: AtomicU64::new(0),
             total_trades: AtomicU64::new(0),
+            next_exec_seq: AtomicU64::new(1),
+            book_seq: AtomicU64::new(0),
+            last_update_ns: DashMap::new(),
+            tick_size_cents: 1,
+            lot_size: 1,
+            market_protection_cents,
+            halt_on_crossed_book: config.halt_on_crossed_book,
+            halted: AtomicBool::new(false),
+            quote_only: AtomicBool::new(config.quote_only),
+            quote_only_books: DashMap::new(),
+            max_total_orders: config.max_total_orders,
+            allow_modify_quantity_increase: config.allow_modify_quantity_increase,
+            strict_market_time_in_force: config.strict_market_time_in_force,
+            reject_locking_orders: config.reject_locking_orders,
+            max_sweep_orders: config.max_sweep_orders,
+            sweep_cap_policy: config.sweep_cap_policy,
+            trade_timestamp_source: config.trade_timestamp_source,
+            trade_tick_direction: config.trade_tick_direction,
+            one_sided_mid: config.one_sided_mid,
+            gtc_expiry_ns: (config.gtc_default_expiry_secs > 0)
+                .then(|| config.gtc_default_expiry_secs.saturating_mul(1_000_000_000)),
+            expiries: Mutex::new(BTreeSet::new()),
         }
     }
 
+    /// Only accept prices on a `tick_size` (dollars) grid.
+    /// `Config::validate` rejects ticks that aren't whole cents; direct callers
+    /// passing one anyway fall back to one cent.
+    pub fn with_tick_size(mut self, tick_size: f64) -> Self {
+        self.tick_size_cents = dollars_to_cents(tick_size).unwrap_or(1).max(1);
+        self
+    }
+
+    /// Only accept quantities in whole lots of `lot_size` units. Resting
+    /// orders then always hold whole lots, so every fill is whole lots too.
+    pub fn with_lot_size(mut self, lot_size: i64) -> Self {
+        self.lot_size = lot_size.max(1);
+        self
+    }
+
     pub fn next_order_id(&self) -> u64 {
         self.next_order_id.fetch_add(1, Ordering::Relaxed)
     }
@@ -37,8 +189,414 @@ impl Engine {
         self.total_trades.load(Ordering::Relaxed)
     }
 
+    /// Latest book sequence; see `MarketSnapshot::seq`.
+    pub fn book_seq(&self) -> u64 {
+        self.book_seq.load(Ordering::Relaxed)
+    }
+
+    /// Advance and return the book sequence after a change to `symbol`'s
+    /// book. Call with that book's write lock held.
+    fn next_book_seq(&self, symbol: &str) -> u64 {
+        self.last_update_ns.insert(symbol.to_string(), unix_nanos());
+        self.book_seq.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// `(timestamp_ns, engine_ts, server_ts)` for a trade stamped `engine_ns`
+    /// by the matching engine and `server_ns` by the server.
+    fn trade_timestamps(&self, engine_ns: i64, server_ns: u64) -> (u64, Option<u64>, Option<u64>) {
+        let engine_ns = engine_ns.max(0) as u64;
+        match self.trade_timestamp_source {
+            TradeTimestampSource::Server => (server_ns, None, None),
+            TradeTimestampSource::Engine => (engine_ns, None, Some(server_ns)),
+            TradeTimestampSource::Both => (server_ns, Some(engine_ns), Some(server_ns)),
+        }
+    }
+
+    /// The book's last trade price, if tick directions are reported and it
+    /// has traded. Read before matching, under the write lock.
+    fn last_trade_cents(&self, book: &OwnedOrderBook) -> Option<i64> {
+        if !self.trade_tick_direction {
+            return None;
+        }
+        Some(book.get_last_trade_price()).filter(|&p| p > 0)
+    }
+
+    /// The configured lifetime order cap, if it has been reached.
+    pub fn order_cap_reached(&self) -> Option<u64> {
+        (self.max_total_orders > 0 && self.total_orders() >= self.max_total_orders)
+            .then_some(self.max_total_orders)
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted.load(Ordering::Relaxed)
+    }
+
+    pub fn set_halted(&self, halted: bool) {
+        self.halted.store(halted, Ordering::Relaxed);
+    }
+
+    /// The default symbol, traded by orders that don't name one.
+    pub fn symbol(&self) -> &str {
+        &self.symbol
+    }
+
+    /// The default symbol's book, for tests that need to hold its lock.
+    #[cfg(test)]
+    pub fn default_book(&self) -> Arc<RwLock<OwnedOrderBook>> {
+        Arc::clone(&self.book)
+    }
+
+    pub fn has_symbol(&self, symbol: &str) -> bool {
+        self.books.contains_key(symbol)
+    }
+
+    /// Symbol of a resting order.
+    pub fn order_symbol(&self, order_id: u64) -> Option<String> {
+        self.order_symbols.get(&order_id).map(|s| s.clone())
+    }
+
+    fn symbol_of<'a>(&'a self, req: &'a OrderRequest) -> &'a str {
+        req.symbol.as_deref().unwrap_or(&self.symbol)
+    }
+
+    /// Refuse an order for a symbol without a book once `max_symbols`
+    /// books exist.
+    pub fn check_symbol(&self, symbol: &str) -> Result<(), ApiError> {
+        if self.books.contains_key(symbol) || self.books.len() < self.max_symbols {
+            return Ok(());
+        }
+        Err(ApiError::Validation(format!(
+            "Symbol limit of {} reached; no book for '{}'",
+            self.max_symbols, symbol
+        )))
+    }
+
+    /// Book for `symbol`, created empty on its first order.
+    fn book_or_create(&self, symbol: &str) -> Result<Arc<RwLock<OwnedOrderBook>>, ApiError> {
+        if let Some(book) = self.books.get(symbol) {
+            return Ok(book.clone());
+        }
+        self.check_symbol(symbol)?;
+        let book = self
+            .books
+            .entry(symbol.to_string())
+            .or_insert_with(|| {
+                Arc::new(RwLock::new(
+                    OwnedOrderBook::new().with_call_timing(self.ffi_call_timing),
+                ))
+            })
+            .clone();
+        Ok(book)
+    }
+
+    /// Book `order_id` rests on. The map entry is cloned out so no shard
+    /// lock is held across an await.
+    fn book_of(&self, order_id: u64) -> Result<Arc<RwLock<OwnedOrderBook>>, ApiError> {
+        self.symbol_book_of(order_id).map(|(_, book)| book)
+    }
+
+    /// Symbol and book `order_id` rests on.
+    fn symbol_book_of(
+        &self,
+        order_id: u64,
+    ) -> Result<(String, Arc<RwLock<OwnedOrderBook>>), ApiError> {
+        let symbol = self
+            .order_symbol(order_id)
+            .ok_or(ApiError::NotFound(order_id))?;
+        let book = self
+            .books
+            .get(&symbol)
+            .map(|b| b.clone())
+            .ok_or(ApiError::NotFound(order_id))?;
+        Ok((symbol, book))
+    }
+
+    /// Keep `order_symbols` in step with a match on `symbol`'s book: the
+    /// incoming order is mapped if it rests, and resting orders it filled
+    /// or STP-cancelled are dropped once they have left the book.
+    fn track_symbols(
+        &self,
+        book: &OwnedOrderBook,
+        symbol: &str,
+        order_id: u64,
+        result: &OrderResult,
+    ) {
+        if book.get_order(order_id).is_some() {
+            self.order_symbols.insert(order_id, symbol.to_string());
+        }
+        let touched = result
+            .trades
+            .iter()
+            .flat_map(|t| [t.buy_order_id, t.sell_order_id])
+            .chain(result.stp_result.cancelled_orders.iter().copied());
+        for id in touched.filter(|&id| id != order_id) {
+            if book.get_order(id).is_none() {
+                self.order_symbols.remove(&id);
+            }
+        }
+    }
+
+    pub fn is_quote_only(&self, symbol: &str) -> bool {
+        self.quote_only_books
+            .get(symbol)
+            .map_or_else(|| self.quote_only.load(Ordering::Relaxed), |q| *q)
+    }
+
+    /// Switch quote-only mode for `symbol`'s book, or for every book,
+    /// including ones not yet created, if `None`.
+    pub fn set_quote_only(&self, symbol: Option<&str>, quote_only: bool) {
+        match symbol {
+            Some(symbol) => {
+                self.quote_only_books.insert(symbol.to_string(), quote_only);
+            }
+            None => {
+                self.quote_only.store(quote_only, Ordering::Relaxed);
+                self.quote_only_books.clear();
+            }
+        }
+    }
+
+    /// Verify the book is not crossed. A crossed book means the FFI left the
+    /// book in a state matching should have prevented, so it is logged as a
+    /// critical alert and, if configured, order entry is halted.
+    pub async fn check_integrity(&self) -> IntegrityReport {
+        let snapshot = self.get_snapshot().await;
+        self.integrity_report(&snapshot)
+    }
+
+    fn integrity_report(&self, snapshot: &MarketSnapshot) -> IntegrityReport {
+        let crossed = integrity::is_crossed(snapshot);
+
+        if crossed {
+            tracing::error!(
+                event = "CrossedBook",
+                best_bid = ?snapshot.best_bid,
+                best_ask = ?snapshot.best_ask,
+                halting = self.halt_on_crossed_book,
+            );
+            m::record_crossed_book();
+            if self.halt_on_crossed_book {
+                self.set_halted(true);
+            }
+        }
+
+        IntegrityReport {
+            crossed,
+            best_bid: snapshot.best_bid,
+            best_ask: snapshot.best_ask,
+            halted: self.is_halted(),
+        }
+    }
+
     pub async fn add_order(&self, req: OrderRequest) -> Result<OrderResponse, ApiError> {
-        validate_order_request(&req)?;
+        let mut order = self.prepare_order(req)?;
+        let book = self.book_or_create(self.symbol_of(&order.req))?;
+        let executed = {
+            let mut book = book.write().await;
+            self.check_quote_only(&book, &order)?;
+            self.check_locking(&book, &order)?;
+            self.apply_sweep_cap(&book, &mut order)?;
+            self.execute_order(&mut book, &order)
+        };
+        self.order_response(&order, executed)
+    }
+
+    /// Cancel `order_id` and submit `req` under one write lock, so no reader
+    /// sees both orders or neither. Fails without touching the book if the
+    /// replacement is invalid or the original isn't resting. If the engine
+    /// rejects the replacement, the original stays cancelled and the inner
+    /// result carries the rejection. The replacement must be for the
+    /// original's symbol.
+    pub async fn cancel_replace(
+        &self,
+        order_id: u64,
+        req: OrderRequest,
+    ) -> Result<(CancelResponse, Result<OrderResponse, ApiError>), ApiError> {
+        let mut order = self.prepare_order(req)?;
+        let book = self.book_of(order_id)?;
+        let symbol = self.symbol_of(&order.req);
+        if !Arc::ptr_eq(&book, &self.book_or_create(symbol)?) {
+            return Err(ApiError::Validation(format!(
+                "Replacement for order {} must be for the same symbol, not {}",
+                order_id, symbol
+            )));
+        }
+        let executed = {
+            let mut book = book.write().await;
+            self.check_quote_only(&book, &order)?;
+            self.check_locking(&book, &order)?;
+            self.apply_sweep_cap(&book, &mut order)?;
+            if !book.cancel_order(order_id) {
+                return Err(ApiError::NotFound(order_id));
+            }
+            self.order_symbols.remove(&order_id);
+            self.execute_order(&mut book, &order)
+        };
+
+        let cancel = CancelResponse {
+            order_id,
+            cancelled: true,
+            reason: CancelReason::User,
+            seq: executed.seq,
+        };
+        Ok((cancel, self.order_response(&order, executed)))
+    }
+
+    /// Validate an order collected for the opening auction and assign its id.
+    /// It stays off the book until the uncross. The auction only runs for
+    /// the default symbol.
+    pub fn accept_for_auction(&self, req: &OrderRequest) -> Result<u64, ApiError> {
+        validate_order_request(req, self.strict_market_time_in_force)?;
+        if self.symbol_of(req) != self.symbol {
+            return Err(ApiError::Validation(format!(
+                "The opening auction only collects orders for {}",
+                self.symbol
+            )));
+        }
+        self.check_lot_size(req.quantity, req.time_in_force)?;
+        if let (OrderType::Limit, Some(price)) = (req.order_type, req.price) {
+            self.check_tick_size(dollars_to_cents(price)?)?;
+        }
+        self.total_orders.fetch_add(1, Ordering::Relaxed);
+        Ok(self.next_order_id())
+    }
+
+    /// Run the opening uncross under one write lock. Matched quantities all
+    /// trade at `price`: the sell legs rest at that price and the buy legs
+    /// take them out. Remainders of GTC limit orders then join the book at
+    /// their own limit; any other remainder is dropped.
+    pub async fn uncross(
+        &self,
+        price: f64,
+        legs: &[UncrossLeg<'_>],
+    ) -> Result<UncrossExecution, ApiError> {
+        let price_cents = dollars_to_cents(price)?;
+        let timestamp_ns = unix_nanos();
+        let mut book = self.book.write().await;
+        let mut last_trade_cents = self.last_trade_cents(&book);
+
+        let mut results = Vec::new();
+        for (side, tif) in [
+            (Side::Sell, types::OB_TIF_GTC),
+            (Side::Buy, types::OB_TIF_IOC),
+        ] {
+            for leg in legs.iter().filter(|l| l.req.side == side && l.matched > 0) {
+                let result = book.add_order(
+                    &leg.req.trader_id,
+                    leg.order_id,
+                    Some(price_cents),
+                    leg.matched,
+                    ffi_side(side),
+                    types::OB_ORDER_TYPE_LIMIT,
+                    tif,
+                    types::OB_STP_ALLOW,
+                );
+                if !result.accepted {
+                    tracing::error!(
+                        event = "UncrossLegRejected",
+                        order_id = leg.order_id,
+                        reject_reason = ?result.reject_reason,
+                    );
+                }
+                results.push(result);
+            }
+        }
+
+        for leg in legs {
+            let remainder = leg.req.quantity - leg.matched;
+            let rests = leg.req.order_type == OrderType::Limit
+                && leg.req.time_in_force.rests()
+                && remainder > 0;
+            let Some(limit) = leg.req.price.filter(|_| rests) else {
+                continue;
+            };
+            results.push(book.add_order(
+                &leg.req.trader_id,
+                leg.order_id,
+                Some(dollars_to_cents(limit)?),
+                remainder,
+                ffi_side(leg.req.side),
+                types::OB_ORDER_TYPE_LIMIT,
+                ffi_tif(leg.req.time_in_force),
+                ffi_stp(leg.req.stp_mode.unwrap_or_default()),
+            ));
+            // The remainder keeps what the order filled in the uncross
+            if leg.matched > 0 {
+                book.credit_fill(leg.order_id, leg.matched);
+            }
+            if let Some(at) = leg.req.expires_at_ns {
+                self.expiries.lock().unwrap().insert((at, leg.order_id));
+            }
+        }
+
+        let mut trades = Vec::new();
+        for result in &results {
+            let first_exec_seq = self
+                .next_exec_seq
+                .fetch_add(result.trades.len() as u64, Ordering::Relaxed);
+            trades.extend(
+                result
+                    .trades
+                    .iter()
+                    .zip(first_exec_seq..)
+                    .map(|(t, exec_seq)| {
+                        let (timestamp_ns, engine_ts, server_ts) =
+                            self.trade_timestamps(t.timestamp_ns, timestamp_ns);
+                        let tick_direction = self.trade_tick_direction.then(|| {
+                            let direction = TickDirection::between(last_trade_cents, t.price);
+                            last_trade_cents = Some(t.price);
+                            direction
+                        });
+                        TradeResponse {
+                            trade_id: exec_seq,
+                            exec_seq,
+                            symbol: self.symbol.clone(),
+                            buy_order_id: t.buy_order_id,
+                            sell_order_id: t.sell_order_id,
+                            price: cents_to_dollars(t.price),
+                            quantity: t.quantity,
+                            timestamp_ns,
+                            engine_ts,
+                            server_ts,
+                            fee: None,
+                            tick_direction,
+                        }
+                    }),
+            );
+        }
+        self.total_trades
+            .fetch_add(trades.len() as u64, Ordering::Relaxed);
+
+        // Read back what rests: a remainder may have traded against another
+        let resting: Vec<(u64, i64)> = legs
+            .iter()
+            .map(|leg| {
+                let quantity = book.get_order(leg.order_id).map_or(0, |o| o.quantity);
+                (leg.order_id, quantity)
+            })
+            .collect();
+        for &(order_id, _) in resting.iter().filter(|(_, q)| *q > 0) {
+            self.order_symbols.insert(order_id, self.symbol.clone());
+        }
+        if let Some(horizon) = self.gtc_expiry_ns {
+            let at = timestamp_ns.saturating_add(horizon);
+            let mut expiries = self.expiries.lock().unwrap();
+            for &(order_id, _) in resting.iter().filter(|(_, q)| *q > 0) {
+                expiries.insert((at, order_id));
+            }
+        }
+
+        Ok(UncrossExecution {
+            trades,
+            resting,
+            seq: self.next_book_seq(&self.symbol),
+        })
+    }
+
+    /// Validate a request and translate it to FFI values, assigning its id.
+    fn prepare_order(&self, req: OrderRequest) -> Result<PreparedOrder, ApiError> {
+        validate_order_request(&req, self.strict_market_time_in_force)?;
+        self.check_lot_size(req.quantity, req.time_in_force)?;
 
         let order_id = self.next_order_id();
 
@@ -48,90 +606,442 @@ impl Engine {
                 let p = req.price.ok_or_else(|| {
                     ApiError::Validation("Limit orders require a price".into())
                 })?;
-                Some(dollars_to_cents(p)?)
+                let cents = dollars_to_cents(p)?;
+                self.check_tick_size(cents)?;
+                Some(cents)
             }
         };
 
-        let side = match req.side {
-            Side::Buy => types::OB_SIDE_BUY,
-            Side::Sell => types::OB_SIDE_SELL,
-        };
+        let side = ffi_side(req.side);
         let order_type = match req.order_type {
             OrderType::Limit => types::OB_ORDER_TYPE_LIMIT,
             OrderType::Market => types::OB_ORDER_TYPE_MARKET,
         };
-        let tif = match req.time_in_force {
-            TimeInForce::Gtc => types::OB_TIF_GTC,
-            TimeInForce::Ioc => types::OB_TIF_IOC,
-            TimeInForce::Fok => types::OB_TIF_FOK,
+        let tif = ffi_tif(req.time_in_force);
+        let stp = ffi_stp(req.stp_mode.unwrap_or_default());
+
+        // "GTC" on a venue without perpetual orders means GTD at the horizon
+        let expires_at_ns = match (req.order_type, req.time_in_force, self.gtc_expiry_ns) {
+            (OrderType::Limit, TimeInForce::Gtc, Some(horizon)) => {
+                Some(unix_nanos().saturating_add(horizon))
+            }
+            (_, TimeInForce::Gtd, _) => req.expires_at_ns,
+            _ => None,
+        };
+
+        Ok(PreparedOrder {
+            req,
+            order_id,
+            price_cents,
+            side,
+            order_type,
+            tif,
+            stp,
+            expires_at_ns,
+            sweep_trimmed: 0,
+        })
+    }
+
+    /// With `reject_locking_orders`, refuse a GTC or GTD limit order priced
+    /// exactly at the best opposite price. IOC and FOK orders never rest, so
+    /// can't lock the market. Call with the book lock held so the touch can't
+    /// move.
+    fn check_locking(&self, book: &OwnedOrderBook, order: &PreparedOrder) -> Result<(), ApiError> {
+        if !self.reject_locking_orders
+            || order.order_type != types::OB_ORDER_TYPE_LIMIT
+            || !rests_on_book(order.tif)
+        {
+            return Ok(());
+        }
+        let Some(price_cents) = order.price_cents else {
+            return Ok(());
+        };
+        let (opposite, side_name) = match order.req.side {
+            Side::Buy => (book.get_best_ask(), "ask"),
+            Side::Sell => (book.get_best_bid(), "bid"),
+        };
+        if opposite > 0 && price_cents == opposite {
+            return Err(ApiError::engine(
+                RejectReason::LocksMarket,
+                format!(
+                    "Order at {:.2} would lock the market against the best {}",
+                    cents_to_dollars(price_cents),
+                    side_name
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// In quote-only mode, refuse any order that would trade on arrival:
+    /// market orders always, limit orders priced at or through the best
+    /// opposite price. Call with the book lock held so the touch can't move.
+    fn check_quote_only(
+        &self,
+        book: &OwnedOrderBook,
+        order: &PreparedOrder,
+    ) -> Result<(), ApiError> {
+        if !self.is_quote_only(self.symbol_of(&order.req)) {
+            return Ok(());
+        }
+        let Some(price_cents) = order.price_cents else {
+            return Err(ApiError::engine(
+                RejectReason::QuoteOnly,
+                "Quote-only mode: market orders are not accepted",
+            ));
+        };
+        let (opposite, crosses, side_name) = match order.req.side {
+            Side::Buy => {
+                let ask = book.get_best_ask();
+                (ask, ask > 0 && price_cents >= ask, "ask")
+            }
+            Side::Sell => {
+                let bid = book.get_best_bid();
+                (bid, bid > 0 && price_cents <= bid, "bid")
+            }
         };
-        let stp = match req.stp_mode {
-            StpMode::Allow => types::OB_STP_ALLOW,
-            StpMode::CancelNewest => types::OB_STP_CANCEL_NEWEST,
-            StpMode::CancelOldest => types::OB_STP_CANCEL_OLDEST,
-            StpMode::CancelBoth => types::OB_STP_CANCEL_BOTH,
-            StpMode::DecrementAndCancel => types::OB_STP_DECREMENT_AND_CANCEL,
+        if crosses {
+            return Err(ApiError::engine(
+                RejectReason::QuoteOnly,
+                format!(
+                    "Quote-only mode: order at {:.2} would cross the best {} at {:.2}",
+                    cents_to_dollars(price_cents),
+                    side_name,
+                    cents_to_dollars(opposite)
+                ),
+            ));
+        }
+        Ok(())
+    }
+
+    /// With `max_sweep_orders`, keep a market, IOC or FOK order from trading
+    /// against more resting orders than that. The allowed quantity is read
+    /// from the book before matching, since fills can't be undone after.
+    /// Depending on policy the order is refused or cut down to what the
+    /// allowed orders hold; a FOK can't be cut down, so it is refused.
+    fn apply_sweep_cap(
+        &self,
+        book: &OwnedOrderBook,
+        order: &mut PreparedOrder,
+    ) -> Result<(), ApiError> {
+        if self.max_sweep_orders == 0 || rests_on_book(order.tif) {
+            return Ok(());
+        }
+        let resting_side = match order.req.side {
+            Side::Buy => types::OB_SIDE_SELL,
+            Side::Sell => types::OB_SIDE_BUY,
         };
+        let sweepable = book.get_sweepable_quantity(
+            resting_side,
+            self.max_sweep_orders,
+            order.price_cents,
+            order.req.quantity,
+        );
+        // Nothing to trade against: the engine's usual handling applies
+        if sweepable == 0 || order.req.quantity <= sweepable {
+            return Ok(());
+        }
 
-        let result = {
-            let mut book = self.book.write().await;
-            book.add_order(
+        if self.sweep_cap_policy == SweepCapPolicy::Reject || order.tif == types::OB_TIF_FOK {
+            return Err(ApiError::engine(
+                RejectReason::SweepCapExceeded,
+                format!(
+                    "Order would trade against more than {} resting orders",
+                    self.max_sweep_orders
+                ),
+            ));
+        }
+        order.sweep_trimmed = order.req.quantity - sweepable;
+        order.req.quantity = sweepable;
+        Ok(())
+    }
+
+    /// Match a prepared order. Must be called with the book write lock held.
+    fn execute_order(&self, book: &mut OwnedOrderBook, order: &PreparedOrder) -> ExecutedOrder {
+        let req = &order.req;
+        let (price_cents, order_type, tif) = match self.market_protection_cents {
+            Some(offset) if req.order_type == OrderType::Market => {
+                protect_market_order(book, req.side, offset, order.tif)
+                    .unwrap_or((order.price_cents, order.order_type, order.tif))
+            }
+            _ => (order.price_cents, order.order_type, order.tif),
+        };
+        let last_trade_cents = self.last_trade_cents(book);
+        let result = match (req.all_or_none, req.post_only, price_cents) {
+            (true, _, Some(price)) => book.add_all_or_none_order(
                 &req.trader_id,
-                order_id,
+                order.order_id,
+                price,
+                req.quantity,
+                order.side,
+                order.stp,
+            ),
+            (_, true, Some(price)) => book.add_post_only_order(
+                &req.trader_id,
+                order.order_id,
+                price,
+                req.quantity,
+                order.side,
+                order.stp,
+            ),
+            _ => book.add_order(
+                &req.trader_id,
+                order.order_id,
                 price_cents,
                 req.quantity,
-                side,
+                order.side,
                 order_type,
                 tif,
-                stp,
-            )
+                order.stp,
+            ),
         };
+        // A GTC remainder that would cross the trader's own orders, passed
+        // over by DECREMENT_AND_CANCEL, is cancelled by the book rather than
+        // left resting
+        let remainder_cancelled = result.accepted
+            && result.remaining_quantity > 0
+            && order_type == types::OB_ORDER_TYPE_LIMIT
+            && rests_on_book(tif)
+            && book.get_order(order.order_id).is_none();
+        // Sequence the trades while still holding the lock so exec_seq
+        // follows matching order
+        let first_exec_seq = self
+            .next_exec_seq
+            .fetch_add(result.trades.len() as u64, Ordering::Relaxed);
+        self.track_symbols(book, self.symbol_of(req), order.order_id, &result);
+        ExecutedOrder {
+            result,
+            last_trade_cents,
+            first_exec_seq,
+            seq: self.next_book_seq(self.symbol_of(req)),
+            timestamp_ns: unix_nanos(),
+            remainder_cancelled,
+        }
+    }
+
+    fn order_response(
+        &self,
+        order: &PreparedOrder,
+        executed: ExecutedOrder,
+    ) -> Result<OrderResponse, ApiError> {
+        let ExecutedOrder {
+            result,
+            mut last_trade_cents,
+            first_exec_seq,
+            seq,
+            timestamp_ns,
+            remainder_cancelled,
+        } = executed;
+        let order_id = order.order_id;
+        let symbol = self.symbol_of(&order.req);
 
         self.total_orders.fetch_add(1, Ordering::Relaxed);
         self.total_trades
             .fetch_add(result.trades.len() as u64, Ordering::Relaxed);
 
+        if !result.accepted && !result.trades.is_empty() {
+            // A rejected order should never have matched. The fills may have
+            // happened anyway, so this is not an ordinary rejection, and
+            // nothing downstream has booked them: halt until an operator
+            // has reviewed the book.
+            self.set_halted(true);
+            tracing::error!(
+                event = "InconsistentEngineResult",
+                order_id,
+                reject_reason = ?result.reject_reason,
+                trades = ?result.trades,
+            );
+            m::record_inconsistent_engine_result();
+            return Err(ApiError::Internal(format!(
+                "Order {} was rejected by the engine after {} trade(s); trading halted until the book is reviewed",
+                order_id,
+                result.trades.len()
+            )));
+        }
+
         if !result.accepted {
-            let reason = result
-                .reject_reason
-                .unwrap_or_else(|| "Unknown rejection".into());
-            return Err(ApiError::EngineRejection(reason));
+            return Err(engine_rejection(result.reject_code, result.reject_reason));
         }
 
+        // STP reports the incoming order too when it cancels it; only resting
+        // orders are of interest here
+        let stp_cancelled = result
+            .stp_result
+            .cancelled_orders
+            .iter()
+            .copied()
+            .filter(|&id| id != order_id)
+            .collect();
+        let stp_outcome = result.stp_result.self_trade.then(|| StpOutcome {
+            action: result.stp_result.action.clone().unwrap_or_default(),
+            cancelled_orders: result.stp_result.cancelled_orders.clone(),
+        });
+
         let trades: Vec<TradeResponse> = result
             .trades
             .iter()
-            .map(|t| TradeResponse {
-                trade_id: t.trade_id,
-                buy_order_id: t.buy_order_id,
-                sell_order_id: t.sell_order_id,
-                price: cents_to_dollars(t.price),
-                quantity: t.quantity,
+            .zip(first_exec_seq..)
+            .map(|(t, exec_seq)| {
+                let (timestamp_ns, engine_ts, server_ts) =
+                    self.trade_timestamps(t.timestamp_ns, timestamp_ns);
+                let tick_direction = self.trade_tick_direction.then(|| {
+                    let direction = TickDirection::between(last_trade_cents, t.price);
+                    last_trade_cents = Some(t.price);
+                    direction
+                });
+                TradeResponse {
+                    // Each symbol's book numbers its trades from 1, so
+                    // the venue-wide execution sequence stands in
+                    trade_id: exec_seq,
+                    exec_seq,
+                    symbol: symbol.to_string(),
+                    buy_order_id: t.buy_order_id,
+                    sell_order_id: t.sell_order_id,
+                    price: cents_to_dollars(t.price),
+                    quantity: t.quantity,
+                    timestamp_ns,
+                    engine_ts,
+                    server_ts,
+                    fee: None,
+                    tick_direction,
+                }
             })
             .collect();
 
+        // Only a remainder left on the book needs sweeping
+        let expires_at_ns = order
+            .expires_at_ns
+            .filter(|_| result.remaining_quantity > 0 && !remainder_cancelled);
+        if let Some(at) = expires_at_ns {
+            self.expiries.lock().unwrap().insert((at, order_id));
+        }
+
         Ok(OrderResponse {
             order_id,
             accepted: true,
             reject_reason: None,
             trades,
-            remaining_quantity: result.remaining_quantity,
+            remaining_quantity: result.remaining_quantity + order.sweep_trimmed,
+            remainder_cancelled,
+            expires_at_ns,
+            trades_truncated: false,
+            trade_summary: None,
+            stp_cancelled,
+            stp_outcome,
+            seq,
         })
     }
 
-    pub async fn cancel_order(&self, order_id: u64) -> Result<CancelResponse, ApiError> {
-        let cancelled = {
-            let mut book = self.book.write().await;
+    pub async fn cancel_order(
+        &self,
+        order_id: u64,
+        reason: CancelReason,
+    ) -> Result<CancelResponse, ApiError> {
+        let (symbol, book) = self.symbol_book_of(order_id)?;
+        let seq = {
+            let mut book = book.write().await;
             book.cancel_order(order_id)
+                .then(|| self.next_book_seq(&symbol))
         };
 
-        if !cancelled {
+        let Some(seq) = seq else {
             return Err(ApiError::NotFound(order_id));
-        }
+        };
+        self.order_symbols.remove(&order_id);
 
         Ok(CancelResponse {
             order_id,
             cancelled: true,
+            reason,
+            seq,
+        })
+    }
+
+    /// Cancel every order whose expiry is at or before `now_ns`. Orders that
+    /// already left the book are skipped.
+    pub async fn expire_due(&self, now_ns: u64) -> Vec<CancelResponse> {
+        let due: Vec<u64> = {
+            let mut expiries = self.expiries.lock().unwrap();
+            let rest = expiries.split_off(&(now_ns.saturating_add(1), 0));
+            let due = std::mem::replace(&mut *expiries, rest);
+            due.into_iter().map(|(_, order_id)| order_id).collect()
+        };
+        if due.is_empty() {
+            return Vec::new();
+        }
+
+        let mut expired = Vec::new();
+        for order_id in due {
+            let Ok((symbol, book)) = self.symbol_book_of(order_id) else {
+                continue;
+            };
+            let mut book = book.write().await;
+            if book.cancel_order(order_id) {
+                self.order_symbols.remove(&order_id);
+                expired.push(CancelResponse {
+                    order_id,
+                    cancelled: true,
+                    reason: CancelReason::Expired,
+                    seq: self.next_book_seq(&symbol),
+                });
+            }
+        }
+        expired
+    }
+
+    /// Empty every book and zero the order and trade counters, as at startup.
+    /// Order ids and sequence numbers keep counting, so nothing issued before
+    /// the reset is reused. Returns the resting orders removed and the new
+    /// book sequence.
+    pub async fn reset(&self) -> (usize, u64) {
+        let mut books: Vec<_> = self
+            .books
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+        books.sort_by(|a, b| a.0.cmp(&b.0));
+        // Hold every book at once so no order lands mid-reset
+        let mut guards = Vec::with_capacity(books.len());
+        for (_, book) in &books {
+            guards.push(book.write().await);
+        }
+        let removed = guards.iter_mut().map(|book| book.clear()).sum();
+        self.order_symbols.clear();
+        self.expiries.lock().unwrap().clear();
+        self.total_orders.store(0, Ordering::Relaxed);
+        self.total_trades.store(0, Ordering::Relaxed);
+        let now = unix_nanos();
+        for (symbol, _) in &books {
+            self.last_update_ns.insert(symbol.clone(), now);
+        }
+        (removed, self.book_seq.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    /// Current state of a resting order. Orders that were never accepted,
+    /// or have since been fully filled or cancelled, are `NotFound`.
+    pub async fn get_order(&self, order_id: u64) -> Result<OrderStatusResponse, ApiError> {
+        let view = self
+            .book_of(order_id)?
+            .read()
+            .await
+            .get_order(order_id)
+            .ok_or(ApiError::NotFound(order_id))?;
+        Ok(OrderStatusResponse {
+            order_id,
+            side: if view.side == types::OB_SIDE_BUY {
+                Side::Buy
+            } else {
+                Side::Sell
+            },
+            price: cents_to_dollars(view.price),
+            original_quantity: view.original_quantity,
+            remaining_quantity: view.quantity,
+            filled_quantity: view.filled_quantity,
+            status: if view.filled_quantity > 0 {
+                OrderStatus::PartiallyFilled
+            } else {
+                OrderStatus::Resting
+            },
         })
     }
 
@@ -143,26 +1053,49 @@ impl Engine {
         if req.new_quantity <= 0 {
             return Err(ApiError::Validation("Quantity must be positive".into()));
         }
-        if req.new_price < 0.0 {
-            return Err(ApiError::Validation("Price cannot be negative".into()));
+        // Only limit orders rest, so a modify always needs a real price
+        if req.new_price <= 0.0 {
+            return Err(ApiError::Validation(
+                "Price must be positive for limit orders".into(),
+            ));
         }
 
         let new_price_cents = dollars_to_cents(req.new_price)?;
+        self.check_tick_size(new_price_cents)?;
+        self.check_lot_size(req.new_quantity, TimeInForce::Gtc)?;
 
-        let result = {
-            let mut book = self.book.write().await;
-            book.modify_order(order_id, new_price_cents, req.new_quantity)
+        let (symbol, book) = self.symbol_book_of(order_id)?;
+        let (result, seq) = {
+            let mut book = book.write().await;
+            if !self.allow_modify_quantity_increase {
+                let resting = book
+                    .get_order(order_id)
+                    .ok_or(ApiError::NotFound(order_id))?
+                    .quantity;
+                if req.new_quantity > resting {
+                    return Err(ApiError::engine(
+                        RejectReason::QuantityIncrease,
+                        format!(
+                            "Modify cannot increase quantity ({} resting, {} requested); submit a new order",
+                            resting, req.new_quantity
+                        ),
+                    ));
+                }
+            }
+            let result = book.modify_order(order_id, new_price_cents, req.new_quantity);
+            let seq = if result.accepted {
+                self.next_book_seq(&symbol)
+            } else {
+                0
+            };
+            (result, seq)
         };
 
         if !result.accepted {
-            let reason = result
-                .reject_reason
-                .unwrap_or_else(|| "Unknown rejection".into());
-            // Distinguish "not found" from other rejections
-            if reason.contains("not found") {
+            if result.reject_code == Some(RejectReason::NotFound) {
                 return Err(ApiError::NotFound(order_id));
             }
-            return Err(ApiError::EngineRejection(reason));
+            return Err(engine_rejection(result.reject_code, result.reject_reason));
         }
 
         Ok(ModifyResponse {
@@ -173,66 +1106,411 @@ impl Engine {
             new_price: cents_to_dollars(result.new_price),
             old_quantity: result.old_quantity,
             new_quantity: result.new_quantity,
+            seq,
         })
     }
 
-    pub async fn get_snapshot(&self) -> MarketSnapshot {
-        let snap = {
-            let book = self.book.read().await;
-            book.get_snapshot()
-        };
-
-        let has_bid = snap.bid_price > 0;
-        let has_ask = snap.ask_price > 0;
-        let has_both = has_bid && has_ask;
+    /// Decrement a resting order's quantity in place. Unlike a modify, a reduce
+    /// never changes price and always keeps the order's queue position.
+    pub async fn reduce_order(
+        &self,
+        order_id: u64,
+        req: ReduceRequest,
+    ) -> Result<ReduceResponse, ApiError> {
+        if req.reduce_by <= 0 {
+            return Err(ApiError::Validation("reduceBy must be positive".into()));
+        }
+        self.check_lot_size(req.reduce_by, TimeInForce::Gtc)?;
 
-        MarketSnapshot {
-            best_bid: if has_bid { Some(cents_to_dollars(snap.bid_price)) } else { None },
-            best_ask: if has_ask { Some(cents_to_dollars(snap.ask_price)) } else { None },
-            spread: if has_both { Some(cents_to_dollars(snap.spread)) } else { None },
-            mid_price: if has_both { Some(cents_to_dollars(snap.mid_price)) } else { None },
-            last_trade_price: cents_to_optional_dollars(snap.last_trade_price),
-            last_trade_qty: if snap.last_trade_qty == 0 {
-                None
+        let (symbol, book) = self.symbol_book_of(order_id)?;
+        let (result, seq) = {
+            let mut book = book.write().await;
+            let result = book.reduce_order(order_id, req.reduce_by);
+            let seq = if result.accepted {
+                self.next_book_seq(&symbol)
             } else {
-                Some(snap.last_trade_qty)
-            },
+                0
+            };
+            if book.get_order(order_id).is_none() {
+                self.order_symbols.remove(&order_id);
+            }
+            (result, seq)
+        };
+
+        if !result.accepted {
+            if result.reject_code == Some(RejectReason::NotFound) {
+                return Err(ApiError::NotFound(order_id));
+            }
+            return Err(engine_rejection(result.reject_code, result.reject_reason));
         }
+
+        Ok(ReduceResponse {
+            order_id,
+            price: cents_to_dollars(result.old_price),
+            old_quantity: result.old_quantity,
+            remaining_quantity: result.new_quantity,
+            seq,
+        })
     }
-}
 
-// ======================================================================
-// Price conversion helpers
-// ======================================================================
+    pub async fn get_queue_position(
+        &self,
+        order_id: u64,
+    ) -> Result<QueuePositionResponse, ApiError> {
+        let position = {
+            let book = self.book_of(order_id)?;
+            let book = book.read().await;
+            book.get_queue_position(order_id)
+        };
 
-fn dollars_to_cents(dollars: f64) -> Result<i64, ApiError> {
-    if dollars < 0.0 {
-        return Err(ApiError::Validation("Price cannot be negative".into()));
+        let position = position.ok_or(ApiError::NotFound(order_id))?;
+        Ok(QueuePositionResponse {
+            order_id,
+            orders_ahead: position.orders_ahead,
+            quantity_ahead: position.quantity_ahead,
+        })
     }
-    let cents = (dollars * 100.0).round() as i64;
-    // Verify no sub-cent precision was lost
-    let roundtrip = cents as f64 / 100.0;
-    if (roundtrip - dollars).abs() > 0.001 {
-        return Err(ApiError::Validation(
-            "Price precision limited to 2 decimal places (cents)".into(),
-        ));
+
+    /// Up to `max_levels` aggregated levels per side, prices in cents.
+    pub async fn get_depth(&self, max_levels: usize) -> DepthBook {
+        let book = self.book.read().await;
+        let side = |s| {
+            book.get_depth(s, max_levels)
+                .into_iter()
+                .map(|l| (l.price, l.quantity))
+                .collect()
+        };
+        DepthBook {
+            bids: side(types::OB_SIDE_BUY),
+            asks: side(types::OB_SIDE_SELL),
+        }
     }
-    Ok(cents)
-}
 
-fn cents_to_dollars(cents: i64) -> f64 {
-    cents as f64 / 100.0
-}
+    /// Up to `levels` aggregated levels per side with their order counts,
+    /// prices in dollars, and the book sequence they were read at.
+    pub async fn get_market_depth(&self, levels: usize) -> MarketDepth {
+        let book = self.book.read().await;
+        let side = |s| {
+            book.get_depth(s, levels)
+                .into_iter()
+                .map(|l| BookLevel {
+                    price: cents_to_dollars(l.price),
+                    quantity: l.quantity,
+                    order_count: l.order_count,
+                })
+                .collect()
+        };
+        MarketDepth {
+            bids: side(types::OB_SIDE_BUY),
+            asks: side(types::OB_SIDE_SELL),
+            seq: self.book_seq.load(Ordering::Relaxed),
+        }
+    }
 
-fn cents_to_optional_dollars(cents: i64) -> Option<f64> {
-    if cents == 0 {
+    /// Cost of taking `quantity` from the book on `side`, passing over
+    /// all-or-none orders it couldn't take.
+    pub async fn estimate_execution(
+        &self,
+        side: Side,
+        quantity: i64,
+    ) -> Result<ExecutionEstimate, ApiError> {
+        if quantity <= 0 {
+            return Err(ApiError::Validation(format!(
+                "Quantity must be positive, got {}",
+                quantity
+            )));
+        }
+        let fill = self
+            .book
+            .read()
+            .await
+            .estimate_fill(ffi_side(side), quantity);
+        Ok(ExecutionEstimate {
+            side,
+            quantity,
+            fillable_quantity: fill.filled_quantity,
+            fully_fillable: fill.filled_quantity == quantity,
+            average_price: (fill.filled_quantity > 0)
+                .then(|| fill.cost_cents as f64 / fill.filled_quantity as f64 / 100.0),
+            worst_price: fill.worst_price.map(cents_to_dollars),
+            total_cost: cents_to_dollars(fill.cost_cents),
+            levels: fill.levels,
+        })
+    }
+
+    fn check_tick_size(&self, price_cents: i64) -> Result<(), ApiError> {
+        if price_cents % self.tick_size_cents != 0 {
+            return Err(ApiError::Validation(format!(
+                "Price {:.2} is not a multiple of tick size {:.2}",
+                cents_to_dollars(price_cents),
+                cents_to_dollars(self.tick_size_cents)
+            )));
+        }
+        Ok(())
+    }
+
+    /// FOK semantics under a lot size: the order must fill its entire
+    /// quantity in whole lots or not at all. A FOK for a part-lot quantity
+    /// could never do so, whatever the book holds, so it is refused up front
+    /// rather than reaching matching.
+    fn check_lot_size(&self, quantity: i64, tif: TimeInForce) -> Result<(), ApiError> {
+        if quantity % self.lot_size == 0 {
+            return Ok(());
+        }
+        let message = if tif == TimeInForce::Fok {
+            format!(
+                "FOK quantity {} cannot fill in whole lots of {}; a FOK must fill entirely in whole lots",
+                quantity, self.lot_size
+            )
+        } else {
+            format!(
+                "Quantity {} is not a multiple of lot size {}",
+                quantity, self.lot_size
+            )
+        };
+        Err(ApiError::Validation(message))
+    }
+
+    pub async fn get_snapshot(&self) -> MarketSnapshot {
+        let (snap, seq) = {
+            let book = self.book.read().await;
+            (book.get_snapshot(), self.book_seq.load(Ordering::Relaxed))
+        };
+        market_snapshot(snap, seq, self.one_sided_mid)
+    }
+
+    /// Snapshot of `symbol`'s book; `UnknownSymbol` if it has never traded.
+    pub async fn get_snapshot_for(&self, symbol: &str) -> Result<MarketSnapshot, ApiError> {
+        let book = self
+            .books
+            .get(symbol)
+            .map(|b| b.clone())
+            .ok_or_else(|| ApiError::UnknownSymbol(symbol.to_string()))?;
+        let (snap, seq) = {
+            let book = book.read().await;
+            (book.get_snapshot(), self.book_seq.load(Ordering::Relaxed))
+        };
+        Ok(market_snapshot(snap, seq, self.one_sided_mid))
+    }
+
+    /// Snapshot without waiting: None if a writer holds the book.
+    pub fn try_get_snapshot(&self) -> Option<MarketSnapshot> {
+        let book = self.book.try_read().ok()?;
+        let (snap, seq) = (book.get_snapshot(), self.book_seq.load(Ordering::Relaxed));
+        Some(market_snapshot(snap, seq, self.one_sided_mid))
+    }
+
+    /// Best level on each side, read from the top of the depth.
+    pub async fn get_ticker(&self) -> Ticker {
+        let (bid, ask) = {
+            let book = self.book.read().await;
+            (
+                book.get_depth(types::OB_SIDE_BUY, 1).pop(),
+                book.get_depth(types::OB_SIDE_SELL, 1).pop(),
+            )
+        };
+
+        Ticker {
+            best_bid: bid.map(|l| cents_to_dollars(l.price)),
+            best_ask: ask.map(|l| cents_to_dollars(l.price)),
+            bid_quantity: bid.map_or(0, |l| l.quantity),
+            ask_quantity: ask.map_or(0, |l| l.quantity),
+            bid_order_count: bid.map_or(0, |l| l.order_count),
+            ask_order_count: ask.map_or(0, |l| l.order_count),
+        }
+    }
+
+    /// Status of every instrument with a book, by symbol. The halt is
+    /// venue-wide.
+    pub async fn symbols(&self) -> Vec<SymbolStatus> {
+        let mut books: Vec<_> = self
+            .books
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect();
+        books.sort_by(|a, b| a.0.cmp(&b.0));
+        let mut symbols = Vec::with_capacity(books.len());
+        for (symbol, book) in books {
+            let stats = book.read().await.get_book_stats();
+            let last_update_ns = self.last_update_ns.get(&symbol).map(|t| *t);
+            let quote_only = self.is_quote_only(&symbol);
+            symbols.push(SymbolStatus {
+                symbol,
+                last_update_ns,
+                resting_orders: stats.bid_orders + stats.ask_orders,
+                halted: self.is_halted(),
+                quote_only,
+            });
+        }
+        symbols
+    }
+
+    pub async fn get_book_stats(&self) -> BookStats {
+        let stats = self.book.read().await.get_book_stats();
+        BookStats {
+            bid_orders: stats.bid_orders,
+            ask_orders: stats.ask_orders,
+            bid_quantity: stats.bid_quantity,
+            ask_quantity: stats.ask_quantity,
+        }
+    }
+}
+
+/// Convert FFI price data (integer cents, 0 for absent) into the API snapshot.
+fn market_snapshot(snap: PriceData, seq: u64, one_sided_mid: OneSidedMid) -> MarketSnapshot {
+    let has_bid = snap.bid_price > 0;
+    let has_ask = snap.ask_price > 0;
+    let has_both = has_bid && has_ask;
+
+    // Derive spread and mid from integer cents and convert once, rather
+    // than using the FFI's mid (truncated to whole cents) or subtracting
+    // f64 dollar prices
+    let spread_cents = snap.ask_price - snap.bid_price;
+    let mid_half_cents = snap.bid_price + snap.ask_price;
+    let mid_price = match (has_bid, has_ask, one_sided_mid) {
+        (true, true, _) => Some(mid_half_cents as f64 / 200.0),
+        (false, false, _) | (_, _, OneSidedMid::None) => None,
+        (_, _, OneSidedMid::BestPrice) => {
+            Some(cents_to_dollars(snap.bid_price.max(snap.ask_price)))
+        }
+        (_, _, OneSidedMid::LastTrade) => cents_to_optional_dollars(snap.last_trade_price),
+    };
+
+    MarketSnapshot {
+        best_bid: if has_bid { Some(cents_to_dollars(snap.bid_price)) } else { None },
+        best_ask: if has_ask { Some(cents_to_dollars(snap.ask_price)) } else { None },
+        spread: if has_both { Some(cents_to_dollars(spread_cents)) } else { None },
+        mid_price,
+        last_trade_price: cents_to_optional_dollars(snap.last_trade_price),
+        last_trade_qty: if snap.last_trade_qty == 0 {
+            None
+        } else {
+            Some(snap.last_trade_qty)
+        },
+        seq,
+    }
+}
+
+fn ffi_side(side: Side) -> u32 {
+    match side {
+        Side::Buy => types::OB_SIDE_BUY,
+        Side::Sell => types::OB_SIDE_SELL,
+    }
+}
+
+fn ffi_tif(tif: TimeInForce) -> u32 {
+    match tif {
+        TimeInForce::Gtc => types::OB_TIF_GTC,
+        TimeInForce::Ioc => types::OB_TIF_IOC,
+        TimeInForce::Fok => types::OB_TIF_FOK,
+        TimeInForce::Gtd => types::OB_TIF_GTD,
+    }
+}
+
+/// GTC and GTD look the same to the book; only this side knows the expiry.
+fn rests_on_book(tif: u32) -> bool {
+    tif == types::OB_TIF_GTC || tif == types::OB_TIF_GTD
+}
+
+fn ffi_stp(mode: StpMode) -> u32 {
+    match mode {
+        StpMode::Allow => types::OB_STP_ALLOW,
+        StpMode::CancelNewest => types::OB_STP_CANCEL_NEWEST,
+        StpMode::CancelOldest => types::OB_STP_CANCEL_OLDEST,
+        StpMode::CancelBoth => types::OB_STP_CANCEL_BOTH,
+        StpMode::DecrementAndCancel => types::OB_STP_DECREMENT_AND_CANCEL,
+    }
+}
+
+/// The book's refusal of an order or amendment, as an API error.
+fn engine_rejection(code: Option<RejectReason>, message: Option<String>) -> ApiError {
+    ApiError::engine(
+        code.unwrap_or(RejectReason::Unknown),
+        message.unwrap_or_else(|| "Unknown rejection".into()),
+    )
+}
+
+/// Convert a market order into a marketable limit order priced `offset_cents`
+/// through the best opposite price, so it cannot fill beyond that worst price.
+/// Any unfilled remainder is cancelled (IOC, or FOK if requested).
+///
+/// Returns `None` when the order should go through unchanged: the opposite side
+/// is empty (the engine rejects it for lack of liquidity) or the order is GTC
+/// (the engine rejects that combination for market orders).
+fn protect_market_order(
+    book: &OwnedOrderBook,
+    side: Side,
+    offset_cents: i64,
+    tif: u32,
+) -> Option<(Option<i64>, u32, u32)> {
+    if rests_on_book(tif) {
+        return None;
+    }
+    let limit = match side {
+        Side::Buy => {
+            let best_ask = book.get_best_ask();
+            if best_ask == 0 {
+                return None;
+            }
+            best_ask + offset_cents
+        }
+        Side::Sell => {
+            let best_bid = book.get_best_bid();
+            if best_bid == 0 {
+                return None;
+            }
+            (best_bid - offset_cents).max(1)
+        }
+    };
+    Some((Some(limit), types::OB_ORDER_TYPE_LIMIT, tif))
+}
+
+// ======================================================================
+// Price conversion helpers
+// ======================================================================
+
+fn dollars_to_cents(dollars: f64) -> Result<i64, ApiError> {
+    if dollars < 0.0 {
+        return Err(ApiError::Validation("Price cannot be negative".into()));
+    }
+    let cents = (dollars * 100.0).round() as i64;
+    // Verify no sub-cent precision was lost
+    let roundtrip = cents as f64 / 100.0;
+    if (roundtrip - dollars).abs() > 0.001 {
+        return Err(ApiError::Validation(
+            "Price precision limited to 2 decimal places (cents)".into(),
+        ));
+    }
+    Ok(cents)
+}
+
+fn cents_to_dollars(cents: i64) -> f64 {
+    cents as f64 / 100.0
+}
+
+fn cents_to_optional_dollars(cents: i64) -> Option<f64> {
+    if cents == 0 {
         None
     } else {
         Some(cents_to_dollars(cents))
     }
 }
 
-fn validate_order_request(req: &OrderRequest) -> Result<(), ApiError> {
+/// Wall-clock time in nanoseconds since the Unix epoch.
+pub(crate) fn unix_nanos() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0)
+}
+
+/// Largest accepted order quantity. Leaves headroom so a position, the
+/// trader's resting exposure and a new order can be summed without overflow.
+const MAX_ORDER_QUANTITY: i64 = i64::MAX / 4;
+
+/// With `strict_market_tif`, market orders must be IOC or FOK: a market order
+/// can't rest, so GTC is refused up front rather than by the engine.
+fn validate_order_request(req: &OrderRequest, strict_market_tif: bool) -> Result<(), ApiError> {
     if req.trader_id.is_empty() {
         return Err(ApiError::Validation("traderId is required".into()));
     }
@@ -241,9 +1519,75 @@ fn validate_order_request(req: &OrderRequest) -> Result<(), ApiError> {
             "traderId must be 64 characters or less".into(),
         ));
     }
+    if let Some(symbol) = &req.symbol {
+        let valid_char = |c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '/' | '.');
+        if symbol.is_empty() || symbol.len() > 32 || !symbol.chars().all(valid_char) {
+            return Err(ApiError::Validation(format!(
+                "symbol must be 1-32 letters, digits, '-', '/' or '.', got '{}'",
+                symbol
+            )));
+        }
+    }
     if req.quantity <= 0 {
         return Err(ApiError::Validation("Quantity must be positive".into()));
     }
+    if req.quantity > MAX_ORDER_QUANTITY {
+        return Err(ApiError::Validation(format!(
+            "Quantity {} exceeds maximum {}",
+            req.quantity, MAX_ORDER_QUANTITY
+        )));
+    }
+    if strict_market_tif
+        && req.order_type == OrderType::Market
+        && req.time_in_force == TimeInForce::Gtc
+    {
+        return Err(ApiError::Validation(
+            "Market orders must be IOC or FOK; a market order cannot rest on the book".into(),
+        ));
+    }
+    if req.all_or_none
+        && (req.order_type != OrderType::Limit || req.time_in_force != TimeInForce::Gtc)
+    {
+        return Err(ApiError::Validation(
+            "allOrNone is only supported on GTC limit orders".into(),
+        ));
+    }
+    if req.post_only {
+        if req.order_type != OrderType::Limit || !req.time_in_force.rests() {
+            return Err(ApiError::Validation(
+                "postOnly is only supported on GTC and GTD limit orders".into(),
+            ));
+        }
+        if req.all_or_none {
+            return Err(ApiError::Validation(
+                "postOnly cannot be combined with allOrNone".into(),
+            ));
+        }
+    }
+    match (req.time_in_force, req.expires_at_ns) {
+        (TimeInForce::Gtd, _) if req.order_type != OrderType::Limit => {
+            return Err(ApiError::Validation(
+                "GTD is only supported on limit orders".into(),
+            ));
+        }
+        (TimeInForce::Gtd, None) => {
+            return Err(ApiError::Validation(
+                "GTD orders require expiresAtNs".into(),
+            ));
+        }
+        (TimeInForce::Gtd, Some(at)) if at <= unix_nanos() => {
+            return Err(ApiError::Validation(format!(
+                "expiresAtNs {} is not in the future",
+                at
+            )));
+        }
+        (TimeInForce::Gtd, Some(_)) | (_, None) => {}
+        (_, Some(_)) => {
+            return Err(ApiError::Validation(
+                "expiresAtNs is only accepted on GTD orders".into(),
+            ));
+        }
+    }
     if req.order_type == OrderType::Limit {
         match req.price {
             None => {
@@ -256,7 +1600,16 @@ fn validate_order_request(req: &OrderRequest) -> Result<(), ApiError> {
                     "Price must be positive for limit orders".into(),
                 ))
             }
-            _ => {}
+            Some(p) => {
+                // Notional in cents must fit in i64
+                let cents = dollars_to_cents(p)?;
+                if cents.checked_mul(req.quantity).is_none() {
+                    return Err(ApiError::Validation(format!(
+                        "Order value of {} at {:.2} is too large",
+                        req.quantity, p
+                    )));
+                }
+            }
         }
     }
     Ok(())
@@ -290,15 +1643,7 @@ mod tests {
     #[tokio::test]
     async fn test_engine_add_order() {
         let engine = Engine::new();
-        let req = OrderRequest {
-            trader_id: "alice".into(),
-            price: Some(100.50),
-            quantity: 100,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
+        let req = OrderRequest::limit("alice", 100.50, 100, Side::Buy);
         let resp = engine.add_order(req).await.unwrap();
         assert!(resp.accepted);
         assert_eq!(resp.remaining_quantity, 100);
@@ -311,27 +1656,11 @@ mod tests {
         let engine = Engine::new();
 
         // Resting sell at $100.50
-        let sell = OrderRequest {
-            trader_id: "seller".into(),
-            price: Some(100.50),
-            quantity: 50,
-            side: Side::Sell,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
+        let sell = OrderRequest::limit("seller", 100.50, 50, Side::Sell);
         engine.add_order(sell).await.unwrap();
 
         // Crossing buy at $100.50
-        let buy = OrderRequest {
-            trader_id: "buyer".into(),
-            price: Some(100.50),
-            quantity: 30,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
+        let buy = OrderRequest::limit("buyer", 100.50, 30, Side::Buy);
         let resp = engine.add_order(buy).await.unwrap();
         assert_eq!(resp.trades.len(), 1);
         assert_eq!(resp.trades[0].price, 100.50);
@@ -343,23 +1672,15 @@ mod tests {
     #[tokio::test]
     async fn test_engine_cancel() {
         let engine = Engine::new();
-        let req = OrderRequest {
-            trader_id: "alice".into(),
-            price: Some(100.00),
-            quantity: 100,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
+        let req = OrderRequest::limit("alice", 100.00, 100, Side::Buy);
         let resp = engine.add_order(req).await.unwrap();
         let oid = resp.order_id;
 
-        let cancel = engine.cancel_order(oid).await.unwrap();
+        let cancel = engine.cancel_order(oid, CancelReason::User).await.unwrap();
         assert!(cancel.cancelled);
 
         // Cancel again should fail
-        assert!(engine.cancel_order(oid).await.is_err());
+        assert!(engine.cancel_order(oid, CancelReason::User).await.is_err());
     }
 
     #[tokio::test]
@@ -367,26 +1688,10 @@ mod tests {
         let engine = Engine::new();
 
         // Need a sell to establish spread
-        let sell = OrderRequest {
-            trader_id: "seller".into(),
-            price: Some(105.00),
-            quantity: 50,
-            side: Side::Sell,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
+        let sell = OrderRequest::limit("seller", 105.00, 50, Side::Sell);
         engine.add_order(sell).await.unwrap();
 
-        let buy = OrderRequest {
-            trader_id: "buyer".into(),
-            price: Some(100.00),
-            quantity: 100,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
+        let buy = OrderRequest::limit("buyer", 100.00, 100, Side::Buy);
         let resp = engine.add_order(buy).await.unwrap();
         let oid = resp.order_id;
 
@@ -407,114 +1712,1272 @@ mod tests {
         assert_eq!(modify.new_quantity, 60);
     }
 
+    async fn resting_bid(engine: &Engine, quantity: i64) -> u64 {
+        engine
+            .add_order(OrderRequest::limit("buyer", 100.00, quantity, Side::Buy))
+            .await
+            .unwrap()
+            .order_id
+    }
+
+    fn modify_to(new_quantity: i64) -> ModifyRequest {
+        ModifyRequest {
+            new_price: 100.00,
+            new_quantity,
+        }
+    }
+
     #[tokio::test]
-    async fn test_engine_snapshot() {
+    async fn test_modify_quantity_increase_allowed_by_default() {
         let engine = Engine::new();
+        let oid = resting_bid(&engine, 50).await;
 
-        let buy = OrderRequest {
-            trader_id: "buyer".into(),
-            price: Some(99.00),
-            quantity: 100,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
-        engine.add_order(buy).await.unwrap();
+        let modify = engine.modify_order(oid, modify_to(80)).await.unwrap();
+        assert_eq!(modify.new_quantity, 80);
+    }
 
-        let sell = OrderRequest {
-            trader_id: "seller".into(),
-            price: Some(101.00),
-            quantity: 100,
-            side: Side::Sell,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
+    #[tokio::test]
+    async fn test_modify_to_zero_price_rejected() {
+        let engine = Engine::new();
+        let oid = resting_bid(&engine, 50).await;
+
+        for new_price in [0.0, -1.0] {
+            let req = ModifyRequest {
+                new_price,
+                new_quantity: 50,
+            };
+            assert!(matches!(
+                engine.modify_order(oid, req).await,
+                Err(ApiError::Validation(msg)) if msg.contains("must be positive")
+            ));
+        }
+        assert_eq!(engine.get_order(oid).await.unwrap().price, 100.00);
+    }
+
+    #[tokio::test]
+    async fn test_modify_quantity_increase_rejected_when_disabled() {
+        let engine = Engine::with_config(EngineConfig {
+            allow_modify_quantity_increase: false,
+            ..EngineConfig::default()
+        });
+        let oid = resting_bid(&engine, 50).await;
+
+        let err = engine.modify_order(oid, modify_to(51)).await.unwrap_err();
+        let ApiError::EngineRejection(cause) = err else {
+            panic!("expected an engine rejection, got {:?}", err);
         };
-        engine.add_order(sell).await.unwrap();
+        assert_eq!(cause.reason, RejectReason::QuantityIncrease);
 
-        let snap = engine.get_snapshot().await;
-        assert_eq!(snap.best_bid, Some(99.00));
-        assert_eq!(snap.best_ask, Some(101.00));
-        assert_eq!(snap.spread, Some(2.00));
-        assert_eq!(snap.mid_price, Some(100.00));
+        // Same or smaller is still fine
+        assert!(engine.modify_order(oid, modify_to(50)).await.is_ok());
+        assert!(engine.modify_order(oid, modify_to(20)).await.is_ok());
+        assert!(matches!(
+            engine.modify_order(999, modify_to(20)).await,
+            Err(ApiError::NotFound(999))
+        ));
     }
 
     #[tokio::test]
-    async fn test_validation_empty_trader_id() {
+    async fn test_gtc_default_expiry_applied_and_swept() {
+        let engine = Engine::with_config(EngineConfig {
+            gtc_default_expiry_secs: 60,
+            ..EngineConfig::default()
+        });
+        let before = unix_nanos();
+        let resp = engine.add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc)).await.unwrap();
+        let expires = resp.expires_at_ns.unwrap();
+        assert!(expires >= before + 60_000_000_000);
+        assert!(expires <= unix_nanos() + 60_000_000_000);
+
+        // IOC never rests, so it gets no expiry
+        let ioc = engine.add_order(limit_order_req(Side::Sell, 101.00, TimeInForce::Ioc)).await.unwrap();
+        assert!(ioc.expires_at_ns.is_none());
+
+        assert!(engine.expire_due(expires - 1).await.is_empty());
+        assert!(engine.get_ticker().await.best_bid.is_some());
+
+        let swept = engine.expire_due(expires).await;
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].order_id, resp.order_id);
+        assert_eq!(swept[0].reason, CancelReason::Expired);
+        assert!(engine.get_ticker().await.best_bid.is_none());
+        // Swept entries are not reported twice
+        assert!(engine.expire_due(expires).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gtc_without_default_expiry_is_perpetual() {
         let engine = Engine::new();
-        let req = OrderRequest {
-            trader_id: "".into(),
-            price: Some(100.00),
-            quantity: 100,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
-        assert!(engine.add_order(req).await.is_err());
+        let resp = engine.add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc)).await.unwrap();
+        assert!(resp.expires_at_ns.is_none());
+        assert!(engine.expire_due(u64::MAX).await.is_empty());
     }
 
     #[tokio::test]
-    async fn test_validation_negative_quantity() {
+    async fn test_post_only_order_rejected_instead_of_trading() {
         let engine = Engine::new();
-        let req = OrderRequest {
-            trader_id: "alice".into(),
-            price: Some(100.00),
-            quantity: -10,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
+        engine
+            .add_order(limit_order_req(Side::Sell, 100.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
+        let post_only = |price| OrderRequest {
+            post_only: true,
+            ..limit_order_req(Side::Buy, price, TimeInForce::Gtc)
         };
-        assert!(engine.add_order(req).await.is_err());
+
+        let result = engine.add_order(post_only(100.00)).await;
+        match result {
+            Err(ApiError::EngineRejection(cause)) => {
+                assert_eq!(cause.reason, RejectReason::PostOnlyWouldMatch);
+                assert_eq!(cause.message, "post-only would cross");
+            }
+            other => panic!("expected a post-only rejection, got {:?}", other),
+        }
+        let ticker = engine.get_ticker().await;
+        assert_eq!(ticker.best_ask, Some(100.00));
+        assert!(ticker.best_bid.is_none());
+
+        // Priced below the ask it rests like any other limit order
+        let resp = engine.add_order(post_only(99.99)).await.unwrap();
+        assert!(resp.trades.is_empty());
+        assert_eq!(resp.remaining_quantity, 10);
+
+        // Only resting orders can be post-only
+        let ioc = OrderRequest {
+            post_only: true,
+            ..limit_order_req(Side::Buy, 99.00, TimeInForce::Ioc)
+        };
+        assert!(matches!(
+            engine.add_order(ioc).await,
+            Err(ApiError::Validation(_))
+        ));
     }
 
     #[tokio::test]
-    async fn test_validation_limit_without_price() {
+    async fn test_gtd_order_rests_until_its_expiry() {
         let engine = Engine::new();
-        let req = OrderRequest {
-            trader_id: "alice".into(),
-            price: None,
-            quantity: 100,
-            side: Side::Buy,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
+        let expires = unix_nanos() + 3_600_000_000_000;
+        let gtd = OrderRequest {
+            expires_at_ns: Some(expires),
+            ..limit_order_req(Side::Buy, 100.00, TimeInForce::Gtd)
         };
-        assert!(engine.add_order(req).await.is_err());
+        let resp = engine.add_order(gtd.clone()).await.unwrap();
+        assert_eq!(resp.expires_at_ns, Some(expires));
+        assert!(engine.expire_due(expires - 1).await.is_empty());
+
+        let swept = engine.expire_due(expires).await;
+        assert_eq!(swept.len(), 1);
+        assert_eq!(swept[0].order_id, resp.order_id);
+        assert!(engine.get_ticker().await.best_bid.is_none());
+
+        // A fully filled GTD order has nothing left to expire
+        engine.add_order(gtd).await.unwrap();
+        let sell = engine
+            .add_order(OrderRequest {
+                expires_at_ns: Some(expires),
+                ..limit_order_req(Side::Sell, 100.00, TimeInForce::Gtd)
+            })
+            .await
+            .unwrap();
+        assert_eq!(sell.trades.len(), 1);
+        assert!(sell.expires_at_ns.is_none());
+    }
+
+    #[test]
+    fn test_gtd_expiry_validation() {
+        let req = |tif, expires_at_ns| OrderRequest {
+            expires_at_ns,
+            ..limit_order_req(Side::Buy, 100.00, tif)
+        };
+        let future = unix_nanos() + 60_000_000_000;
+        assert!(validate_order_request(&req(TimeInForce::Gtd, Some(future)), true).is_ok());
+        for bad in [
+            req(TimeInForce::Gtd, None),
+            req(TimeInForce::Gtd, Some(1)),
+            req(TimeInForce::Gtc, Some(future)),
+            OrderRequest {
+                order_type: OrderType::Market,
+                price: None,
+                ..req(TimeInForce::Gtd, Some(future))
+            },
+        ] {
+            assert!(validate_order_request(&bad, true).is_err(), "{:?}", bad);
+        }
     }
 
     #[tokio::test]
-    async fn test_market_order_through_engine() {
+    async fn test_order_at_best_ask_rejected_under_lock_prevention() {
+        let engine = Engine::with_config(EngineConfig {
+            reject_locking_orders: true,
+            ..EngineConfig::default()
+        });
+        engine
+            .add_order(limit_order_req(Side::Sell, 100.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
+
+        let result = engine
+            .add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc))
+            .await;
+        assert!(matches!(
+            result,
+            Err(ApiError::EngineRejection(ref c)) if c.reason == RejectReason::LocksMarket
+        ));
+        assert_eq!(engine.get_snapshot().await.best_ask, Some(100.00));
+
+        // Below the touch rests; IOC at the touch can't rest so may trade
+        assert!(engine
+            .add_order(limit_order_req(Side::Buy, 99.99, TimeInForce::Gtc))
+            .await
+            .is_ok());
+        let resp = engine
+            .add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Ioc))
+            .await
+            .unwrap();
+        assert_eq!(resp.trades.len(), 1);
+    }
+
+    async fn many_small_asks(engine: &Engine, count: usize) {
+        for i in 0..count {
+            let price = 100.00 + (i / 10) as f64 * 0.01;
+            let mut req = limit_order_req(Side::Sell, price, TimeInForce::Gtc);
+            req.quantity = 1;
+            engine.add_order(req).await.unwrap();
+        }
+    }
+
+    fn sweep_capped_engine(policy: SweepCapPolicy) -> Engine {
+        Engine::with_config(EngineConfig {
+            max_sweep_orders: 20,
+            sweep_cap_policy: policy,
+            ..EngineConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_capped_sweep_fills_allowed_orders_and_cancels_rest() {
+        let engine = sweep_capped_engine(SweepCapPolicy::AcceptPartial);
+        many_small_asks(&engine, 30).await;
+
+        let resp = engine.add_order(market_buy(50)).await.unwrap();
+        assert_eq!(resp.trades.len(), 20);
+        assert_eq!(resp.trades.iter().map(|t| t.quantity).sum::<i64>(), 20);
+        assert_eq!(resp.remaining_quantity, 30);
+        assert_eq!(engine.get_book_stats().await.ask_orders, 10);
+    }
+
+    #[tokio::test]
+    async fn test_capped_sweep_rejected_under_reject_policy() {
+        let engine = sweep_capped_engine(SweepCapPolicy::Reject);
+        many_small_asks(&engine, 30).await;
+
+        let result = engine.add_order(market_buy(50)).await;
+        assert!(matches!(result, Err(ApiError::EngineRejection(_))));
+        assert_eq!(engine.get_book_stats().await.ask_orders, 30);
+
+        // Within the cap the order goes through untouched
+        let resp = engine.add_order(market_buy(15)).await.unwrap();
+        assert_eq!(resp.trades.len(), 15);
+    }
+
+    #[tokio::test]
+    async fn test_symbols_report_last_update_and_resting_orders() {
         let engine = Engine::new();
+        let symbols = engine.symbols().await;
+        assert_eq!(symbols.len(), 1);
+        assert_eq!(symbols[0].symbol, "DEFAULT");
+        assert!(symbols[0].last_update_ns.is_none());
 
-        // Resting sell
-        let sell = OrderRequest {
-            trader_id: "seller".into(),
-            price: Some(100.00),
-            quantity: 100,
-            side: Side::Sell,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        };
-        engine.add_order(sell).await.unwrap();
+        let before = unix_nanos();
+        engine
+            .add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
+        engine.set_halted(true);
 
-        // Market buy
-        let buy = OrderRequest {
-            trader_id: "buyer".into(),
-            price: None,
-            quantity: 40,
-            side: Side::Buy,
-            order_type: OrderType::Market,
-            time_in_force: TimeInForce::Ioc,
-            stp_mode: StpMode::Allow,
+        let status = &engine.symbols().await[0];
+        assert!(status.last_update_ns.unwrap() >= before);
+        assert_eq!(status.resting_orders, 1);
+        assert!(status.halted);
+    }
+
+    #[tokio::test]
+    async fn test_symbols_report_their_own_last_update() {
+        let engine = Engine::new();
+        let eth = |side, price| OrderRequest {
+            symbol: Some("ETH-USD".into()),
+            ..limit_order_req(side, price, TimeInForce::Gtc)
         };
-        let resp = engine.add_order(buy).await.unwrap();
-        assert!(resp.accepted);
+        engine.add_order(eth(Side::Buy, 100.00)).await.unwrap();
+        let last_update = |symbols: &[SymbolStatus], symbol: &str| {
+            symbols
+                .iter()
+                .find(|s| s.symbol == symbol)
+                .unwrap()
+                .last_update_ns
+        };
+
+        let symbols = engine.symbols().await;
+        let eth_updated = last_update(&symbols, "ETH-USD").unwrap();
+        assert!(last_update(&symbols, "DEFAULT").is_none());
+
+        // A change to one book leaves the other's time alone
+        let resp = engine
+            .add_order(limit_order_req(Side::Sell, 101.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
+        let symbols = engine.symbols().await;
+        assert!(last_update(&symbols, "DEFAULT").unwrap() >= eth_updated);
+        assert_eq!(last_update(&symbols, "ETH-USD"), Some(eth_updated));
+
+        engine
+            .cancel_order(resp.order_id, CancelReason::User)
+            .await
+            .unwrap();
+        let symbols = engine.symbols().await;
+        assert_eq!(last_update(&symbols, "ETH-USD"), Some(eth_updated));
+    }
+
+    #[tokio::test]
+    async fn test_order_at_best_ask_trades_by_default() {
+        let engine = Engine::new();
+        engine
+            .add_order(limit_order_req(Side::Sell, 100.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
+        let resp = engine
+            .add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
         assert_eq!(resp.trades.len(), 1);
-        assert_eq!(resp.trades[0].price, 100.00);
-        assert_eq!(resp.trades[0].quantity, 40);
+    }
+
+    fn limit_order_req(side: Side, price: f64, time_in_force: TimeInForce) -> OrderRequest {
+        OrderRequest {
+            time_in_force,
+            ..OrderRequest::limit("alice", price, 10, side)
+        }
+    }
+
+    #[test]
+    fn test_rejected_result_with_trades_is_internal_error() {
+        use crate::ffi::safe_wrapper::{StpResult, Trade};
+
+        let engine = Engine::new();
+        let order = engine.prepare_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Fok)).unwrap();
+        // What a FOK that matched and then rolled back would look like
+        let executed = ExecutedOrder {
+            result: OrderResult {
+                accepted: false,
+                reject_reason: Some("FOK order cannot be fully filled".into()),
+                reject_code: Some(RejectReason::FokUnfillable),
+                trades: vec![Trade {
+                    trade_id: 1,
+                    buy_order_id: order.order_id,
+                    sell_order_id: 7,
+                    price: 10000,
+                    quantity: 4,
+                    timestamp_ns: 0,
+                }],
+                remaining_quantity: 6,
+                stp_result: StpResult {
+                    self_trade: false,
+                    cancelled_orders: Vec::new(),
+                    action: None,
+                },
+            },
+            last_trade_cents: None,
+            first_exec_seq: 1,
+            seq: 1,
+            timestamp_ns: 0,
+            remainder_cancelled: false,
+        };
+
+        let err = engine.order_response(&order, executed).unwrap_err();
+        assert!(matches!(err, ApiError::Internal(msg) if msg.contains("after 1 trade")));
+        assert!(engine.is_halted());
+    }
+
+    #[tokio::test]
+    async fn test_plain_rejection_stays_engine_rejection() {
+        let engine = Engine::new();
+        let result = engine.add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Fok)).await;
+        assert!(matches!(result, Err(ApiError::EngineRejection(_))));
+        assert!(!engine.is_halted());
+    }
+
+    #[tokio::test]
+    async fn test_engine_reduce_keeps_priority() {
+        let engine = Engine::new();
+
+        let first = engine
+            .add_order(OrderRequest::limit("seller1", 100.00, 100, Side::Sell))
+            .await
+            .unwrap();
+        let second = engine
+            .add_order(OrderRequest::limit("seller2", 100.00, 100, Side::Sell))
+            .await
+            .unwrap();
+
+        let reduce = engine
+            .reduce_order(first.order_id, ReduceRequest { reduce_by: 70 })
+            .await
+            .unwrap();
+        assert_eq!(reduce.old_quantity, 100);
+        assert_eq!(reduce.remaining_quantity, 30);
+        assert_eq!(reduce.price, 100.00);
+
+        // The reduced order is still first in the queue
+        let buy = engine
+            .add_order(OrderRequest::limit("buyer", 100.00, 30, Side::Buy))
+            .await
+            .unwrap();
+        assert_eq!(buy.trades.len(), 1);
+        assert_eq!(buy.trades[0].sell_order_id, first.order_id);
+        assert_ne!(buy.trades[0].sell_order_id, second.order_id);
+    }
+
+    #[tokio::test]
+    async fn test_engine_over_reduce_rejected() {
+        let engine = Engine::new();
+        let resp = engine
+            .add_order(OrderRequest::limit("alice", 100.00, 50, Side::Buy))
+            .await
+            .unwrap();
+
+        let result = engine
+            .reduce_order(resp.order_id, ReduceRequest { reduce_by: 51 })
+            .await;
+        match result.unwrap_err() {
+            ApiError::EngineRejection(cause) => {
+                assert_eq!(cause.reason, RejectReason::ReduceExceedsRemaining)
+            }
+            e => panic!("Expected EngineRejection, got {:?}", e),
+        }
+
+        // Unknown order
+        assert!(matches!(
+            engine
+                .reduce_order(999, ReduceRequest { reduce_by: 1 })
+                .await,
+            Err(ApiError::NotFound(999))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_all_or_none_order_never_partially_fills() {
+        let engine = Engine::new();
+        let aon = OrderRequest {
+            quantity: 50,
+            all_or_none: true,
+            symbol: None,
+            ..limit_order_req(Side::Sell, 100.00, TimeInForce::Gtc)
+        };
+        engine.add_order(aon).await.unwrap();
+        let buy = |quantity| OrderRequest {
+            trader_id: "bob".into(),
+            quantity,
+            ..limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc)
+        };
+
+        // Too small to take all 50, and resting would cross the AON: refused
+        // before anything trades
+        assert!(matches!(
+            engine.add_order(buy(20)).await,
+            Err(ApiError::EngineRejection(ref c)) if c.reason == RejectReason::AonUnfillable
+        ));
+        assert_eq!(engine.get_book_stats().await.ask_quantity, 50);
+        assert_eq!(engine.get_book_stats().await.bid_orders, 0);
+
+        // The estimate passes over it the same way
+        let estimate = engine.estimate_execution(Side::Buy, 20).await.unwrap();
+        assert_eq!(estimate.fillable_quantity, 0);
+
+        let resp = engine.add_order(buy(50)).await.unwrap();
+        assert_eq!(resp.trades.len(), 1);
+        assert_eq!(resp.trades[0].quantity, 50);
+        assert!(!resp.remainder_cancelled);
+
+        // Only GTC limit orders can be all-or-none
+        let ioc = OrderRequest {
+            all_or_none: true,
+            symbol: None,
+            ..limit_order_req(Side::Buy, 100.00, TimeInForce::Ioc)
+        };
+        assert!(matches!(
+            engine.add_order(ioc).await,
+            Err(ApiError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fok_must_fill_in_whole_lots() {
+        let engine = Engine::new().with_lot_size(10);
+        for price in [100.00, 100.50] {
+            let mut ask = limit_order_req(Side::Sell, price, TimeInForce::Gtc);
+            ask.quantity = 20;
+            engine.add_order(ask).await.unwrap();
+        }
+        let fok = |quantity| OrderRequest {
+            trader_id: "bob".into(),
+            quantity,
+            ..limit_order_req(Side::Buy, 101.00, TimeInForce::Fok)
+        };
+
+        // 40 is on offer, enough for 25, but 25 is not a whole number of lots
+        match engine.add_order(fok(25)).await.unwrap_err() {
+            ApiError::Validation(msg) => assert!(msg.contains("whole lots of 10"), "{}", msg),
+            e => panic!("Expected Validation, got {:?}", e),
+        }
+        let stats = engine.get_book_stats().await;
+        assert_eq!(stats.ask_quantity, 40);
+
+        let resp = engine.add_order(fok(30)).await.unwrap();
+        assert_eq!(resp.remaining_quantity, 0);
+        assert_eq!(resp.trades.iter().map(|t| t.quantity).sum::<i64>(), 30);
+        assert!(resp.trades.iter().all(|t| t.quantity % 10 == 0));
+
+        let mut odd = limit_order_req(Side::Buy, 99.00, TimeInForce::Gtc);
+        odd.quantity = 15;
+        assert!(matches!(
+            engine.add_order(odd).await,
+            Err(ApiError::Validation(msg)) if msg.contains("lot size 10")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_engine_tick_size_on_add_and_modify() {
+        let engine = Engine::new().with_tick_size(0.05);
+
+        let off_tick = OrderRequest::limit("alice", 100.03, 10, Side::Buy);
+        assert!(matches!(
+            engine.add_order(off_tick).await,
+            Err(ApiError::Validation(_))
+        ));
+
+        let resp = engine
+            .add_order(OrderRequest::limit("alice", 100.05, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        // Off-tick modify is rejected and leaves the order untouched
+        let result = engine
+            .modify_order(
+                resp.order_id,
+                ModifyRequest {
+                    new_price: 100.12,
+                    new_quantity: 10,
+                },
+            )
+            .await;
+        match result.unwrap_err() {
+            ApiError::Validation(msg) => assert!(msg.contains("tick size")),
+            e => panic!("Expected Validation, got {:?}", e),
+        }
+
+        let modify = engine
+            .modify_order(
+                resp.order_id,
+                ModifyRequest {
+                    new_price: 100.10,
+                    new_quantity: 10,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(modify.old_price, 100.05);
+        assert_eq!(modify.new_price, 100.10);
+    }
+
+    #[tokio::test]
+    async fn test_engine_queue_position() {
+        let engine = Engine::new();
+        let mut ids = Vec::new();
+        for trader in ["first", "second"] {
+            let resp = engine
+                .add_order(OrderRequest::limit(trader, 100.00, 25, Side::Buy))
+                .await
+                .unwrap();
+            ids.push(resp.order_id);
+        }
+
+        let pos = engine.get_queue_position(ids[1]).await.unwrap();
+        assert_eq!(pos.orders_ahead, 1);
+        assert_eq!(pos.quantity_ahead, 25);
+
+        // Once cancelled, the order is no longer resting
+        engine.cancel_order(ids[1], CancelReason::User).await.unwrap();
+        assert!(matches!(
+            engine.get_queue_position(ids[1]).await,
+            Err(ApiError::NotFound(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_engine_get_order_reports_partial_fill() {
+        let engine = Engine::new();
+        let order =
+            |trader: &str, quantity, side| OrderRequest::limit(trader, 100.00, quantity, side);
+        let sell = engine
+            .add_order(order("seller", 40, Side::Sell))
+            .await
+            .unwrap();
+        let view = engine.get_order(sell.order_id).await.unwrap();
+        assert_eq!(view.status, OrderStatus::Resting);
+        assert_eq!(view.remaining_quantity, 40);
+
+        let buy = engine
+            .add_order(order("buyer", 15, Side::Buy))
+            .await
+            .unwrap();
+        let view = engine.get_order(sell.order_id).await.unwrap();
+        assert_eq!(view.side, Side::Sell);
+        assert_eq!(view.price, 100.00);
+        assert_eq!(view.original_quantity, 40);
+        assert_eq!(view.remaining_quantity, 25);
+        assert_eq!(view.filled_quantity, 15);
+        assert_eq!(view.status, OrderStatus::PartiallyFilled);
+
+        // Fully filled and never-seen orders are both gone
+        for id in [buy.order_id, 999] {
+            assert!(matches!(
+                engine.get_order(id).await,
+                Err(ApiError::NotFound(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_engine_get_order_after_modify_and_reduce() {
+        let engine = Engine::new();
+        let sell = engine
+            .add_order(OrderRequest::limit("seller", 100.00, 40, Side::Sell))
+            .await
+            .unwrap();
+        engine
+            .add_order(OrderRequest::limit("buyer", 100.00, 15, Side::Buy))
+            .await
+            .unwrap();
+        // Original quantity always equals filled plus remaining
+        let assert_quantities = |view: OrderStatusResponse, original, remaining| {
+            assert_eq!(view.original_quantity, original);
+            assert_eq!(view.filled_quantity, 15);
+            assert_eq!(view.remaining_quantity, remaining);
+            assert_eq!(view.status, OrderStatus::PartiallyFilled);
+        };
+
+        let modify = |new_price, new_quantity| ModifyRequest {
+            new_price,
+            new_quantity,
+        };
+        engine
+            .modify_order(sell.order_id, modify(100.00, 10))
+            .await
+            .unwrap();
+        assert_quantities(engine.get_order(sell.order_id).await.unwrap(), 25, 10);
+
+        engine
+            .reduce_order(sell.order_id, ReduceRequest { reduce_by: 4 })
+            .await
+            .unwrap();
+        assert_quantities(engine.get_order(sell.order_id).await.unwrap(), 21, 6);
+
+        engine
+            .modify_order(sell.order_id, modify(101.00, 8))
+            .await
+            .unwrap();
+        assert_quantities(engine.get_order(sell.order_id).await.unwrap(), 23, 8);
+    }
+
+    #[tokio::test]
+    async fn test_symbols_trade_on_separate_books() {
+        let engine = Engine::new();
+        let order = |symbol: &str, price, side| OrderRequest {
+            symbol: Some(symbol.into()),
+            ..limit_order_req(side, price, TimeInForce::Gtc)
+        };
+        let btc = engine
+            .add_order(order("BTC-USD", 100.00, Side::Sell))
+            .await
+            .unwrap();
+        // Would cross the BTC-USD ask if the books were shared
+        let eth = engine
+            .add_order(order("ETH-USD", 101.00, Side::Buy))
+            .await
+            .unwrap();
+        assert!(eth.trades.is_empty());
+
+        let btc_book = engine.get_snapshot_for("BTC-USD").await.unwrap();
+        assert_eq!((btc_book.best_bid, btc_book.best_ask), (None, Some(100.00)));
+        let eth_book = engine.get_snapshot_for("ETH-USD").await.unwrap();
+        assert_eq!((eth_book.best_bid, eth_book.best_ask), (Some(101.00), None));
+        assert!(engine.get_snapshot().await.best_ask.is_none());
+        assert!(matches!(
+            engine.get_snapshot_for("SOL-USD").await,
+            Err(ApiError::UnknownSymbol(_))
+        ));
+        let symbols: Vec<_> = engine
+            .symbols()
+            .await
+            .into_iter()
+            .map(|s| s.symbol)
+            .collect();
+        assert_eq!(symbols, ["BTC-USD", "DEFAULT", "ETH-USD"]);
+
+        // Order ids find their own book
+        let modify = ModifyRequest {
+            new_price: 102.00,
+            new_quantity: 10,
+        };
+        engine.modify_order(btc.order_id, modify).await.unwrap();
+        assert_eq!(engine.get_order(btc.order_id).await.unwrap().price, 102.00);
+        engine
+            .cancel_order(eth.order_id, CancelReason::User)
+            .await
+            .unwrap();
+        let eth_book = engine.get_snapshot_for("ETH-USD").await.unwrap();
+        assert!(eth_book.best_bid.is_none());
+        assert!(engine.order_symbol(eth.order_id).is_none());
+
+        // A fill takes the resting order out of the map too
+        let take = engine
+            .add_order(OrderRequest {
+                order_type: OrderType::Market,
+                time_in_force: TimeInForce::Ioc,
+                price: None,
+                ..order("BTC-USD", 0.0, Side::Buy)
+            })
+            .await
+            .unwrap();
+        assert_eq!(take.trades.len(), 1);
+        assert!(engine.order_symbol(btc.order_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_new_symbols_capped() {
+        let engine = Engine::with_config(EngineConfig {
+            max_symbols: 2,
+            ..EngineConfig::default()
+        });
+        let order = |symbol: &str| OrderRequest {
+            symbol: Some(symbol.into()),
+            ..OrderRequest::limit("alice", 100.00, 5, Side::Buy)
+        };
+        engine.add_order(order("ETH-USD")).await.unwrap();
+
+        let err = engine.add_order(order("BTC-USD")).await.unwrap_err();
+        assert!(matches!(err, ApiError::Validation(ref m) if m.contains("Symbol limit")));
+        assert!(!engine.has_symbol("BTC-USD"));
+        // Symbols that already have a book still trade
+        engine.add_order(order("ETH-USD")).await.unwrap();
+        engine.add_order(order("DEFAULT")).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_exec_seq_orders_trades_across_symbols() {
+        let engine = Engine::new();
+        let order = |symbol: Option<&str>, side| OrderRequest {
+            symbol: symbol.map(Into::into),
+            ..OrderRequest::limit(
+                if side == Side::Sell { "alice" } else { "bob" },
+                100.00,
+                5,
+                side,
+            )
+        };
+        let mut trades = Vec::new();
+        for symbol in [
+            None,
+            Some("ETH-USD"),
+            Some("BTC-USD"),
+            None,
+            Some("ETH-USD"),
+        ] {
+            engine.add_order(order(symbol, Side::Sell)).await.unwrap();
+            let resp = engine.add_order(order(symbol, Side::Buy)).await.unwrap();
+            trades.extend(resp.trades);
+        }
+
+        // Books number their own trades, but exec_seq runs venue-wide
+        let seqs: Vec<(u64, &str)> = trades
+            .iter()
+            .map(|t| (t.exec_seq, t.symbol.as_str()))
+            .collect();
+        assert_eq!(
+            seqs,
+            [
+                (1, "DEFAULT"),
+                (2, "ETH-USD"),
+                (3, "BTC-USD"),
+                (4, "DEFAULT"),
+                (5, "ETH-USD")
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_engine_snapshot() {
+        let engine = Engine::new();
+
+        let buy = OrderRequest::limit("buyer", 99.00, 100, Side::Buy);
+        engine.add_order(buy).await.unwrap();
+
+        let sell = OrderRequest::limit("seller", 101.00, 100, Side::Sell);
+        engine.add_order(sell).await.unwrap();
+
+        let snap = engine.get_snapshot().await;
+        assert_eq!(snap.best_bid, Some(99.00));
+        assert_eq!(snap.best_ask, Some(101.00));
+        assert_eq!(snap.spread, Some(2.00));
+        assert_eq!(snap.mid_price, Some(100.00));
+    }
+
+    /// Snapshot of a book left with one bid at 99.00 after a trade at
+    /// 100.00 took out the asks.
+    async fn one_sided_snapshot(one_sided_mid: OneSidedMid) -> MarketSnapshot {
+        let engine = Engine::with_config(EngineConfig {
+            one_sided_mid,
+            ..EngineConfig::default()
+        });
+        for (side, price) in [
+            (Side::Sell, 100.00),
+            (Side::Buy, 100.00),
+            (Side::Buy, 99.00),
+        ] {
+            engine
+                .add_order(limit_order_req(side, price, TimeInForce::Gtc))
+                .await
+                .unwrap();
+        }
+        engine.get_snapshot().await
+    }
+
+    #[tokio::test]
+    async fn test_one_sided_mid_options() {
+        let snap = one_sided_snapshot(OneSidedMid::None).await;
+        assert_eq!(snap.best_bid, Some(99.00));
+        assert_eq!(snap.best_ask, None);
+        assert_eq!(snap.mid_price, None);
+
+        let snap = one_sided_snapshot(OneSidedMid::BestPrice).await;
+        assert_eq!(snap.mid_price, Some(99.00));
+        assert_eq!(snap.spread, None);
+
+        let snap = one_sided_snapshot(OneSidedMid::LastTrade).await;
+        assert_eq!(snap.mid_price, Some(100.00));
+
+        // An empty book has no mid under any option
+        let engine = Engine::with_config(EngineConfig {
+            one_sided_mid: OneSidedMid::BestPrice,
+            ..EngineConfig::default()
+        });
+        assert_eq!(engine.get_snapshot().await.mid_price, None);
+    }
+
+    #[tokio::test]
+    async fn test_validation_empty_trader_id() {
+        let engine = Engine::new();
+        let req = OrderRequest::limit("", 100.00, 100, Side::Buy);
+        assert!(engine.add_order(req).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_validation_negative_quantity() {
+        let engine = Engine::new();
+        let req = OrderRequest::limit("alice", 100.00, -10, Side::Buy);
+        assert!(engine.add_order(req).await.is_err());
+    }
+
+    #[test]
+    fn test_validation_quantity_at_i64_boundary() {
+        let order = |quantity, order_type, price| OrderRequest {
+            price,
+            order_type,
+            ..OrderRequest::market("alice", quantity, Side::Buy)
+        };
+
+        assert!(validate_order_request(&order(MAX_ORDER_QUANTITY, OrderType::Market, None), true).is_ok());
+        for quantity in [MAX_ORDER_QUANTITY + 1, i64::MAX] {
+            let err = validate_order_request(&order(quantity, OrderType::Market, None), true).unwrap_err();
+            assert!(err.to_string().contains("exceeds maximum"));
+        }
+
+        // In range on its own, but quantity × price in cents overflows
+        let err = validate_order_request(&order(MAX_ORDER_QUANTITY, OrderType::Limit, Some(100.00)), true)
+            .unwrap_err();
+        assert!(err.to_string().contains("too large"));
+        let max_at_price = i64::MAX / 10_000;
+        assert!(validate_order_request(&order(max_at_price, OrderType::Limit, Some(100.00)), true).is_ok());
+        assert!(validate_order_request(&order(max_at_price + 1, OrderType::Limit, Some(100.00)), true).is_err());
+    }
+
+    #[test]
+    fn test_strict_mode_rejects_gtc_market_order() {
+        let market = |time_in_force| OrderRequest {
+            time_in_force,
+            ..OrderRequest::market("alice", 10, Side::Buy)
+        };
+
+        let err = validate_order_request(&market(TimeInForce::Gtc), true).unwrap_err();
+        assert!(matches!(err, ApiError::Validation(msg) if msg.contains("IOC or FOK")));
+        assert!(validate_order_request(&market(TimeInForce::Ioc), true).is_ok());
+        assert!(validate_order_request(&market(TimeInForce::Fok), true).is_ok());
+        // Lenient mode leaves the combination to the engine
+        assert!(validate_order_request(&market(TimeInForce::Gtc), false).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_validation_limit_without_price() {
+        let engine = Engine::new();
+        let req = OrderRequest {
+            price: None,
+            ..OrderRequest::limit("alice", 100.00, 100, Side::Buy)
+        };
+        assert!(engine.add_order(req).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_market_order_through_engine() {
+        let engine = Engine::new();
+
+        // Resting sell
+        let sell = OrderRequest::limit("seller", 100.00, 100, Side::Sell);
+        engine.add_order(sell).await.unwrap();
+
+        // Market buy
+        let buy = OrderRequest::market("buyer", 40, Side::Buy);
+        let resp = engine.add_order(buy).await.unwrap();
+        assert!(resp.accepted);
+        assert_eq!(resp.trades.len(), 1);
+        assert_eq!(resp.trades[0].price, 100.00);
+        assert_eq!(resp.trades[0].quantity, 40);
+    }
+
+    async fn thin_ask_book(engine: &Engine) {
+        for price in [100.00, 105.00] {
+            engine
+                .add_order(OrderRequest::limit("seller", price, 10, Side::Sell))
+                .await
+                .unwrap();
+        }
+    }
+
+    fn market_buy(quantity: i64) -> OrderRequest {
+        OrderRequest::market("buyer", quantity, Side::Buy)
+    }
+
+    #[tokio::test]
+    async fn test_trades_tagged_with_tick_direction() {
+        use crate::models::trade::TickDirection::{Down, Up, Zero};
+
+        let engine = Engine::with_config(EngineConfig {
+            trade_tick_direction: true,
+            ..EngineConfig::default()
+        });
+        let sell = |price| OrderRequest {
+            trader_id: "seller".into(),
+            ..limit_order_req(Side::Sell, price, TimeInForce::Gtc)
+        };
+        let directions = |response: OrderResponse| {
+            response
+                .trades
+                .iter()
+                .map(|t| t.tick_direction.unwrap())
+                .collect::<Vec<_>>()
+        };
+        for price in [100.00, 101.00, 99.00, 99.00] {
+            engine.add_order(sell(price)).await.unwrap();
+        }
+
+        // The book's first trade is a zero tick; ticks within a sweep
+        // compare against the trade before them
+        let sweep = engine.add_order(market_buy(30)).await.unwrap();
+        assert_eq!(directions(sweep), [Zero, Zero, Up]);
+        engine.add_order(sell(99.50)).await.unwrap();
+        let down = engine.add_order(market_buy(10)).await.unwrap();
+        assert_eq!(directions(down), [Down]);
+
+        // Off by default
+        let engine = Engine::new();
+        engine.add_order(sell(100.00)).await.unwrap();
+        let trade = &engine.add_order(market_buy(10)).await.unwrap().trades[0];
+        assert!(trade.tick_direction.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_unprotected_market_order_sweeps_thin_book() {
+        let engine = Engine::new();
+        thin_ask_book(&engine).await;
+
+        let resp = engine.add_order(market_buy(20)).await.unwrap();
+        assert_eq!(resp.trades.len(), 2);
+        assert_eq!(resp.trades[1].price, 105.00);
+        assert_eq!(resp.remaining_quantity, 0);
+    }
+
+    #[tokio::test]
+    async fn test_protected_market_order_stops_at_offset() {
+        let engine = Engine::with_config(EngineConfig {
+            market_protection_offset: Some(1.00),
+            ..EngineConfig::default()
+        });
+        thin_ask_book(&engine).await;
+
+        // Protected at 100.00 + 1.00: the 105.00 level is out of reach
+        let resp = engine.add_order(market_buy(20)).await.unwrap();
+        assert_eq!(resp.trades.len(), 1);
+        assert_eq!(resp.trades[0].price, 100.00);
+        assert_eq!(resp.remaining_quantity, 10);
+
+        // The unfilled remainder was cancelled, not rested as a bid
+        let snap = engine.get_snapshot().await;
+        assert_eq!(snap.best_bid, None);
+        assert_eq!(snap.best_ask, Some(105.00));
+    }
+
+    #[tokio::test]
+    async fn test_protected_market_order_without_liquidity_rejected() {
+        let engine = Engine::with_config(EngineConfig {
+            market_protection_offset: Some(1.00),
+            ..EngineConfig::default()
+        });
+        assert!(matches!(
+            engine.add_order(market_buy(10)).await,
+            Err(ApiError::EngineRejection(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_integrity_check_on_healthy_book() {
+        let engine = Engine::with_config(EngineConfig {
+            halt_on_crossed_book: true,
+            ..EngineConfig::default()
+        });
+        thin_ask_book(&engine).await;
+
+        let report = engine.check_integrity().await;
+        assert!(!report.crossed);
+        assert!(!report.halted);
+        assert!(!engine.is_halted());
+    }
+
+    #[test]
+    fn test_crossed_book_alerts_and_halts() {
+        let crossed = |halt_on_crossed_book| {
+            let engine = Engine::with_config(EngineConfig {
+                halt_on_crossed_book,
+                ..EngineConfig::default()
+            });
+            // What the FFI would report after leaving the book crossed
+            let snapshot = MarketSnapshot {
+                best_bid: Some(101.00),
+                best_ask: Some(99.00),
+                ..MarketSnapshot::default()
+            };
+            let report = engine.integrity_report(&snapshot);
+            (engine, report)
+        };
+
+        let (engine, report) = crossed(true);
+        assert!(report.crossed);
+        assert_eq!(
+            (report.best_bid, report.best_ask),
+            (Some(101.00), Some(99.00))
+        );
+        assert!(report.halted);
+        assert!(engine.is_halted());
+
+        let (engine, report) = crossed(false);
+        assert!(report.crossed);
+        assert!(!report.halted);
+        assert!(!engine.is_halted());
+    }
+
+    #[tokio::test]
+    async fn test_exec_seq_strictly_increasing_across_orders() {
+        let engine = Engine::new();
+        thin_ask_book(&engine).await;
+
+        let mut seqs = Vec::new();
+        for _ in 0..4 {
+            let resp = engine.add_order(market_buy(5)).await.unwrap();
+            seqs.extend(resp.trades.iter().map(|t| t.exec_seq));
+        }
+
+        assert_eq!(seqs.len(), 4);
+        assert!(seqs.windows(2).all(|w| w[0] < w[1]));
+    }
+
+    #[tokio::test]
+    async fn test_sweep_trades_share_timestamp_and_sequence() {
+        let engine = Engine::new();
+        thin_ask_book(&engine).await;
+
+        let resp = engine.add_order(market_buy(15)).await.unwrap();
+        assert_eq!(resp.trades.len(), 2);
+        assert_eq!(resp.trades[1].exec_seq, resp.trades[0].exec_seq + 1);
+        assert_eq!(resp.trades[0].timestamp_ns, resp.trades[1].timestamp_ns);
+        assert!(resp.trades[0].timestamp_ns > 0);
+    }
+
+    #[tokio::test]
+    async fn test_trade_timestamp_sources() {
+        let stamped = |source| async move {
+            let engine = Engine::with_config(EngineConfig {
+                trade_timestamp_source: source,
+                ..EngineConfig::default()
+            });
+            thin_ask_book(&engine).await;
+            let before = unix_nanos();
+            let trade = engine.add_order(market_buy(5)).await.unwrap().trades[0].clone();
+            (before, trade)
+        };
+
+        let (before, trade) = stamped(TradeTimestampSource::Server).await;
+        assert!(trade.timestamp_ns >= before);
+        assert_eq!((trade.engine_ts, trade.server_ts), (None, None));
+
+        // The engine clock counts from boot, well behind the Unix epoch clock
+        let (before, trade) = stamped(TradeTimestampSource::Engine).await;
+        assert!(trade.timestamp_ns > 0 && trade.timestamp_ns < before);
+        assert!(trade.server_ts.unwrap() >= before);
+        assert!(trade.engine_ts.is_none());
+
+        let (before, trade) = stamped(TradeTimestampSource::Both).await;
+        assert_eq!(trade.server_ts, Some(trade.timestamp_ns));
+        assert!(trade.timestamp_ns >= before);
+        assert!(trade.engine_ts.unwrap() < before);
+        let json = serde_json::to_value(&trade).unwrap();
+        assert!(json["engineTs"].is_u64() && json["serverTs"].is_u64());
+    }
+
+    async fn quote(engine: &Engine, bid: f64, ask: f64) {
+        for (side, price) in [(Side::Buy, bid), (Side::Sell, ask)] {
+            engine
+                .add_order(OrderRequest::limit("mm", price, 10, side))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_one_tick_spread_is_exact() {
+        let engine = Engine::new();
+        quote(&engine, 100.00, 100.01).await;
+
+        let snap = engine.get_snapshot().await;
+        assert_eq!(snap.spread, Some(0.01));
+        // The FFI mid truncates to 100.00; the half-cent must survive
+        assert_eq!(snap.mid_price, Some(100.005));
+    }
+
+    #[tokio::test]
+    async fn test_one_tick_spread_exact_at_high_prices() {
+        let engine = Engine::new();
+        quote(&engine, 12345.67, 12345.68).await;
+
+        let snap = engine.get_snapshot().await;
+        assert_eq!(snap.spread, Some(0.01));
+        assert_eq!(snap.mid_price, Some(12345.675));
+    }
+
+    #[tokio::test]
+    async fn test_single_order_change_emits_one_level_delta() {
+        use crate::engine::depth::DepthFeed;
+
+        let engine = Engine::new();
+        quote(&engine, 99.00, 101.00).await;
+        quote(&engine, 98.00, 102.00).await;
+
+        let mut feed = DepthFeed::new(20);
+        let initial = feed.next_frame(engine.get_depth(20).await).unwrap();
+        assert!(initial.snapshot);
+        assert_eq!(initial.bids.len() + initial.asks.len(), 4);
+
+        engine
+            .add_order(OrderRequest::limit("mm", 99.00, 5, Side::Buy))
+            .await
+            .unwrap();
+
+        let delta = feed.next_frame(engine.get_depth(20).await).unwrap();
+        assert!(!delta.snapshot);
+        assert_eq!(delta.bids, vec![(9900, 15)]);
+        assert!(delta.asks.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ticker_counts_orders_at_best_bid() {
+        let engine = Engine::new();
+        let empty = engine.get_ticker().await;
+        assert_eq!((empty.bid_order_count, empty.ask_order_count), (0, 0));
+        assert!(empty.best_bid.is_none());
+
+        for (price, quantity) in [(100.00, 10), (100.00, 20), (100.00, 5), (99.00, 50)] {
+            engine
+                .add_order(OrderRequest::limit("mm", price, quantity, Side::Buy))
+                .await
+                .unwrap();
+        }
+
+        let ticker = engine.get_ticker().await;
+        assert_eq!(ticker.best_bid, Some(100.00));
+        assert_eq!(ticker.bid_quantity, 35);
+        assert_eq!(ticker.bid_order_count, 3);
+        assert_eq!(ticker.ask_order_count, 0);
+        assert_eq!(ticker.ask_quantity, 0);
+    }
+
+    #[tokio::test]
+    async fn test_book_stats_count_resting_orders() {
+        let engine = Engine::new();
+        let empty = engine.get_book_stats().await;
+        assert_eq!((empty.bid_orders, empty.ask_orders), (0, 0));
+        assert_eq!((empty.bid_quantity, empty.ask_quantity), (0, 0));
+
+        let orders = [
+            (Side::Buy, 100.00, 10),
+            (Side::Buy, 100.00, 20),
+            (Side::Buy, 99.00, 50),
+            (Side::Sell, 101.00, 7),
+            (Side::Sell, 103.00, 3),
+        ];
+        for (side, price, quantity) in orders {
+            engine
+                .add_order(OrderRequest::limit("mm", price, quantity, side))
+                .await
+                .unwrap();
+        }
+
+        let stats = engine.get_book_stats().await;
+        assert_eq!(stats.bid_orders, 3);
+        assert_eq!(stats.ask_orders, 2);
+        assert_eq!(stats.bid_quantity, 80);
+        assert_eq!(stats.ask_quantity, 10);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_seq_advances_with_mutations() {
+        let engine = Engine::new();
+        assert_eq!(engine.get_snapshot().await.seq, 0);
+
+        let resp = engine
+            .add_order(limit_order_req(Side::Buy, 100.00, TimeInForce::Gtc))
+            .await
+            .unwrap();
+        let after_add = engine.get_snapshot().await.seq;
+        assert_eq!(after_add, resp.seq);
+        assert!(after_add > 0);
+
+        let cancel = engine
+            .cancel_order(resp.order_id, CancelReason::User)
+            .await
+            .unwrap();
+        let after_cancel = engine.get_snapshot().await.seq;
+        assert_eq!(after_cancel, cancel.seq);
+        assert!(after_cancel > after_add);
+
+        // Failed mutations leave the book, and so the sequence, unchanged
+        assert!(engine.cancel_order(resp.order_id, CancelReason::User).await.is_err());
+        assert_eq!(engine.get_snapshot().await.seq, after_cancel);
     }
 }
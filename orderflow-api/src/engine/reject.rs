@@ -0,0 +1,88 @@
+use serde::Serialize;
+
+use crate::ffi::types;
+
+/// Why an order or amendment was refused, reported to clients as the error
+/// body's `reasonCode` next to the human-readable message.
+///
+/// Most come from the C++ engine's `reject_code`; the rest are checks the
+/// Rust engine layer makes before calling into the book.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RejectReason {
+    DuplicateId,
+    InvalidQuantity,
+    MissingPrice,
+    InvalidPrice,
+    MarketOrderGtc,
+    InvalidAllOrNone,
+    InvalidPostOnly,
+    FokUnfillable,
+    NoLiquidity,
+    PostOnlyWouldMatch,
+    AonUnfillable,
+    NotFound,
+    CrossedSpread,
+    ReduceExceedsRemaining,
+    /// Would trade at, but not through, the best opposite price
+    LocksMarket,
+    QuoteOnly,
+    SweepCapExceeded,
+    QuantityIncrease,
+    OrderCapReached,
+    /// A code this build doesn't know, from a newer engine
+    Unknown,
+}
+
+impl RejectReason {
+    /// Map the FFI's `reject_code`. `None` for `OB_REJECT_NONE`.
+    pub fn from_code(code: u32) -> Option<Self> {
+        Some(match code {
+            types::OB_REJECT_NONE => return None,
+            types::OB_REJECT_DUPLICATE_ID => Self::DuplicateId,
+            types::OB_REJECT_INVALID_QUANTITY => Self::InvalidQuantity,
+            types::OB_REJECT_MISSING_PRICE => Self::MissingPrice,
+            types::OB_REJECT_INVALID_PRICE => Self::InvalidPrice,
+            types::OB_REJECT_MARKET_ORDER_GTC => Self::MarketOrderGtc,
+            types::OB_REJECT_INVALID_ALL_OR_NONE => Self::InvalidAllOrNone,
+            types::OB_REJECT_INVALID_POST_ONLY => Self::InvalidPostOnly,
+            types::OB_REJECT_FOK_UNFILLABLE => Self::FokUnfillable,
+            types::OB_REJECT_NO_LIQUIDITY => Self::NoLiquidity,
+            types::OB_REJECT_POST_ONLY_WOULD_MATCH => Self::PostOnlyWouldMatch,
+            types::OB_REJECT_AON_UNFILLABLE => Self::AonUnfillable,
+            types::OB_REJECT_NOT_FOUND => Self::NotFound,
+            types::OB_REJECT_CROSSED_SPREAD => Self::CrossedSpread,
+            types::OB_REJECT_REDUCE_EXCEEDS_REMAINING => Self::ReduceExceedsRemaining,
+            _ => Self::Unknown,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_map_to_reasons() {
+        assert_eq!(RejectReason::from_code(types::OB_REJECT_NONE), None);
+        assert_eq!(
+            RejectReason::from_code(types::OB_REJECT_DUPLICATE_ID),
+            Some(RejectReason::DuplicateId)
+        );
+        assert_eq!(
+            RejectReason::from_code(types::OB_REJECT_POST_ONLY_WOULD_MATCH),
+            Some(RejectReason::PostOnlyWouldMatch)
+        );
+        assert_eq!(
+            RejectReason::from_code(types::OB_REJECT_REDUCE_EXCEEDS_REMAINING),
+            Some(RejectReason::ReduceExceedsRemaining)
+        );
+        assert_eq!(RejectReason::from_code(99), Some(RejectReason::Unknown));
+    }
+
+    #[test]
+    fn test_serialized_as_snake_case() {
+        let json = serde_json::to_value(RejectReason::FokUnfillable).unwrap();
+        assert_eq!(json, "fok_unfillable");
+    }
+}
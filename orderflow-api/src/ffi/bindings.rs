@@ -14,6 +14,8 @@ extern "C" {
 
     pub fn ob_orderbook_cancel_order(book: *mut c_void, id: u64) -> bool;
 
+    pub fn ob_orderbook_clear(book: *mut c_void) -> usize;
+
     pub fn ob_orderbook_modify_order(
         book: *mut c_void,
         id: u64,
@@ -21,6 +23,14 @@ extern "C" {
         new_quantity: i64,
     ) -> *mut ObModifyResultT;
 
+    pub fn ob_orderbook_reduce_order(
+        book: *mut c_void,
+        id: u64,
+        reduce_by: i64,
+    ) -> *mut ObModifyResultT;
+
+    pub fn ob_orderbook_credit_fill(book: *mut c_void, id: u64, filled: i64) -> bool;
+
     // Market data queries
     pub fn ob_orderbook_get_snapshot(book: *const c_void) -> ObPriceDataT;
     pub fn ob_orderbook_get_best_bid(book: *const c_void) -> i64;
@@ -29,6 +39,27 @@ extern "C" {
     pub fn ob_orderbook_get_mid_price(book: *const c_void) -> i64;
     pub fn ob_orderbook_get_last_trade_price(book: *const c_void) -> i64;
     pub fn ob_orderbook_get_last_trade_qty(book: *const c_void) -> i64;
+    pub fn ob_orderbook_get_queue_position(book: *const c_void, id: u64) -> ObQueuePositionT;
+    pub fn ob_orderbook_get_order(book: *const c_void, id: u64) -> ObOrderInfoT;
+    pub fn ob_orderbook_get_book_stats(book: *const c_void) -> ObBookStatsT;
+    pub fn ob_orderbook_get_depth(
+        book: *const c_void,
+        side: u32,
+        out: *mut ObDepthLevelT,
+        max_levels: usize,
+    ) -> usize;
+    pub fn ob_orderbook_get_sweepable_quantity(
+        book: *const c_void,
+        side: u32,
+        max_orders: usize,
+        limit_price: i64,
+        quantity: i64,
+    ) -> i64;
+    pub fn ob_orderbook_estimate_fill(
+        book: *const c_void,
+        side: u32,
+        quantity: i64,
+    ) -> ObFillEstimateT;
 
     // Memory cleanup
     pub fn ob_free_order_result(result: *mut ObOrderResultT);
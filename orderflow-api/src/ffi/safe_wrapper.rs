@@ -1,9 +1,12 @@
 use std::ffi::{CStr, CString};
 use std::os::raw::c_void;
 use std::slice;
+use std::time::Instant;
 
 use super::bindings;
 use super::types::*;
+use crate::engine::reject::RejectReason;
+use crate::middleware::metrics as m;
 
 // ======================================================================
 // Safe Rust types (owned, no raw pointers)
@@ -30,6 +33,7 @@ pub struct StpResult {
 pub struct OrderResult {
     pub accepted: bool,
     pub reject_reason: Option<String>,
+    pub reject_code: Option<RejectReason>,
     pub trades: Vec<Trade>,
     pub remaining_quantity: i64,
     pub stp_result: StpResult,
@@ -39,6 +43,7 @@ pub struct OrderResult {
 pub struct ModifyResult {
     pub accepted: bool,
     pub reject_reason: Option<String>,
+    pub reject_code: Option<RejectReason>,
     pub old_price: i64,
     pub new_price: i64,
     pub old_quantity: i64,
@@ -56,6 +61,50 @@ pub struct PriceData {
     pub last_trade_qty: i64,
 }
 
+#[derive(Debug, Clone, Copy)]
+pub struct QueuePosition {
+    pub orders_ahead: usize,
+    pub quantity_ahead: i64,
+}
+
+/// A resting order as the book currently holds it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OrderView {
+    pub side: u32,
+    pub price: i64,
+    /// Quantity still resting
+    pub quantity: i64,
+    /// Quantity as submitted
+    pub original_quantity: i64,
+    pub filled_quantity: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DepthLevel {
+    pub price: i64,
+    pub quantity: i64,
+    pub order_count: usize,
+}
+
+/// Resting order counts and quantity across every level of each side.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BookStats {
+    pub bid_orders: usize,
+    pub ask_orders: usize,
+    pub bid_quantity: i64,
+    pub ask_quantity: i64,
+}
+
+/// What an order would take from the book, prices in integer cents.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FillEstimate {
+    pub filled_quantity: i64,
+    pub cost_cents: i64,
+    /// Price of the last level reached; `None` if nothing would fill
+    pub worst_price: Option<i64>,
+    pub levels: usize,
+}
+
 // ======================================================================
 // Conversion helpers
 // ======================================================================
@@ -103,6 +152,7 @@ fn convert_order_result(raw: *mut ObOrderResultT) -> OrderResult {
         OrderResult {
             accepted: r.accepted,
             reject_reason: ptr_to_option_string(r.reject_reason),
+            reject_code: RejectReason::from_code(r.reject_code),
             trades,
             remaining_quantity: r.remaining_quantity,
             stp_result: StpResult {
@@ -120,6 +170,7 @@ fn convert_modify_result(raw: *mut ObModifyResultT) -> ModifyResult {
         ModifyResult {
             accepted: r.accepted,
             reject_reason: ptr_to_option_string(r.reject_reason),
+            reject_code: RejectReason::from_code(r.reject_code),
             old_price: r.old_price,
             new_price: r.new_price,
             old_quantity: r.old_quantity,
@@ -134,6 +185,8 @@ fn convert_modify_result(raw: *mut ObModifyResultT) -> ModifyResult {
 
 pub struct OwnedOrderBook {
     ptr: *mut c_void,
+    /// Record how long each timed FFI call spends in C++
+    time_calls: bool,
 }
 
 // SAFETY: The C++ OrderBook is single-threaded. We enforce exclusive access
@@ -143,13 +196,41 @@ pub struct OwnedOrderBook {
 unsafe impl Send for OwnedOrderBook {}
 unsafe impl Sync for OwnedOrderBook {}
 
+impl Default for OwnedOrderBook {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl OwnedOrderBook {
     pub fn new() -> Self {
         let ptr = unsafe { bindings::ob_orderbook_create() };
         assert!(!ptr.is_null(), "Failed to create OrderBook");
-        Self { ptr }
+        Self {
+            ptr,
+            time_calls: false,
+        }
+    }
+
+    /// Time `add_order`, `modify_order`, `cancel_order` and `get_snapshot`
+    /// into `orderflow_ffi_call_seconds`. Only the C++ call is timed, not the
+    /// conversion of its result or any lock wait.
+    pub fn with_call_timing(mut self, enabled: bool) -> Self {
+        self.time_calls = enabled;
+        self
+    }
+
+    fn timed<T>(&self, operation: &'static str, call: impl FnOnce() -> T) -> T {
+        if !self.time_calls {
+            return call();
+        }
+        let start = Instant::now();
+        let out = call();
+        m::record_ffi_call(operation, start);
+        out
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn add_order(
         &mut self,
         trader_id: &str,
@@ -160,6 +241,85 @@ impl OwnedOrderBook {
         order_type: u32,
         time_in_force: u32,
         stp_mode: u32,
+    ) -> OrderResult {
+        self.submit(
+            trader_id,
+            id,
+            price,
+            quantity,
+            side,
+            order_type,
+            time_in_force,
+            stp_mode,
+            false,
+            false,
+        )
+    }
+
+    /// Add a GTC limit order that only ever executes its full quantity at
+    /// once. It trades on arrival only if it can fill completely, and while
+    /// resting it is passed over by incoming orders too small to take it all.
+    pub fn add_all_or_none_order(
+        &mut self,
+        trader_id: &str,
+        id: u64,
+        price: i64,
+        quantity: i64,
+        side: u32,
+        stp_mode: u32,
+    ) -> OrderResult {
+        self.submit(
+            trader_id,
+            id,
+            Some(price),
+            quantity,
+            side,
+            OB_ORDER_TYPE_LIMIT,
+            OB_TIF_GTC,
+            stp_mode,
+            true,
+            false,
+        )
+    }
+
+    /// Add a GTC limit order that only adds liquidity: it is rejected with
+    /// "post-only would cross" instead of trading on arrival.
+    pub fn add_post_only_order(
+        &mut self,
+        trader_id: &str,
+        id: u64,
+        price: i64,
+        quantity: i64,
+        side: u32,
+        stp_mode: u32,
+    ) -> OrderResult {
+        self.submit(
+            trader_id,
+            id,
+            Some(price),
+            quantity,
+            side,
+            OB_ORDER_TYPE_LIMIT,
+            OB_TIF_GTC,
+            stp_mode,
+            false,
+            true,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn submit(
+        &mut self,
+        trader_id: &str,
+        id: u64,
+        price: Option<i64>,
+        quantity: i64,
+        side: u32,
+        order_type: u32,
+        time_in_force: u32,
+        stp_mode: u32,
+        all_or_none: bool,
+        post_only: bool,
     ) -> OrderResult {
         let c_trader_id = CString::new(trader_id).unwrap_or_default();
 
@@ -173,9 +333,13 @@ impl OwnedOrderBook {
             time_in_force,
             stp_mode,
             has_price: price.is_some(),
+            all_or_none,
+            post_only,
         };
 
-        let raw = unsafe { bindings::ob_orderbook_add_order(self.ptr, &c_order) };
+        let raw = self.timed("add_order", || unsafe {
+            bindings::ob_orderbook_add_order(self.ptr, &c_order)
+        });
         assert!(!raw.is_null(), "ob_orderbook_add_order returned NULL");
 
         let result = convert_order_result(raw);
@@ -184,12 +348,20 @@ impl OwnedOrderBook {
     }
 
     pub fn cancel_order(&mut self, id: u64) -> bool {
-        unsafe { bindings::ob_orderbook_cancel_order(self.ptr, id) }
+        self.timed("cancel_order", || unsafe {
+            bindings::ob_orderbook_cancel_order(self.ptr, id)
+        })
+    }
+
+    /// Remove every resting order. Returns how many there were.
+    pub fn clear(&mut self) -> usize {
+        self.timed("clear", || unsafe { bindings::ob_orderbook_clear(self.ptr) })
     }
 
     pub fn modify_order(&mut self, id: u64, new_price: i64, new_quantity: i64) -> ModifyResult {
-        let raw =
-            unsafe { bindings::ob_orderbook_modify_order(self.ptr, id, new_price, new_quantity) };
+        let raw = self.timed("modify_order", || unsafe {
+            bindings::ob_orderbook_modify_order(self.ptr, id, new_price, new_quantity)
+        });
         assert!(!raw.is_null(), "ob_orderbook_modify_order returned NULL");
 
         let result = convert_modify_result(raw);
@@ -197,8 +369,25 @@ impl OwnedOrderBook {
         result
     }
 
+    pub fn reduce_order(&mut self, id: u64, reduce_by: i64) -> ModifyResult {
+        let raw = unsafe { bindings::ob_orderbook_reduce_order(self.ptr, id, reduce_by) };
+        assert!(!raw.is_null(), "ob_orderbook_reduce_order returned NULL");
+
+        let result = convert_modify_result(raw);
+        unsafe { bindings::ob_free_modify_result(raw) };
+        result
+    }
+
+    /// Count `filled` quantity the order traded off the book, in an auction
+    /// uncross, toward it. Returns false if the order isn't resting.
+    pub fn credit_fill(&mut self, id: u64, filled: i64) -> bool {
+        unsafe { bindings::ob_orderbook_credit_fill(self.ptr, id, filled) }
+    }
+
     pub fn get_snapshot(&self) -> PriceData {
-        let raw = unsafe { bindings::ob_orderbook_get_snapshot(self.ptr as *const _) };
+        let raw = self.timed("get_snapshot", || unsafe {
+            bindings::ob_orderbook_get_snapshot(self.ptr as *const _)
+        });
         PriceData {
             timestamp_ns: raw.timestamp_ns,
             bid_price: raw.bid_price,
@@ -233,6 +422,95 @@ impl OwnedOrderBook {
     pub fn get_last_trade_qty(&self) -> i64 {
         unsafe { bindings::ob_orderbook_get_last_trade_qty(self.ptr as *const _) }
     }
+
+    /// Orders and quantity ahead of `id` at its price level, or `None` if the
+    /// order is not resting.
+    pub fn get_queue_position(&self, id: u64) -> Option<QueuePosition> {
+        let raw = unsafe { bindings::ob_orderbook_get_queue_position(self.ptr as *const _, id) };
+        if !raw.found {
+            return None;
+        }
+        Some(QueuePosition {
+            orders_ahead: raw.orders_ahead,
+            quantity_ahead: raw.quantity_ahead,
+        })
+    }
+
+    pub fn get_order(&self, id: u64) -> Option<OrderView> {
+        let raw = unsafe { bindings::ob_orderbook_get_order(self.ptr as *const _, id) };
+        if !raw.found {
+            return None;
+        }
+        Some(OrderView {
+            side: raw.side,
+            price: raw.price,
+            quantity: raw.quantity,
+            original_quantity: raw.original_quantity,
+            filled_quantity: raw.filled_quantity,
+        })
+    }
+
+    pub fn get_book_stats(&self) -> BookStats {
+        let raw = unsafe { bindings::ob_orderbook_get_book_stats(self.ptr as *const _) };
+        BookStats {
+            bid_orders: raw.bid_orders,
+            ask_orders: raw.ask_orders,
+            bid_quantity: raw.bid_quantity,
+            ask_quantity: raw.ask_quantity,
+        }
+    }
+
+    /// Aggregated levels for one side, best price first.
+    pub fn get_depth(&self, side: u32, max_levels: usize) -> Vec<DepthLevel> {
+        let mut buf = vec![ObDepthLevelT::default(); max_levels];
+        let n = unsafe {
+            bindings::ob_orderbook_get_depth(self.ptr as *const _, side, buf.as_mut_ptr(), max_levels)
+        };
+        buf.truncate(n);
+        buf.into_iter()
+            .map(|l| DepthLevel {
+                price: l.price,
+                quantity: l.quantity,
+                order_count: l.order_count,
+            })
+            .collect()
+    }
+
+    /// Quantity an order of `quantity` could take from the first
+    /// `max_orders` resting orders on `side`, in matching order. Levels
+    /// beyond `limit_price` don't count, nor do all-or-none orders too large
+    /// to take.
+    pub fn get_sweepable_quantity(
+        &self,
+        side: u32,
+        max_orders: usize,
+        limit_price: Option<i64>,
+        quantity: i64,
+    ) -> i64 {
+        unsafe {
+            bindings::ob_orderbook_get_sweepable_quantity(
+                self.ptr as *const _,
+                side,
+                max_orders,
+                limit_price.unwrap_or(0),
+                quantity,
+            )
+        }
+    }
+
+    /// Walk the opposite side from the best price for an order of
+    /// `quantity` on `side`, ignoring price limits and self-trade
+    /// prevention. All-or-none orders too large to take are passed over.
+    pub fn estimate_fill(&self, side: u32, quantity: i64) -> FillEstimate {
+        let raw =
+            unsafe { bindings::ob_orderbook_estimate_fill(self.ptr as *const _, side, quantity) };
+        FillEstimate {
+            filled_quantity: raw.filled_quantity,
+            cost_cents: raw.cost,
+            worst_price: (raw.filled_quantity > 0).then_some(raw.worst_price),
+            levels: raw.levels,
+        }
+    }
 }
 
 impl Drop for OwnedOrderBook {
@@ -257,6 +535,32 @@ mod tests {
         // Drop cleans up
     }
 
+    #[test]
+    fn test_call_timing_records_histograms() {
+        let recorder = metrics_exporter_prometheus::PrometheusBuilder::new().build_recorder();
+        let handle = recorder.handle();
+        metrics::with_local_recorder(&recorder, || {
+            let untimed = OwnedOrderBook::new();
+            untimed.get_snapshot();
+            assert!(handle.render().is_empty());
+
+            let mut book = OwnedOrderBook::new().with_call_timing(true);
+            book.add_order("traderA", 1, Some(10050), 100, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+            book.modify_order(1, 10040, 100);
+            book.get_snapshot();
+            book.cancel_order(1);
+        });
+
+        let rendered = handle.render();
+        for operation in ["add_order", "modify_order", "get_snapshot", "cancel_order"] {
+            let count = format!(
+                "orderflow_ffi_call_seconds_count{{operation=\"{}\"}} 1",
+                operation
+            );
+            assert!(rendered.contains(&count), "{} missing", count);
+        }
+    }
+
     #[test]
     fn test_empty_book_snapshot() {
         let book = OwnedOrderBook::new();
@@ -298,6 +602,53 @@ mod tests {
         assert_eq!(r2.remaining_quantity, 0);
     }
 
+    #[test]
+    fn test_all_or_none_order_skipped_by_smaller_orders() {
+        let mut book = OwnedOrderBook::new();
+        book.add_all_or_none_order("seller", 1, 10000, 50, OB_SIDE_SELL, OB_STP_ALLOW);
+        book.add_order("seller", 2, Some(10010), 30, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        // Too small for the AON: trades behind it at the next level
+        let r = book.add_order("buyer", 3, Some(10010), 20, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_IOC, OB_STP_ALLOW);
+        assert_eq!(r.trades.len(), 1);
+        assert_eq!(r.trades[0].sell_order_id, 2);
+        assert_eq!(book.get_order(1).unwrap().quantity, 50);
+
+        // A GTC remainder would rest crossed against the AON: refused
+        let r = book.add_order("buyer", 6, Some(10000), 20, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        assert!(!r.accepted);
+        assert_eq!(r.reject_code, Some(RejectReason::AonUnfillable));
+        assert!(book.get_order(6).is_none());
+
+        // Large enough: takes the AON whole
+        let r = book.add_order("buyer", 4, Some(10000), 60, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_IOC, OB_STP_ALLOW);
+        assert_eq!(r.trades.len(), 1);
+        assert_eq!(r.trades[0].quantity, 50);
+        assert!(book.get_order(1).is_none());
+
+        // Incoming AON that crosses but can't fill in full is refused
+        let r = book.add_all_or_none_order("buyer", 5, 10010, 20, OB_SIDE_BUY, OB_STP_ALLOW);
+        assert!(!r.accepted);
+        assert!(r.reject_reason.unwrap().contains("AON"));
+    }
+
+    #[test]
+    fn test_post_only_order_rejected_when_crossing() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("seller", 1, Some(10000), 50, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let r = book.add_post_only_order("buyer", 2, 10000, 20, OB_SIDE_BUY, OB_STP_ALLOW);
+        assert!(!r.accepted);
+        assert_eq!(r.reject_reason.as_deref(), Some("post-only would cross"));
+        assert_eq!(r.reject_code, Some(RejectReason::PostOnlyWouldMatch));
+        assert_eq!(book.get_order(1).unwrap().quantity, 50);
+
+        // Below the ask it rests as usual
+        let r = book.add_post_only_order("buyer", 3, 9990, 20, OB_SIDE_BUY, OB_STP_ALLOW);
+        assert!(r.accepted);
+        assert_eq!(book.get_best_bid(), 9990);
+    }
+
     #[test]
     fn test_market_order() {
         let mut book = OwnedOrderBook::new();
@@ -321,6 +672,20 @@ mod tests {
         assert!(!book.cancel_order(999));
     }
 
+    #[test]
+    fn test_clear_empties_book() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("seller", 1, Some(10000), 50, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("buyer", 2, Some(10000), 20, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("buyer", 3, Some(9900), 20, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        assert_eq!(book.clear(), 2);
+        assert_eq!(book.get_best_bid(), 0);
+        assert_eq!(book.get_best_ask(), 0);
+        assert_eq!(book.get_last_trade_price(), 0);
+        assert!(book.get_order(1).is_none());
+    }
+
     #[test]
     fn test_modify_order() {
         let mut book = OwnedOrderBook::new();
@@ -347,10 +712,171 @@ mod tests {
         let m3 = book.modify_order(1, 10500, 60);
         assert!(!m3.accepted);
         assert!(m3.reject_reason.is_some());
+        assert_eq!(m3.reject_code, Some(RejectReason::CrossedSpread));
 
         // Not found
         let m4 = book.modify_order(999, 10000, 50);
         assert!(!m4.accepted);
+        assert_eq!(m4.reject_code, Some(RejectReason::NotFound));
+    }
+
+    #[test]
+    fn test_reduce_order() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("seller", 1, Some(10000), 100, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let r1 = book.reduce_order(1, 40);
+        assert!(r1.accepted);
+        assert_eq!(r1.old_quantity, 100);
+        assert_eq!(r1.new_quantity, 60);
+        assert_eq!(r1.old_price, 10000);
+        assert_eq!(r1.new_price, 10000);
+
+        // Over-reduce rejected, order untouched
+        let r2 = book.reduce_order(1, 61);
+        assert!(!r2.accepted);
+        assert!(r2.reject_reason.is_some());
+        assert_eq!(r2.reject_code, Some(RejectReason::ReduceExceedsRemaining));
+
+        // Reduce to zero removes the order
+        let r3 = book.reduce_order(1, 60);
+        assert!(r3.accepted);
+        assert_eq!(r3.new_quantity, 0);
+        assert_eq!(book.get_best_ask(), 0);
+        assert!(!book.cancel_order(1));
+    }
+
+    #[test]
+    fn test_queue_position() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("a", 1, Some(10000), 40, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(10000), 60, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("c", 3, Some(9900), 10, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let first = book.get_queue_position(1).unwrap();
+        assert_eq!(first.orders_ahead, 0);
+        assert_eq!(first.quantity_ahead, 0);
+
+        let second = book.get_queue_position(2).unwrap();
+        assert_eq!(second.orders_ahead, 1);
+        assert_eq!(second.quantity_ahead, 40);
+
+        // Other price levels don't count
+        assert_eq!(book.get_queue_position(3).unwrap().orders_ahead, 0);
+
+        assert!(book.get_queue_position(999).is_none());
+    }
+
+    #[test]
+    fn test_get_order_tracks_remaining() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("a", 1, Some(10000), 40, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(10000), 15, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let view = book.get_order(1).unwrap();
+        assert_eq!(
+            view,
+            OrderView {
+                side: OB_SIDE_SELL,
+                price: 10000,
+                quantity: 25,
+                original_quantity: 40,
+                filled_quantity: 15,
+            }
+        );
+        // Fully filled orders are gone
+        assert!(book.get_order(2).is_none());
+        assert!(book.get_order(999).is_none());
+    }
+
+    #[test]
+    fn test_depth_aggregates_levels_best_first() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("a", 1, Some(10000), 40, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(10000), 60, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("c", 3, Some(9900), 10, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("d", 4, Some(10100), 5, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let bids = book.get_depth(OB_SIDE_BUY, 10);
+        assert_eq!(bids.len(), 2);
+        assert_eq!(bids[0], DepthLevel { price: 10000, quantity: 100, order_count: 2 });
+        assert_eq!(bids[1].price, 9900);
+
+        assert_eq!(book.get_depth(OB_SIDE_SELL, 10).len(), 1);
+        assert_eq!(book.get_depth(OB_SIDE_BUY, 1).len(), 1);
+    }
+
+    #[test]
+    fn test_book_stats_sum_all_levels() {
+        let mut book = OwnedOrderBook::new();
+        assert_eq!(book.get_book_stats(), BookStats { bid_orders: 0, ask_orders: 0, bid_quantity: 0, ask_quantity: 0 });
+
+        book.add_order("a", 1, Some(10000), 40, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(9900), 60, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("c", 3, Some(10100), 5, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        assert_eq!(book.get_book_stats(), BookStats { bid_orders: 2, ask_orders: 1, bid_quantity: 100, ask_quantity: 5 });
+    }
+
+    #[test]
+    fn test_sweepable_quantity_counts_orders_in_matching_order() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("a", 1, Some(10000), 10, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(10000), 20, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("c", 3, Some(10100), 40, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        assert_eq!(book.get_sweepable_quantity(OB_SIDE_SELL, 1, None, 100), 10);
+        assert_eq!(book.get_sweepable_quantity(OB_SIDE_SELL, 3, None, 100), 70);
+        assert_eq!(book.get_sweepable_quantity(OB_SIDE_SELL, 10, Some(10000), 100), 30);
+        assert_eq!(book.get_sweepable_quantity(OB_SIDE_BUY, 10, None, 100), 0);
+    }
+
+    #[test]
+    fn test_sweepable_quantity_passes_over_untakeable_all_or_none() {
+        let mut book = OwnedOrderBook::new();
+        book.add_all_or_none_order("a", 1, 10000, 50, OB_SIDE_SELL, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(10000), 20, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        // Too small for the AON: only the order behind it counts
+        assert_eq!(book.get_sweepable_quantity(OB_SIDE_SELL, 1, None, 30), 20);
+        assert_eq!(book.get_sweepable_quantity(OB_SIDE_SELL, 2, None, 60), 70);
+    }
+
+    #[test]
+    fn test_estimate_fill_walks_opposite_side_from_best() {
+        let mut book = OwnedOrderBook::new();
+        book.add_order("a", 1, Some(9900), 10, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(9950), 5, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("c", 3, Some(10000), 10, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+        book.add_order("d", 4, Some(10010), 20, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let buy = book.estimate_fill(OB_SIDE_BUY, 25);
+        assert_eq!(buy.filled_quantity, 25);
+        assert_eq!(buy.cost_cents, 10 * 10000 + 15 * 10010);
+        assert_eq!(buy.worst_price, Some(10010));
+        assert_eq!(buy.levels, 2);
+
+        let sell = book.estimate_fill(OB_SIDE_SELL, 50);
+        assert_eq!(sell.filled_quantity, 15);
+        assert_eq!(sell.worst_price, Some(9900));
+
+        assert_eq!(OwnedOrderBook::new().estimate_fill(OB_SIDE_BUY, 10), FillEstimate::default());
+    }
+
+    #[test]
+    fn test_estimate_fill_passes_over_untakeable_all_or_none() {
+        let mut book = OwnedOrderBook::new();
+        book.add_all_or_none_order("a", 1, 10000, 50, OB_SIDE_SELL, OB_STP_ALLOW);
+        book.add_order("b", 2, Some(10010), 30, OB_SIDE_SELL, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
+
+        let small = book.estimate_fill(OB_SIDE_BUY, 20);
+        assert_eq!(small.filled_quantity, 20);
+        assert_eq!(small.cost_cents, 20 * 10010);
+        assert_eq!(small.levels, 1);
+
+        let large = book.estimate_fill(OB_SIDE_BUY, 60);
+        assert_eq!(large.filled_quantity, 60);
+        assert_eq!(large.cost_cents, 50 * 10000 + 10 * 10010);
     }
 
     #[test]
@@ -361,6 +887,7 @@ mod tests {
         let r = book.add_order("buyer", 2, Some(10000), 100, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_FOK, OB_STP_ALLOW);
         assert!(!r.accepted);
         assert!(r.reject_reason.is_some());
+        assert_eq!(r.reject_code, Some(RejectReason::FokUnfillable));
     }
 
     #[test]
@@ -380,9 +907,11 @@ mod tests {
         let mut book = OwnedOrderBook::new();
         let r1 = book.add_order("traderA", 1, Some(10000), 100, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
         assert!(r1.accepted);
+        assert_eq!(r1.reject_code, None);
 
         let r2 = book.add_order("traderA", 1, Some(10000), 100, OB_SIDE_BUY, OB_ORDER_TYPE_LIMIT, OB_TIF_GTC, OB_STP_ALLOW);
         assert!(!r2.accepted);
+        assert_eq!(r2.reject_code, Some(RejectReason::DuplicateId));
     }
 
     #[test]
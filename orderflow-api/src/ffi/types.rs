@@ -12,6 +12,7 @@ pub const OB_ORDER_TYPE_MARKET: u32 = 1;
 pub const OB_TIF_GTC: u32 = 0;
 pub const OB_TIF_IOC: u32 = 1;
 pub const OB_TIF_FOK: u32 = 2;
+pub const OB_TIF_GTD: u32 = 3;
 
 // Mirrors ob_stp_mode_t
 pub const OB_STP_ALLOW: u32 = 0;
@@ -20,6 +21,23 @@ pub const OB_STP_CANCEL_OLDEST: u32 = 2;
 pub const OB_STP_CANCEL_BOTH: u32 = 3;
 pub const OB_STP_DECREMENT_AND_CANCEL: u32 = 4;
 
+// Mirrors ob_reject_code_t
+pub const OB_REJECT_NONE: u32 = 0;
+pub const OB_REJECT_DUPLICATE_ID: u32 = 1;
+pub const OB_REJECT_INVALID_QUANTITY: u32 = 2;
+pub const OB_REJECT_MISSING_PRICE: u32 = 3;
+pub const OB_REJECT_INVALID_PRICE: u32 = 4;
+pub const OB_REJECT_MARKET_ORDER_GTC: u32 = 5;
+pub const OB_REJECT_INVALID_ALL_OR_NONE: u32 = 6;
+pub const OB_REJECT_INVALID_POST_ONLY: u32 = 7;
+pub const OB_REJECT_FOK_UNFILLABLE: u32 = 8;
+pub const OB_REJECT_NO_LIQUIDITY: u32 = 9;
+pub const OB_REJECT_POST_ONLY_WOULD_MATCH: u32 = 10;
+pub const OB_REJECT_AON_UNFILLABLE: u32 = 11;
+pub const OB_REJECT_NOT_FOUND: u32 = 12;
+pub const OB_REJECT_CROSSED_SPREAD: u32 = 13;
+pub const OB_REJECT_REDUCE_EXCEEDS_REMAINING: u32 = 14;
+
 #[repr(C)]
 pub struct ObOrderT {
     pub trader_id: *const c_char,
@@ -31,6 +49,8 @@ pub struct ObOrderT {
     pub time_in_force: u32,
     pub stp_mode: u32,
     pub has_price: bool,
+    pub all_or_none: bool,
+    pub post_only: bool,
 }
 
 #[repr(C)]
@@ -55,6 +75,7 @@ pub struct ObStpResultT {
 pub struct ObOrderResultT {
     pub accepted: bool,
     pub reject_reason: *mut c_char,
+    pub reject_code: u32,
     pub trades: *mut ObTradeT,
     pub trades_len: usize,
     pub remaining_quantity: i64,
@@ -65,6 +86,7 @@ pub struct ObOrderResultT {
 pub struct ObModifyResultT {
     pub accepted: bool,
     pub reject_reason: *mut c_char,
+    pub reject_code: u32,
     pub old_price: i64,
     pub new_price: i64,
     pub old_quantity: i64,
@@ -82,3 +104,48 @@ pub struct ObPriceDataT {
     pub last_trade_price: i64,
     pub last_trade_qty: i64,
 }
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ObQueuePositionT {
+    pub found: bool,
+    pub orders_ahead: usize,
+    pub quantity_ahead: i64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ObOrderInfoT {
+    pub found: bool,
+    pub side: u32,
+    pub price: i64,
+    pub quantity: i64,
+    pub original_quantity: i64,
+    pub filled_quantity: i64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ObDepthLevelT {
+    pub price: i64,
+    pub quantity: i64,
+    pub order_count: usize,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ObBookStatsT {
+    pub bid_orders: usize,
+    pub ask_orders: usize,
+    pub bid_quantity: i64,
+    pub ask_quantity: i64,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct ObFillEstimateT {
+    pub filled_quantity: i64,
+    pub cost: i64,
+    pub worst_price: i64,
+    pub levels: usize,
+}
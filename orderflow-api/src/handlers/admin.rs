@@ -0,0 +1,590 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::Ordering;
+
+use axum::extract::State;
+use axum::Json;
+use serde_json::json;
+
+use crate::config::RiskConfig;
+use crate::middleware::admin_auth::AdminActor;
+use crate::middleware::order_id::OrderId;
+use crate::models::admin::{
+    AdminAuditEntry, HaltStatus, PositionImportResult, Positions, QuoteOnlyStatus, ResetResult,
+};
+use crate::models::debug::DebugState;
+use crate::models::error::ApiError;
+use crate::models::market::{IntegrityReport, OpeningPrice};
+use crate::models::order::CancelResponse;
+use crate::state::AppState;
+
+pub async fn get_risk_config(
+    State(state): State<AppState>,
+    _actor: AdminActor,
+) -> Json<RiskConfig> {
+    Json(state.risk.config().clone())
+}
+
+pub async fn check_integrity(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+) -> Json<IntegrityReport> {
+    let was_halted = state.engine.is_halted();
+    let report = state.engine.check_integrity().await;
+    if report.halted && !was_halted {
+        state
+            .admin_audit
+            .admin_action(&actor, "integrity_halt", json!(false), json!(true));
+    }
+    Json(report)
+}
+
+pub async fn set_opening_price(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+    Json(req): Json<OpeningPrice>,
+) -> Result<Json<OpeningPrice>, ApiError> {
+    let symbol = req
+        .symbol
+        .unwrap_or_else(|| state.engine.symbol().to_string());
+    let old = state.risk.opening_price(&symbol);
+    state.risk.set_opening_price(&symbol, req.price)?;
+    state.admin_audit.config_changed(
+        &actor,
+        &format!("risk.openingPrices.{}", symbol),
+        json!(old),
+        json!(req.price),
+    );
+    Ok(Json(OpeningPrice {
+        symbol: Some(symbol),
+        price: req.price,
+    }))
+}
+
+/// Seed positions from a map of trader → symbol → position, e.g. overnight
+/// positions carried from another system. Listed positions are replaced.
+/// Positions already over the limit are taken anyway and reported back.
+pub async fn import_positions(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+    Json(positions): Json<Positions>,
+) -> Result<Json<PositionImportResult>, ApiError> {
+    if let Some(trader_id) = positions.keys().find(|t| t.is_empty() || t.len() > 64) {
+        return Err(ApiError::Validation(format!(
+            "Invalid trader id '{}': must be 1-64 characters",
+            trader_id
+        )));
+    }
+    let old: BTreeMap<&str, BTreeMap<&str, i64>> = positions
+        .iter()
+        .map(|(trader_id, by_symbol)| {
+            let old = by_symbol
+                .keys()
+                .map(|symbol| (symbol.as_str(), state.risk.get_position(trader_id, symbol)))
+                .collect();
+            (trader_id.as_str(), old)
+        })
+        .collect();
+    let over_limit = state.risk.import_positions(&positions);
+    state
+        .admin_audit
+        .admin_action(&actor, "positions_import", json!(old), json!(positions));
+    Ok(Json(PositionImportResult {
+        imported: positions.values().map(BTreeMap::len).sum(),
+        over_limit,
+    }))
+}
+
+/// Wipe every book, the engine's order and trade counts, and all positions,
+/// without a restart: for integration tests and end-of-day flattening.
+pub async fn reset(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+) -> Json<ResetResult> {
+    let result = state.order_service.reset().await;
+    state.admin_audit.admin_action(
+        &actor,
+        "reset",
+        json!({
+            "ordersRemoved": result.orders_removed,
+            "positionsCleared": result.positions_cleared
+        }),
+        json!(null),
+    );
+    Json(result)
+}
+
+pub async fn halt(State(state): State<AppState>, AdminActor(actor): AdminActor) -> Json<HaltStatus> {
+    set_halt(&state, &actor, true)
+}
+
+pub async fn resume(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+) -> Json<HaltStatus> {
+    set_halt(&state, &actor, false)
+}
+
+fn set_halt(state: &AppState, actor: &str, halted: bool) -> Json<HaltStatus> {
+    let was_halted = state.engine.is_halted();
+    state.engine.set_halted(halted);
+    let action = if halted { "halt" } else { "resume" };
+    state
+        .admin_audit
+        .admin_action(actor, action, json!(was_halted), json!(halted));
+    Json(HaltStatus { halted })
+}
+
+/// Switch quote-only mode, where orders that would trade on arrival are
+/// refused and only resting liquidity is accepted. A `symbol` switches that
+/// book only and must name one; without one every book is switched.
+pub async fn set_quote_only(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+    Json(req): Json<QuoteOnlyStatus>,
+) -> Result<Json<QuoteOnlyStatus>, ApiError> {
+    let symbols = match &req.symbol {
+        Some(symbol) if !state.engine.has_symbol(symbol) => {
+            return Err(ApiError::Validation(format!("Unknown symbol '{}'", symbol)));
+        }
+        Some(symbol) => vec![symbol.clone()],
+        None => state
+            .engine
+            .symbols()
+            .await
+            .into_iter()
+            .map(|s| s.symbol)
+            .collect(),
+    };
+    let was_quote_only: BTreeMap<&str, bool> = symbols
+        .iter()
+        .map(|symbol| (symbol.as_str(), state.engine.is_quote_only(symbol)))
+        .collect();
+    state
+        .engine
+        .set_quote_only(req.symbol.as_deref(), req.quote_only);
+    let now_quote_only: BTreeMap<&str, bool> = symbols
+        .iter()
+        .map(|symbol| (symbol.as_str(), req.quote_only))
+        .collect();
+    state.admin_audit.admin_action(
+        &actor,
+        "quote_only",
+        json!(was_quote_only),
+        json!(now_quote_only),
+    );
+    Ok(Json(req))
+}
+
+/// Pull any resting order, whoever owns it. The cancel is broadcast with
+/// reason `admin` and audited with the acting principal.
+pub async fn cancel_order(
+    State(state): State<AppState>,
+    AdminActor(actor): AdminActor,
+    OrderId(order_id): OrderId,
+) -> Result<Json<CancelResponse>, ApiError> {
+    let owner = state.risk.trader_for_order(order_id);
+    let response = state.order_service.admin_cancel(order_id).await?;
+    state.admin_audit.admin_action(
+        &actor,
+        "cancel_order",
+        json!({ "orderId": order_id, "traderId": owner }),
+        json!({ "orderId": order_id, "cancelled": true }),
+    );
+    Ok(Json(response))
+}
+
+pub async fn get_audit_log(
+    State(state): State<AppState>,
+    _actor: AdminActor,
+) -> Json<Vec<AdminAuditEntry>> {
+    Json(state.admin_audit.entries())
+}
+
+/// Only routed when `server.debug_endpoints` is set, on the debug listener.
+pub async fn get_debug_state(
+    State(state): State<AppState>,
+    _actor: AdminActor,
+) -> Json<DebugState> {
+    let order_registry = state.risk.registered_orders();
+    let mut open_order_counts = std::collections::BTreeMap::new();
+    for order in &order_registry {
+        *open_order_counts.entry(order.trader_id.clone()).or_insert(0) += 1;
+    }
+
+    Json(DebugState {
+        positions: state.risk.positions(),
+        open_order_counts,
+        order_registry,
+        rate_limiter_keys: state.rate_limiter.bucket_keys(),
+        ws_connections: state.ws_connections.load(Ordering::Relaxed),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::engine::reject::RejectReason;
+    use crate::models::order::*;
+
+    #[tokio::test]
+    async fn test_risk_config_matches_loaded() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use axum::routing::get;
+        use tower::ServiceExt;
+
+        let mut config = Config::default();
+        config.risk.max_order_size = 5_000;
+        config.risk.price_band_percent = 2.5;
+        config.risk.allowed_traders = vec!["pilot".into()];
+        config
+            .server
+            .admin_api_keys
+            .insert("secret".into(), "ops".into());
+        let app = axum::Router::new()
+            .route("/risk-config", get(get_risk_config))
+            .with_state(AppState::new(&config));
+        let request = |key: Option<&str>| {
+            let builder = Request::builder().uri("/risk-config");
+            let builder = match key {
+                Some(key) => builder.header("X-Admin-Key", key),
+                None => builder,
+            };
+            builder.body(Body::empty()).unwrap()
+        };
+
+        // Overrides and allow-lists are for admins only
+        let resp = app.clone().oneshot(request(None)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let resp = app.oneshot(request(Some("secret"))).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(body, serde_json::to_value(&config.risk).unwrap());
+        assert_eq!(body["maxOrderSize"], 5_000);
+        assert_eq!(body["priceBandPercent"], 2.5);
+        assert_eq!(body["allowedTraders"][0], "pilot");
+    }
+
+    #[tokio::test]
+    async fn test_debug_state_after_activity() {
+        let state = AppState::new(&Config::default());
+        let order =
+            |trader: &str, side, quantity| OrderRequest::limit(trader, 100.0, quantity, side);
+        let svc = &state.order_service;
+        svc.submit_order(order("alice", Side::Sell, 10)).await.unwrap();
+        svc.submit_order(order("alice", Side::Sell, 10)).await.unwrap();
+        svc.submit_order(order("bob", Side::Buy, 5)).await.unwrap();
+
+        let Json(debug) = get_debug_state(State(state.clone()), AdminActor("ops".into())).await;
+
+        assert_eq!(debug.positions["alice"]["DEFAULT"], -5);
+        assert_eq!(debug.positions["bob"]["DEFAULT"], 5);
+        assert_eq!(debug.open_order_counts["alice"], 2);
+        assert!(!debug.open_order_counts.contains_key("bob"));
+        assert_eq!(debug.order_registry.len(), 2);
+        assert_eq!(debug.order_registry[0].resting_quantity, 5);
+        assert_eq!(debug.rate_limiter_keys, vec!["alice", "bob"]);
+        assert_eq!(debug.ws_connections, 0);
+    }
+
+    #[tokio::test]
+    async fn test_halt_records_admin_action_with_actor() {
+        let state = AppState::new(&Config::default());
+
+        let Json(status) = halt(State(state.clone()), AdminActor("ops-oncall".into())).await;
+        assert!(status.halted);
+        assert!(state.engine.is_halted());
+
+        let entries = state.admin_audit.entries();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].event, "AdminAction");
+        assert_eq!(entries[0].actor, "ops-oncall");
+        assert_eq!(entries[0].action, "halt");
+        assert_eq!(entries[0].old_value, json!(false));
+        assert_eq!(entries[0].new_value, json!(true));
+        assert!(entries[0].timestamp_ns > 0);
+    }
+
+    #[tokio::test]
+    async fn test_quote_only_rejects_crossing_orders_only() {
+        let state = AppState::new(&Config::default());
+        let order = |trader: &str, side, price| OrderRequest::limit(trader, price, 10, side);
+        let eth = |trader: &str, side, price| OrderRequest {
+            symbol: Some("ETH-USD".into()),
+            ..order(trader, side, price)
+        };
+        let svc = &state.order_service;
+        svc.submit_order(order("mm", Side::Sell, 101.0))
+            .await
+            .unwrap();
+        svc.submit_order(eth("mm", Side::Sell, 101.0))
+            .await
+            .unwrap();
+
+        let Json(status) = set_quote_only(
+            State(state.clone()),
+            AdminActor("ops".into()),
+            Json(QuoteOnlyStatus {
+                symbol: Some("DEFAULT".into()),
+                quote_only: true,
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(status.quote_only);
+        let entry = &state.admin_audit.entries()[0];
+        assert_eq!(entry.action, "quote_only");
+        assert_eq!(entry.old_value, json!({ "DEFAULT": false }));
+        assert_eq!(entry.new_value, json!({ "DEFAULT": true }));
+
+        // Resting below the ask is fine; lifting it is not
+        let resp = svc
+            .submit_order(order("bob", Side::Buy, 100.0))
+            .await
+            .unwrap();
+        assert_eq!(resp.remaining_quantity, 10);
+        let err = svc
+            .submit_order(order("bob", Side::Buy, 101.0))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ApiError::EngineRejection(c) if c.reason == RejectReason::QuoteOnly));
+
+        // Other books keep trading
+        let resp = svc
+            .submit_order(eth("bob", Side::Buy, 101.0))
+            .await
+            .unwrap();
+        assert_eq!(resp.trades.len(), 1);
+        assert!(!state.engine.is_quote_only("ETH-USD"));
+
+        let unknown = set_quote_only(
+            State(state.clone()),
+            AdminActor("ops".into()),
+            Json(QuoteOnlyStatus {
+                symbol: Some("XYZ".into()),
+                quote_only: false,
+            }),
+        )
+        .await;
+        assert!(matches!(unknown, Err(ApiError::Validation(_))));
+        assert!(state.engine.is_quote_only("DEFAULT"));
+
+        // Without a symbol every book switches
+        let Json(status) = set_quote_only(
+            State(state.clone()),
+            AdminActor("ops".into()),
+            Json(QuoteOnlyStatus {
+                symbol: None,
+                quote_only: true,
+            }),
+        )
+        .await
+        .unwrap();
+        assert!(status.symbol.is_none());
+        assert!(state.engine.is_quote_only("ETH-USD"));
+        assert_eq!(
+            state.admin_audit.entries()[1].old_value,
+            json!({ "DEFAULT": true, "ETH-USD": false })
+        );
+    }
+
+    #[tokio::test]
+    async fn test_position_import_reports_and_audits() {
+        let state = AppState::new(&Config::default());
+        let import = |positions: Positions| {
+            import_positions(
+                State(state.clone()),
+                AdminActor("ops".into()),
+                Json(positions),
+            )
+        };
+        let limit = state.risk.config().max_position_per_trader;
+
+        let held = |symbol: &str, position| BTreeMap::from([(symbol.to_string(), position)]);
+        let positions = BTreeMap::from([
+            ("alice".into(), held("DEFAULT", 10)),
+            ("bob".into(), held("ETH-USD", -limit - 1)),
+        ]);
+        let Json(result) = import(positions).await.unwrap();
+        assert_eq!(result.imported, 2);
+        assert_eq!(result.over_limit, ["bob"]);
+        assert_eq!(state.risk.get_position("bob", "ETH-USD"), -limit - 1);
+        assert_eq!(state.risk.get_position("bob", "DEFAULT"), 0);
+
+        let entry = &state.admin_audit.entries()[0];
+        assert_eq!(entry.action, "positions_import");
+        assert_eq!(
+            entry.old_value,
+            json!({"alice": {"DEFAULT": 0}, "bob": {"ETH-USD": 0}})
+        );
+
+        let bad = import(BTreeMap::from([(String::new(), held("DEFAULT", 1))])).await;
+        assert!(matches!(bad, Err(ApiError::Validation(_))));
+    }
+
+    #[tokio::test]
+    async fn test_opening_price_change_audited() {
+        let state = AppState::new(&Config::default());
+
+        let Json(set) = set_opening_price(
+            State(state.clone()),
+            AdminActor("ops".into()),
+            Json(OpeningPrice {
+                symbol: None,
+                price: 101.5,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(set.price, 101.5);
+        assert_eq!(set.symbol.as_deref(), Some("DEFAULT"));
+
+        let entry = &state.admin_audit.entries()[0];
+        assert_eq!(entry.event, "ConfigChanged");
+        assert_eq!(entry.action, "risk.openingPrices.DEFAULT");
+        assert_eq!(entry.old_value, json!(null));
+        assert_eq!(entry.new_value, json!(101.5));
+        assert_eq!(state.risk.opening_price("DEFAULT"), Some(101.5));
+        assert_eq!(state.risk.opening_price("ETH-USD"), None);
+    }
+
+    #[tokio::test]
+    async fn test_admin_key_resolves_principal() {
+        use axum::extract::FromRequestParts;
+        use axum::http::Request;
+
+        let mut config = Config::default();
+        config
+            .server
+            .admin_api_keys
+            .insert("secret".into(), "alice-ops".into());
+        let state = AppState::new(&config);
+
+        let (mut parts, _) = Request::builder()
+            .header("X-Admin-Key", "secret")
+            .body(())
+            .unwrap()
+            .into_parts();
+        let AdminActor(actor) = AdminActor::from_request_parts(&mut parts, &state)
+            .await
+            .unwrap();
+        assert_eq!(actor, "alice-ops");
+
+        let (mut parts, _) = Request::builder().body(()).unwrap().into_parts();
+        assert!(matches!(
+            AdminActor::from_request_parts(&mut parts, &state).await,
+            Err(ApiError::Unauthorized(_))
+        ));
+
+        let (mut parts, _) = Request::builder()
+            .header("X-Admin-Key", "guess")
+            .body(())
+            .unwrap()
+            .into_parts();
+        assert!(matches!(
+            AdminActor::from_request_parts(&mut parts, &state).await,
+            Err(ApiError::Forbidden(_))
+        ));
+
+        // Without any keys configured nothing gets through
+        let state = AppState::new(&Config::default());
+        let (mut parts, _) = Request::builder().body(()).unwrap().into_parts();
+        assert!(matches!(
+            AdminActor::from_request_parts(&mut parts, &state).await,
+            Err(ApiError::Forbidden(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_reset_wipes_book_and_positions() {
+        let state = AppState::new(&Config::default());
+        let order = |trader_id: &str, side, symbol: Option<&str>| OrderRequest {
+            symbol: symbol.map(Into::into),
+            ..OrderRequest::limit(trader_id, 100.0, 10, side)
+        };
+        let svc = &state.order_service;
+        for (trader_id, side) in [("alice", Side::Buy), ("bob", Side::Sell)] {
+            svc.submit_order(order(trader_id, side, None))
+                .await
+                .unwrap();
+        }
+        let resting = svc
+            .submit_order(order("alice", Side::Buy, None))
+            .await
+            .unwrap();
+        svc.submit_order(order("carol", Side::Sell, Some("ETH")))
+            .await
+            .unwrap();
+        let mut rx = state.ws_broadcast.subscribe();
+
+        let Json(result) = reset(State(state.clone()), AdminActor("ops".into())).await;
+        assert_eq!(result.orders_removed, 2);
+        assert_eq!(result.positions_cleared, 2);
+        assert!(state.engine.get_snapshot().await.best_bid.is_none());
+        let eth = state.engine.get_snapshot_for("ETH").await.unwrap();
+        assert!(eth.best_ask.is_none());
+        assert_eq!(state.engine.total_orders(), 0);
+        assert_eq!(state.risk.get_position("alice", "DEFAULT"), 0);
+        assert!(state.risk.trader_for_order(resting.order_id).is_none());
+        assert!(matches!(
+            svc.order_history(resting.order_id),
+            Err(ApiError::NotFound(_))
+        ));
+
+        let msg: serde_json::Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(msg["type"], "bookReset");
+        assert_eq!(msg["seq"], result.seq);
+        assert_eq!(state.admin_audit.entries().pop().unwrap().action, "reset");
+
+        // Trading picks up on the empty book
+        let resp = svc
+            .submit_order(order("bob", Side::Sell, None))
+            .await
+            .unwrap();
+        assert!(resp.trades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_admin_cancels_order_it_does_not_own() {
+        let state = AppState::new(&Config::default());
+        let resp = state
+            .order_service
+            .submit_order(OrderRequest::limit("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        let mut rx = state.ws_broadcast.subscribe();
+
+        let Json(cancelled) = cancel_order(
+            State(state.clone()),
+            AdminActor("ops-oncall".into()),
+            OrderId(resp.order_id),
+        )
+        .await
+        .unwrap();
+        assert_eq!(cancelled.reason, CancelReason::Admin);
+        assert!(state.engine.get_snapshot().await.best_bid.is_none());
+
+        let msg: serde_json::Value = serde_json::from_str(&rx.try_recv().unwrap()).unwrap();
+        assert_eq!(msg["type"], "orderCancelled");
+        assert_eq!(msg["data"]["reason"], "admin");
+
+        let entry = state.admin_audit.entries().pop().unwrap();
+        assert_eq!(entry.actor, "ops-oncall");
+        assert_eq!(entry.action, "cancel_order");
+        assert_eq!(entry.old_value["traderId"], "alice");
+
+        let again = cancel_order(
+            State(state),
+            AdminActor("ops-oncall".into()),
+            OrderId(resp.order_id),
+        )
+        .await;
+        assert!(matches!(again, Err(ApiError::NotFound(_))));
+    }
+}
@@ -0,0 +1,109 @@
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use futures_util::stream;
+use tokio::sync::broadcast;
+
+use super::websocket::MAX_WS_CONNECTIONS;
+use crate::state::AppState;
+
+/// Holds a slot in the shared streaming-connection count for as long as the
+/// SSE stream is alive.
+struct ConnectionGuard(Arc<AtomicU64>);
+
+impl Drop for ConnectionGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+        tracing::info!(
+            event = "SseDisconnected",
+            active = self.0.load(Ordering::Relaxed)
+        );
+    }
+}
+
+/// Server-Sent Events view of the same broadcast feed the WebSocket serves.
+/// Shares the WebSocket connection cap.
+pub async fn sse_events(State(state): State<AppState>) -> Response {
+    let current = state.ws_connections.load(Ordering::Relaxed);
+    if current >= MAX_WS_CONNECTIONS {
+        return (
+            axum::http::StatusCode::SERVICE_UNAVAILABLE,
+            "Too many streaming connections",
+        )
+            .into_response();
+    }
+
+    state.ws_connections.fetch_add(1, Ordering::Relaxed);
+    tracing::info!(
+        event = "SseConnected",
+        active = state.ws_connections.load(Ordering::Relaxed)
+    );
+    let guard = ConnectionGuard(Arc::clone(&state.ws_connections));
+    let rx = state.ws_broadcast.subscribe();
+
+    let events = stream::unfold((rx, guard), |(mut rx, guard)| async move {
+        let data = match rx.recv().await {
+            Ok(text) => text,
+            Err(broadcast::error::RecvError::Lagged(n)) => {
+                tracing::warn!(event = "SseLagged", skipped = n);
+                serde_json::json!({
+                    "type": "error",
+                    "data": { "message": format!("Missed {} messages", n) }
+                })
+                .to_string()
+            }
+            Err(broadcast::error::RecvError::Closed) => return None,
+        };
+        Some((
+            Ok::<_, Infallible>(Event::default().data(data)),
+            (rx, guard),
+        ))
+    });
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use futures_util::StreamExt;
+
+    #[tokio::test]
+    async fn test_broadcast_message_arrives_as_sse_event() {
+        let state = AppState::new(&Config::default());
+        let response = sse_events(State(state.clone())).await;
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+        assert_eq!(state.ws_connections.load(Ordering::Relaxed), 1);
+
+        let msg = r#"{"type":"orderCancelled","data":{"orderId":7}}"#;
+        state.ws_broadcast.send(msg.to_string()).unwrap();
+
+        let mut body = response.into_body().into_data_stream();
+        let frame = body.next().await.unwrap().unwrap();
+        assert_eq!(frame, format!("data: {}\n\n", msg));
+
+        drop(body);
+        assert_eq!(state.ws_connections.load(Ordering::Relaxed), 0);
+    }
+
+    #[tokio::test]
+    async fn test_sse_respects_connection_cap() {
+        let state = AppState::new(&Config::default());
+        state
+            .ws_connections
+            .store(MAX_WS_CONNECTIONS, Ordering::Relaxed);
+
+        let response = sse_events(State(state)).await;
+        assert_eq!(
+            response.status(),
+            axum::http::StatusCode::SERVICE_UNAVAILABLE
+        );
+    }
+}
@@ -2,6 +2,8 @@ use axum::extract::State;
 use axum::Json;
 use serde::Serialize;
 
+use crate::engine::orderbook::unix_nanos;
+use crate::models::trade::ActivityStats;
 use crate::state::AppState;
 
 #[derive(Serialize)]
@@ -9,17 +11,27 @@ use crate::state::AppState;
 pub struct HealthResponse {
     pub status: &'static str,
     pub uptime_seconds: u64,
+    /// Whether new orders are accepted under the trading-hours schedule
+    pub market_open: bool,
     pub total_orders: u64,
     pub total_trades: u64,
+    pub activity: ActivityStats,
 }
 
 pub async fn health_check(
     State(state): State<AppState>,
 ) -> Json<HealthResponse> {
+    let status = if state.order_service.warmup_remaining().is_some() {
+        "warming_up"
+    } else {
+        "healthy"
+    };
     Json(HealthResponse {
-        status: "healthy",
+        status,
         uptime_seconds: state.start_time.elapsed().as_secs(),
+        market_open: state.order_service.market_open(),
         total_orders: state.engine.total_orders(),
         total_trades: state.engine.total_trades(),
+        activity: state.trade_history.activity(unix_nanos()),
     })
 }
@@ -1,11 +1,265 @@
-use axum::extract::State;
+use axum::extract::{Query, State};
 use axum::Json;
 
-use crate::models::market::MarketSnapshot;
+use crate::models::error::ApiError;
+use crate::models::market::{
+    BookStats, DepthQuery, EstimateQuery, ExecutionEstimate, MarketDepth, MarketSnapshot,
+    OrderConstraints, SymbolQuery, SymbolsResponse, Ticker,
+};
 use crate::state::AppState;
 
+const DEFAULT_DEPTH_LEVELS: usize = 10;
+
+/// Snapshot of the `?symbol=` book, or of the default symbol's without one.
 pub async fn get_market_snapshot(
     State(state): State<AppState>,
-) -> Json<MarketSnapshot> {
-    Json(state.engine.get_snapshot().await)
+    Query(query): Query<SymbolQuery>,
+) -> Result<Json<MarketSnapshot>, ApiError> {
+    let snapshot = match &query.symbol {
+        Some(symbol) => state.engine.get_snapshot_for(symbol).await?,
+        None => state.engine.get_snapshot().await,
+    };
+    Ok(Json(snapshot))
+}
+
+pub async fn get_ticker(State(state): State<AppState>) -> Json<Ticker> {
+    Json(state.engine.get_ticker().await)
+}
+
+/// Aggregated depth, `levels` per side up to `server.max_depth_levels`.
+pub async fn get_depth(
+    State(state): State<AppState>,
+    Query(query): Query<DepthQuery>,
+) -> Result<Json<MarketDepth>, ApiError> {
+    let max = state.max_depth_levels;
+    let levels = query.levels.unwrap_or(DEFAULT_DEPTH_LEVELS.min(max));
+    if levels == 0 || levels > max {
+        return Err(ApiError::Validation(format!(
+            "levels must be between 1 and {}, got {}",
+            max, levels
+        )));
+    }
+    Ok(Json(state.engine.get_market_depth(levels).await))
+}
+
+pub async fn get_book_stats(State(state): State<AppState>) -> Json<BookStats> {
+    Json(state.engine.get_book_stats().await)
+}
+
+pub async fn get_symbols(State(state): State<AppState>) -> Json<SymbolsResponse> {
+    let mut symbols = state.engine.symbols().await;
+    // A tripped circuit breaker halts its own symbol only
+    for status in &mut symbols {
+        status.halted |= state.order_service.circuit_breaker_tripped(&status.symbol);
+    }
+    Json(SymbolsResponse { symbols })
+}
+
+/// Estimated fill for a hypothetical market order, walked over the current
+/// depth. Read-only: nothing is submitted.
+pub async fn get_estimate(
+    State(state): State<AppState>,
+    Query(query): Query<EstimateQuery>,
+) -> Result<Json<ExecutionEstimate>, ApiError> {
+    let estimate = state
+        .engine
+        .estimate_execution(query.side, query.quantity)
+        .await?;
+    Ok(Json(estimate))
+}
+
+/// Validation limits from the effective risk config.
+pub async fn get_constraints(State(state): State<AppState>) -> Json<OrderConstraints> {
+    let risk = state.risk.config();
+    Json(OrderConstraints {
+        symbol: state.engine.symbol().to_string(),
+        tick_size: risk.tick_size,
+        lot_size: risk.lot_size,
+        min_order_size: risk.min_order_size,
+        max_order_size: risk.max_order_size,
+        price_band_percent: risk.price_band_percent,
+        one_sided_price_band_percent: (risk.one_sided_price_band_percent > 0.0)
+            .then_some(risk.one_sided_price_band_percent),
+        max_order_notional: risk.max_order_notional,
+        max_position_per_trader: risk.max_position_per_trader,
+        max_orders_per_second: risk.max_orders_per_second,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::market::BookLevel;
+    use crate::models::order::{OrderRequest, Side};
+
+    #[tokio::test]
+    async fn test_estimate_reports_vwap_over_known_book() {
+        let state = AppState::new(&Config::default());
+        for (price, quantity) in [(100.00, 10), (100.10, 20), (100.50, 30)] {
+            state
+                .order_service
+                .submit_order(OrderRequest::limit("mm", price, quantity, Side::Sell))
+                .await
+                .unwrap();
+        }
+        let estimate = |side, quantity| {
+            get_estimate(
+                State(state.clone()),
+                Query(EstimateQuery { side, quantity }),
+            )
+        };
+
+        // 10 @ 100.00 + 20 @ 100.10 + 10 @ 100.50 = 4007.00
+        let Json(buy) = estimate(Side::Buy, 40).await.unwrap();
+        assert!(buy.fully_fillable);
+        assert_eq!(buy.fillable_quantity, 40);
+        assert!((buy.total_cost - 4007.0).abs() < 1e-9);
+        assert!((buy.average_price.unwrap() - 100.175).abs() < 1e-9);
+        assert_eq!(buy.worst_price, Some(100.50));
+        assert_eq!(buy.levels, 3);
+
+        let Json(too_big) = estimate(Side::Buy, 100).await.unwrap();
+        assert!(!too_big.fully_fillable);
+        assert_eq!(too_big.fillable_quantity, 60);
+
+        let Json(no_bids) = estimate(Side::Sell, 10).await.unwrap();
+        assert_eq!(no_bids.fillable_quantity, 0);
+        assert!(no_bids.average_price.is_none());
+
+        // The estimate leaves the book untouched
+        assert_eq!(state.engine.get_depth(10).await.asks.len(), 3);
+        assert!(matches!(
+            estimate(Side::Buy, 0).await,
+            Err(ApiError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_depth_aggregates_levels_and_caps_request() {
+        let mut config = Config::default();
+        config.server.max_depth_levels = 2;
+        let state = AppState::new(&config);
+        let orders = [
+            ("a", 99.50, 10, Side::Buy),
+            ("b", 99.50, 15, Side::Buy),
+            ("c", 99.00, 5, Side::Buy),
+            ("d", 98.00, 5, Side::Buy),
+            ("e", 100.25, 7, Side::Sell),
+        ];
+        for (trader, price, quantity, side) in orders {
+            state
+                .order_service
+                .submit_order(OrderRequest::limit(trader, price, quantity, side))
+                .await
+                .unwrap();
+        }
+        let depth = |levels| get_depth(State(state.clone()), Query(DepthQuery { levels }));
+
+        let levels = |side: &[BookLevel]| {
+            side.iter()
+                .map(|l| (l.price, l.quantity, l.order_count))
+                .collect::<Vec<_>>()
+        };
+
+        let Json(depth_2) = depth(Some(2)).await.unwrap();
+        assert_eq!(levels(&depth_2.bids), [(99.50, 25, 2), (99.00, 5, 1)]);
+        assert_eq!(levels(&depth_2.asks), [(100.25, 7, 1)]);
+
+        // Defaults to 10 levels, capped at the configured maximum
+        assert_eq!(depth(None).await.unwrap().bids.len(), 2);
+        for levels in [0, 3] {
+            assert!(matches!(
+                depth(Some(levels)).await,
+                Err(ApiError::Validation(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_by_symbol() {
+        let state = AppState::new(&Config::default());
+        state
+            .order_service
+            .submit_order(OrderRequest {
+                symbol: Some("ETH-USD".into()),
+                ..OrderRequest::limit("mm", 2_500.00, 10, Side::Buy)
+            })
+            .await
+            .unwrap();
+        let snapshot = |symbol: Option<&str>| {
+            get_market_snapshot(
+                State(state.clone()),
+                Query(SymbolQuery {
+                    symbol: symbol.map(Into::into),
+                }),
+            )
+        };
+
+        let Json(eth) = snapshot(Some("ETH-USD")).await.unwrap();
+        assert_eq!(eth.best_bid, Some(2_500.00));
+        let Json(default) = snapshot(None).await.unwrap();
+        assert!(default.best_bid.is_none());
+        assert!(matches!(
+            snapshot(Some("BTC-USD")).await,
+            Err(ApiError::UnknownSymbol(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_halts_only_its_symbol() {
+        let mut config = Config::default();
+        config.risk.circuit_breaker_percent = Some(5.0);
+        let state = AppState::new(&config);
+        let svc = &state.order_service;
+        for (price, seller) in [(100.0, "alice"), (106.0, "carol")] {
+            svc.submit_order(OrderRequest::limit(seller, price, 10, Side::Sell))
+                .await
+                .unwrap();
+            svc.submit_order(OrderRequest::limit("bob", price, 10, Side::Buy))
+                .await
+                .unwrap();
+        }
+        svc.submit_order(OrderRequest {
+            symbol: Some("ETH-USD".into()),
+            ..OrderRequest::limit("mm", 2_500.00, 10, Side::Buy)
+        })
+        .await
+        .unwrap();
+
+        let Json(resp) = get_symbols(State(state.clone())).await;
+        let halted: Vec<_> = resp
+            .symbols
+            .iter()
+            .map(|s| (s.symbol.as_str(), s.halted))
+            .collect();
+        assert_eq!(halted, [("DEFAULT", true), ("ETH-USD", false)]);
+    }
+
+    #[tokio::test]
+    async fn test_constraints_reflect_loaded_config() {
+        let mut config = Config::default();
+        config.risk.tick_size = 0.05;
+        config.risk.min_order_size = 10;
+        config.risk.max_order_size = 5_000;
+        config.risk.price_band_percent = 2.5;
+        config.risk.one_sided_price_band_percent = 0.0;
+        config.risk.max_order_notional = Some(250_000.0);
+
+        let Json(constraints) = get_constraints(State(AppState::new(&config))).await;
+        let body = serde_json::to_value(&constraints).unwrap();
+
+        assert_eq!(body["symbol"], "DEFAULT");
+        assert_eq!(body["tickSize"], 0.05);
+        assert_eq!(body["lotSize"], 1);
+        assert_eq!(body["minOrderSize"], 10);
+        assert_eq!(body["maxOrderSize"], 5_000);
+        assert_eq!(body["priceBandPercent"], 2.5);
+        assert!(body.get("oneSidedPriceBandPercent").is_none());
+        assert_eq!(body["maxOrderNotional"], 250_000.0);
+        assert_eq!(
+            body["maxPositionPerTrader"],
+            config.risk.max_position_per_trader
+        );
+    }
 }
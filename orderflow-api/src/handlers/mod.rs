@@ -1,4 +1,7 @@
+pub mod admin;
+pub mod events;
 pub mod health;
 pub mod market;
 pub mod orders;
+pub mod trades;
 pub mod websocket;
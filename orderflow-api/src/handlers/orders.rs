@@ -1,32 +1,732 @@
-use axum::extract::{Path, State};
-use axum::http::StatusCode;
+use axum::extract::{Path, Query, State};
+use axum::http::{HeaderMap, StatusCode};
 use axum::Json;
 
+use crate::middleware::json_body::JsonBody;
+use crate::middleware::order_id::OrderId;
 use crate::models::error::ApiError;
 use crate::models::order::*;
+use crate::models::trade::ExecutionsResponse;
+use crate::services::idempotency::KeyClaim;
 use crate::state::AppState;
 
+const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
 pub async fn submit_order(
     State(state): State<AppState>,
-    Json(req): Json<OrderRequest>,
+    headers: HeaderMap,
+    JsonBody(req): JsonBody<OrderRequest>,
 ) -> Result<(StatusCode, Json<OrderResponse>), ApiError> {
-    let response = state.order_service.submit_order(req).await?;
+    submit(&state, &headers, req).await
+}
+
+pub async fn submit_order_simple(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<SimpleOrderQuery>,
+) -> Result<(StatusCode, Json<OrderResponse>), ApiError> {
+    submit(&state, &headers, query.into()).await
+}
+
+/// Submit up to `server.max_batch_orders` orders in order, answering with
+/// one result per order in the same order. Each order is handled as its own
+/// submit; the batch is not transactional and earlier orders' fills affect
+/// later ones. Idempotency keys are not supported here.
+pub async fn submit_batch(
+    State(state): State<AppState>,
+    JsonBody(reqs): JsonBody<Vec<OrderRequest>>,
+) -> Result<Json<Vec<OrderOutcome>>, ApiError> {
+    if reqs.is_empty() || reqs.len() > state.max_batch_orders {
+        return Err(ApiError::Validation(format!(
+            "A batch must hold between 1 and {} orders, got {}",
+            state.max_batch_orders,
+            reqs.len()
+        )));
+    }
+    let results = state
+        .order_service
+        .submit_batch(reqs)
+        .await
+        .into_iter()
+        .map(|result| {
+            result
+                .map(|mut response| {
+                    response.truncate_trades(state.max_inline_trades);
+                    response
+                })
+                .into()
+        })
+        .collect();
+    Ok(Json(results))
+}
+
+async fn submit(
+    state: &AppState,
+    headers: &HeaderMap,
+    req: OrderRequest,
+) -> Result<(StatusCode, Json<OrderResponse>), ApiError> {
+    let key = match headers.get(IDEMPOTENCY_KEY_HEADER) {
+        Some(value) => Some(
+            value
+                .to_str()
+                .map_err(|_| ApiError::Validation("Idempotency-Key must be ASCII".into()))?
+                .to_string(),
+        ),
+        None => None,
+    };
+    // A retry under a completed key gets the original response back. In
+    // upsert mode, a resubmission that differs from what the key last
+    // applied modifies the order it created instead.
+    let trader_id = req.trader_id.clone();
+    if let Some(key) = &key {
+        match state.idempotency.reserve(&trader_id, key)? {
+            KeyClaim::Reserved => {}
+            KeyClaim::Completed(done) if state.idempotency_key_upsert && done.request != req => {
+                let response = upsert(state, done.response.order_id, &req).await?;
+                state.idempotency.complete(&trader_id, key, &req, &response);
+                return Ok((StatusCode::OK, Json(response)));
+            }
+            KeyClaim::Completed(done) => return Ok((StatusCode::OK, Json(done.response))),
+        }
+    }
+
+    let mut response = match state.order_service.submit_order(req.clone()).await {
+        Ok(resp) => resp,
+        Err(e) => {
+            if let Some(key) = &key {
+                state.idempotency.release(&trader_id, key);
+            }
+            return Err(e);
+        }
+    };
+
+    response.truncate_trades(state.max_inline_trades);
+    if let Some(key) = &key {
+        state.idempotency.complete(&trader_id, key, &req, &response);
+    }
     Ok((StatusCode::CREATED, Json(response)))
 }
 
+/// Apply a resubmitted order to the one its key already created, as a modify
+/// to the new price and quantity. The quantity is the order's full size, so
+/// what has already filled comes off it. Only a limit order from the same
+/// trader, for the same symbol and on the same side can be modified; the
+/// order must still be resting.
+async fn upsert(
+    state: &AppState,
+    order_id: u64,
+    req: &OrderRequest,
+) -> Result<OrderResponse, ApiError> {
+    let new_price = match (req.order_type, req.price) {
+        (OrderType::Limit, Some(price)) => price,
+        _ => {
+            return Err(ApiError::Validation(
+                "Only a limit order can replace an existing order by key".into(),
+            ))
+        }
+    };
+    match state.risk.trader_for_order(order_id) {
+        None => return Err(ApiError::NotFound(order_id)),
+        Some(owner) if owner != req.trader_id => {
+            return Err(ApiError::Forbidden(format!(
+                "Order {} belongs to a different trader",
+                order_id
+            )))
+        }
+        Some(_) => {}
+    }
+    let symbol = req.symbol.as_deref().unwrap_or(state.engine.symbol());
+    let resting_symbol = state
+        .engine
+        .order_symbol(order_id)
+        .ok_or(ApiError::NotFound(order_id))?;
+    if resting_symbol != symbol {
+        return Err(ApiError::Validation(format!(
+            "Order {} is for {}, not {}; cancel it and submit a new order",
+            order_id, resting_symbol, symbol
+        )));
+    }
+    let resting = state.engine.get_order(order_id).await?;
+    if resting.side != req.side {
+        return Err(ApiError::Validation(format!(
+            "Order {} is on the other side; cancel it and submit a new order",
+            order_id
+        )));
+    }
+    if req.quantity <= resting.filled_quantity {
+        return Err(ApiError::Validation(format!(
+            "Order {} has already filled {}, leaving nothing of quantity {} to rest",
+            order_id, resting.filled_quantity, req.quantity
+        )));
+    }
+
+    let modified = state
+        .order_service
+        .modify_order(
+            order_id,
+            ModifyRequest {
+                new_price,
+                new_quantity: req.quantity - resting.filled_quantity,
+            },
+        )
+        .await?;
+
+    let response = OrderResponse {
+        order_id,
+        accepted: true,
+        reject_reason: None,
+        trades: Vec::new(),
+        remaining_quantity: modified.new_quantity,
+        remainder_cancelled: false,
+        expires_at_ns: None,
+        trades_truncated: false,
+        trade_summary: None,
+        stp_cancelled: Vec::new(),
+        stp_outcome: None,
+        seq: modified.seq,
+    };
+    Ok(response)
+}
+
 pub async fn modify_order(
     State(state): State<AppState>,
-    Path(order_id): Path<u64>,
-    Json(req): Json<ModifyRequest>,
+    OrderId(order_id): OrderId,
+    JsonBody(req): JsonBody<ModifyRequest>,
+) -> Result<Json<ModifyResponse>, ApiError> {
+    let response = state.order_service.modify_order(order_id, req).await?;
+    Ok(Json(response))
+}
+
+/// Modify the order `trader_id` submitted under idempotency key `key`.
+pub async fn modify_order_by_key(
+    State(state): State<AppState>,
+    Path((trader_id, key)): Path<(String, String)>,
+    JsonBody(req): JsonBody<ModifyRequest>,
 ) -> Result<Json<ModifyResponse>, ApiError> {
+    let order_id = state.idempotency.resolve(&trader_id, &key)?;
     let response = state.order_service.modify_order(order_id, req).await?;
     Ok(Json(response))
 }
 
+pub async fn reduce_order(
+    State(state): State<AppState>,
+    OrderId(order_id): OrderId,
+    JsonBody(req): JsonBody<ReduceRequest>,
+) -> Result<Json<ReduceResponse>, ApiError> {
+    let response = state.order_service.reduce_order(order_id, req).await?;
+    Ok(Json(response))
+}
+
 pub async fn cancel_order(
     State(state): State<AppState>,
-    Path(order_id): Path<u64>,
+    OrderId(order_id): OrderId,
+) -> Result<Json<CancelResponse>, ApiError> {
+    let response = state.order_service.cancel_order(order_id).await?;
+    Ok(Json(response))
+}
+
+/// Answers 201 if the replacement was accepted. If the engine rejected it,
+/// the original is still cancelled: 200 with the cancel and the rejection.
+pub async fn cancel_replace(
+    State(state): State<AppState>,
+    OrderId(order_id): OrderId,
+    JsonBody(req): JsonBody<OrderRequest>,
+) -> Result<(StatusCode, Json<CancelReplaceResponse>), ApiError> {
+    let mut response = state.order_service.cancel_replace(order_id, req).await?;
+    let status = match &mut response.order {
+        OrderOutcome::Accepted(order) => {
+            order.truncate_trades(state.max_inline_trades);
+            StatusCode::CREATED
+        }
+        OrderOutcome::Rejected(_) => StatusCode::OK,
+    };
+    Ok((status, Json(response)))
+}
+
+/// Cancel the order `trader_id` submitted under idempotency key `key`.
+pub async fn cancel_order_by_key(
+    State(state): State<AppState>,
+    Path((trader_id, key)): Path<(String, String)>,
 ) -> Result<Json<CancelResponse>, ApiError> {
+    let order_id = state.idempotency.resolve(&trader_id, &key)?;
     let response = state.order_service.cancel_order(order_id).await?;
     Ok(Json(response))
 }
+
+pub async fn get_executions(
+    State(state): State<AppState>,
+    OrderId(order_id): OrderId,
+) -> Json<ExecutionsResponse> {
+    Json(state.trade_history.executions(order_id))
+}
+
+/// Time-ordered lifecycle of an order: submit, modifies, reductions, fills
+/// and the cancel that removed it, if any.
+pub async fn get_order_history(
+    State(state): State<AppState>,
+    OrderId(order_id): OrderId,
+) -> Result<Json<OrderHistoryResponse>, ApiError> {
+    let response = state.order_service.order_history(order_id)?;
+    Ok(Json(response))
+}
+
+/// Current state of a resting order; 404 once it has left the book.
+pub async fn get_order(
+    State(state): State<AppState>,
+    OrderId(order_id): OrderId,
+) -> Result<Json<OrderStatusResponse>, ApiError> {
+    let response = state.engine.get_order(order_id).await?;
+    Ok(Json(response))
+}
+
+pub async fn get_queue_position(
+    State(state): State<AppState>,
+    OrderId(order_id): OrderId,
+) -> Result<Json<QueuePositionResponse>, ApiError> {
+    let response = state.engine.get_queue_position(order_id).await?;
+    Ok(Json(response))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+
+    #[tokio::test]
+    async fn test_simple_submit_matches_json_submit() {
+        let uri = "/api/v1/orders/simple?trader=alice&side=BUY&price=100.50&qty=100&type=LIMIT"
+            .parse()
+            .unwrap();
+        let query = Query::<SimpleOrderQuery>::try_from_uri(&uri).unwrap();
+        let simple_state = AppState::new(&Config::default());
+        let (simple_status, Json(simple)) =
+            submit_order_simple(State(simple_state), HeaderMap::new(), query)
+                .await
+                .unwrap();
+
+        let req: OrderRequest = serde_json::from_str(
+            r#"{"traderId":"alice","side":"BUY","price":100.50,"quantity":100,"orderType":"LIMIT"}"#,
+        )
+        .unwrap();
+        let json_state = AppState::new(&Config::default());
+        let (json_status, Json(json)) =
+            submit_order(State(json_state), HeaderMap::new(), JsonBody(req))
+                .await
+                .unwrap();
+
+        assert_eq!(simple_status, json_status);
+        assert_eq!(
+            serde_json::to_value(&simple).unwrap(),
+            serde_json::to_value(&json).unwrap()
+        );
+        assert!(simple.accepted);
+        assert_eq!(simple.remaining_quantity, 100);
+    }
+
+    #[tokio::test]
+    async fn test_batch_runs_in_order_with_per_order_errors() {
+        let mut config = Config::default();
+        config.server.max_batch_orders = 3;
+        let state = AppState::new(&config);
+        let order =
+            |trader: &str, quantity, side| OrderRequest::limit(trader, 100.00, quantity, side);
+        let batch = vec![
+            order("seller", 10, Side::Sell),
+            order("buyer", 0, Side::Buy),
+            // Trades against the first order in the batch
+            order("buyer", 4, Side::Buy),
+        ];
+
+        let Json(results) = submit_batch(State(state.clone()), JsonBody(batch))
+            .await
+            .unwrap();
+        let json = serde_json::to_value(&results).unwrap();
+        assert_eq!(json[0]["remainingQuantity"], 10);
+        assert_eq!(json[1]["code"], 422);
+        assert_eq!(json[1]["ruleId"], "min_order_size");
+        assert_eq!(json[2]["trades"][0]["quantity"], 4);
+        assert_eq!(json[2]["trades"][0]["sellOrderId"], json[0]["orderId"]);
+
+        for size in [0, 4] {
+            let batch = vec![order("buyer", 1, Side::Buy); size];
+            assert!(matches!(
+                submit_batch(State(state.clone()), JsonBody(batch)).await,
+                Err(ApiError::Validation(_))
+            ));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_large_fill_truncated_with_summary() {
+        let mut config = Config::default();
+        config.server.max_inline_trades = 3;
+        let state = AppState::new(&config);
+
+        for price in [100.00, 100.01, 100.02, 100.03, 100.04] {
+            let sell = OrderRequest::limit("seller", price, 10, Side::Sell);
+            state.order_service.submit_order(sell).await.unwrap();
+        }
+
+        let sweep = OrderRequest {
+            time_in_force: TimeInForce::Ioc,
+            ..OrderRequest::limit("buyer", 100.04, 50, Side::Buy)
+        };
+        let (_, Json(resp)) = submit_order(State(state.clone()), HeaderMap::new(), JsonBody(sweep))
+            .await
+            .unwrap();
+
+        assert_eq!(resp.trades.len(), 3);
+        assert!(resp.trades_truncated);
+        let summary = resp.trade_summary.as_ref().unwrap();
+        assert_eq!(summary.count, 5);
+        assert_eq!(summary.filled_quantity, 50);
+        assert!((summary.avg_price - 100.02).abs() < 1e-9);
+
+        // The full fill is still available from the executions endpoint
+        let execs = state.trade_history.executions(resp.order_id);
+        assert_eq!(execs.trades.len(), 5);
+    }
+
+    #[tokio::test]
+    async fn test_small_fill_not_truncated() {
+        let state = AppState::new(&Config::default());
+        let (_, Json(resp)) = submit_order(
+            State(state),
+            HeaderMap::new(),
+            JsonBody(OrderRequest::limit("alice", 100.00, 10, Side::Buy)),
+        )
+        .await
+        .unwrap();
+
+        assert!(!resp.trades_truncated);
+        assert!(resp.trade_summary.is_none());
+        let body = serde_json::to_value(&resp).unwrap();
+        assert!(body.get("tradesTruncated").is_none());
+    }
+
+    fn resting_buy() -> OrderRequest {
+        OrderRequest::limit("alice", 100.00, 10, Side::Buy)
+    }
+
+    fn with_key(key: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(IDEMPOTENCY_KEY_HEADER, key.parse().unwrap());
+        headers
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_idempotency_key() {
+        let state = AppState::new(&Config::default());
+        let (_, Json(submitted)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(resting_buy()),
+        )
+        .await
+        .unwrap();
+
+        let by_key = |trader_id: &str| {
+            cancel_order_by_key(
+                State(state.clone()),
+                Path((trader_id.to_string(), "client-1".to_string())),
+            )
+        };
+
+        // Another trader's key of the same name is not theirs to use
+        assert!(matches!(
+            by_key("mallory").await,
+            Err(ApiError::KeyNotFound(_))
+        ));
+        let Json(cancelled) = by_key("alice").await.unwrap();
+        assert_eq!(cancelled.order_id, submitted.order_id);
+        assert!(cancelled.cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_modify_by_idempotency_key() {
+        let state = AppState::new(&Config::default());
+        let (_, Json(submitted)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(resting_buy()),
+        )
+        .await
+        .unwrap();
+
+        let Json(modified) = modify_order_by_key(
+            State(state),
+            Path(("alice".to_string(), "client-1".to_string())),
+            JsonBody(ModifyRequest {
+                new_price: 100.50,
+                new_quantity: 10,
+            }),
+        )
+        .await
+        .unwrap();
+        assert_eq!(modified.order_id, submitted.order_id);
+        assert_eq!(modified.new_price, 100.50);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_by_unknown_key_not_found() {
+        let state = AppState::new(&Config::default());
+        let result = cancel_order_by_key(
+            State(state),
+            Path(("alice".to_string(), "missing".to_string())),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::KeyNotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_custom_risk_plugin_refuses_order() {
+        use crate::models::error::RiskRule;
+        use crate::services::risk_plugin::{OrderContext, RiskPlugin};
+        use crate::services::risk_service::RiskService;
+
+        struct BuysOnly;
+        impl RiskPlugin for BuysOnly {
+            fn check(&self, ctx: &OrderContext) -> Result<(), ApiError> {
+                if ctx.side == Side::Sell {
+                    return Err(ApiError::risk(RiskRule::Custom, "buys only"));
+                }
+                Ok(())
+            }
+        }
+
+        let config = Config::default();
+        let risk = RiskService::new(config.risk.clone()).with_plugin(BuysOnly);
+        let state = AppState::with_risk(&config, risk);
+        let submit = |side| {
+            submit_order(
+                State(state.clone()),
+                HeaderMap::new(),
+                JsonBody(OrderRequest::limit("alice", 100.00, 10, side)),
+            )
+        };
+
+        assert!(submit(Side::Buy).await.is_ok());
+        assert!(matches!(
+            submit(Side::Sell).await,
+            Err(ApiError::RiskRejection(c)) if c.rule_id == RiskRule::Custom
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retried_key_replays_response() {
+        let state = AppState::new(&Config::default());
+        let (status, Json(first)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(resting_buy()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let (status, Json(retried)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(resting_buy()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(retried.order_id, first.order_id);
+        assert_eq!(retried.seq, first.seq);
+        assert_eq!(state.engine.total_orders(), 1);
+
+        // The same key from another trader is a separate submission
+        let (status, Json(other)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(OrderRequest::limit("bob", 99.00, 10, Side::Buy)),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+        assert_ne!(other.order_id, first.order_id);
+    }
+
+    #[tokio::test]
+    async fn test_duplicate_key_modifies_in_upsert_mode() {
+        let mut config = Config::default();
+        config.server.idempotency_key_upsert = true;
+        let state = AppState::new(&config);
+        let (status, Json(submitted)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(resting_buy()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::CREATED);
+
+        let resubmit = OrderRequest {
+            price: Some(99.50),
+            quantity: 25,
+            ..resting_buy()
+        };
+        let (status, Json(upserted)) = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(resubmit),
+        )
+        .await
+        .unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(upserted.order_id, submitted.order_id);
+        assert_eq!(upserted.remaining_quantity, 25);
+
+        let snapshot = state.engine.get_snapshot().await;
+        assert_eq!(snapshot.best_bid, Some(99.50));
+        assert_eq!(state.engine.total_orders(), 1);
+
+        // Switching side can't be expressed as a modify
+        let flipped = OrderRequest {
+            side: Side::Sell,
+            ..resting_buy()
+        };
+        let result = submit_order(
+            State(state.clone()),
+            with_key("client-1"),
+            JsonBody(flipped),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::Validation(_))));
+
+        // Nor can a move to another symbol
+        let moved = OrderRequest {
+            symbol: Some("ETH-USD".into()),
+            ..resting_buy()
+        };
+        let result =
+            submit_order(State(state.clone()), with_key("client-1"), JsonBody(moved)).await;
+        assert!(matches!(result, Err(ApiError::Validation(msg)) if msg.contains("ETH-USD")));
+
+        // Only the owner may amend the order
+        let result = upsert(
+            &state,
+            submitted.order_id,
+            &OrderRequest::limit("mallory", 99.00, 10, Side::Buy),
+        )
+        .await;
+        assert!(matches!(result, Err(ApiError::Forbidden(_))));
+        assert_eq!(state.engine.get_snapshot().await.best_bid, Some(99.50));
+    }
+
+    #[tokio::test]
+    async fn test_upsert_retry_after_partial_fill_keeps_filled_quantity_off() {
+        let mut config = Config::default();
+        config.server.idempotency_key_upsert = true;
+        let state = AppState::new(&config);
+        let resubmit =
+            |req| submit_order(State(state.clone()), with_key("client-1"), JsonBody(req));
+        let (_, Json(submitted)) = resubmit(resting_buy()).await.unwrap();
+        state
+            .order_service
+            .submit_order(OrderRequest::limit("bob", 100.00, 6, Side::Sell))
+            .await
+            .unwrap();
+
+        // A plain retry of the same request replays the stored response
+        // rather than putting the full size back on the book
+        let (status, Json(replayed)) = resubmit(resting_buy()).await.unwrap();
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(replayed.order_id, submitted.order_id);
+        assert_eq!(replayed.seq, submitted.seq);
+        let order = state.engine.get_order(submitted.order_id).await.unwrap();
+        assert_eq!(order.remaining_quantity, 4);
+
+        // A changed request sizes the order to 10 in all, 6 of them filled
+        let repriced = OrderRequest {
+            price: Some(99.50),
+            ..resting_buy()
+        };
+        let (_, Json(upserted)) = resubmit(repriced.clone()).await.unwrap();
+        assert_eq!(upserted.remaining_quantity, 4);
+        let order = state.engine.get_order(submitted.order_id).await.unwrap();
+        assert_eq!(order.original_quantity, 10);
+        assert_eq!(order.filled_quantity, 6);
+        assert_eq!(order.price, 99.50);
+
+        // Retrying that amendment is a replay too
+        let (_, Json(replayed)) = resubmit(repriced).await.unwrap();
+        assert_eq!(replayed.seq, upserted.seq);
+
+        // Nothing would be left to rest
+        let shrunk = OrderRequest {
+            quantity: 6,
+            ..resting_buy()
+        };
+        assert!(matches!(
+            resubmit(shrunk).await,
+            Err(ApiError::Validation(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_order_history_reconstructs_lifecycle() {
+        let state = AppState::new(&Config::default());
+        let order = |trader: &str, side, price| OrderRequest::limit(trader, price, 10, side);
+        let svc = &state.order_service;
+        let resting = svc
+            .submit_order(order("alice", Side::Buy, 100.0))
+            .await
+            .unwrap();
+        let order_id = resting.order_id;
+        svc.modify_order(
+            order_id,
+            ModifyRequest {
+                new_price: 100.5,
+                new_quantity: 8,
+            },
+        )
+        .await
+        .unwrap();
+        let taker = svc
+            .submit_order(OrderRequest {
+                quantity: 3,
+                ..order("bob", Side::Sell, 100.5)
+            })
+            .await
+            .unwrap();
+        svc.cancel_order(order_id).await.unwrap();
+
+        let Json(history) = get_order_history(State(state.clone()), OrderId(order_id))
+            .await
+            .unwrap();
+        let kinds: Vec<_> = history.events.iter().map(|e| e.event).collect();
+        assert_eq!(kinds, ["submitted", "modified", "filled", "cancelled"]);
+        assert!(history
+            .events
+            .windows(2)
+            .all(|w| w[0].timestamp_ns <= w[1].timestamp_ns));
+        assert_eq!(history.events[0].details["traderId"], "alice");
+        assert_eq!(history.events[1].details["newPrice"], 100.5);
+        assert_eq!(history.events[2].details["quantity"], 3);
+        assert_eq!(
+            history.events[2].details["counterpartyOrderId"],
+            taker.order_id
+        );
+        assert_eq!(history.events[3].details["reason"], "user");
+
+        // The taker sees its own side of the same fill
+        let Json(taker_history) = get_order_history(State(state.clone()), OrderId(taker.order_id))
+            .await
+            .unwrap();
+        let kinds: Vec<_> = taker_history.events.iter().map(|e| e.event).collect();
+        assert_eq!(kinds, ["submitted", "filled"]);
+
+        let unknown = get_order_history(State(state), OrderId(999)).await;
+        assert!(matches!(unknown, Err(ApiError::NotFound(999))));
+    }
+
+    #[test]
+    fn test_simple_query_rejects_bad_side() {
+        let uri = "/api/v1/orders/simple?trader=alice&side=LONG&price=100&qty=1&type=LIMIT"
+            .parse()
+            .unwrap();
+        assert!(Query::<SimpleOrderQuery>::try_from_uri(&uri).is_err());
+    }
+}
@@ -0,0 +1,130 @@
+use axum::extract::{Query, State};
+use axum::Json;
+
+use crate::models::error::ApiError;
+use crate::models::trade::{TradesQuery, TradesResponse};
+use crate::state::AppState;
+
+const DEFAULT_PAGE_SIZE: usize = 100;
+const MAX_PAGE_SIZE: usize = 1_000;
+
+/// Recent trades from the in-memory buffer, newest first, optionally only
+/// those a given trader took part in. With `sinceId` the tape is read
+/// forward instead: trades after that id, oldest first, so a poller can
+/// pass its last seen id each time.
+pub async fn get_trades(
+    State(state): State<AppState>,
+    Query(query): Query<TradesQuery>,
+) -> Result<Json<TradesResponse>, ApiError> {
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_PAGE_SIZE)
+        .clamp(1, MAX_PAGE_SIZE);
+    if let Some(since_id) = query.since_id {
+        if query.before.is_some() {
+            return Err(ApiError::Validation(
+                "sinceId and before cannot be combined".into(),
+            ));
+        }
+        let trades = state
+            .trade_history
+            .trades_since(query.trader_id.as_deref(), since_id, limit);
+        return Ok(Json(TradesResponse {
+            trades,
+            next_before: None,
+        }));
+    }
+    let trades = state
+        .trade_history
+        .trades_page(query.trader_id.as_deref(), query.before, limit);
+    let next_before = (trades.len() == limit)
+        .then(|| trades.last().map(|t| t.trade_id))
+        .flatten();
+    Ok(Json(TradesResponse {
+        trades,
+        next_before,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::Config;
+    use crate::models::order::{OrderRequest, Side};
+
+    fn limit(trader: &str, side: Side, quantity: i64) -> OrderRequest {
+        OrderRequest::limit(trader, 100.00, quantity, side)
+    }
+
+    #[tokio::test]
+    async fn test_trader_sees_own_fills_only() {
+        let state = AppState::new(&Config::default());
+        for req in [
+            limit("alice", Side::Sell, 10),
+            limit("bob", Side::Buy, 4),
+            limit("carol", Side::Buy, 6),
+        ] {
+            state.order_service.submit_order(req).await.unwrap();
+        }
+
+        let page = |trader: &str| TradesQuery {
+            trader_id: Some(trader.into()),
+            ..TradesQuery::default()
+        };
+        let Json(alice) = get_trades(State(state.clone()), Query(page("alice")))
+            .await
+            .unwrap();
+        assert_eq!(alice.trades.len(), 2);
+        assert!(alice.next_before.is_none());
+
+        let Json(bob) = get_trades(State(state.clone()), Query(page("bob")))
+            .await
+            .unwrap();
+        assert_eq!(bob.trades.len(), 1);
+        assert_eq!(bob.trades[0].quantity, 4);
+
+        let Json(dave) = get_trades(State(state), Query(page("dave"))).await.unwrap();
+        assert!(dave.trades.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_tape_reads_forward_from_since_id() {
+        let state = AppState::new(&Config::default());
+        for symbol in [None, Some("ETH-USD"), None] {
+            for side in [Side::Sell, Side::Buy] {
+                let req = OrderRequest {
+                    symbol: symbol.map(Into::into),
+                    ..limit(if side == Side::Sell { "alice" } else { "bob" }, side, 5)
+                };
+                state.order_service.submit_order(req).await.unwrap();
+            }
+        }
+        let tape = |since_id, limit| {
+            get_trades(
+                State(state.clone()),
+                Query(TradesQuery {
+                    since_id: Some(since_id),
+                    limit: Some(limit),
+                    ..TradesQuery::default()
+                }),
+            )
+        };
+
+        // Ids stay unique across symbols' books
+        let Json(all) = tape(0, 10).await.unwrap();
+        let ids: Vec<u64> = all.trades.iter().map(|t| t.trade_id).collect();
+        assert_eq!(ids, [1, 2, 3]);
+        assert!(all.trades.iter().all(|t| t.timestamp_ns > 0));
+
+        let Json(page) = tape(1, 1).await.unwrap();
+        assert_eq!(page.trades[0].trade_id, 2);
+        assert!(tape(3, 10).await.unwrap().trades.is_empty());
+
+        let uri = "/api/v1/trades?since_id=1&before=3".parse().unwrap();
+        let query = Query::<TradesQuery>::try_from_uri(&uri).unwrap();
+        assert!(matches!(
+            get_trades(State(state), query).await,
+            Err(ApiError::Validation(_))
+        ));
+    }
+}
@@ -1,16 +1,157 @@
+use std::collections::HashSet;
 use std::sync::atomic::Ordering;
+use std::time::Duration;
 
-use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
-use axum::extract::State;
+use axum::extract::ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Query, State};
 use axum::response::IntoResponse;
+use serde::Deserialize;
 use tokio::sync::broadcast;
+use tokio::time::MissedTickBehavior;
 
+use crate::engine::depth::DepthFeed;
+use crate::services::ws_sessions::WsSession;
 use crate::state::AppState;
 
-const MAX_WS_CONNECTIONS: u64 = 100;
+pub(crate) const MAX_WS_CONNECTIONS: u64 = 100;
+
+/// Levels per side included in binary depth frames
+const DEPTH_LEVELS: usize = 20;
+/// How often a binary depth subscriber's book is diffed and sent
+const DEPTH_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Default, Deserialize)]
+pub struct WsParams {
+    /// `binary` adds MessagePack depth frames (see `DepthFrame`) alongside
+    /// the JSON event stream: one full snapshot, then per-level deltas.
+    #[serde(default)]
+    pub depth: Option<DepthFormat>,
+    /// Cancel this trader's open orders when the connection drops and isn't
+    /// resumed within the grace window
+    #[serde(default)]
+    pub cancel_on_disconnect: Option<String>,
+    /// Token from the `session` message of an earlier connection. Within the
+    /// grace window it restores that connection's subscriptions, and the
+    /// parameters above are ignored.
+    #[serde(default)]
+    pub resume: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DepthFormat {
+    Binary,
+}
+
+/// Broadcast message types carried by each channel a client can subscribe
+/// to. Types not listed here, like `session` and `error`, go to everyone.
+const CHANNELS: &[(&str, &[&str])] = &[
+    ("trades", &["trade"]),
+    ("snapshots", &["snapshot"]),
+    (
+        "orders",
+        &[
+            "orderCancelled",
+            "orderExpired",
+            "orderModified",
+            "orderReduced",
+        ],
+    ),
+    (
+        "alerts",
+        &["circuitBreaker", "imbalanceAlert", "marketQualityAlert"],
+    ),
+    ("book", &["bookReset", "auctionUncross"]),
+];
+
+fn channel_of(kind: &str) -> Option<&'static str> {
+    CHANNELS
+        .iter()
+        .find(|(_, kinds)| kinds.contains(&kind))
+        .map(|(channel, _)| *channel)
+}
+
+#[derive(Deserialize)]
+struct MessageType<'a> {
+    #[serde(rename = "type", borrow)]
+    kind: &'a str,
+}
+
+/// Whether a client subscribed to `channels` (every channel, if `None`)
+/// should be sent this broadcast message.
+fn wanted(channels: Option<&HashSet<String>>, text: &str) -> bool {
+    let Some(channels) = channels else {
+        return true;
+    };
+    match serde_json::from_str::<MessageType>(text) {
+        Ok(msg) => channel_of(msg.kind).is_none_or(|channel| channels.contains(channel)),
+        Err(_) => true,
+    }
+}
+
+/// Requests a client can send as text frames.
+#[derive(Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+enum ClientRequest {
+    /// Replace the channels this connection receives
+    Subscribe { channels: Vec<String> },
+}
+
+/// Apply a text frame from the client to its session, returning the reply
+/// to send. Frames that aren't a request are ignored.
+fn handle_client_text(session: &mut WsSession, text: &str) -> Option<String> {
+    let Ok(ClientRequest::Subscribe { channels }) = serde_json::from_str(text) else {
+        return None;
+    };
+    let known = |c: &&String| channel_names().any(|name| name == *c);
+    if let Some(unknown) = channels.iter().find(|c| !known(c)) {
+        let error = serde_json::json!({
+            "type": "error",
+            "data": { "message": format!("Unknown channel '{}'", unknown) }
+        });
+        return Some(error.to_string());
+    }
+    let channels: HashSet<String> = channels.into_iter().collect();
+    let subscribed: Vec<&str> = channel_names().filter(|c| channels.contains(*c)).collect();
+    let reply = serde_json::json!({
+        "type": "subscribed",
+        "data": { "channels": subscribed }
+    });
+    session.channels = Some(channels);
+    Some(reply.to_string())
+}
+
+fn channel_names() -> impl Iterator<Item = &'static str> {
+    CHANNELS.iter().map(|(channel, _)| *channel)
+}
+
+/// Messages buffered for one connection between batch flushes.
+#[derive(Default)]
+struct OutboundBatch {
+    messages: Vec<String>,
+}
+
+impl OutboundBatch {
+    fn push(&mut self, message: String) {
+        self.messages.push(message);
+    }
+
+    /// Drain buffered messages into a single JSON array frame, preserving
+    /// broadcast order. Messages are already serialized JSON, so they are
+    /// spliced in as-is.
+    fn take_frame(&mut self) -> Option<String> {
+        if self.messages.is_empty() {
+            return None;
+        }
+        let frame = format!("[{}]", self.messages.join(","));
+        self.messages.clear();
+        Some(frame)
+    }
+}
 
 pub async fn ws_upgrade(
     State(state): State<AppState>,
+    Query(params): Query<WsParams>,
     ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
     let current = state.ws_connections.load(Ordering::Relaxed);
@@ -22,52 +163,146 @@ pub async fn ws_upgrade(
             .into_response();
     }
 
-    ws.on_upgrade(move |socket| handle_ws(socket, state))
+    let resumed = params
+        .resume
+        .as_deref()
+        .and_then(|token| state.ws_sessions.resume(token));
+    let is_resumed = resumed.is_some();
+    let session = resumed.unwrap_or_else(|| WsSession {
+        binary_depth: params.depth == Some(DepthFormat::Binary),
+        cancel_on_disconnect: params.cancel_on_disconnect,
+        channels: None,
+    });
+    // Oversized messages are refused by the protocol layer before they are
+    // buffered, and surface as a receive error in handle_ws
+    ws.max_message_size(state.ws_max_message_bytes)
+        .max_frame_size(state.ws_max_message_bytes)
+        .on_upgrade(move |socket| handle_ws(socket, state, session, is_resumed))
         .into_response()
 }
 
-async fn handle_ws(mut socket: WebSocket, state: AppState) {
+async fn handle_ws(mut socket: WebSocket, state: AppState, mut session: WsSession, resumed: bool) {
     state.ws_connections.fetch_add(1, Ordering::Relaxed);
     tracing::info!(
         event = "WsConnected",
-        active = state.ws_connections.load(Ordering::Relaxed)
+        active = state.ws_connections.load(Ordering::Relaxed),
+        resumed,
     );
 
     let mut rx = state.ws_broadcast.subscribe();
 
+    // Every connection gets a fresh token to resume this session with
+    let token = state.ws_sessions.issue_token();
+    let hello = serde_json::json!({
+        "type": "session",
+        "data": {
+            "resumeToken": token,
+            "resumed": resumed,
+            "graceMs": state.ws_sessions.grace().as_millis() as u64
+        }
+    });
+    // Then the book as it stands, so the client isn't blank until the next
+    // event
+    let snapshot = state.engine.get_snapshot().await;
+    let initial = serde_json::json!({
+        "type": "snapshot",
+        "seq": snapshot.seq,
+        "data": snapshot
+    });
+    let binary_depth = session.binary_depth;
+    for frame in [hello, initial] {
+        if socket.send(Message::Text(frame.to_string())).await.is_err() {
+            state.ws_connections.fetch_sub(1, Ordering::Relaxed);
+            end_session(&state, token, session);
+            return;
+        }
+    }
+
+    // With batching on, messages are buffered and flushed as one JSON array
+    // frame per interval. The ticker is created either way so select! has a
+    // branch to poll; it's only polled when batching.
+    let batching = state.ws_batch_interval.is_some();
+    let mut batch = OutboundBatch::default();
+    let mut flush = tokio::time::interval(
+        state.ws_batch_interval.unwrap_or(Duration::from_secs(3600)),
+    );
+    flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    // Binary depth subscribers get the book diffed against what they were
+    // last sent; the first tick fires immediately and sends the snapshot.
+    let mut depth_feed = DepthFeed::new(DEPTH_LEVELS);
+    let mut depth_tick = tokio::time::interval(DEPTH_INTERVAL);
+    depth_tick.set_missed_tick_behavior(MissedTickBehavior::Skip);
+
     // Forward broadcast messages to the WebSocket client
     loop {
         tokio::select! {
             // Receive from broadcast channel
             msg = rx.recv() => {
-                match msg {
-                    Ok(text) => {
-                        if socket.send(Message::Text(text.into())).await.is_err() {
-                            break;
-                        }
-                    }
+                let text = match msg {
+                    Ok(text) => text,
                     Err(broadcast::error::RecvError::Lagged(n)) => {
                         tracing::warn!(event = "WsLagged", skipped = n);
                         // Send a lag notification
-                        let lag_msg = serde_json::json!({
+                        serde_json::json!({
                             "type": "error",
                             "data": { "message": format!("Missed {} messages", n) }
-                        });
-                        let _ = socket.send(Message::Text(lag_msg.to_string().into())).await;
+                        })
+                        .to_string()
                     }
                     Err(broadcast::error::RecvError::Closed) => break,
+                };
+                if !wanted(session.channels.as_ref(), &text) {
+                    continue;
+                }
+                if batching {
+                    batch.push(text);
+                } else if socket.send(Message::Text(text)).await.is_err() {
+                    break;
+                }
+            }
+            _ = flush.tick(), if batching => {
+                if let Some(frame) = batch.take_frame() {
+                    if socket.send(Message::Text(frame)).await.is_err() {
+                        break;
+                    }
+                }
+            }
+            _ = depth_tick.tick(), if binary_depth => {
+                let depth = state.engine.get_depth(DEPTH_LEVELS).await;
+                if let Some(frame) = depth_feed.next_frame(depth) {
+                    if socket.send(Message::Binary(frame.encode())).await.is_err() {
+                        break;
+                    }
                 }
             }
             // Receive from client (for ping/pong or close)
             client_msg = socket.recv() => {
                 match client_msg {
                     Some(Ok(Message::Close(_))) | None => break,
+                    Some(Err(e)) => {
+                        tracing::warn!(event = "WsProtocolError", error = %e);
+                        let close = CloseFrame {
+                            code: close_code::PROTOCOL,
+                            reason: "protocol error".into(),
+                        };
+                        let _ = socket.send(Message::Close(Some(close))).await;
+                        break;
+                    }
                     Some(Ok(Message::Ping(data))) => {
-                        if socket.send(Message::Pong(data)).await.is_err() {
+                        let pong = Message::Pong(data);
+                        if socket.send(pong).await.is_err() {
                             break;
                         }
                     }
-                    _ => {} // Ignore text/binary from client for now
+                    Some(Ok(Message::Text(text))) => {
+                        if let Some(reply) = handle_client_text(&mut session, &text) {
+                            if socket.send(Message::Text(reply)).await.is_err() {
+                                break;
+                            }
+                        }
+                    }
+                    _ => {} // Ignore binary frames from the client
                 }
             }
         }
@@ -78,4 +313,343 @@ async fn handle_ws(mut socket: WebSocket, state: AppState) {
         event = "WsDisconnected",
         active = state.ws_connections.load(Ordering::Relaxed)
     );
+    end_session(&state, token, session);
+}
+
+/// Park a dropped connection's session for resumption. If nobody resumes it
+/// within the grace window, its cancel-on-disconnect fires, unless the server
+/// is read-only.
+fn end_session(state: &AppState, token: String, session: WsSession) {
+    let grace = state.ws_sessions.grace();
+    state.ws_sessions.park(&token, session);
+    let state = state.clone();
+    tokio::spawn(async move {
+        tokio::time::sleep(grace).await;
+        // Already resumed by a new connection
+        let Some(session) = state.ws_sessions.expire(&token) else {
+            return;
+        };
+        if state.read_only {
+            return;
+        }
+        if let Some(trader_id) = session.cancel_on_disconnect {
+            let cancelled = state.order_service.cancel_on_disconnect(&trader_id).await;
+            tracing::info!(event = "WsCancelOnDisconnect", trader_id = %trader_id, cancelled);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::SocketAddr;
+
+    /// Serve `/ws` for `state` on an ephemeral port.
+    async fn spawn_ws(state: AppState) -> SocketAddr {
+        let app = axum::Router::new()
+            .route("/ws", axum::routing::get(ws_upgrade))
+            .with_state(state);
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        addr
+    }
+
+    #[tokio::test]
+    async fn test_rapid_events_batched_into_one_frame() {
+        let (tx, mut rx) = broadcast::channel(16);
+        for id in 1..=3 {
+            let msg = serde_json::json!({ "type": "trade", "data": { "tradeId": id } });
+            tx.send(msg.to_string()).unwrap();
+        }
+
+        let mut batch = OutboundBatch::default();
+        while let Ok(msg) = rx.try_recv() {
+            batch.push(msg);
+        }
+
+        let frame = batch.take_frame().unwrap();
+        let parsed: serde_json::Value = serde_json::from_str(&frame).unwrap();
+        let events = parsed.as_array().unwrap();
+        assert_eq!(events.len(), 3);
+        for (i, event) in events.iter().enumerate() {
+            assert_eq!(event["data"]["tradeId"], i as u64 + 1);
+        }
+
+        // Nothing left to flush until more messages arrive
+        assert!(batch.take_frame().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_oversized_inbound_frame_closes_connection() {
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        let mut config = crate::config::Config::default();
+        config.server.ws_max_message_bytes = 1024;
+        let addr = spawn_ws(AppState::new(&config)).await;
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+        let hello = client.next().await.unwrap().unwrap();
+        assert!(matches!(hello, ClientMessage::Text(ref t) if t.contains("resumeToken")));
+        let snapshot = client.next().await.unwrap().unwrap();
+        assert!(matches!(snapshot, ClientMessage::Text(ref t) if t.contains("snapshot")));
+
+        // Within the limit: ignored, connection stays open
+        client.send(ClientMessage::text("x".repeat(1024))).await.unwrap();
+        client.send(ClientMessage::Ping(vec![1])).await.unwrap();
+        let pong = client.next().await.unwrap().unwrap();
+        assert!(matches!(pong, ClientMessage::Pong(_)));
+
+        client.send(ClientMessage::text("x".repeat(1025))).await.unwrap();
+        let reply = tokio::time::timeout(Duration::from_secs(5), client.next())
+            .await
+            .expect("server did not close the connection");
+        match reply {
+            Some(Ok(ClientMessage::Close(Some(frame)))) => {
+                assert_eq!(frame.code, CloseCode::Protocol)
+            }
+            other => panic!("expected close frame, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resume_token_restores_session() {
+        use crate::models::order::{OrderRequest, Side};
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        let mut config = crate::config::Config::default();
+        config.server.ws_resume_grace_ms = 60_000;
+        let state = AppState::new(&config);
+        let addr = spawn_ws(state.clone()).await;
+
+        let order = state
+            .order_service
+            .submit_order(OrderRequest::limit("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        let resting = |state: &AppState| state.risk.trader_for_order(order.order_id).is_some();
+
+        // Returns the session message and the first binary depth frame, if any
+        let connect = |query: String| async move {
+            let (mut client, _) =
+                tokio_tungstenite::connect_async(format!("ws://{}/ws?{}", addr, query))
+                    .await
+                    .unwrap();
+            let hello = match client.next().await.unwrap().unwrap() {
+                ClientMessage::Text(t) => serde_json::from_str::<serde_json::Value>(&t).unwrap(),
+                other => panic!("expected session message, got {:?}", other),
+            };
+            // Initial snapshot
+            let snapshot = client.next().await.unwrap().unwrap();
+            assert!(matches!(snapshot, ClientMessage::Text(_)));
+            let depth = tokio::time::timeout(Duration::from_millis(200), client.next()).await;
+            let got_depth = matches!(depth, Ok(Some(Ok(ClientMessage::Binary(_)))));
+            (client, hello, got_depth)
+        };
+
+        let (mut client, hello, got_depth) =
+            connect("depth=binary&cancel_on_disconnect=alice".into()).await;
+        assert_eq!(hello["data"]["resumed"], false);
+        assert!(got_depth);
+        let token = hello["data"]["resumeToken"].as_str().unwrap().to_string();
+        client.close(None).await.unwrap();
+
+        // Reconnect once the server has parked the session: binary depth
+        // comes back with no params
+        while state.ws_connections.load(Ordering::Relaxed) > 0 {
+            tokio::task::yield_now().await;
+        }
+        let (_client, hello, got_depth) = connect(format!("resume={}", token)).await;
+        assert_eq!(hello["data"]["resumed"], true);
+        assert!(got_depth);
+        assert!(resting(&state));
+
+        // A used token can't be replayed
+        let (_, replay, _) = connect(format!("resume={}", token)).await;
+        assert_eq!(replay["data"]["resumed"], false);
+    }
+
+    /// A session parked at the start of a paused-clock test, for `alice`.
+    async fn parked_alice(read_only: bool) -> (AppState, u64, String) {
+        use crate::models::order::{OrderRequest, Side};
+
+        let mut config = crate::config::Config::default();
+        config.server.ws_resume_grace_ms = 300;
+        let mut state = AppState::new(&config);
+        state.read_only = read_only;
+        let order = state
+            .order_service
+            .submit_order(OrderRequest::limit("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        let token = state.ws_sessions.issue_token();
+        let session = WsSession {
+            cancel_on_disconnect: Some("alice".into()),
+            ..WsSession::default()
+        };
+        end_session(&state, token.clone(), session);
+        (state, order.order_id, token)
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_cancel_on_disconnect_fires_after_grace() {
+        let (state, order_id, _) = parked_alice(false).await;
+
+        tokio::time::sleep(Duration::from_millis(299)).await;
+        assert!(state.risk.trader_for_order(order_id).is_some());
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        assert!(state.risk.trader_for_order(order_id).is_none());
+        assert!(state.engine.get_snapshot().await.best_bid.is_none());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_resumed_session_skips_cancel_on_disconnect() {
+        let (state, order_id, token) = parked_alice(false).await;
+
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert!(state.ws_sessions.resume(&token).is_some());
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(state.risk.trader_for_order(order_id).is_some());
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_read_only_skips_cancel_on_disconnect() {
+        let (state, order_id, _) = parked_alice(true).await;
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        assert!(state.risk.trader_for_order(order_id).is_some());
+        assert_eq!(state.engine.get_snapshot().await.best_bid, Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_sent_on_connect() {
+        use crate::models::order::{OrderRequest, Side};
+        use futures_util::StreamExt;
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        let state = AppState::new(&crate::config::Config::default());
+        let addr = spawn_ws(state.clone()).await;
+
+        state
+            .order_service
+            .submit_order(OrderRequest::limit("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+        let mut frames = Vec::new();
+        for _ in 0..2 {
+            match client.next().await.unwrap().unwrap() {
+                ClientMessage::Text(t) => {
+                    frames.push(serde_json::from_str::<serde_json::Value>(&t).unwrap())
+                }
+                other => panic!("expected a text frame, got {:?}", other),
+            }
+        }
+        assert_eq!(frames[0]["type"], "session");
+        assert_eq!(frames[1]["type"], "snapshot");
+        assert_eq!(frames[1]["data"]["bestBid"], 100.0);
+        assert_eq!(frames[1]["seq"], state.engine.book_seq());
+        assert_eq!(state.ws_connections.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    fn test_messages_filtered_by_subscribed_channels() {
+        let trade = r#"{"type":"trade","seq":1,"data":{}}"#;
+        let modified = r#"{"type":"orderModified","seq":2,"data":{}}"#;
+        let error = r#"{"type":"error","data":{"message":"lagged"}}"#;
+
+        // Never subscribed: everything
+        assert!(wanted(None, trade));
+        assert!(wanted(None, modified));
+
+        let trades = HashSet::from(["trades".to_string()]);
+        assert!(wanted(Some(&trades), trade));
+        assert!(!wanted(Some(&trades), modified));
+        assert!(wanted(Some(&trades), error));
+
+        // Subscribing to nothing still leaves control messages
+        let none = HashSet::new();
+        assert!(!wanted(Some(&none), trade));
+        assert!(wanted(Some(&none), error));
+    }
+
+    #[test]
+    fn test_subscribe_replaces_channels() {
+        let mut session = WsSession::default();
+        let reply = handle_client_text(
+            &mut session,
+            r#"{"type":"subscribe","channels":["snapshots","trades"]}"#,
+        );
+        let reply: serde_json::Value = serde_json::from_str(&reply.unwrap()).unwrap();
+        assert_eq!(reply["type"], "subscribed");
+        let expected = serde_json::json!(["trades", "snapshots"]);
+        assert_eq!(reply["data"]["channels"], expected);
+
+        let orders = Some(HashSet::from(["orders".to_string()]));
+        let subscribe = r#"{"type":"subscribe","channels":["orders"]}"#;
+        assert!(handle_client_text(&mut session, subscribe).is_some());
+        assert_eq!(session.channels, orders);
+
+        // Unknown channels are refused without touching the subscription
+        let subscribe = r#"{"type":"subscribe","channels":["nope"]}"#;
+        let reply = handle_client_text(&mut session, subscribe);
+        assert!(reply.unwrap().contains("Unknown channel 'nope'"));
+        assert_eq!(session.channels, orders);
+
+        // Anything else is ignored
+        assert!(handle_client_text(&mut session, "hello").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_trades_subscriber_skips_order_events() {
+        use crate::models::order::{OrderRequest, Side};
+        use futures_util::{SinkExt, StreamExt};
+        use tokio_tungstenite::tungstenite::Message as ClientMessage;
+
+        let state = AppState::new(&crate::config::Config::default());
+        let addr = spawn_ws(state.clone()).await;
+
+        let (mut client, _) = tokio_tungstenite::connect_async(format!("ws://{}/ws", addr))
+            .await
+            .unwrap();
+        for expected in ["session", "snapshot"] {
+            let frame = client.next().await.unwrap().unwrap();
+            assert!(matches!(frame, ClientMessage::Text(ref t) if t.contains(expected)));
+        }
+
+        let subscribe = r#"{"type":"subscribe","channels":["trades"]}"#;
+        client.send(ClientMessage::text(subscribe)).await.unwrap();
+        let ClientMessage::Text(ack) = client.next().await.unwrap().unwrap() else {
+            panic!("expected a subscribed ack");
+        };
+        let ack: serde_json::Value = serde_json::from_str(&ack).unwrap();
+        assert_eq!(ack["data"]["channels"], serde_json::json!(["trades"]));
+
+        let order = |trader_id: &str, price: f64, side: Side| {
+            OrderRequest::limit(trader_id, price, 10, side)
+        };
+        let service = &state.order_service;
+        let bid = service.submit_order(order("alice", 100.0, Side::Buy));
+        bid.await.unwrap();
+        let resting = service.submit_order(order("alice", 99.0, Side::Buy));
+        let resting = resting.await.unwrap();
+        service.cancel_order(resting.order_id).await.unwrap();
+        let ask = service.submit_order(order("bob", 100.0, Side::Sell));
+        ask.await.unwrap();
+
+        // The orderCancelled went out first but was filtered
+        let ClientMessage::Text(frame) = client.next().await.unwrap().unwrap() else {
+            panic!("expected a text frame");
+        };
+        let frame: serde_json::Value = serde_json::from_str(&frame).unwrap();
+        assert_eq!(frame["type"], "trade");
+    }
 }
@@ -0,0 +1,8 @@
+pub mod config;
+pub mod engine;
+pub mod ffi;
+pub mod handlers;
+pub mod middleware;
+pub mod models;
+pub mod services;
+pub mod state;
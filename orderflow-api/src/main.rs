@@ -1,21 +1,13 @@
-mod config;
-mod engine;
-mod ffi;
-mod handlers;
-mod middleware;
-mod models;
-mod services;
-mod state;
-
-use axum::routing::{get, post, put};
+use axum::routing::{delete, get, post, put};
 use axum::Router;
-use metrics_exporter_prometheus::PrometheusBuilder;
+use metrics_exporter_prometheus::{PrometheusBuilder, PrometheusHandle};
 use tower_http::cors::CorsLayer;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber::EnvFilter;
 
-use config::Config;
-use state::AppState;
+use orderflow_api::config::Config;
+use orderflow_api::state::AppState;
+use orderflow_api::{handlers, middleware, services};
 
 #[tokio::main]
 async fn main() {
@@ -32,51 +24,343 @@ async fn main() {
             .init();
     }
 
-    // Initialize Prometheus metrics recorder
-    let prom_handle = PrometheusBuilder::new()
-        .install_recorder()
-        .expect("Failed to install Prometheus recorder");
-
     let config = Config::load();
+    if let Err(e) = config.validate() {
+        tracing::error!("Invalid configuration: {}", e);
+        std::process::exit(1);
+    }
+
+    // Initialize Prometheus metrics recorder. Without one the metric helpers
+    // record into the no-op recorder, so trading carries on unmetered.
+    let prom_handle = match PrometheusBuilder::new().install_recorder() {
+        Ok(handle) => Some(handle),
+        Err(e) if config.server.metrics_required => {
+            tracing::error!("Failed to install Prometheus recorder: {}", e);
+            std::process::exit(1);
+        }
+        Err(e) => {
+            tracing::warn!(
+                "Failed to install Prometheus recorder, /metrics disabled: {}",
+                e
+            );
+            None
+        }
+    };
     let bind_addr = format!("{}:{}", config.server.host, config.server.port);
 
     let state = AppState::new(&config);
 
-    let app = Router::new()
+    // Restore positions saved by the last run, then keep saving them
+    let shutdown_risk = std::sync::Arc::clone(&state.risk);
+    let state_file = config.risk.state_file.as_ref().map(std::path::PathBuf::from);
+    let mut restored = false;
+    if let Some(path) = &state_file {
+        match state.risk.load_state(path) {
+            Ok(true) => {
+                tracing::info!("Restored risk state from {}", path.display());
+                restored = true;
+            }
+            Ok(false) => tracing::info!("No risk state at {}, starting flat", path.display()),
+            Err(e) => {
+                tracing::error!("Failed to restore risk state from {}: {}", path.display(), e);
+                std::process::exit(1);
+            }
+        }
+
+        let risk = std::sync::Arc::clone(&state.risk);
+        let path = path.clone();
+        let every = std::time::Duration::from_secs(config.risk.state_persist_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = risk.save_state(&path) {
+                    tracing::warn!("Failed to save risk state: {}", e);
+                }
+            }
+        });
+    }
+
+    // Seed start-of-day positions carried over from another system. A
+    // restored state file is more recent, so it takes precedence.
+    match &config.risk.position_import_file {
+        Some(path) if restored => tracing::info!(
+            "Skipping position import from {}: positions restored from the state file",
+            path
+        ),
+        Some(path) => match state.risk.load_positions(std::path::Path::new(path)) {
+            Ok(over_limit) => tracing::info!(
+                "Imported positions from {} ({} over the position limit)",
+                path,
+                over_limit.len()
+            ),
+            Err(e) => {
+                tracing::error!("Failed to import positions from {}: {}", path, e);
+                std::process::exit(1);
+            }
+        },
+        None => {}
+    }
+
+    // Periodically report rejections suppressed by log sampling
+    let summary_every = std::time::Duration::from_secs(config.audit.rejection_summary_secs.max(1));
+    tokio::spawn(services::audit_service::flush_summaries(
+        std::sync::Arc::clone(&state.rejections),
+        summary_every,
+    ));
+
+    // Drop expired idempotency keys
+    let idempotency = std::sync::Arc::clone(&state.idempotency);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            ticker.tick().await;
+            idempotency.purge_expired();
+        }
+    });
+
+    // Drop HTTP rate-limit buckets for clients that have gone quiet
+    let http_rate_limiter = std::sync::Arc::clone(&state.http_rate_limiter);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            ticker.tick().await;
+            http_rate_limiter.retain_recent();
+        }
+    });
+
+    // Forget rejection penalties that have fully expired
+    let risk = std::sync::Arc::clone(&state.risk);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(60));
+        loop {
+            ticker.tick().await;
+            risk.prune_penalties(std::time::Instant::now());
+        }
+    });
+
+    // Uncross the opening auction once its collection window closes
+    if let Some(closes_at) = state.order_service.auction_closes_at() {
+        let order_service = std::sync::Arc::clone(&state.order_service);
+        tokio::spawn(async move {
+            tokio::time::sleep_until(closes_at.into()).await;
+            order_service.run_opening_auction().await;
+        });
+    }
+
+    // Sweep resting orders past their expiry
+    let order_service = std::sync::Arc::clone(&state.order_service);
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(std::time::Duration::from_secs(1));
+        loop {
+            ticker.tick().await;
+            order_service.expire_orders().await;
+        }
+    });
+
+    // Publish the book's snapshot for risk checks off the submit path
+    if config.risk.snapshot_poll_interval_ms > 0 {
+        let order_service = std::sync::Arc::clone(&state.order_service);
+        let every = std::time::Duration::from_millis(config.risk.snapshot_poll_interval_ms);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                order_service.publish_snapshot().await;
+            }
+        });
+    }
+
+    // Push top-of-book snapshots to WebSocket clients
+    if config.server.snapshot_interval_ms > 0 {
+        let order_service = std::sync::Arc::clone(&state.order_service);
+        let every = std::time::Duration::from_millis(config.server.snapshot_interval_ms);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            loop {
+                ticker.tick().await;
+                order_service.broadcast_snapshot().await;
+            }
+        });
+    }
+
+    // Sample the spread for market-quality alerts
+    if config.server.market_quality_max_spread.is_some() {
+        let order_service = std::sync::Arc::clone(&state.order_service);
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(std::time::Duration::from_secs(1));
+            loop {
+                ticker.tick().await;
+                order_service.check_market_quality().await;
+            }
+        });
+    }
+
+    if config.server.read_only {
+        tracing::warn!("Read-only mode: mutating endpoints are disabled");
+    }
+
+    if config.server.debug_endpoints {
+        let addr = &config.server.debug_bind_addr;
+        let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+        tracing::warn!(
+            "Debug endpoints enabled at http://{}/api/v1/admin/debug/*",
+            addr
+        );
+        let app = build_debug_router(state.clone());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    }
+    let app = build_router(state, prom_handle);
+
+    config.log_effective();
+
+    let listener = tokio::net::TcpListener::bind(&bind_addr).await.unwrap();
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<std::net::SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal())
+    .await
+    .unwrap();
+
+    if let Some(path) = &state_file {
+        if let Err(e) = shutdown_risk.save_state(path) {
+            tracing::error!("Failed to save risk state on shutdown: {}", e);
+        }
+    }
+}
+
+/// Internal-state endpoints, served on their own listener so they are never
+/// reachable through the trading port.
+fn build_debug_router(state: AppState) -> Router {
+    Router::new()
+        .route(
+            "/api/v1/admin/debug/state",
+            get(handlers::admin::get_debug_state),
+        )
+        .layer(TraceLayer::new_for_http())
+        .with_state(state)
+}
+
+fn build_router(state: AppState, metrics: Option<PrometheusHandle>) -> Router {
+    // Trading doesn't depend on metrics: without a recorder /metrics is
+    // simply not served
+    let metrics_route = match metrics {
+        Some(handle) => Router::new().route(
+            "/metrics",
+            get(move || {
+                let handle = handle.clone();
+                async move { handle.render() }
+            }),
+        ),
+        None => Router::new(),
+    };
+
+    Router::new()
+        .merge(metrics_route)
         .route("/api/v1/orders", post(handlers::orders::submit_order))
+        .route(
+            "/api/v1/orders/simple",
+            post(handlers::orders::submit_order_simple),
+        )
+        .route("/api/v1/orders/batch", post(handlers::orders::submit_batch))
         .route(
             "/api/v1/orders/:id",
-            put(handlers::orders::modify_order).delete(handlers::orders::cancel_order),
+            get(handlers::orders::get_order)
+                .put(handlers::orders::modify_order)
+                .delete(handlers::orders::cancel_order),
+        )
+        .route(
+            "/api/v1/orders/by-key/:trader_id/:key",
+            put(handlers::orders::modify_order_by_key)
+                .delete(handlers::orders::cancel_order_by_key),
+        )
+        .route(
+            "/api/v1/orders/:id/cancel-replace",
+            post(handlers::orders::cancel_replace),
         )
+        .route(
+            "/api/v1/orders/:id/reduce",
+            post(handlers::orders::reduce_order),
+        )
+        .route(
+            "/api/v1/orders/:id/executions",
+            get(handlers::orders::get_executions),
+        )
+        .route(
+            "/api/v1/orders/:id/history",
+            get(handlers::orders::get_order_history),
+        )
+        .route(
+            "/api/v1/orders/:id/position",
+            get(handlers::orders::get_queue_position),
+        )
+        .route("/api/v1/trades", get(handlers::trades::get_trades))
         .route("/api/v1/market", get(handlers::market::get_market_snapshot))
+        .route("/api/v1/market/ticker", get(handlers::market::get_ticker))
+        .route("/api/v1/market/depth", get(handlers::market::get_depth))
+        .route("/api/v1/book/stats", get(handlers::market::get_book_stats))
+        .route("/api/v1/estimate", get(handlers::market::get_estimate))
+        .route("/api/v1/symbols", get(handlers::market::get_symbols))
+        .route(
+            "/api/v1/constraints",
+            get(handlers::market::get_constraints),
+        )
         .route("/api/v1/health", get(handlers::health::health_check))
         .route("/api/v1/ws", get(handlers::websocket::ws_upgrade))
+        .route("/api/v1/events", get(handlers::events::sse_events))
         .route(
-            "/metrics",
-            get(move || {
-                let handle = prom_handle.clone();
-                async move { handle.render() }
-            }),
+            "/api/v1/admin/risk-config",
+            get(handlers::admin::get_risk_config),
+        )
+        .route(
+            "/api/v1/admin/opening-price",
+            put(handlers::admin::set_opening_price),
+        )
+        .route(
+            "/api/v1/admin/positions/import",
+            post(handlers::admin::import_positions),
+        )
+        .route(
+            "/api/v1/admin/integrity-check",
+            post(handlers::admin::check_integrity),
+        )
+        .route(
+            "/api/v1/admin/orders/:id",
+            delete(handlers::admin::cancel_order),
+        )
+        .route("/api/v1/admin/reset", post(handlers::admin::reset))
+        .route("/api/v1/admin/halt", post(handlers::admin::halt))
+        .route("/api/v1/admin/resume", post(handlers::admin::resume))
+        .route(
+            "/api/v1/admin/quote-only",
+            put(handlers::admin::set_quote_only),
         )
+        .route(
+            "/api/v1/admin/audit-log",
+            get(handlers::admin::get_audit_log),
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::read_only::reject_mutations,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::http_rate_limit::throttle,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::envelope::wrap_success,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::numbers_as_strings::stringify_numbers,
+        ))
         .layer(TraceLayer::new_for_http())
         .layer(CorsLayer::permissive())
-        .with_state(state);
-
-    tracing::info!("OrderFlow API listening on {}", bind_addr);
-    tracing::info!(
-        "Risk config: size [{}, {}], band ±{:.1}%, position ±{}, rate {}/s",
-        config.risk.min_order_size,
-        config.risk.max_order_size,
-        config.risk.price_band_percent,
-        config.risk.max_position_per_trader,
-        config.risk.max_orders_per_second
-    );
-
-    let listener = tokio::net::TcpListener::bind(&bind_addr).await.unwrap();
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
+        .with_state(state)
 }
 
 async fn shutdown_signal() {
@@ -85,3 +369,97 @@ async fn shutdown_signal() {
         .expect("Failed to install CTRL+C handler");
     tracing::info!("Shutting down...");
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::{Request, StatusCode};
+    use tower::ServiceExt;
+
+    fn get_req(uri: &str) -> Request<Body> {
+        Request::builder().uri(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_router_serves_trading_api_without_metrics() {
+        let config = Config::default();
+        let app = build_router(AppState::new(&config), None);
+
+        let resp = app
+            .clone()
+            .oneshot(get_req("/api/v1/health"))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = app.oneshot(get_req("/metrics")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_router_serves_metrics_with_recorder() {
+        let config = Config::default();
+        let handle = PrometheusBuilder::new().build_recorder().handle();
+        let app = build_router(AppState::new(&config), Some(handle));
+
+        let resp = app.oneshot(get_req("/metrics")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_non_numeric_order_id_returns_validation_error() {
+        let config = Config::default();
+        let app = build_router(AppState::new(&config), None);
+
+        for uri in [
+            "/api/v1/orders/abc",
+            "/api/v1/orders/-1",
+            "/api/v1/orders/0",
+        ] {
+            let req = Request::builder()
+                .method("DELETE")
+                .uri(uri)
+                .body(Body::empty())
+                .unwrap();
+            let resp = app.clone().oneshot(req).await.unwrap();
+            assert_eq!(resp.status(), StatusCode::BAD_REQUEST, "{}", uri);
+            let body = axum::body::to_bytes(resp.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+            assert_eq!(body["error"], "order id must be a positive integer");
+            assert_eq!(body["code"], 400);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_debug_state_needs_admin_key_and_debug_listener() {
+        let mut config = Config::default();
+        config.server.debug_endpoints = true;
+        config
+            .server
+            .admin_api_keys
+            .insert("secret".into(), "ops".into());
+        let state = AppState::new(&config);
+        let debug = build_debug_router(state.clone());
+        let uri = "/api/v1/admin/debug/state";
+
+        let resp = debug.clone().oneshot(get_req(uri)).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNAUTHORIZED);
+
+        let req = Request::builder()
+            .uri(uri)
+            .header(middleware::admin_auth::ADMIN_KEY_HEADER, "secret")
+            .body(Body::empty())
+            .unwrap();
+        let resp = debug.oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+
+        // Never served on the trading port
+        let resp = build_router(state, None)
+            .oneshot(get_req(uri))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,39 @@
+use axum::async_trait;
+use axum::extract::FromRequestParts;
+use axum::http::request::Parts;
+
+use crate::models::error::ApiError;
+use crate::state::AppState;
+
+pub const ADMIN_KEY_HEADER: &str = "x-admin-key";
+
+/// Principal behind an admin request, resolved from the `X-Admin-Key` header
+/// against `server.admin_api_keys`: 401 without the header, 403 for an
+/// unknown key. With no keys configured every request is refused with 403.
+#[derive(Debug, Clone)]
+pub struct AdminActor(pub String);
+
+#[async_trait]
+impl FromRequestParts<AppState> for AdminActor {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &AppState) -> Result<Self, ApiError> {
+        if state.admin_keys.is_empty() {
+            return Err(ApiError::Forbidden(
+                "Admin API is disabled: no server.admin_api_keys configured".into(),
+            ));
+        }
+
+        let key = parts
+            .headers
+            .get(ADMIN_KEY_HEADER)
+            .and_then(|v| v.to_str().ok())
+            .ok_or_else(|| ApiError::Unauthorized("Missing X-Admin-Key header".into()))?;
+
+        state
+            .admin_keys
+            .get(key)
+            .map(|principal| AdminActor(principal.clone()))
+            .ok_or_else(|| ApiError::Forbidden("Unknown admin key".into()))
+    }
+}
@@ -0,0 +1,166 @@
+use axum::body::{to_bytes, Body};
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderValue, Uri};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use serde::Serialize;
+
+use crate::engine::orderbook::unix_nanos;
+use crate::models::error::ApiError;
+use crate::state::AppState;
+
+/// Largest response body buffered for rewriting
+const MAX_BODY_BYTES: usize = 16 * 1024 * 1024;
+
+/// Successful response body, shaped like `ErrorBody` for clients that want
+/// one envelope for both.
+#[derive(Serialize)]
+pub struct Envelope {
+    pub data: serde_json::Value,
+    pub meta: EnvelopeMeta,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvelopeMeta {
+    /// The response's own `seq` when it has one, else the book sequence when
+    /// the response was sent
+    pub seq: u64,
+    pub timestamp_ns: u64,
+}
+
+/// `?envelope=true` or `?envelope=false`, if the request says.
+fn envelope_param(uri: &Uri) -> Option<bool> {
+    uri.query()?
+        .split('&')
+        .filter_map(|pair| pair.strip_prefix("envelope="))
+        .find_map(|value| match value {
+            "true" | "1" => Some(true),
+            "false" | "0" => Some(false),
+            _ => None,
+        })
+}
+
+/// Wrap successful JSON responses in an `Envelope`, when configured for
+/// every client or asked for by this request. Errors already have their own
+/// envelope and pass through unchanged.
+pub async fn wrap_success(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let wanted = envelope_param(req.uri()).unwrap_or(state.envelope_responses);
+    let resp = next.run(req).await;
+    let is_json = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if !wanted || !is_json || !resp.status().is_success() {
+        return resp;
+    }
+
+    let (mut parts, body) = resp.into_parts();
+    let bytes = match to_bytes(body, MAX_BODY_BYTES).await {
+        Ok(bytes) => bytes,
+        Err(e) => {
+            return ApiError::Internal(format!("Failed to read response: {}", e)).into_response()
+        }
+    };
+    let Ok(data) = serde_json::from_slice::<serde_json::Value>(&bytes) else {
+        return Response::from_parts(parts, Body::from(bytes));
+    };
+    let seq = data["seq"]
+        .as_u64()
+        .unwrap_or_else(|| state.engine.book_seq());
+    let envelope = Envelope {
+        data,
+        meta: EnvelopeMeta {
+            seq,
+            timestamp_ns: unix_nanos(),
+        },
+    };
+
+    let body = serde_json::to_string(&envelope).unwrap_or_default();
+    parts
+        .headers
+        .insert(header::CONTENT_LENGTH, HeaderValue::from(body.len()));
+    Response::from_parts(parts, Body::from(body))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::handlers;
+
+    fn app(config: &Config) -> Router {
+        let state = AppState::new(config);
+        Router::new()
+            .route("/api/v1/orders", post(handlers::orders::submit_order))
+            .route("/api/v1/market", get(handlers::market::get_market_snapshot))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                wrap_success,
+            ))
+            .with_state(state)
+    }
+
+    async fn json(app: &Router, req: Request) -> (StatusCode, serde_json::Value) {
+        let resp = app.clone().oneshot(req).await.unwrap();
+        let status = resp.status();
+        let bytes = to_bytes(resp.into_body(), MAX_BODY_BYTES).await.unwrap();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    fn order(uri: &str) -> Request {
+        let body =
+            r#"{"traderId":"alice","price":1.00,"quantity":10,"side":"BUY","orderType":"LIMIT"}"#;
+        Request::post(uri)
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_enveloped_response_wraps_bare_body() {
+        let app = app(&Config::default());
+        let (_, submitted) = json(&app, order("/api/v1/orders")).await;
+        assert!(submitted.get("data").is_none());
+
+        let market = || Request::get("/api/v1/market").body(Body::empty()).unwrap();
+        let (_, bare) = json(&app, market()).await;
+        let enveloped_req = Request::get("/api/v1/market?envelope=true")
+            .body(Body::empty())
+            .unwrap();
+        let (status, enveloped) = json(&app, enveloped_req).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(enveloped["data"], bare);
+        assert_eq!(enveloped["meta"]["seq"], bare["seq"]);
+        assert!(enveloped["meta"]["timestampNs"].as_u64().unwrap() > 0);
+    }
+
+    #[tokio::test]
+    async fn test_config_default_and_errors() {
+        let mut config = Config::default();
+        config.server.envelope_responses = true;
+        let app = app(&config);
+
+        let (status, created) = json(&app, order("/api/v1/orders")).await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert_eq!(created["meta"]["seq"], created["data"]["seq"]);
+
+        // The request can still opt out
+        let (_, bare) = json(&app, order("/api/v1/orders?envelope=false")).await;
+        assert!(bare["orderId"].is_u64());
+
+        // Errors keep their own shape
+        let req = Request::get("/api/v1/market?symbol=NOPE")
+            .body(Body::empty())
+            .unwrap();
+        let (status, error) = json(&app, req).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(error["error"].is_string());
+        assert!(error.get("meta").is_none());
+    }
+}
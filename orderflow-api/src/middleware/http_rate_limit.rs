@@ -0,0 +1,154 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::num::NonZeroU32;
+
+use axum::extract::{ConnectInfo, MatchedPath, Request, State};
+use axum::http::{header, HeaderValue};
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+use governor::clock::{Clock, DefaultClock};
+use governor::{DefaultKeyedRateLimiter, Quota, RateLimiter};
+
+use crate::models::error::ApiError;
+use crate::state::AppState;
+
+/// Per-route request limits keyed by client IP, from
+/// `server.http_rate_limits`. Routes without an entry are not limited.
+pub struct HttpRateLimiter {
+    /// Route pattern, e.g. `/api/v1/market` → requests/sec per IP
+    routes: HashMap<String, (NonZeroU32, DefaultKeyedRateLimiter<IpAddr>)>,
+    clock: DefaultClock,
+}
+
+impl HttpRateLimiter {
+    pub fn new(limits: &HashMap<String, u32>) -> Self {
+        let routes = limits
+            .iter()
+            .filter_map(|(route, &per_second)| {
+                let per_second = NonZeroU32::new(per_second)?;
+                let limiter = RateLimiter::keyed(Quota::per_second(per_second));
+                Some((route.clone(), (per_second, limiter)))
+            })
+            .collect();
+        Self {
+            routes,
+            clock: DefaultClock::default(),
+        }
+    }
+
+    /// Count a request to `route` from `ip`. When over the limit, returns
+    /// how many whole seconds until the next one would be allowed.
+    pub fn check(&self, route: &str, ip: IpAddr) -> Result<(), (NonZeroU32, u64)> {
+        let Some((per_second, limiter)) = self.routes.get(route) else {
+            return Ok(());
+        };
+        limiter.check_key(&ip).map_err(|not_until| {
+            let wait = not_until.wait_time_from(self.clock.now());
+            (*per_second, wait.as_secs_f64().ceil().max(1.0) as u64)
+        })
+    }
+
+    /// Forget IPs whose buckets have fully refilled.
+    pub fn retain_recent(&self) {
+        for (_, limiter) in self.routes.values() {
+            limiter.retain_recent();
+        }
+    }
+}
+
+/// Refuse requests over their route's per-IP limit with 429 and a
+/// `Retry-After` header. Requests without a known peer address share one
+/// bucket.
+pub async fn throttle(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map_or_else(|| req.uri().path(), |p| p.as_str());
+    let ip = req
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(IpAddr::V4(Ipv4Addr::UNSPECIFIED), |c| c.0.ip());
+
+    if let Err((per_second, retry_after)) = state.http_rate_limiter.check(route, ip) {
+        let message = format!(
+            "Too many requests to {} from {} (max {}/sec)",
+            route, ip, per_second
+        );
+        let mut resp = ApiError::RateLimited(message).into_response();
+        resp.headers_mut()
+            .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        return resp;
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::get;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::handlers;
+
+    fn app() -> Router {
+        let mut config = Config::default();
+        config
+            .server
+            .http_rate_limits
+            .insert("/api/v1/market".into(), 3);
+        let state = AppState::new(&config);
+        Router::new()
+            .route("/api/v1/market", get(handlers::market::get_market_snapshot))
+            .route("/api/v1/health", get(handlers::health::health_check))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                throttle,
+            ))
+            .with_state(state)
+    }
+
+    fn from(uri: &str, ip: [u8; 4]) -> Request {
+        let mut req = Request::get(uri).body(Body::empty()).unwrap();
+        req.extensions_mut()
+            .insert(ConnectInfo(SocketAddr::from((ip, 40_000))));
+        req
+    }
+
+    #[tokio::test]
+    async fn test_rapid_snapshot_polling_throttled_per_ip() {
+        let app = app();
+        for _ in 0..3 {
+            let resp = app
+                .clone()
+                .oneshot(from("/api/v1/market", [10, 0, 0, 1]))
+                .await
+                .unwrap();
+            assert_eq!(resp.status(), StatusCode::OK);
+        }
+
+        let resp = app
+            .clone()
+            .oneshot(from("/api/v1/market", [10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(resp.headers()[header::RETRY_AFTER], "1");
+
+        // Other clients and unlisted routes are unaffected
+        let resp = app
+            .clone()
+            .oneshot(from("/api/v1/market", [10, 0, 0, 2]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+        let resp = app
+            .oneshot(from("/api/v1/health", [10, 0, 0, 1]))
+            .await
+            .unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+}
@@ -0,0 +1,101 @@
+use axum::async_trait;
+use axum::body::Bytes;
+use axum::extract::rejection::JsonRejection;
+use axum::extract::{FromRequest, Request};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use serde::de::DeserializeOwned;
+
+use crate::models::error::ApiError;
+use crate::state::AppState;
+
+/// A JSON request body. With `server.strict_content_type`, a body sent
+/// without a JSON content type is refused with a structured 415 instead of
+/// axum's plain-text one; otherwise the body is parsed whatever the header
+/// says. Malformed JSON is rejected as a bare `Json` would.
+#[derive(Debug, Clone, Copy)]
+pub struct JsonBody<T>(pub T);
+
+#[async_trait]
+impl<T: DeserializeOwned> FromRequest<AppState> for JsonBody<T> {
+    type Rejection = Response;
+
+    async fn from_request(req: Request, state: &AppState) -> Result<Self, Response> {
+        if !state.strict_content_type {
+            let bytes = Bytes::from_request(req, state)
+                .await
+                .map_err(IntoResponse::into_response)?;
+            return Json::<T>::from_bytes(&bytes)
+                .map(|Json(value)| JsonBody(value))
+                .map_err(IntoResponse::into_response);
+        }
+
+        let content_type = req
+            .headers()
+            .get(header::CONTENT_TYPE)
+            .map(|v| String::from_utf8_lossy(v.as_bytes()).into_owned());
+        match Json::<T>::from_request(req, state).await {
+            Ok(Json(value)) => Ok(JsonBody(value)),
+            Err(JsonRejection::MissingJsonContentType(_)) => {
+                let got = content_type.map_or("no Content-Type".into(), |ct| format!("'{}'", ct));
+                Err(ApiError::UnsupportedMediaType(format!(
+                    "Request body must be sent as Content-Type: application/json, got {}",
+                    got
+                ))
+                .into_response())
+            }
+            Err(e) => Err(e.into_response()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::handlers;
+
+    fn app(strict: bool) -> Router {
+        let mut config = Config::default();
+        config.server.strict_content_type = strict;
+        Router::new()
+            .route("/api/v1/orders", post(handlers::orders::submit_order))
+            .with_state(AppState::new(&config))
+    }
+
+    fn submit(content_type: &str) -> Request {
+        let body =
+            r#"{"traderId":"alice","price":100.0,"quantity":10,"side":"BUY","orderType":"LIMIT"}"#;
+        Request::post("/api/v1/orders")
+            .header("content-type", content_type)
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_text_plain_order_rejected_with_415() {
+        let resp = app(true).oneshot(submit("text/plain")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::UNSUPPORTED_MEDIA_TYPE);
+
+        let body = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["code"], 415);
+        assert!(body["error"].as_str().unwrap().contains("'text/plain'"));
+
+        let resp = app(true).oneshot(submit("application/json")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_lenient_mode_parses_any_content_type() {
+        let resp = app(false).oneshot(submit("text/plain")).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+}
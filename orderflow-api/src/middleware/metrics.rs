@@ -26,3 +26,20 @@ pub fn record_engine_latency(start: Instant) {
     let duration = start.elapsed().as_secs_f64();
     histogram!("orderflow_engine_latency_seconds").record(duration);
 }
+
+pub fn record_crossed_book() {
+    counter!("orderflow_crossed_book_total").increment(1);
+}
+
+pub fn record_inconsistent_engine_result() {
+    counter!("orderflow_inconsistent_engine_result_total").increment(1);
+}
+
+pub fn record_market_quality_alert(state: &'static str) {
+    counter!("orderflow_market_quality_alerts_total", "state" => state).increment(1);
+}
+
+pub fn record_ffi_call(operation: &'static str, start: Instant) {
+    let duration = start.elapsed().as_secs_f64();
+    histogram!("orderflow_ffi_call_seconds", "operation" => operation).record(duration);
+}
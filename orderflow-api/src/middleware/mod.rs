@@ -1 +1,8 @@
+pub mod admin_auth;
+pub mod envelope;
+pub mod http_rate_limit;
+pub mod json_body;
 pub mod metrics;
+pub mod numbers_as_strings;
+pub mod order_id;
+pub mod read_only;
@@ -0,0 +1,100 @@
+use axum::extract::{Request, State};
+use axum::http::{header, HeaderMap, HeaderValue};
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::models::number::with_string_numbers;
+use crate::state::AppState;
+
+/// Whether the client asked for string numbers with an `Accept` parameter,
+/// e.g. `Accept: application/json; numbers=string`.
+fn accepts_string_numbers(headers: &HeaderMap) -> bool {
+    headers
+        .get_all(header::ACCEPT)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split([',', ';']))
+        .any(|param| param.trim().eq_ignore_ascii_case("numbers=string"))
+}
+
+/// Serialize quantities and ids in JSON responses as strings, when
+/// configured for every client or negotiated by this one. The response
+/// models mark those fields with `number::large_integer`.
+pub async fn stringify_numbers(
+    State(state): State<AppState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let wanted = state.numbers_as_strings || accepts_string_numbers(req.headers());
+    let mut resp = with_string_numbers(wanted, next.run(req)).await;
+    if !state.numbers_as_strings {
+        // The body depends on `Accept`, so caches must key on it
+        resp.headers_mut()
+            .append(header::VARY, HeaderValue::from_static("accept"));
+    }
+    resp
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::{to_bytes, Body};
+    use axum::http::StatusCode;
+    use axum::routing::post;
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::handlers;
+
+    fn app(config: &Config) -> Router {
+        let state = AppState::new(config);
+        Router::new()
+            .route("/api/v1/orders", post(handlers::orders::submit_order))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                stringify_numbers,
+            ))
+            .with_state(state)
+    }
+
+    #[tokio::test]
+    async fn test_large_quantity_round_trips_as_string() {
+        let mut config = Config::default();
+        config.risk.max_order_size = i64::MAX / 8;
+        config.risk.max_position_per_trader = i64::MAX / 8;
+        let quantity = "9007199254740993"; // 2^53 + 1
+        let body = format!(
+            r#"{{"traderId":"alice","price":1.00,"quantity":"{}","side":"BUY","orderType":"LIMIT"}}"#,
+            quantity
+        );
+        let req = Request::post("/api/v1/orders")
+            .header("content-type", "application/json")
+            .header("accept", "application/json; numbers=string")
+            .body(Body::from(body))
+            .unwrap();
+
+        let resp = app(&config).oneshot(req).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+        assert_eq!(resp.headers()[header::VARY], "accept");
+        let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["remainingQuantity"], quantity);
+        assert!(json["orderId"].is_string());
+    }
+
+    #[tokio::test]
+    async fn test_numbers_unchanged_by_default() {
+        let body =
+            r#"{"traderId":"alice","price":1.00,"quantity":10,"side":"BUY","orderType":"LIMIT"}"#;
+        let req = Request::post("/api/v1/orders")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap();
+
+        let resp = app(&Config::default()).oneshot(req).await.unwrap();
+        let bytes = to_bytes(resp.into_body(), usize::MAX).await.unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(json["remainingQuantity"], 10);
+    }
+}
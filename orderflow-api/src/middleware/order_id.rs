@@ -0,0 +1,27 @@
+use axum::async_trait;
+use axum::extract::rejection::PathRejection;
+use axum::extract::{FromRequestParts, Path};
+use axum::http::request::Parts;
+
+use crate::models::error::ApiError;
+
+/// An `:id` path segment parsed as an order id. Unlike a bare `Path<u64>`,
+/// a non-numeric, negative or zero id is rejected with the crate's
+/// structured validation error instead of axum's plain-text 400.
+#[derive(Debug, Clone, Copy)]
+pub struct OrderId(pub u64);
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for OrderId {
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, ApiError> {
+        match Path::<u64>::from_request_parts(parts, state).await {
+            Ok(Path(id)) if id > 0 => Ok(OrderId(id)),
+            Ok(_) | Err(PathRejection::FailedToDeserializePathParams(_)) => Err(
+                ApiError::Validation("order id must be a positive integer".into()),
+            ),
+            Err(e) => Err(ApiError::Internal(e.body_text())),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use axum::extract::{Request, State};
+use axum::http::Method;
+use axum::middleware::Next;
+use axum::response::{IntoResponse, Response};
+
+use crate::models::error::ApiError;
+use crate::state::AppState;
+
+/// On a read-only replica, refuse anything that isn't a read. Market data,
+/// health, WebSocket and SSE subscriptions are all GETs and pass through.
+pub async fn reject_mutations(State(state): State<AppState>, req: Request, next: Next) -> Response {
+    let is_read = matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS);
+    if state.read_only && !is_read {
+        return ApiError::ReadOnly.into_response();
+    }
+    next.run(req).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::Body;
+    use axum::http::StatusCode;
+    use axum::routing::{get, post};
+    use axum::Router;
+    use tower::ServiceExt;
+
+    use crate::config::Config;
+    use crate::handlers;
+
+    fn app(read_only: bool) -> Router {
+        let mut config = Config::default();
+        config.server.read_only = read_only;
+        let state = AppState::new(&config);
+        Router::new()
+            .route("/api/v1/orders", post(handlers::orders::submit_order))
+            .route("/api/v1/market", get(handlers::market::get_market_snapshot))
+            .layer(axum::middleware::from_fn_with_state(
+                state.clone(),
+                reject_mutations,
+            ))
+            .with_state(state)
+    }
+
+    fn submit() -> Request {
+        let body =
+            r#"{"traderId":"alice","price":100.0,"quantity":10,"side":"BUY","orderType":"LIMIT"}"#;
+        Request::post("/api/v1/orders")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_read_only_rejects_submit_but_serves_market() {
+        let app = app(true);
+
+        let resp = app.clone().oneshot(submit()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let market = Request::get("/api/v1/market").body(Body::empty()).unwrap();
+        let resp = app.oneshot(market).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_writable_instance_accepts_submit() {
+        let resp = app(false).oneshot(submit()).await.unwrap();
+        assert_eq!(resp.status(), StatusCode::CREATED);
+    }
+}
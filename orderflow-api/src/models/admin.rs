@@ -0,0 +1,57 @@
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+/// One entry in the admin audit trail.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AdminAuditEntry {
+    /// `ConfigChanged` for settings, `AdminAction` for operational actions
+    pub event: &'static str,
+    /// Principal resolved from the admin API key
+    pub actor: String,
+    /// What was changed or done, e.g. `halt` or `risk.openingPrice`
+    pub action: String,
+    pub old_value: serde_json::Value,
+    pub new_value: serde_json::Value,
+    pub timestamp_ns: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HaltStatus {
+    pub halted: bool,
+}
+
+/// Net positions as trader_id → symbol → position; also the body of
+/// `POST /api/v1/admin/positions/import`.
+pub type Positions = BTreeMap<String, BTreeMap<String, i64>>;
+
+/// Response to `POST /api/v1/admin/positions/import`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionImportResult {
+    pub imported: usize,
+    /// Traders whose imported position is already over the position limit
+    pub over_limit: Vec<String>,
+}
+
+/// Response to `POST /api/v1/admin/reset`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResetResult {
+    pub orders_removed: usize,
+    pub positions_cleared: usize,
+    /// Book sequence of the reset; see `MarketSnapshot::seq`
+    pub seq: u64,
+}
+
+/// Body and response of `PUT /api/v1/admin/quote-only`.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuoteOnlyStatus {
+    /// Instrument to switch; omitted switches every symbol
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub symbol: Option<String>,
+    pub quote_only: bool,
+}
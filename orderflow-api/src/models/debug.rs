@@ -0,0 +1,31 @@
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use super::admin::Positions;
+use super::number::large_integer;
+use super::order::Side;
+
+/// Dump of in-memory service registries for incident response.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DebugState {
+    /// trader_id → symbol → net filled position
+    pub positions: Positions,
+    /// trader_id → number of registered open orders
+    pub open_order_counts: BTreeMap<String, usize>,
+    pub order_registry: Vec<RegisteredOrder>,
+    pub rate_limiter_keys: Vec<String>,
+    pub ws_connections: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegisteredOrder {
+    #[serde(serialize_with = "large_integer")]
+    pub order_id: u64,
+    pub trader_id: String,
+    pub side: Side,
+    #[serde(serialize_with = "large_integer")]
+    pub resting_quantity: i64,
+}
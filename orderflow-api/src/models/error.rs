@@ -3,6 +3,8 @@ use axum::response::{IntoResponse, Response};
 use axum::Json;
 use serde::Serialize;
 
+use crate::engine::reject::RejectReason;
+
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
     #[error("Validation error: {0}")]
@@ -11,41 +13,195 @@ pub enum ApiError {
     #[error("Order not found: {0}")]
     NotFound(u64),
 
-    #[error("Engine rejected: {0}")]
-    EngineRejection(String),
+    #[error("Unknown symbol: {0}")]
+    UnknownSymbol(String),
+
+    #[error("Idempotency key not found: {0}")]
+    KeyNotFound(String),
+
+    #[error("Engine rejected: {}", .0.message)]
+    EngineRejection(EngineCause),
 
-    #[error("Risk rejection: {0}")]
-    RiskRejection(String),
+    #[error("Risk rejection: {}", .0.reason)]
+    RiskRejection(RiskCause),
 
     #[error("Rate limited: {0}")]
     RateLimited(String),
 
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
+    #[error("Trading halted: {0}")]
+    Halted(String),
+
+    #[error("Read-only replica")]
+    ReadOnly,
+
     #[error("Internal error: {0}")]
     Internal(String),
 }
 
-#[derive(Serialize)]
-struct ErrorBody {
-    error: String,
-    code: u16,
+impl ApiError {
+    pub fn engine(reason: RejectReason, message: impl Into<String>) -> Self {
+        ApiError::EngineRejection(EngineCause {
+            reason,
+            message: message.into(),
+        })
+    }
+
+    pub fn risk(rule_id: RiskRule, reason: impl Into<String>) -> Self {
+        ApiError::RiskRejection(RiskCause {
+            rule_id,
+            reason: reason.into(),
+        })
+    }
 }
 
-impl IntoResponse for ApiError {
-    fn into_response(self) -> Response {
-        let (status, message) = match &self {
+/// Why the engine refused an order or amendment.
+#[derive(Debug, Clone)]
+pub struct EngineCause {
+    pub reason: RejectReason,
+    pub message: String,
+}
+
+/// The risk rule that refused an order, and why.
+#[derive(Debug, Clone)]
+pub struct RiskCause {
+    pub rule_id: RiskRule,
+    pub reason: String,
+}
+
+/// Stable identifiers for risk rules, named after the `[risk]` setting that
+/// configures each one.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskRule {
+    MinOrderSize,
+    MaxOrderSize,
+    PriceBandPercent,
+    OneSidedPriceBandPercent,
+    MaxPositionPerTrader,
+    MaxOrderNotional,
+    MaxModifiesPerOrder,
+    ReduceOnlyTraders,
+    /// A `RiskPlugin` rule with no setting of its own
+    Custom,
+}
+
+/// JSON body of an error response.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ErrorBody {
+    pub error: String,
+    pub code: u16,
+    /// Set on risk rejections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule_id: Option<RiskRule>,
+    /// Set on engine rejections
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason_code: Option<RejectReason>,
+}
+
+impl ApiError {
+    /// Status and body this error is reported with.
+    pub fn to_body(&self) -> (StatusCode, ErrorBody) {
+        let (status, message) = match self {
             ApiError::Validation(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
             ApiError::NotFound(id) => (StatusCode::NOT_FOUND, format!("Order {} not found", id)),
-            ApiError::EngineRejection(msg) => (StatusCode::CONFLICT, msg.clone()),
-            ApiError::RiskRejection(msg) => (StatusCode::UNPROCESSABLE_ENTITY, msg.clone()),
+            ApiError::UnknownSymbol(symbol) => {
+                (StatusCode::NOT_FOUND, format!("Unknown symbol '{}'", symbol))
+            }
+            ApiError::KeyNotFound(key) => (
+                StatusCode::NOT_FOUND,
+                format!("No order for idempotency key '{}' (unknown or expired)", key),
+            ),
+            ApiError::EngineRejection(cause) => (StatusCode::CONFLICT, cause.message.clone()),
+            ApiError::RiskRejection(cause) => {
+                (StatusCode::UNPROCESSABLE_ENTITY, cause.reason.clone())
+            }
             ApiError::RateLimited(msg) => (StatusCode::TOO_MANY_REQUESTS, msg.clone()),
+            ApiError::UnsupportedMediaType(msg) => {
+                (StatusCode::UNSUPPORTED_MEDIA_TYPE, msg.clone())
+            }
+            ApiError::Unauthorized(msg) => (StatusCode::UNAUTHORIZED, msg.clone()),
+            ApiError::Forbidden(msg) => (StatusCode::FORBIDDEN, msg.clone()),
+            ApiError::Conflict(msg) => (StatusCode::CONFLICT, msg.clone()),
+            ApiError::Halted(msg) => (StatusCode::SERVICE_UNAVAILABLE, msg.clone()),
+            ApiError::ReadOnly => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                "This instance is a read-only replica".into(),
+            ),
             ApiError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
         };
 
+        let rule_id = match self {
+            ApiError::RiskRejection(cause) => Some(cause.rule_id),
+            _ => None,
+        };
+        let reason_code = match self {
+            ApiError::EngineRejection(cause) => Some(cause.reason),
+            _ => None,
+        };
         let body = ErrorBody {
             error: message,
             code: status.as_u16(),
+            rule_id,
+            reason_code,
         };
+        (status, body)
+    }
+}
 
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        let (status, body) = self.to_body();
         (status, Json(body)).into_response()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::body::to_bytes;
+
+    async fn body_of(err: ApiError) -> serde_json::Value {
+        let bytes = to_bytes(err.into_response().into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&bytes).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_risk_rejection_body_carries_rule_id() {
+        let err = ApiError::risk(
+            RiskRule::MaxOrderSize,
+            "Order size 20000 exceeds maximum 10000",
+        );
+        let body = body_of(err).await;
+        assert_eq!(body["code"], 422);
+        assert_eq!(body["ruleId"], "max_order_size");
+        assert_eq!(body["error"], "Order size 20000 exceeds maximum 10000");
+
+        let body = body_of(ApiError::NotFound(7)).await;
+        assert!(body.get("ruleId").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_engine_rejection_body_carries_reason_code() {
+        let err = ApiError::engine(RejectReason::PostOnlyWouldMatch, "post-only would cross");
+        let body = body_of(err).await;
+        assert_eq!(body["code"], 409);
+        assert_eq!(body["reasonCode"], "post_only_would_match");
+        assert_eq!(body["error"], "post-only would cross");
+        assert!(body.get("ruleId").is_none());
+    }
+}
@@ -1,6 +1,9 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+use super::number::{large_integer, optional_large_integer};
+use super::order::Side;
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct MarketSnapshot {
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -14,5 +17,163 @@ pub struct MarketSnapshot {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub last_trade_price: Option<f64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(serialize_with = "optional_large_integer")]
     pub last_trade_qty: Option<i64>,
+    /// Book sequence this snapshot reflects. WebSocket events carry the
+    /// sequence of the change they report; apply only those with a higher one.
+    pub seq: u64,
+}
+
+/// Top of book only, for clients polling the touch. Quantities and order
+/// counts are zero when a side is empty.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_bid: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_ask: Option<f64>,
+    #[serde(serialize_with = "large_integer")]
+    pub bid_quantity: i64,
+    #[serde(serialize_with = "large_integer")]
+    pub ask_quantity: i64,
+    /// Orders queued at the best bid
+    pub bid_order_count: usize,
+    /// Orders queued at the best ask
+    pub ask_order_count: usize,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SymbolQuery {
+    /// Defaults to the configured `engine.symbol`
+    pub symbol: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DepthQuery {
+    /// Levels per side; defaults to 10
+    pub levels: Option<usize>,
+}
+
+/// Aggregated levels on each side of the book, best price first.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MarketDepth {
+    pub bids: Vec<BookLevel>,
+    pub asks: Vec<BookLevel>,
+    /// Book sequence this depth reflects; see `MarketSnapshot::seq`
+    pub seq: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookLevel {
+    pub price: f64,
+    #[serde(serialize_with = "large_integer")]
+    pub quantity: i64,
+    pub order_count: usize,
+}
+
+/// Resting orders and quantity per side across the whole book. Coarser than
+/// depth, for clients that only want the book's size.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookStats {
+    pub bid_orders: usize,
+    pub ask_orders: usize,
+    #[serde(serialize_with = "large_integer")]
+    pub bid_quantity: i64,
+    #[serde(serialize_with = "large_integer")]
+    pub ask_quantity: i64,
+}
+
+/// Operational overview of one instrument.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolStatus {
+    pub symbol: String,
+    /// Wall-clock time of the last book change, absent until the first one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_update_ns: Option<u64>,
+    pub resting_orders: usize,
+    /// Venue-wide halt, or the symbol's circuit breaker tripped
+    pub halted: bool,
+    pub quote_only: bool,
+}
+
+/// Order entry limits in force, for clients to validate orders before
+/// submitting them. Prices are in dollars, sizes in units.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderConstraints {
+    pub symbol: String,
+    /// Prices must be a whole multiple of this
+    pub tick_size: f64,
+    /// Quantities must be a whole multiple of this
+    pub lot_size: i64,
+    pub min_order_size: i64,
+    pub max_order_size: i64,
+    /// Limit prices must be within this percent of the reference price
+    pub price_band_percent: f64,
+    /// Band around the best price when the book is one-sided with no other
+    /// reference; absent when that check is off
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub one_sided_price_band_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_order_notional: Option<f64>,
+    pub max_position_per_trader: i64,
+    pub max_orders_per_second: u32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EstimateQuery {
+    pub side: Side,
+    pub quantity: i64,
+}
+
+/// What an order would cost if it hit the book now. Nothing is submitted;
+/// prices are in dollars.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionEstimate {
+    pub side: Side,
+    #[serde(serialize_with = "large_integer")]
+    pub quantity: i64,
+    /// How much of `quantity` the resting liquidity covers
+    #[serde(serialize_with = "large_integer")]
+    pub fillable_quantity: i64,
+    pub fully_fillable: bool,
+    /// Volume-weighted price across the levels reached
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub average_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub worst_price: Option<f64>,
+    /// Notional of the fillable quantity
+    pub total_cost: f64,
+    pub levels: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SymbolsResponse {
+    pub symbols: Vec<SymbolStatus>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub crossed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_bid: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub best_ask: Option<f64>,
+    pub halted: bool,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpeningPrice {
+    /// Instrument the price is for; omitted means the default symbol
+    #[serde(default)]
+    pub symbol: Option<String>,
+    pub price: f64,
 }
@@ -1,4 +1,7 @@
+pub mod admin;
+pub mod debug;
 pub mod error;
 pub mod market;
+pub mod number;
 pub mod order;
 pub mod trade;
@@ -0,0 +1,111 @@
+use std::fmt::Display;
+use std::future::Future;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Accept an integer either as a JSON number or as a decimal string, for
+/// clients that send large quantities as strings to avoid float rounding.
+pub fn i64_from_number_or_string<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum NumberOrString {
+        Number(i64),
+        String(String),
+    }
+
+    match NumberOrString::deserialize(deserializer)? {
+        NumberOrString::Number(n) => Ok(n),
+        NumberOrString::String(s) => s
+            .trim()
+            .parse()
+            .map_err(|_| serde::de::Error::custom(format!("invalid integer string \"{}\"", s))),
+    }
+}
+
+tokio::task_local! {
+    /// Whether the request being served negotiated string numbers; set by
+    /// the `numbers_as_strings` middleware around the handler.
+    static STRING_NUMBERS: bool;
+}
+
+/// Run `fut` with quantities and ids serialized as strings or numbers.
+pub async fn with_string_numbers<F: Future>(enabled: bool, fut: F) -> F::Output {
+    STRING_NUMBERS.scope(enabled, fut).await
+}
+
+fn string_numbers() -> bool {
+    STRING_NUMBERS.try_with(|enabled| *enabled).unwrap_or(false)
+}
+
+/// Serialize a quantity or id, which can exceed 2^53 where JavaScript's
+/// number type starts losing precision, as a decimal string when the current
+/// request asked for string numbers.
+pub fn large_integer<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display + Serialize,
+    S: Serializer,
+{
+    if string_numbers() {
+        serializer.collect_str(value)
+    } else {
+        value.serialize(serializer)
+    }
+}
+
+/// `large_integer` for optional fields.
+pub fn optional_large_integer<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    T: Display + Serialize,
+    S: Serializer,
+{
+    match value {
+        Some(value) => large_integer(value, serializer),
+        None => serializer.serialize_none(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Fill {
+        #[serde(serialize_with = "large_integer")]
+        order_id: u64,
+        #[serde(serialize_with = "optional_large_integer")]
+        last_qty: Option<i64>,
+        price: f64,
+        seq: u64,
+    }
+
+    fn fill() -> serde_json::Value {
+        serde_json::to_value(Fill {
+            order_id: 7,
+            last_qty: Some(9_007_199_254_740_993),
+            price: 100.5,
+            seq: 12,
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_only_marked_fields_become_strings() {
+        let value = with_string_numbers(true, async { fill() }).await;
+
+        assert_eq!(value["orderId"], "7");
+        assert_eq!(value["lastQty"], "9007199254740993");
+        assert_eq!(value["price"], 100.5);
+        assert_eq!(value["seq"], 12);
+    }
+
+    #[tokio::test]
+    async fn test_numbers_outside_negotiated_request() {
+        assert_eq!(fill()["orderId"], 7);
+        let value = with_string_numbers(false, async { fill() }).await;
+        assert_eq!(value["lastQty"], 9_007_199_254_740_993_i64);
+    }
+}
@@ -1,57 +1,338 @@
 use serde::{Deserialize, Serialize};
 
+use super::error::{ApiError, ErrorBody};
+use super::number::{i64_from_number_or_string, large_integer};
 use super::trade::TradeResponse;
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderRequest {
     pub trader_id: String,
     pub price: Option<f64>,
+    #[serde(deserialize_with = "i64_from_number_or_string")]
     pub quantity: i64,
     pub side: Side,
     pub order_type: OrderType,
     #[serde(default)]
     pub time_in_force: TimeInForce,
+    /// Unset takes the trader's configured default, or `ALLOW`
+    #[serde(default)]
+    pub stp_mode: Option<StpMode>,
+    /// Only ever execute the full quantity in one go; GTC limit orders only.
+    /// The order trades on arrival if it can fill completely, and otherwise
+    /// rests until an order large enough to take all of it arrives.
+    #[serde(default)]
+    pub all_or_none: bool,
+    /// Only ever add liquidity; GTC and GTD limit orders only. An order
+    /// that would trade on arrival is rejected rather than filled.
     #[serde(default)]
-    pub stp_mode: StpMode,
+    pub post_only: bool,
+    /// Book to trade on, created on the symbol's first order. Unset trades
+    /// the configured `engine.symbol`.
+    #[serde(default)]
+    pub symbol: Option<String>,
+    /// When a GTD order's resting remainder is cancelled, in Unix
+    /// nanoseconds. Required for GTD and refused for other time in force.
+    #[serde(default)]
+    pub expires_at_ns: Option<u64>,
 }
 
-#[derive(Debug, Serialize)]
+#[cfg(test)]
+impl OrderRequest {
+    /// A GTC limit order with every optional field left unset.
+    pub fn limit(trader_id: &str, price: f64, quantity: i64, side: Side) -> Self {
+        Self {
+            trader_id: trader_id.into(),
+            price: Some(price),
+            quantity,
+            side,
+            order_type: OrderType::Limit,
+            time_in_force: TimeInForce::Gtc,
+            stp_mode: None,
+            all_or_none: false,
+            post_only: false,
+            symbol: None,
+            expires_at_ns: None,
+        }
+    }
+
+    /// An IOC market order with every optional field left unset.
+    pub fn market(trader_id: &str, quantity: i64, side: Side) -> Self {
+        Self {
+            price: None,
+            order_type: OrderType::Market,
+            time_in_force: TimeInForce::Ioc,
+            ..Self::limit(trader_id, 0.0, quantity, side)
+        }
+    }
+}
+
+/// Flat query-string form of `OrderRequest` for clients that can't easily
+/// send JSON, e.g. `?trader=alice&side=BUY&price=100.50&qty=100&type=LIMIT`.
+#[derive(Debug, Deserialize)]
+pub struct SimpleOrderQuery {
+    pub trader: String,
+    pub side: Side,
+    pub price: Option<f64>,
+    pub qty: i64,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    #[serde(default)]
+    pub tif: TimeInForce,
+    #[serde(default)]
+    pub stp: Option<StpMode>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+}
+
+impl From<SimpleOrderQuery> for OrderRequest {
+    fn from(q: SimpleOrderQuery) -> Self {
+        Self {
+            trader_id: q.trader,
+            price: q.price,
+            quantity: q.qty,
+            side: q.side,
+            order_type: q.order_type,
+            time_in_force: q.tif,
+            stp_mode: q.stp,
+            all_or_none: false,
+            post_only: false,
+            symbol: q.symbol,
+            expires_at_ns: None,
+        }
+    }
+}
+
+/// What self-trade prevention did while an order matched.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StpOutcome {
+    /// The engine's description of the last action taken
+    pub action: String,
+    /// Every order it cancelled, the incoming one included
+    pub cancelled_orders: Vec<u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderResponse {
+    #[serde(serialize_with = "large_integer")]
     pub order_id: u64,
     pub accepted: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reject_reason: Option<String>,
     pub trades: Vec<TradeResponse>,
+    #[serde(serialize_with = "large_integer")]
     pub remaining_quantity: i64,
+    /// The remainder was cancelled instead of resting, because it would have
+    /// crossed the trader's own orders that self-trade prevention passed over
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub remainder_cancelled: bool,
+    /// When the resting remainder will be cancelled, in Unix nanoseconds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at_ns: Option<u64>,
+    /// Set when `trades` was cut short; the full list is available from
+    /// `GET /api/v1/orders/:id/executions`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub trades_truncated: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trade_summary: Option<TradeSummary>,
+    /// Resting orders that self-trade prevention cancelled while matching
+    #[serde(skip)]
+    pub stp_cancelled: Vec<u64>,
+    /// Set when self-trade prevention triggered, for the audit log
+    #[serde(skip)]
+    pub stp_outcome: Option<StpOutcome>,
+    /// Book sequence of this submission; see `MarketSnapshot::seq`
+    pub seq: u64,
+}
+
+impl OrderResponse {
+    /// Keep at most `max` trades inline, replacing the rest with a summary of
+    /// the whole fill.
+    pub fn truncate_trades(&mut self, max: usize) {
+        if self.trades.len() <= max {
+            return;
+        }
+        let filled_quantity: i64 = self.trades.iter().map(|t| t.quantity).sum();
+        let notional: f64 = self
+            .trades
+            .iter()
+            .map(|t| t.price * t.quantity as f64)
+            .sum();
+        self.trade_summary = Some(TradeSummary {
+            count: self.trades.len(),
+            filled_quantity,
+            avg_price: notional / filled_quantity as f64,
+        });
+        self.trades.truncate(max);
+        self.trades_truncated = true;
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeSummary {
+    pub count: usize,
+    #[serde(serialize_with = "large_integer")]
+    pub filled_quantity: i64,
+    pub avg_price: f64,
 }
 
 #[derive(Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModifyRequest {
     pub new_price: f64,
+    #[serde(deserialize_with = "i64_from_number_or_string")]
     pub new_quantity: i64,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ModifyResponse {
+    #[serde(serialize_with = "large_integer")]
     pub order_id: u64,
     pub accepted: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub reject_reason: Option<String>,
     pub old_price: f64,
     pub new_price: f64,
+    #[serde(serialize_with = "large_integer")]
     pub old_quantity: i64,
+    #[serde(serialize_with = "large_integer")]
     pub new_quantity: i64,
+    pub seq: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReduceRequest {
+    #[serde(deserialize_with = "i64_from_number_or_string")]
+    pub reduce_by: i64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReduceResponse {
+    #[serde(serialize_with = "large_integer")]
+    pub order_id: u64,
+    pub price: f64,
+    #[serde(serialize_with = "large_integer")]
+    pub old_quantity: i64,
+    #[serde(serialize_with = "large_integer")]
+    pub remaining_quantity: i64,
+    pub seq: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QueuePositionResponse {
+    #[serde(serialize_with = "large_integer")]
+    pub order_id: u64,
+    pub orders_ahead: usize,
+    #[serde(serialize_with = "large_integer")]
+    pub quantity_ahead: i64,
+}
+
+/// Current state of an order still on the book.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderStatusResponse {
+    #[serde(serialize_with = "large_integer")]
+    pub order_id: u64,
+    pub side: Side,
+    pub price: f64,
+    /// Quantity as submitted
+    #[serde(serialize_with = "large_integer")]
+    pub original_quantity: i64,
+    #[serde(serialize_with = "large_integer")]
+    pub remaining_quantity: i64,
+    #[serde(serialize_with = "large_integer")]
+    pub filled_quantity: i64,
+    pub status: OrderStatus,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStatus {
+    /// Resting with nothing filled yet
+    Resting,
+    /// Resting after one or more fills
+    PartiallyFilled,
 }
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CancelResponse {
+    #[serde(serialize_with = "large_integer")]
     pub order_id: u64,
     pub cancelled: bool,
+    pub reason: CancelReason,
+    pub seq: u64,
+}
+
+/// One step in an order's lifecycle.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderEvent {
+    /// `submitted`, `modified`, `reduced`, `filled` or `cancelled`
+    pub event: &'static str,
+    pub timestamp_ns: u64,
+    pub seq: u64,
+    /// Event-specific fields, e.g. the new price of a modify
+    pub details: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderHistoryResponse {
+    #[serde(serialize_with = "large_integer")]
+    pub order_id: u64,
+    /// Oldest first
+    pub events: Vec<OrderEvent>,
+}
+
+/// One order's outcome where the request as a whole went through (a batch
+/// entry, or the replacement in a cancel/replace): its usual response if
+/// accepted, or the error body a single submit would have returned.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum OrderOutcome {
+    Accepted(OrderResponse),
+    Rejected(ErrorBody),
+}
+
+impl From<Result<OrderResponse, ApiError>> for OrderOutcome {
+    fn from(result: Result<OrderResponse, ApiError>) -> Self {
+        match result {
+            Ok(response) => OrderOutcome::Accepted(response),
+            Err(e) => OrderOutcome::Rejected(e.to_body().1),
+        }
+    }
+}
+
+/// The original order is cancelled even if the engine rejects its
+/// replacement, so the cancel is always reported.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelReplaceResponse {
+    pub cancelled: CancelResponse,
+    pub order: OrderOutcome,
+}
+
+/// Which code path removed an order from the book.
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum CancelReason {
+    /// Explicit cancel request from the client
+    User,
+    /// Order lifetime ran out
+    Expired,
+    /// The owning session disconnected
+    Disconnect,
+    /// Self-trade prevention cancelled the resting order
+    Stp,
+    /// An operator force-cancelled the order
+    Admin,
 }
 
 // --- Enums matching C++ types ---
@@ -70,23 +351,28 @@ pub enum OrderType {
     Market,
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum TimeInForce {
+    #[default]
     Gtc,
     Ioc,
     Fok,
+    /// Good till date: rests like GTC until `expiresAtNs`
+    Gtd,
 }
 
-impl Default for TimeInForce {
-    fn default() -> Self {
-        Self::Gtc
+impl TimeInForce {
+    /// Whether an unfilled limit remainder stays on the book.
+    pub fn rests(self) -> bool {
+        matches!(self, TimeInForce::Gtc | TimeInForce::Gtd)
     }
 }
 
-#[derive(Debug, Clone, Copy, Deserialize, Serialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Default, Deserialize, Serialize, PartialEq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum StpMode {
+    #[default]
     Allow,
     CancelNewest,
     CancelOldest,
@@ -94,8 +380,15 @@ pub enum StpMode {
     DecrementAndCancel,
 }
 
-impl Default for StpMode {
-    fn default() -> Self {
-        Self::Allow
+impl StpMode {
+    /// The wire name, as accepted in `stpMode`.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StpMode::Allow => "ALLOW",
+            StpMode::CancelNewest => "CANCEL_NEWEST",
+            StpMode::CancelOldest => "CANCEL_OLDEST",
+            StpMode::CancelBoth => "CANCEL_BOTH",
+            StpMode::DecrementAndCancel => "DECREMENT_AND_CANCEL",
+        }
     }
 }
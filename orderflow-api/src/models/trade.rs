@@ -1,11 +1,142 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize)]
+use super::number::large_integer;
+
+/// A single execution.
+///
+/// Ordering: `exec_seq` is assigned under the book lock at match time, so it
+/// totally orders every trade the engine produces: consecutive within one
+/// sweep and strictly increasing across orders. Clients merging streams should
+/// sort on `exec_seq`, not `trade_id` or `timestamp_ns`. `trade_id` is
+/// unique across every symbol's book and currently equals `exec_seq`.
+///
+/// Clocks: by default `timestamp_ns` is wall-clock nanoseconds since the Unix
+/// epoch taken by the server at match time; trades from the same aggressive
+/// order share it, and it can step backwards if the host clock is adjusted.
+/// With `engine.trade_timestamp_source = "engine"` it is the matching
+/// engine's per-trade monotonic clock instead. That clock never steps back
+/// but counts from an arbitrary origin (host boot), so it can't be compared
+/// with wall-clock times or across hosts; `server_ts` is then always set as
+/// a wall-clock reference. `"both"` keeps server time in `timestamp_ns` and
+/// reports both clocks.
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeResponse {
+    #[serde(serialize_with = "large_integer")]
     pub trade_id: u64,
+    pub exec_seq: u64,
+    /// Instrument whose book the trade matched on
+    pub symbol: String,
+    #[serde(serialize_with = "large_integer")]
     pub buy_order_id: u64,
+    #[serde(serialize_with = "large_integer")]
     pub sell_order_id: u64,
     pub price: f64,
+    #[serde(serialize_with = "large_integer")]
     pub quantity: i64,
+    pub timestamp_ns: u64,
+    /// Engine monotonic clock, with the `both` timestamp source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub engine_ts: Option<u64>,
+    /// Server wall clock, with the `engine` or `both` timestamp source
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_ts: Option<u64>,
+    /// The submitter's own fee, on trades in a submit response when a fee
+    /// schedule is configured. Not kept in the trade history or printed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<TradeFee>,
+    /// Price move from the book's previous trade, with
+    /// `engine.trade_tick_direction`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tick_direction: Option<TickDirection>,
+}
+
+/// A trade's price against the previous trade on the same book. A book's
+/// first trade is a zero tick.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TickDirection {
+    Up,
+    Down,
+    Zero,
+}
+
+impl TickDirection {
+    /// `previous` is `None` before a book's first trade.
+    pub fn between<T: PartialOrd>(previous: Option<T>, price: T) -> Self {
+        match previous {
+            Some(previous) if price > previous => TickDirection::Up,
+            Some(previous) if price < previous => TickDirection::Down,
+            _ => TickDirection::Zero,
+        }
+    }
+}
+
+/// One side's fee on a trade, in dollars, at the tier it paid. A negative
+/// fee is a rebate.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradeFee {
+    pub amount: f64,
+    pub tier: String,
+}
+
+/// Both sides' fees on one trade. Each side only ever sees its own leg.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TradeFees {
+    pub maker: TradeFee,
+    pub taker: TradeFee,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExecutionsResponse {
+    #[serde(serialize_with = "large_integer")]
+    pub order_id: u64,
+    #[serde(serialize_with = "large_integer")]
+    pub filled_quantity: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_price: Option<f64>,
+    pub trades: Vec<TradeResponse>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradesQuery {
+    /// Only trades this trader was buyer or seller in
+    pub trader_id: Option<String>,
+    /// Cursor: only trades with a lower trade id
+    pub before: Option<u64>,
+    /// Tape cursor: only trades with a higher trade id, oldest first
+    #[serde(alias = "since_id")]
+    pub since_id: Option<u64>,
+    pub limit: Option<usize>,
+}
+
+/// A page of buffered trades: newest first, or oldest first after `sinceId`.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TradesResponse {
+    pub trades: Vec<TradeResponse>,
+    /// Pass as `before` to fetch the next page; absent on the last page
+    /// and when paging forward with `sinceId`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_before: Option<u64>,
+}
+
+/// Trade activity over trailing windows, from the in-memory trade buffer.
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityStats {
+    pub last_1m: WindowStats,
+    pub last_5m: WindowStats,
+    pub last_1h: WindowStats,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WindowStats {
+    pub trades: u64,
+    #[serde(serialize_with = "large_integer")]
+    pub volume: i64,
 }
@@ -0,0 +1,91 @@
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::engine::orderbook::unix_nanos;
+use crate::models::admin::AdminAuditEntry;
+
+/// Audit trail of admin mutations. Every entry is logged as a structured
+/// event and the most recent ones are kept in memory for the audit-log
+/// endpoint.
+pub struct AdminAuditLog {
+    entries: Mutex<VecDeque<AdminAuditEntry>>,
+    capacity: usize,
+}
+
+impl AdminAuditLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// A runtime setting changed, e.g. the opening price.
+    pub fn config_changed(&self, actor: &str, setting: &str, old_value: Value, new_value: Value) {
+        self.record("ConfigChanged", actor, setting, old_value, new_value);
+    }
+
+    /// An operational action, e.g. halting order entry.
+    pub fn admin_action(&self, actor: &str, action: &str, old_value: Value, new_value: Value) {
+        self.record("AdminAction", actor, action, old_value, new_value);
+    }
+
+    /// Retained entries, oldest first.
+    pub fn entries(&self) -> Vec<AdminAuditEntry> {
+        self.entries.lock().unwrap().iter().cloned().collect()
+    }
+
+    fn record(
+        &self,
+        event: &'static str,
+        actor: &str,
+        action: &str,
+        old_value: Value,
+        new_value: Value,
+    ) {
+        let entry = AdminAuditEntry {
+            event,
+            actor: actor.to_string(),
+            action: action.to_string(),
+            old_value,
+            new_value,
+            timestamp_ns: unix_nanos(),
+        };
+
+        tracing::info!(
+            event = entry.event,
+            actor = %entry.actor,
+            action = %entry.action,
+            old_value = %entry.old_value,
+            new_value = %entry.new_value,
+            timestamp_ns = entry.timestamp_ns,
+        );
+
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= self.capacity {
+            entries.pop_front();
+        }
+        entries.push_back(entry);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_entries_bounded_oldest_evicted() {
+        let log = AdminAuditLog::new(2);
+        for i in 0..3 {
+            log.admin_action("ops", &format!("action-{}", i), json!(null), json!(i));
+        }
+
+        let entries = log.entries();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "action-1");
+        assert_eq!(entries[1].action, "action-2");
+    }
+}
@@ -0,0 +1,284 @@
+use std::cmp::Ordering;
+use std::sync::Mutex;
+use std::time::Instant;
+
+use crate::models::error::ApiError;
+use crate::models::order::{OrderRequest, OrderType, Side};
+
+/// An order collected during the opening auction, with its engine-assigned id.
+pub struct AuctionOrder {
+    pub order_id: u64,
+    pub req: OrderRequest,
+}
+
+/// Outcome of price discovery: the opening price and the volume it matches.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Uncross {
+    pub price: f64,
+    pub volume: i64,
+}
+
+/// Whether `order` would trade at `price`. Market orders trade at any price.
+fn crosses_at(order: &AuctionOrder, price: f64) -> bool {
+    match (order.req.order_type, order.req.price) {
+        (OrderType::Market, _) | (_, None) => true,
+        (OrderType::Limit, Some(limit)) => match order.req.side {
+            Side::Buy => limit >= price,
+            Side::Sell => limit <= price,
+        },
+    }
+}
+
+/// Buy and sell quantity willing to trade at `price`.
+fn demand_and_supply(orders: &[AuctionOrder], price: f64) -> (i64, i64) {
+    orders
+        .iter()
+        .filter(|o| crosses_at(o, price))
+        .fold((0, 0), |(demand, supply), o| match o.req.side {
+            Side::Buy => (demand + o.req.quantity, supply),
+            Side::Sell => (demand, supply + o.req.quantity),
+        })
+}
+
+/// The limit price that maximises matched volume. Ties go to the smallest
+/// surplus (|demand - supply|), then to the price nearest `reference`, then
+/// to the lower price. `None` when no buy and sell cross.
+pub fn uncross_price(orders: &[AuctionOrder], reference: Option<f64>) -> Option<Uncross> {
+    let mut candidates: Vec<f64> = orders
+        .iter()
+        .filter(|o| o.req.order_type == OrderType::Limit)
+        .filter_map(|o| o.req.price)
+        .collect();
+    candidates.sort_by(f64::total_cmp);
+    candidates.dedup();
+
+    // (volume, surplus, distance from reference) per candidate, best first
+    let rank = |price: f64| {
+        let (demand, supply) = demand_and_supply(orders, price);
+        let distance = reference.map_or(0.0, |r| (price - r).abs());
+        (demand.min(supply), (demand - supply).abs(), distance)
+    };
+    let mut best: Option<(f64, (i64, i64, f64))> = None;
+    for price in candidates {
+        let key = rank(price);
+        let better = match &best {
+            None => true,
+            Some((_, best_key)) => {
+                key.0
+                    .cmp(&best_key.0)
+                    .then(best_key.1.cmp(&key.1))
+                    .then(best_key.2.total_cmp(&key.2))
+                    == Ordering::Greater
+            }
+        };
+        if better {
+            best = Some((price, key));
+        }
+    }
+
+    best.filter(|(_, (volume, _, _))| *volume > 0)
+        .map(|(price, (volume, _, _))| Uncross { price, volume })
+}
+
+/// Quantity each order fills at `uncross`, in the same order as `orders`.
+/// Each side fills by price priority, market orders first, then arrival.
+pub fn allocate(orders: &[AuctionOrder], uncross: Uncross) -> Vec<i64> {
+    let mut fills = vec![0; orders.len()];
+    for side in [Side::Buy, Side::Sell] {
+        let mut queue: Vec<usize> = (0..orders.len())
+            .filter(|&i| orders[i].req.side == side && crosses_at(&orders[i], uncross.price))
+            .collect();
+        queue.sort_by(|&a, &b| {
+            let limit = |i: usize| match orders[i].req.order_type {
+                OrderType::Market => None,
+                OrderType::Limit => orders[i].req.price,
+            };
+            let by_price = match (limit(a), limit(b)) {
+                (None, None) => Ordering::Equal,
+                (None, Some(_)) => Ordering::Less,
+                (Some(_), None) => Ordering::Greater,
+                (Some(pa), Some(pb)) => match side {
+                    Side::Buy => pb.total_cmp(&pa),
+                    Side::Sell => pa.total_cmp(&pb),
+                },
+            };
+            by_price.then(orders[a].order_id.cmp(&orders[b].order_id))
+        });
+
+        let mut left = uncross.volume;
+        for i in queue {
+            let fill = orders[i].req.quantity.min(left);
+            fills[i] = fill;
+            left -= fill;
+            if left == 0 {
+                break;
+            }
+        }
+    }
+    fills
+}
+
+/// Session-open call auction: orders are collected without matching until
+/// the auction is closed, then uncrossed in one go. The engine has a single
+/// book, so there is one auction for the whole venue.
+pub struct AuctionService {
+    closes_at: Instant,
+    /// `None` once the auction has closed
+    orders: Mutex<Option<Vec<AuctionOrder>>>,
+}
+
+impl AuctionService {
+    pub fn new(closes_at: Instant) -> Self {
+        Self {
+            closes_at,
+            orders: Mutex::new(Some(Vec::new())),
+        }
+    }
+
+    /// When collection is scheduled to end.
+    pub fn closes_at(&self) -> Instant {
+        self.closes_at
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.orders.lock().unwrap().is_some()
+    }
+
+    /// Collect `req`, assigning its id with `assign_id` while the auction is
+    /// held open. `None` once the auction has closed: the order goes to
+    /// continuous trading instead.
+    pub fn collect(
+        &self,
+        req: &OrderRequest,
+        assign_id: impl FnOnce(&OrderRequest) -> Result<u64, ApiError>,
+    ) -> Option<Result<u64, ApiError>> {
+        let mut orders = self.orders.lock().unwrap();
+        let orders = orders.as_mut()?;
+        Some(assign_id(req).inspect(|&order_id| {
+            orders.push(AuctionOrder {
+                order_id,
+                req: req.clone(),
+            });
+        }))
+    }
+
+    /// Take a collected order back out of the auction.
+    pub fn withdraw(&self, order_id: u64) -> Option<AuctionOrder> {
+        let mut orders = self.orders.lock().unwrap();
+        let orders = orders.as_mut()?;
+        let index = orders.iter().position(|o| o.order_id == order_id)?;
+        Some(orders.remove(index))
+    }
+
+    /// End collection and hand back every collected order, oldest first.
+    pub fn close(&self) -> Vec<AuctionOrder> {
+        self.orders.lock().unwrap().take().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn order(order_id: u64, side: Side, price: Option<f64>, quantity: i64) -> AuctionOrder {
+        let trader_id = format!("t{}", order_id);
+        AuctionOrder {
+            order_id,
+            req: match price {
+                Some(price) => OrderRequest::limit(&trader_id, price, quantity, side),
+                None => OrderRequest::market(&trader_id, quantity, side),
+            },
+        }
+    }
+
+    fn sample_book() -> Vec<AuctionOrder> {
+        vec![
+            order(1, Side::Buy, Some(10.05), 100),
+            order(2, Side::Buy, Some(10.00), 50),
+            order(3, Side::Buy, Some(9.95), 50),
+            order(4, Side::Sell, Some(9.90), 60),
+            order(5, Side::Sell, Some(10.00), 60),
+            order(6, Side::Sell, Some(10.10), 100),
+        ]
+    }
+
+    #[test]
+    fn test_uncross_at_volume_maximizing_price() {
+        // Matched volume by price: 9.90→60, 9.95→60, 10.00→120, 10.05→100, 10.10→0
+        let uncross = uncross_price(&sample_book(), None).unwrap();
+        assert_eq!(
+            uncross,
+            Uncross {
+                price: 10.00,
+                volume: 120
+            }
+        );
+    }
+
+    #[test]
+    fn test_allocation_by_price_then_time() {
+        let orders = sample_book();
+        let fills = allocate(&orders, uncross_price(&orders, None).unwrap());
+        // The 10.05 buy fills first; the 10.00 buy gets what's left
+        assert_eq!(fills, vec![100, 20, 0, 60, 60, 0]);
+    }
+
+    #[test]
+    fn test_ties_go_to_smallest_surplus_then_reference() {
+        // 10 matches at both 10.00 and 10.01, with less left over at 10.01
+        let orders = vec![
+            order(1, Side::Buy, Some(10.01), 10),
+            order(2, Side::Buy, Some(10.00), 5),
+            order(3, Side::Sell, Some(10.00), 10),
+        ];
+        assert_eq!(uncross_price(&orders, None).unwrap().price, 10.01);
+
+        // Same volume and surplus everywhere in [10.00, 10.02]
+        let orders = vec![
+            order(1, Side::Buy, Some(10.02), 10),
+            order(2, Side::Sell, Some(10.00), 10),
+        ];
+        assert_eq!(uncross_price(&orders, None).unwrap().price, 10.00);
+        assert_eq!(uncross_price(&orders, Some(10.05)).unwrap().price, 10.02);
+    }
+
+    #[test]
+    fn test_market_orders_fill_first_and_no_cross_is_none() {
+        let orders = vec![
+            order(1, Side::Buy, Some(10.00), 10),
+            order(2, Side::Buy, None, 10),
+            order(3, Side::Sell, Some(9.00), 15),
+        ];
+        let uncross = uncross_price(&orders, None).unwrap();
+        assert_eq!(uncross.volume, 15);
+        assert_eq!(allocate(&orders, uncross), vec![5, 10, 15]);
+
+        let apart = vec![
+            order(1, Side::Buy, Some(9.00), 10),
+            order(2, Side::Sell, Some(10.00), 10),
+        ];
+        assert!(uncross_price(&apart, None).is_none());
+    }
+
+    #[test]
+    fn test_collect_withdraw_and_close() {
+        let auction = AuctionService::new(Instant::now());
+        let req = order(0, Side::Buy, Some(10.0), 5).req;
+        let mut next = 1;
+        let mut assign = |_: &OrderRequest| {
+            next += 1;
+            Ok(next)
+        };
+
+        assert_eq!(auction.collect(&req, &mut assign).unwrap().unwrap(), 2);
+        assert_eq!(auction.collect(&req, &mut assign).unwrap().unwrap(), 3);
+        assert!(auction.withdraw(2).is_some());
+        assert!(auction.withdraw(2).is_none());
+
+        let collected = auction.close();
+        assert_eq!(collected.len(), 1);
+        assert_eq!(collected[0].order_id, 3);
+        assert!(!auction.is_open());
+        assert!(auction.collect(&req, &mut assign).is_none());
+    }
+}
@@ -1,5 +1,11 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::Duration;
+
+use dashmap::DashMap;
+
 use crate::models::order::*;
-use crate::models::trade::TradeResponse;
+use crate::models::trade::{TradeFees, TradeResponse};
 
 pub fn order_submitted(order_id: u64, req: &OrderRequest) {
     tracing::info!(
@@ -44,10 +50,45 @@ pub fn order_modified(resp: &ModifyResponse) {
     );
 }
 
-pub fn order_cancelled(order_id: u64) {
+pub fn order_reduced(resp: &ReduceResponse) {
+    tracing::info!(
+        event = "OrderReduced",
+        order_id = resp.order_id,
+        old_quantity = resp.old_quantity,
+        remaining_quantity = resp.remaining_quantity,
+    );
+}
+
+pub fn order_cancelled(resp: &CancelResponse) {
     tracing::info!(
         event = "OrderCancelled",
+        order_id = resp.order_id,
+        reason = ?resp.reason,
+    );
+}
+
+pub fn stp_triggered(order_id: u64, trader_id: &str, stp_mode: StpMode, outcome: &StpOutcome) {
+    tracing::info!(
+        event = "StpTriggered",
         order_id,
+        trader_id = %trader_id,
+        stp_mode = stp_mode.as_str(),
+        action = %outcome.action,
+        cancelled_orders = ?outcome.cancelled_orders,
+    );
+}
+
+/// Both legs of a trade's fees, which the public tape never carries.
+pub fn fees_charged(trade_id: u64, maker: &str, taker: &str, fees: &TradeFees) {
+    tracing::info!(
+        event = "FeesCharged",
+        trade_id,
+        maker_id = %maker,
+        maker_fee = fees.maker.amount,
+        maker_tier = %fees.maker.tier,
+        taker_id = %taker,
+        taker_fee = fees.taker.amount,
+        taker_tier = %fees.taker.tier,
     );
 }
 
@@ -61,3 +102,222 @@ pub fn trade_executed(trade: &TradeResponse) {
         quantity = trade.quantity,
     );
 }
+
+/// Everything one incoming order traded on arrival, for the `OrderFilled`
+/// event.
+#[derive(Debug, PartialEq)]
+pub struct OrderFillSummary {
+    pub order_id: u64,
+    pub filled_quantity: i64,
+    pub trades: usize,
+    /// Distinct traders on the other side; fills against resting orders
+    /// whose trader is unknown count as one
+    pub counterparties: usize,
+    pub vwap: f64,
+}
+
+impl OrderFillSummary {
+    /// Summarize `order_id`'s `trades` against the traders on the other
+    /// side of each. `None` if it didn't trade.
+    pub fn new<'a>(
+        order_id: u64,
+        trades: &[TradeResponse],
+        counterparties: impl IntoIterator<Item = &'a str>,
+    ) -> Option<Self> {
+        let filled_quantity: i64 = trades.iter().map(|t| t.quantity).sum();
+        if filled_quantity == 0 {
+            return None;
+        }
+        let notional: f64 = trades.iter().map(|t| t.price * t.quantity as f64).sum();
+        Some(Self {
+            order_id,
+            filled_quantity,
+            trades: trades.len(),
+            counterparties: counterparties.into_iter().collect::<HashSet<_>>().len(),
+            vwap: notional / filled_quantity as f64,
+        })
+    }
+}
+
+pub fn order_filled(trader_id: &str, summary: &OrderFillSummary) {
+    tracing::info!(
+        event = "OrderFilled",
+        order_id = summary.order_id,
+        trader_id = %trader_id,
+        filled_quantity = summary.filled_quantity,
+        trades = summary.trades,
+        counterparties = summary.counterparties,
+        vwap = summary.vwap,
+    );
+}
+
+#[derive(Default)]
+struct RejectionBucket {
+    seen: u64,
+    suppressed: u64,
+}
+
+/// Samples rejection logs per (trader, source) bucket so a client hammering the
+/// same invalid order can't flood the log. The first rejection in a bucket and
+/// every Nth after it are logged; the rest are counted and reported by
+/// `flush_summary`. Metrics are recorded separately and never sampled.
+pub struct RejectionSampler {
+    every: u64,
+    buckets: DashMap<(String, String), RejectionBucket>,
+}
+
+impl RejectionSampler {
+    pub fn new(every: u32) -> Self {
+        Self {
+            every: u64::from(every.max(1)),
+            buckets: DashMap::new(),
+        }
+    }
+
+    /// Log the rejection if it falls on the sampling interval for its bucket.
+    pub fn order_rejected(&self, order_id: u64, trader_id: &str, reason: &str, source: &str) {
+        if self.sample(trader_id, source) {
+            order_rejected(order_id, reason, source);
+        }
+    }
+
+    fn sample(&self, trader_id: &str, source: &str) -> bool {
+        let mut bucket = self
+            .buckets
+            .entry((trader_id.to_string(), source.to_string()))
+            .or_default();
+        bucket.seen += 1;
+        if (bucket.seen - 1).is_multiple_of(self.every) {
+            true
+        } else {
+            bucket.suppressed += 1;
+            false
+        }
+    }
+
+    /// Log how many rejections were suppressed per bucket since the last
+    /// summary and reset all buckets.
+    pub fn flush_summary(&self) {
+        self.buckets.retain(|(trader_id, source), bucket| {
+            if bucket.suppressed > 0 {
+                tracing::warn!(
+                    event = "OrderRejectionsSuppressed",
+                    trader_id = %trader_id,
+                    source = %source,
+                    suppressed = bucket.suppressed,
+                );
+            }
+            false
+        });
+    }
+}
+
+/// Report suppressed rejections every `every`, for as long as the task runs.
+pub async fn flush_summaries(sampler: Arc<RejectionSampler>, every: Duration) {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + every, every);
+    loop {
+        ticker.tick().await;
+        sampler.flush_summary();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fill(trade_id: u64, sell_order_id: u64, price: f64, quantity: i64) -> TradeResponse {
+        TradeResponse {
+            trade_id,
+            exec_seq: trade_id,
+            symbol: "DEFAULT".into(),
+            buy_order_id: 1,
+            sell_order_id,
+            price,
+            quantity,
+            timestamp_ns: 0,
+            engine_ts: None,
+            server_ts: None,
+            fee: None,
+            tick_direction: None,
+        }
+    }
+
+    #[test]
+    fn test_order_fill_summary_aggregates() {
+        // Three fills against two sellers, one of them twice
+        let trades = [
+            fill(1, 10, 100.00, 10),
+            fill(2, 11, 100.10, 20),
+            fill(3, 12, 100.50, 10),
+        ];
+        let summary = OrderFillSummary::new(1, &trades, ["alice", "bob", "alice"]).unwrap();
+        assert_eq!(summary.order_id, 1);
+        assert_eq!(summary.filled_quantity, 40);
+        assert_eq!(summary.trades, 3);
+        assert_eq!(summary.counterparties, 2);
+        // (1000 + 2002 + 1005) / 40
+        assert!((summary.vwap - 100.175).abs() < 1e-9);
+
+        assert!(OrderFillSummary::new(1, &[], []).is_none());
+    }
+
+    #[test]
+    fn test_sampler_logs_every_nth_rejection() {
+        let sampler = RejectionSampler::new(10);
+        let logged = (0..100).filter(|_| sampler.sample("bot", "risk")).count();
+        assert_eq!(logged, 10);
+
+        let bucket = sampler.buckets.get(&("bot".to_string(), "risk".to_string())).unwrap();
+        assert_eq!(bucket.suppressed, 90);
+    }
+
+    #[test]
+    fn test_sampler_buckets_are_independent() {
+        let sampler = RejectionSampler::new(10);
+        for _ in 0..5 {
+            sampler.sample("bot", "risk");
+        }
+        // First rejection in a fresh bucket is always logged
+        assert!(sampler.sample("alice", "risk"));
+        assert!(sampler.sample("bot", "engine"));
+    }
+
+    #[test]
+    fn test_flush_summary_resets_buckets() {
+        let sampler = RejectionSampler::new(3);
+        for _ in 0..5 {
+            sampler.sample("bot", "risk");
+        }
+        sampler.flush_summary();
+        assert!(sampler.buckets.is_empty());
+        assert!(sampler.sample("bot", "risk"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_summary_flushed_on_timer() {
+        let sampler = Arc::new(RejectionSampler::new(3));
+        tokio::spawn(flush_summaries(
+            Arc::clone(&sampler),
+            Duration::from_secs(60),
+        ));
+        for _ in 0..5 {
+            sampler.sample("bot", "risk");
+        }
+
+        tokio::time::sleep(Duration::from_secs(59)).await;
+        assert!(!sampler.buckets.is_empty());
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        assert!(sampler.buckets.is_empty());
+
+        // And again on the next tick
+        sampler.sample("bot", "risk");
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert!(sampler.buckets.is_empty());
+    }
+
+    #[test]
+    fn test_rate_of_one_logs_everything() {
+        let sampler = RejectionSampler::new(1);
+        assert!((0..20).all(|_| sampler.sample("bot", "risk")));
+    }
+}
@@ -0,0 +1,172 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// Details of a breaker trip, broadcast as a `circuitBreaker` event.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Trip {
+    pub symbol: String,
+    /// Window price furthest from the trigger price
+    pub reference_price: f64,
+    pub trigger_price: f64,
+    pub move_percent: f64,
+    pub cooldown: Duration,
+}
+
+#[derive(Default)]
+struct BreakerState {
+    /// Trade prices inside the window, oldest first
+    prices: VecDeque<(Instant, f64)>,
+    tripped_until: Option<Instant>,
+}
+
+/// Volatility circuit breaker: trips when a symbol's last-trade price moves
+/// more than `max_move_percent` within `window`, then blocks order entry on
+/// that symbol until `cooldown` has passed. Resumption is automatic.
+pub struct CircuitBreaker {
+    window: Duration,
+    max_move_percent: f64,
+    cooldown: Duration,
+    /// symbol → price window and trip
+    state: Mutex<HashMap<String, BreakerState>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(max_move_percent: f64, window: Duration, cooldown: Duration) -> Self {
+        Self {
+            window,
+            max_move_percent,
+            cooldown,
+            state: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Time left before trading in `symbol` resumes, if its breaker is
+    /// tripped.
+    pub fn remaining(&self, symbol: &str, now: Instant) -> Option<Duration> {
+        let states = self.state.lock().unwrap();
+        states
+            .get(symbol)?
+            .tripped_until
+            .and_then(|until| until.checked_duration_since(now))
+            .filter(|d| !d.is_zero())
+    }
+
+    /// Feed a trade price on `symbol`'s book. Returns the trip if this
+    /// trade set it off.
+    pub fn record_trade(&self, symbol: &str, price: f64, now: Instant) -> Option<Trip> {
+        let mut states = self.state.lock().unwrap();
+        let state = states.entry(symbol.to_string()).or_default();
+
+        while let Some(&(at, _)) = state.prices.front() {
+            if now.saturating_duration_since(at) <= self.window {
+                break;
+            }
+            state.prices.pop_front();
+        }
+
+        // The biggest move is against the window's extreme on the far side
+        let reference = state.prices.iter().map(|&(_, p)| p).max_by(|a, b| {
+            let (da, db) = ((price - a).abs() / a, (price - b).abs() / b);
+            da.total_cmp(&db)
+        });
+        state.prices.push_back((now, price));
+
+        let reference = reference.filter(|r| *r > 0.0)?;
+        let move_percent = (price - reference).abs() / reference * 100.0;
+        if move_percent <= self.max_move_percent {
+            return None;
+        }
+
+        state.prices.clear();
+        state.tripped_until = Some(now + self.cooldown);
+        Some(Trip {
+            symbol: symbol.to_string(),
+            reference_price: reference,
+            trigger_price: price,
+            move_percent,
+            cooldown: self.cooldown,
+        })
+    }
+
+    /// Forget every symbol's price window and lift any trip.
+    pub fn clear(&self) {
+        self.state.lock().unwrap().clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new(5.0, Duration::from_secs(10), Duration::from_secs(30))
+    }
+
+    #[test]
+    fn test_fast_move_trips_breaker() {
+        let cb = breaker();
+        let t0 = Instant::now();
+        assert!(cb.record_trade("BTC", 100.0, t0).is_none());
+        assert!(cb
+            .record_trade("BTC", 103.0, t0 + Duration::from_secs(2))
+            .is_none());
+
+        let trip = cb
+            .record_trade("BTC", 106.0, t0 + Duration::from_secs(4))
+            .unwrap();
+        assert_eq!(trip.reference_price, 100.0);
+        assert!((trip.move_percent - 6.0).abs() < 1e-9);
+
+        let tripped_at = t0 + Duration::from_secs(4);
+        assert_eq!(
+            cb.remaining("BTC", tripped_at),
+            Some(Duration::from_secs(30))
+        );
+        assert!(cb
+            .remaining("BTC", tripped_at + Duration::from_secs(29))
+            .is_some());
+        assert!(cb
+            .remaining("BTC", tripped_at + Duration::from_secs(30))
+            .is_none());
+    }
+
+    #[test]
+    fn test_slow_move_outside_window_does_not_trip() {
+        let cb = breaker();
+        let t0 = Instant::now();
+        cb.record_trade("BTC", 100.0, t0);
+        cb.record_trade("BTC", 103.0, t0 + Duration::from_secs(8));
+        // 100 has aged out; 106 vs 103 is under 5%
+        assert!(cb
+            .record_trade("BTC", 106.0, t0 + Duration::from_secs(12))
+            .is_none());
+        assert!(cb.remaining("BTC", t0 + Duration::from_secs(12)).is_none());
+    }
+
+    #[test]
+    fn test_downward_move_trips() {
+        let cb = breaker();
+        let t0 = Instant::now();
+        cb.record_trade("BTC", 100.0, t0);
+        let trip = cb
+            .record_trade("BTC", 94.0, t0 + Duration::from_secs(1))
+            .unwrap();
+        assert_eq!(trip.trigger_price, 94.0);
+    }
+
+    #[test]
+    fn test_symbols_have_separate_windows() {
+        let cb = breaker();
+        let t0 = Instant::now();
+        cb.record_trade("BTC", 100.0, t0);
+        // A different price level on another book is not a move
+        assert!(cb.record_trade("ETH", 3.0, t0).is_none());
+        assert!(cb.record_trade("ETH", 3.1, t0).is_none());
+
+        let trip = cb.record_trade("ETH", 3.3, t0).unwrap();
+        assert_eq!(trip.symbol, "ETH");
+        assert!(cb.remaining("ETH", t0).is_some());
+        assert!(cb.remaining("BTC", t0).is_none());
+    }
+}
@@ -0,0 +1,125 @@
+use crate::config::FeeConfig;
+use crate::models::trade::{TradeFee, TradeFees, TradeResponse};
+
+/// Tier name reported for traders without a tier assignment.
+pub const DEFAULT_TIER: &str = "default";
+
+/// Per-trader maker/taker fee rates from `[fees]`. Traders listed in
+/// `trader_tiers` pay their tier's rates; everyone else pays the defaults.
+pub struct FeeSchedule {
+    config: FeeConfig,
+}
+
+impl FeeSchedule {
+    pub fn new(config: FeeConfig) -> Self {
+        Self { config }
+    }
+
+    /// Whether any trader would be charged or rebated anything.
+    pub fn is_active(&self) -> bool {
+        self.config.maker_fee_bps != 0.0
+            || self.config.taker_fee_bps != 0.0
+            || !self.config.trader_tiers.is_empty()
+    }
+
+    /// Tier name and (maker, taker) rates in basis points for `trader_id`.
+    /// `Config::validate` rejects assignments to unknown tiers; one that
+    /// slips through falls back to the defaults.
+    fn rates(&self, trader_id: &str) -> (&str, f64, f64) {
+        self.config
+            .trader_tiers
+            .get(trader_id)
+            .and_then(|name| Some((name, self.config.tiers.get(name)?)))
+            .map_or(
+                (
+                    DEFAULT_TIER,
+                    self.config.maker_fee_bps,
+                    self.config.taker_fee_bps,
+                ),
+                |(name, tier)| (name.as_str(), tier.maker_fee_bps, tier.taker_fee_bps),
+            )
+    }
+
+    /// Fees on `trade` for the resting `maker` and the aggressing `taker`.
+    pub fn trade_fees(&self, trade: &TradeResponse, maker: &str, taker: &str) -> TradeFees {
+        let notional = trade.price * trade.quantity as f64;
+        let (maker_tier, maker_bps, _) = self.rates(maker);
+        let (taker_tier, _, taker_bps) = self.rates(taker);
+        TradeFees {
+            maker: TradeFee {
+                amount: notional * maker_bps / 10_000.0,
+                tier: maker_tier.to_string(),
+            },
+            taker: TradeFee {
+                amount: notional * taker_bps / 10_000.0,
+                tier: taker_tier.to_string(),
+            },
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FeeTier;
+
+    fn trade(price: f64, quantity: i64) -> TradeResponse {
+        TradeResponse {
+            trade_id: 1,
+            exec_seq: 1,
+            symbol: "DEFAULT".into(),
+            buy_order_id: 1,
+            sell_order_id: 2,
+            price,
+            quantity,
+            timestamp_ns: 0,
+            engine_ts: None,
+            server_ts: None,
+            fee: None,
+            tick_direction: None,
+        }
+    }
+
+    fn schedule() -> FeeSchedule {
+        let mut config = FeeConfig {
+            maker_fee_bps: 1.0,
+            taker_fee_bps: 2.0,
+            ..Default::default()
+        };
+        config.tiers.insert(
+            "market_maker".into(),
+            FeeTier {
+                maker_fee_bps: -0.5,
+                taker_fee_bps: 3.0,
+            },
+        );
+        config
+            .trader_tiers
+            .insert("mm1".into(), "market_maker".into());
+        FeeSchedule::new(config)
+    }
+
+    #[test]
+    fn test_rebate_tier_maker_has_negative_fee() {
+        // $10,000 notional: -0.5 bps rebate to the maker, 2 bps from the taker
+        let fees = schedule().trade_fees(&trade(100.0, 100), "mm1", "alice");
+        assert!((fees.maker.amount - -0.5).abs() < 1e-9, "{:?}", fees);
+        assert_eq!(fees.maker.tier, "market_maker");
+        assert!((fees.taker.amount - 2.0).abs() < 1e-9, "{:?}", fees);
+        assert_eq!(fees.taker.tier, DEFAULT_TIER);
+    }
+
+    #[test]
+    fn test_tier_taker_pays_tier_rate() {
+        let fees = schedule().trade_fees(&trade(100.0, 100), "alice", "mm1");
+        assert!((fees.maker.amount - 1.0).abs() < 1e-9);
+        assert!((fees.taker.amount - 3.0).abs() < 1e-9);
+        assert_eq!(fees.taker.tier, "market_maker");
+    }
+
+    #[test]
+    fn test_zero_config_is_inactive() {
+        assert!(!FeeSchedule::new(FeeConfig::default()).is_active());
+        assert!(schedule().is_active());
+    }
+}
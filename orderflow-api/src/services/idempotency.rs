@@ -0,0 +1,225 @@
+use std::time::{Duration, Instant};
+
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+
+use crate::models::error::ApiError;
+use crate::models::order::{OrderRequest, OrderResponse};
+
+struct KeyEntry {
+    /// None while the submission holding the key is still in progress
+    submission: Option<Submission>,
+    created: Instant,
+}
+
+/// A completed submission: the request last applied under a key and the
+/// response it got.
+#[derive(Clone)]
+pub struct Submission {
+    pub request: OrderRequest,
+    pub response: OrderResponse,
+}
+
+/// Outcome of claiming a key for a submission.
+pub enum KeyClaim {
+    /// The key was free and is now held by this submission
+    Reserved,
+    /// An earlier submission under the key completed
+    Completed(Box<Submission>),
+}
+
+/// Maps client idempotency keys to the response their submission got, so a
+/// retry is answered without submitting twice and a client that never saw
+/// the response can still address the order. Keys belong to the trader that
+/// submitted under them and expire after a fixed TTL.
+pub struct IdempotencyService {
+    /// (trader_id, key) → submission
+    keys: DashMap<(String, String), KeyEntry>,
+    ttl: Duration,
+}
+
+impl IdempotencyService {
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            keys: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Claim `trader_id`'s `key` for a new submission. A key whose
+    /// submission already completed hands back its request and response;
+    /// one whose submission is still in progress is a conflict.
+    pub fn reserve(&self, trader_id: &str, key: &str) -> Result<KeyClaim, ApiError> {
+        let fresh = || KeyEntry {
+            submission: None,
+            created: Instant::now(),
+        };
+        match self.keys.entry((trader_id.to_string(), key.to_string())) {
+            Entry::Occupied(mut e) if e.get().created.elapsed() >= self.ttl => {
+                e.insert(fresh());
+                Ok(KeyClaim::Reserved)
+            }
+            Entry::Occupied(e) => match &e.get().submission {
+                Some(submission) => Ok(KeyClaim::Completed(Box::new(submission.clone()))),
+                None => Err(ApiError::Conflict(format!(
+                    "Idempotency key '{}' is held by a submission still in progress",
+                    key
+                ))),
+            },
+            Entry::Vacant(e) => {
+                e.insert(fresh());
+                Ok(KeyClaim::Reserved)
+            }
+        }
+    }
+
+    /// Bind a key to the request applied under it and the response that
+    /// got, on first submission or after a resubmission amended the order.
+    pub fn complete(
+        &self,
+        trader_id: &str,
+        key: &str,
+        request: &OrderRequest,
+        response: &OrderResponse,
+    ) {
+        if let Some(mut entry) = self.keys.get_mut(&(trader_id.to_string(), key.to_string())) {
+            entry.submission = Some(Submission {
+                request: request.clone(),
+                response: response.clone(),
+            });
+        }
+    }
+
+    /// Free a reserved key after its submission failed.
+    pub fn release(&self, trader_id: &str, key: &str) {
+        self.keys
+            .remove_if(&(trader_id.to_string(), key.to_string()), |_, entry| {
+                entry.submission.is_none()
+            });
+    }
+
+    /// Order id `trader_id` created under `key`, or `KeyNotFound` if the key
+    /// is unknown to that trader, expired, or its submission has not
+    /// completed.
+    pub fn resolve(&self, trader_id: &str, key: &str) -> Result<u64, ApiError> {
+        self.keys
+            .get(&(trader_id.to_string(), key.to_string()))
+            .filter(|entry| entry.created.elapsed() < self.ttl)
+            .and_then(|entry| entry.submission.as_ref().map(|s| s.response.order_id))
+            .ok_or_else(|| ApiError::KeyNotFound(key.to_string()))
+    }
+
+    /// Drop expired keys.
+    pub fn purge_expired(&self) {
+        self.keys
+            .retain(|_, entry| entry.created.elapsed() < self.ttl);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::order::Side;
+
+    fn request() -> OrderRequest {
+        OrderRequest::limit("alice", 100.00, 10, Side::Buy)
+    }
+
+    fn response(order_id: u64) -> OrderResponse {
+        OrderResponse {
+            order_id,
+            accepted: true,
+            reject_reason: None,
+            trades: Vec::new(),
+            remaining_quantity: 10,
+            remainder_cancelled: false,
+            expires_at_ns: None,
+            trades_truncated: false,
+            trade_summary: None,
+            stp_cancelled: Vec::new(),
+            stp_outcome: None,
+            seq: 1,
+        }
+    }
+
+    #[test]
+    fn test_resolve_completed_key() {
+        let svc = IdempotencyService::new(Duration::from_secs(60));
+        svc.reserve("alice", "abc").unwrap();
+        svc.complete("alice", "abc", &request(), &response(42));
+        assert_eq!(svc.resolve("alice", "abc").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_unknown_and_pending_keys_not_found() {
+        let svc = IdempotencyService::new(Duration::from_secs(60));
+        assert!(matches!(
+            svc.resolve("alice", "nope"),
+            Err(ApiError::KeyNotFound(_))
+        ));
+
+        svc.reserve("alice", "pending").unwrap();
+        assert!(matches!(
+            svc.resolve("alice", "pending"),
+            Err(ApiError::KeyNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_pending_key_conflicts_until_released() {
+        let svc = IdempotencyService::new(Duration::from_secs(60));
+        svc.reserve("alice", "abc").unwrap();
+        assert!(matches!(
+            svc.reserve("alice", "abc"),
+            Err(ApiError::Conflict(_))
+        ));
+
+        svc.release("alice", "abc");
+        assert!(matches!(
+            svc.reserve("alice", "abc"),
+            Ok(KeyClaim::Reserved)
+        ));
+    }
+
+    #[test]
+    fn test_completed_key_returns_stored_response() {
+        let svc = IdempotencyService::new(Duration::from_secs(60));
+        svc.reserve("alice", "abc").unwrap();
+        svc.complete("alice", "abc", &request(), &response(42));
+        assert!(matches!(
+            svc.reserve("alice", "abc"),
+            Ok(KeyClaim::Completed(s)) if s.response.order_id == 42
+        ));
+    }
+
+    #[test]
+    fn test_keys_are_per_trader() {
+        let svc = IdempotencyService::new(Duration::from_secs(60));
+        svc.reserve("alice", "abc").unwrap();
+        svc.complete("alice", "abc", &request(), &response(42));
+
+        assert!(matches!(
+            svc.resolve("mallory", "abc"),
+            Err(ApiError::KeyNotFound(_))
+        ));
+        assert!(matches!(
+            svc.reserve("mallory", "abc"),
+            Ok(KeyClaim::Reserved)
+        ));
+        assert_eq!(svc.resolve("alice", "abc").unwrap(), 42);
+    }
+
+    #[test]
+    fn test_expired_key_not_found() {
+        let svc = IdempotencyService::new(Duration::ZERO);
+        svc.reserve("alice", "abc").unwrap();
+        svc.complete("alice", "abc", &request(), &response(42));
+        assert!(matches!(
+            svc.resolve("alice", "abc"),
+            Err(ApiError::KeyNotFound(_))
+        ));
+
+        svc.purge_expired();
+        assert!(svc.keys.is_empty());
+    }
+}
@@ -0,0 +1,110 @@
+use std::sync::Mutex;
+
+use serde::Serialize;
+
+/// Which way the top of book leans.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ImbalanceState {
+    Balanced,
+    BidHeavy,
+    OfferHeavy,
+}
+
+/// A change of imbalance state, broadcast as an `imbalanceAlert` event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImbalanceAlert {
+    pub state: ImbalanceState,
+    /// (bid - ask) / (bid + ask) at the best prices, in [-1, 1]
+    pub imbalance: f64,
+    pub bid_quantity: i64,
+    pub ask_quantity: i64,
+}
+
+/// Top-of-book imbalance, or `None` when both sides are empty.
+pub fn top_of_book_imbalance(bid_quantity: i64, ask_quantity: i64) -> Option<f64> {
+    let total = bid_quantity + ask_quantity;
+    (total > 0).then(|| (bid_quantity - ask_quantity) as f64 / total as f64)
+}
+
+/// Alerts when the top-of-book imbalance crosses `threshold` either way.
+/// The book only counts as balanced again once the imbalance falls below
+/// `threshold - hysteresis`, so a book hovering at the threshold doesn't
+/// flap between states.
+pub struct ImbalanceMonitor {
+    threshold: f64,
+    hysteresis: f64,
+    state: Mutex<ImbalanceState>,
+}
+
+impl ImbalanceMonitor {
+    pub fn new(threshold: f64, hysteresis: f64) -> Self {
+        Self {
+            threshold,
+            hysteresis,
+            state: Mutex::new(ImbalanceState::Balanced),
+        }
+    }
+
+    /// Feed the current best-level quantities. Returns an alert when the
+    /// state changes.
+    pub fn update(&self, bid_quantity: i64, ask_quantity: i64) -> Option<ImbalanceAlert> {
+        let imbalance = top_of_book_imbalance(bid_quantity, ask_quantity).unwrap_or(0.0);
+        let mut state = self.state.lock().unwrap();
+
+        let next = if imbalance >= self.threshold {
+            ImbalanceState::BidHeavy
+        } else if imbalance <= -self.threshold {
+            ImbalanceState::OfferHeavy
+        } else if imbalance.abs() < self.threshold - self.hysteresis {
+            ImbalanceState::Balanced
+        } else {
+            *state
+        };
+        if next == *state {
+            return None;
+        }
+
+        *state = next;
+        Some(ImbalanceAlert {
+            state: next,
+            imbalance,
+            bid_quantity,
+            ask_quantity,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lopsided_book_alerts_and_balanced_does_not() {
+        let monitor = ImbalanceMonitor::new(0.6, 0.2);
+        assert!(monitor.update(100, 100).is_none());
+        assert!(monitor.update(120, 80).is_none());
+
+        let alert = monitor.update(900, 100).unwrap();
+        assert_eq!(alert.state, ImbalanceState::BidHeavy);
+        assert!((alert.imbalance - 0.8).abs() < 1e-9);
+        // Still lopsided: no repeat
+        assert!(monitor.update(950, 50).is_none());
+
+        let alert = monitor.update(10, 90).unwrap();
+        assert_eq!(alert.state, ImbalanceState::OfferHeavy);
+    }
+
+    #[test]
+    fn test_hysteresis_prevents_flapping() {
+        let monitor = ImbalanceMonitor::new(0.6, 0.2);
+        monitor.update(80, 20).unwrap();
+
+        // 0.5 is under the threshold but inside the hysteresis band
+        assert!(monitor.update(75, 25).is_none());
+        assert!(monitor.update(80, 20).is_none());
+
+        let alert = monitor.update(60, 40).unwrap();
+        assert_eq!(alert.state, ImbalanceState::Balanced);
+    }
+}
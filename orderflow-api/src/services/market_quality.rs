@@ -0,0 +1,136 @@
+use std::sync::Mutex;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+
+/// Whether the quoted spread currently meets the obligation.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SpreadState {
+    Normal,
+    Wide,
+}
+
+/// A change of spread state, broadcast as a `marketQualityAlert` event.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MarketQualityAlert {
+    pub state: SpreadState,
+    /// `None` when a side of the book is empty
+    pub spread: Option<f64>,
+    pub max_spread: f64,
+    /// How long the spread has been too wide; zero once it is back to normal
+    pub wide_for: Duration,
+}
+
+#[derive(Default)]
+struct QualityState {
+    /// When the spread last went over the maximum, while it still is
+    wide_since: Option<Instant>,
+    alerted: bool,
+}
+
+/// Alerts when the spread stays wider than `max_spread` for longer than
+/// `grace`, and again once it is back within the maximum. A book missing a
+/// side has no two-sided quote, so it counts as wide.
+pub struct MarketQualityMonitor {
+    max_spread: f64,
+    grace: Duration,
+    state: Mutex<QualityState>,
+}
+
+impl MarketQualityMonitor {
+    pub fn new(max_spread: f64, grace: Duration) -> Self {
+        Self {
+            max_spread,
+            grace,
+            state: Mutex::new(QualityState::default()),
+        }
+    }
+
+    /// Feed the spread sampled at `now`. Returns an alert when the state
+    /// changes.
+    pub fn update(&self, spread: Option<f64>, now: Instant) -> Option<MarketQualityAlert> {
+        let wide = spread.is_none_or(|s| s > self.max_spread);
+        let mut state = self.state.lock().unwrap();
+
+        if !wide {
+            state.wide_since = None;
+            if !std::mem::take(&mut state.alerted) {
+                return None;
+            }
+            return Some(MarketQualityAlert {
+                state: SpreadState::Normal,
+                spread,
+                max_spread: self.max_spread,
+                wide_for: Duration::ZERO,
+            });
+        }
+
+        let since = *state.wide_since.get_or_insert(now);
+        let wide_for = now.saturating_duration_since(since);
+        if state.alerted || wide_for < self.grace {
+            return None;
+        }
+        state.alerted = true;
+        Some(MarketQualityAlert {
+            state: SpreadState::Wide,
+            spread,
+            max_spread: self.max_spread,
+            wide_for,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_persistently_wide_spread_alerts_after_grace() {
+        let monitor = MarketQualityMonitor::new(0.10, Duration::from_secs(5));
+        let t0 = Instant::now();
+
+        assert!(monitor.update(Some(0.50), t0).is_none());
+        assert!(monitor
+            .update(Some(0.50), t0 + Duration::from_secs(4))
+            .is_none());
+
+        let alert = monitor
+            .update(Some(0.40), t0 + Duration::from_secs(5))
+            .unwrap();
+        assert_eq!(alert.state, SpreadState::Wide);
+        assert_eq!(alert.spread, Some(0.40));
+        assert_eq!(alert.wide_for, Duration::from_secs(5));
+        // Still wide: no repeat
+        assert!(monitor
+            .update(Some(0.40), t0 + Duration::from_secs(9))
+            .is_none());
+
+        let alert = monitor
+            .update(Some(0.05), t0 + Duration::from_secs(10))
+            .unwrap();
+        assert_eq!(alert.state, SpreadState::Normal);
+    }
+
+    #[test]
+    fn test_brief_widening_within_grace_does_not_alert() {
+        let monitor = MarketQualityMonitor::new(0.10, Duration::from_secs(5));
+        let t0 = Instant::now();
+
+        assert!(monitor.update(None, t0).is_none());
+        assert!(monitor
+            .update(Some(0.05), t0 + Duration::from_secs(3))
+            .is_none());
+        // The grace period restarts after the spread tightens
+        assert!(monitor
+            .update(Some(0.50), t0 + Duration::from_secs(6))
+            .is_none());
+        assert!(monitor
+            .update(Some(0.50), t0 + Duration::from_secs(10))
+            .is_none());
+        assert!(monitor
+            .update(Some(0.50), t0 + Duration::from_secs(11))
+            .is_some());
+    }
+}
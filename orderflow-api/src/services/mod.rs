@@ -1,4 +1,17 @@
+pub mod admin_audit;
+pub mod auction_service;
 pub mod audit_service;
+pub mod circuit_breaker;
+pub mod fees;
+pub mod idempotency;
+pub mod imbalance;
+pub mod market_quality;
+pub mod order_history;
 pub mod order_service;
 pub mod rate_limiter;
+pub mod risk_plugin;
 pub mod risk_service;
+pub mod snapshot_cache;
+pub mod trade_history;
+pub mod trading_hours;
+pub mod ws_sessions;
@@ -0,0 +1,98 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use serde_json::Value;
+
+use crate::engine::orderbook::unix_nanos;
+use crate::models::order::{OrderEvent, OrderHistoryResponse};
+
+struct HistoryInner {
+    /// order_id → lifecycle events, oldest first
+    events: HashMap<u64, Vec<OrderEvent>>,
+    /// Order ids in the order their first event arrived, for eviction
+    order_ids: VecDeque<u64>,
+}
+
+/// Bounded in-memory journal of order lifecycle events (submit, modify,
+/// reduce, fill, cancel), indexed by order id. Once `capacity` orders are
+/// tracked, the order whose history started first is dropped as a whole.
+pub struct OrderHistoryService {
+    inner: Mutex<HistoryInner>,
+    capacity: usize,
+}
+
+impl OrderHistoryService {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(HistoryInner {
+                events: HashMap::new(),
+                order_ids: VecDeque::new(),
+            }),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Append `event` to `order_id`'s history.
+    pub fn record(&self, order_id: u64, event: &'static str, seq: u64, details: Value) {
+        let entry = OrderEvent {
+            event,
+            timestamp_ns: unix_nanos(),
+            seq,
+            details,
+        };
+
+        let mut inner = self.inner.lock().unwrap();
+        if let Some(events) = inner.events.get_mut(&order_id) {
+            events.push(entry);
+            return;
+        }
+        if inner.order_ids.len() >= self.capacity {
+            if let Some(evicted) = inner.order_ids.pop_front() {
+                inner.events.remove(&evicted);
+            }
+        }
+        inner.order_ids.push_back(order_id);
+        inner.events.insert(order_id, vec![entry]);
+    }
+
+    /// `order_id`'s lifecycle in the order it happened. `None` if the order
+    /// was never seen or has been evicted.
+    pub fn history(&self, order_id: u64) -> Option<OrderHistoryResponse> {
+        let inner = self.inner.lock().unwrap();
+        inner
+            .events
+            .get(&order_id)
+            .map(|events| OrderHistoryResponse {
+                order_id,
+                events: events.clone(),
+            })
+    }
+
+    /// Forget every order's history.
+    pub fn clear(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        inner.events.clear();
+        inner.order_ids.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_oldest_order_evicted_whole() {
+        let history = OrderHistoryService::new(2);
+        history.record(1, "submitted", 1, json!({}));
+        history.record(2, "submitted", 2, json!({}));
+        // More events for a tracked order don't count toward the capacity
+        history.record(1, "cancelled", 3, json!({}));
+        assert_eq!(history.history(1).unwrap().events.len(), 2);
+
+        history.record(3, "submitted", 4, json!({}));
+        assert!(history.history(1).is_none());
+        assert_eq!(history.history(2).unwrap().events[0].event, "submitted");
+        assert_eq!(history.history(3).unwrap().order_id, 3);
+    }
+}
@@ -1,22 +1,73 @@
-use std::sync::Arc;
-use std::time::Instant;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc};
 
-use crate::engine::orderbook::Engine;
+use crate::engine::orderbook::{unix_nanos, Engine, UncrossLeg};
+use crate::engine::reject::RejectReason;
 use crate::middleware::metrics as m;
+use crate::models::admin::ResetResult;
 use crate::models::error::ApiError;
+use crate::models::market::MarketSnapshot;
 use crate::models::order::*;
+use crate::models::trade::TradeResponse;
 
-use super::audit_service as audit;
+use super::auction_service::{self as auction, AuctionService, Uncross};
+use super::audit_service::{self as audit, OrderFillSummary, RejectionSampler};
+use super::circuit_breaker::{CircuitBreaker, Trip};
+use super::fees::FeeSchedule;
+use super::imbalance::ImbalanceMonitor;
+use super::market_quality::{MarketQualityMonitor, SpreadState};
+use super::order_history::OrderHistoryService;
 use super::rate_limiter::RateLimiterService;
 use super::risk_service::RiskService;
+use super::snapshot_cache::{CacheLookup, SnapshotCache};
+use super::trade_history::TradeHistoryService;
+use super::trading_hours::TradingHours;
+
+/// Rejection sources a trader's own orders cause. Market-wide refusals
+/// (halts, warmup, the order cap) don't count toward the penalty box.
+const PENALIZED_SOURCES: &[&str] = &["in_flight", "rate_limit", "risk", "engine"];
 
 pub struct OrderService {
     pub engine: Arc<Engine>,
     risk: Arc<RiskService>,
     rate_limiter: Arc<RateLimiterService>,
+    trade_history: Arc<TradeHistoryService>,
+    order_history: Arc<OrderHistoryService>,
+    rejections: Arc<RejectionSampler>,
     ws_broadcast: broadcast::Sender<String>,
+    /// Order entry stays closed until this long after `start_time`
+    start_time: tokio::time::Instant,
+    warmup: Duration,
+    /// Queue of trade broadcasts held back by the print delay, stamped with
+    /// when they were published; fills in the order response and trade
+    /// history are unaffected
+    delayed_prints: Option<mpsc::UnboundedSender<(tokio::time::Instant, Vec<String>)>>,
+    /// When set, risk checks read the market snapshot through this cache
+    snapshot_cache: Option<SnapshotCache>,
+    /// Halts order entry on a symbol for a cool-down after a fast price move
+    /// on its book
+    circuit_breaker: Option<CircuitBreaker>,
+    /// Checked after every book change; alerts go out on the broadcast
+    imbalance: Option<ImbalanceMonitor>,
+    /// Sampled on a timer; alerts go out on the broadcast
+    market_quality: Option<MarketQualityMonitor>,
+    /// Last top of book sent as a periodic `snapshot` message
+    last_snapshot_broadcast: Mutex<Option<MarketSnapshot>>,
+    /// Opening auction: while it is open, new orders are collected instead
+    /// of matched
+    auction: Option<AuctionService>,
+    /// Per-trader maker/taker fees stamped on each trade
+    fees: Option<FeeSchedule>,
+    /// New orders are refused outside this daily window
+    trading_hours: Option<TradingHours>,
+    /// Log an `OrderFilled` summary per order that trades on arrival
+    log_order_fills: bool,
+    /// Held shared by every operation that changes a book and exclusively
+    /// by `reset`, so a reset never lands between a book change and the
+    /// risk and history updates that follow it
+    reset_gate: tokio::sync::RwLock<()>,
 }
 
 impl OrderService {
@@ -24,65 +75,445 @@ impl OrderService {
         engine: Arc<Engine>,
         risk: Arc<RiskService>,
         rate_limiter: Arc<RateLimiterService>,
+        trade_history: Arc<TradeHistoryService>,
+        order_history: Arc<OrderHistoryService>,
+        rejections: Arc<RejectionSampler>,
         ws_broadcast: broadcast::Sender<String>,
     ) -> Self {
         Self {
             engine,
             risk,
             rate_limiter,
+            trade_history,
+            order_history,
+            rejections,
             ws_broadcast,
+            start_time: tokio::time::Instant::now(),
+            warmup: Duration::ZERO,
+            delayed_prints: None,
+            snapshot_cache: None,
+            circuit_breaker: None,
+            imbalance: None,
+            market_quality: None,
+            last_snapshot_broadcast: Mutex::new(None),
+            auction: None,
+            fees: None,
+            trading_hours: None,
+            log_order_fills: false,
+            reset_gate: tokio::sync::RwLock::new(()),
+        }
+    }
+
+    /// Reject submissions until `warmup` has elapsed since `start_time`.
+    pub fn with_warmup(mut self, start_time: tokio::time::Instant, warmup: Duration) -> Self {
+        self.start_time = start_time;
+        self.warmup = warmup;
+        self
+    }
+
+    /// Delay trade broadcasts by `delay`, for venues that publish prints late.
+    /// A single task releases them, so they go out in the order they traded.
+    pub fn with_trade_print_delay(mut self, delay: Duration) -> Self {
+        if delay.is_zero() {
+            self.delayed_prints = None;
+            return self;
+        }
+        let (queue, mut pending) = mpsc::unbounded_channel();
+        let tx = self.ws_broadcast.clone();
+        tokio::spawn(async move {
+            while let Some((published, prints)) = pending.recv().await {
+                tokio::time::sleep_until(published + delay).await;
+                for msg in prints {
+                    let _ = tx.send(msg);
+                }
+            }
+        });
+        self.delayed_prints = Some(queue);
+        self
+    }
+
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    pub fn with_snapshot_cache(mut self, cache: SnapshotCache) -> Self {
+        self.snapshot_cache = Some(cache);
+        self
+    }
+
+    pub fn with_imbalance_monitor(mut self, monitor: ImbalanceMonitor) -> Self {
+        self.imbalance = Some(monitor);
+        self
+    }
+
+    pub fn with_market_quality_monitor(mut self, monitor: MarketQualityMonitor) -> Self {
+        self.market_quality = Some(monitor);
+        self
+    }
+
+    pub fn with_opening_auction(mut self, auction: AuctionService) -> Self {
+        self.auction = Some(auction);
+        self
+    }
+
+    /// Log an `OrderFilled` summary for each order that trades on arrival.
+    pub fn with_order_fill_log(mut self, enabled: bool) -> Self {
+        self.log_order_fills = enabled;
+        self
+    }
+
+    pub fn with_fee_schedule(mut self, fees: FeeSchedule) -> Self {
+        self.fees = Some(fees);
+        self
+    }
+
+    pub fn with_trading_hours(mut self, hours: TradingHours) -> Self {
+        self.trading_hours = Some(hours);
+        self
+    }
+
+    /// Whether the trading-hours schedule currently allows new orders.
+    /// Always true without a schedule.
+    pub fn market_open(&self) -> bool {
+        self.trading_hours.as_ref().is_none_or(|h| h.is_open())
+    }
+
+    /// Whether `symbol`'s circuit breaker is tripped and blocking orders.
+    pub fn circuit_breaker_tripped(&self, symbol: &str) -> bool {
+        self.circuit_breaker
+            .as_ref()
+            .is_some_and(|cb| cb.remaining(symbol, Instant::now()).is_some())
+    }
+
+    /// When the opening auction stops collecting, if it hasn't uncrossed yet.
+    pub fn auction_closes_at(&self) -> Option<Instant> {
+        self.auction
+            .as_ref()
+            .filter(|a| a.is_open())
+            .map(|a| a.closes_at())
+    }
+
+    /// Snapshot of `symbol` (the default symbol if `None`) for risk checks.
+    /// The cache only holds the default symbol. A stale cached snapshot is
+    /// only used while a writer holds the book; past the cache's hard
+    /// ceiling the book is always read.
+    async fn risk_snapshot(&self, symbol: Option<&str>) -> MarketSnapshot {
+        if let Some(symbol) = symbol.filter(|&s| s != self.engine.symbol()) {
+            // A symbol's first order sees an empty book
+            return self
+                .engine
+                .get_snapshot_for(symbol)
+                .await
+                .unwrap_or_default();
+        }
+        let Some(cache) = &self.snapshot_cache else {
+            return self.engine.get_snapshot().await;
+        };
+        let snapshot = match cache.lookup() {
+            CacheLookup::Fresh(snapshot) => return snapshot,
+            CacheLookup::Stale(cached) => match self.engine.try_get_snapshot() {
+                Some(snapshot) => snapshot,
+                None => return cached,
+            },
+            CacheLookup::Miss => self.engine.get_snapshot().await,
+        };
+        cache.store(snapshot.clone());
+        snapshot
+    }
+
+    /// Read the default book's snapshot into the risk-check cache. Run on a
+    /// timer when `risk.snapshot_poll_interval_ms` is set, so the submit path
+    /// finds a fresh snapshot instead of queueing for the book's lock.
+    pub async fn publish_snapshot(&self) {
+        if let Some(cache) = &self.snapshot_cache {
+            cache.store(self.engine.get_snapshot().await);
         }
     }
 
+    /// Time left in the startup warmup window, if still inside it.
+    pub fn warmup_remaining(&self) -> Option<Duration> {
+        self.warmup
+            .checked_sub(self.start_time.elapsed())
+            .filter(|d| !d.is_zero())
+    }
+
     fn broadcast(&self, msg: &str) {
         // Ignore send errors (no active receivers is fine)
         let _ = self.ws_broadcast.send(msg.to_string());
     }
 
+    /// Broadcast trade prints, or queue them if a print delay is set so the
+    /// submitter isn't held up.
+    fn publish_trades(&self, prints: Vec<String>) {
+        if prints.is_empty() {
+            return;
+        }
+        match &self.delayed_prints {
+            Some(queue) => {
+                let _ = queue.send((tokio::time::Instant::now(), prints));
+            }
+            None => {
+                for msg in &prints {
+                    self.broadcast(msg);
+                }
+            }
+        }
+    }
+
+    /// Record a rejection: always counted in metrics, sampled in the audit log.
+    /// Rejections the trader caused also count toward the penalty box.
+    fn reject(&self, order_id: u64, trader_id: &str, e: &ApiError, source: &str) {
+        self.rejections
+            .order_rejected(order_id, trader_id, &e.to_string(), source);
+        m::record_order_rejected(source);
+
+        if PENALIZED_SOURCES.contains(&source) {
+            if let Some(cooldown) = self.risk.record_rejection(trader_id, Instant::now()) {
+                tracing::warn!(
+                    event = "TraderPenaltyBox",
+                    trader_id,
+                    cooldown_ms = cooldown.as_millis() as u64,
+                );
+            }
+        }
+    }
+
     pub async fn submit_order(&self, req: OrderRequest) -> Result<OrderResponse, ApiError> {
+        self.submit(req, None)
+            .await
+            .and_then(|(_, response)| response)
+    }
+
+    /// Submit orders one after another, each through the full submit path,
+    /// so matching is exactly as if they had arrived back to back. The
+    /// batch is not transactional: a rejection doesn't stop the orders
+    /// after it, and earlier orders' fills and resting quantity are what
+    /// later ones match and are risk-checked against. Orders from other
+    /// requests may interleave.
+    pub async fn submit_batch(
+        &self,
+        reqs: Vec<OrderRequest>,
+    ) -> Vec<Result<OrderResponse, ApiError>> {
+        let mut results = Vec::with_capacity(reqs.len());
+        for req in reqs {
+            results.push(self.submit_order(req).await);
+        }
+        results
+    }
+
+    /// Atomically swap a resting order for a new one. The replacement goes
+    /// through every check a fresh submission does and must come from the
+    /// same trader.
+    pub async fn cancel_replace(
+        &self,
+        order_id: u64,
+        req: OrderRequest,
+    ) -> Result<CancelReplaceResponse, ApiError> {
+        match self.risk.trader_for_order(order_id) {
+            None => return Err(ApiError::NotFound(order_id)),
+            Some(owner) if owner != req.trader_id => {
+                let e = ApiError::Forbidden(format!(
+                    "Order {} belongs to a different trader",
+                    order_id
+                ));
+                self.reject(order_id, &req.trader_id, &e, "cancel_replace");
+                return Err(e);
+            }
+            Some(_) => {}
+        }
+
+        let (cancelled, order) = self.submit(req, Some(order_id)).await?;
+        Ok(CancelReplaceResponse {
+            cancelled: cancelled.expect("replace path always cancels"),
+            order: order.into(),
+        })
+    }
+
+    /// Order entry pipeline. With `replaces`, that order is cancelled in the
+    /// same engine call that adds the new one. Fails outright if the order
+    /// never reached the engine; otherwise returns the cancel, if any, along
+    /// with the engine's verdict on the new order, since the cancel stands
+    /// even when the replacement is rejected.
+    async fn submit(
+        &self,
+        mut req: OrderRequest,
+        replaces: Option<u64>,
+    ) -> Result<(Option<CancelResponse>, Result<OrderResponse, ApiError>), ApiError> {
         let start = Instant::now();
+        let _gate = self.reset_gate.read().await;
+        if req.stp_mode.is_none() {
+            req.stp_mode = self.risk.default_stp_mode(&req.trader_id);
+        }
         let side_str = format!("{:?}", req.side);
         let type_str = format!("{:?}", req.order_type);
 
         m::record_order_submitted(&side_str, &type_str);
 
-        // 1. Rate limit check
-        if let Err(e) = self.rate_limiter.check_rate_limit(&req.trader_id) {
-            audit::order_rejected(0, &e.to_string(), "rate_limit");
-            m::record_order_rejected("rate_limit");
+        // Lifetime order cap
+        if let Some(cap) = self.engine.order_cap_reached() {
+            let e = ApiError::engine(
+                RejectReason::OrderCapReached,
+                format!("Engine order limit of {} reached", cap),
+            );
+            self.reject(0, &req.trader_id, &e, "order_cap");
             return Err(e);
         }
 
-        // 2. Get current snapshot for risk checks (read lock, fast)
-        let snapshot = self.engine.get_snapshot().await;
+        // Order-entry allow-list
+        if let Err(e) = self.risk.check_trader_allowed(&req.trader_id) {
+            self.reject(0, &req.trader_id, &e, "allow_list");
+            return Err(e);
+        }
+
+        // Startup warmup
+        if let Some(remaining) = self.warmup_remaining() {
+            let e = ApiError::Halted(format!(
+                "Order entry opens after startup warmup ({}ms remaining)",
+                remaining.as_millis()
+            ));
+            self.reject(0, &req.trader_id, &e, "warmup");
+            return Err(e);
+        }
+
+        // Trading hours
+        if let Some(hours) = self.trading_hours.as_ref().filter(|h| !h.is_open()) {
+            let e = ApiError::Halted(format!(
+                "Order entry is closed outside trading hours ({})",
+                hours.window()
+            ));
+            self.reject(0, &req.trader_id, &e, "trading_hours");
+            return Err(e);
+        }
+
+        // Trading halt
+        if self.engine.is_halted() {
+            let e = ApiError::Halted("Order entry is halted".into());
+            self.reject(0, &req.trader_id, &e, "halted");
+            return Err(e);
+        }
+
+        // Volatility circuit breaker on the order's symbol
+        let symbol = req
+            .symbol
+            .clone()
+            .unwrap_or_else(|| self.engine.symbol().to_string());
+        if let Some(remaining) = self
+            .circuit_breaker
+            .as_ref()
+            .and_then(|cb| cb.remaining(&symbol, Instant::now()))
+        {
+            let e = ApiError::Halted(format!(
+                "Circuit breaker tripped on {}; trading resumes in {}ms",
+                symbol,
+                remaining.as_millis()
+            ));
+            self.reject(0, &req.trader_id, &e, "circuit_breaker");
+            return Err(e);
+        }
+
+        // Rejection penalty box
+        if let Err(e) = self.risk.check_penalty_box(&req.trader_id, Instant::now()) {
+            self.reject(0, &req.trader_id, &e, "penalty_box");
+            return Err(e);
+        }
+
+        // Concurrency cap — held until this submission completes
+        let _in_flight = match self.rate_limiter.acquire_in_flight(&req.trader_id) {
+            Ok(permit) => permit,
+            Err(e) => {
+                self.reject(0, &req.trader_id, &e, "in_flight");
+                return Err(e);
+            }
+        };
+
+        // 1. Symbol limit and rate limit checks
+        if let Err(e) = self.engine.check_symbol(&symbol) {
+            self.reject(0, &req.trader_id, &e, "symbol");
+            return Err(e);
+        }
+        if let Err(e) = self
+            .rate_limiter
+            .check_rate_limit(&req.trader_id, Some(&symbol))
+        {
+            self.reject(0, &req.trader_id, &e, "rate_limit");
+            return Err(e);
+        }
+
+        // 2. Get current snapshot for risk checks (read lock or cache, fast)
+        let snapshot = self.risk_snapshot(req.symbol.as_deref()).await;
 
         // 3. Risk checks (size, price band, position limit)
         if let Err(e) = self.risk.check_order(
             &req.trader_id,
+            &symbol,
             req.quantity,
             req.side,
             req.order_type,
             req.price,
             &snapshot,
+            replaces,
         ) {
-            audit::order_rejected(0, &e.to_string(), "risk");
-            m::record_order_rejected("risk");
+            self.reject(0, &req.trader_id, &e, "risk");
             return Err(e);
         }
 
+        // Distinct-trader cap, taken only by requests that reach the engine
+        match self.risk.admit_trader(&req.trader_id, Instant::now()) {
+            Ok(Some(evicted)) => {
+                tracing::info!(event = "TraderEvicted", trader_id = %evicted);
+                self.rate_limiter.forget_trader(&evicted);
+            }
+            Ok(None) => {}
+            Err(e) => {
+                self.reject(0, &req.trader_id, &e, "trader_cap");
+                return Err(e);
+            }
+        }
+
         let trader_id = req.trader_id.clone();
         let side = req.side;
+        let stp_mode = req.stp_mode;
+        // Only GTC and GTD limit orders leave a remainder on the book; the engine may
+        // also convert a protected market order to IOC, which never rests
+        let rests = req.order_type == OrderType::Limit && req.time_in_force.rests();
 
         // 4. Audit: order submitted
         audit::order_submitted(0, &req);
+        let submitted = serde_json::json!({
+            "traderId": req.trader_id,
+            "side": req.side,
+            "orderType": req.order_type,
+            "timeInForce": req.time_in_force,
+            "price": req.price,
+            "quantity": req.quantity,
+        });
 
-        // 5. Submit to engine (validates, generates ID, calls FFI)
+        // 5. Submit to engine (validates, generates ID, calls FFI), or hold
+        //    the order for the opening auction
         let engine_start = Instant::now();
-        let response = match self.engine.add_order(req).await {
+        let (cancelled, result, collected) = match replaces {
+            None => match self.collect_for_auction(&req) {
+                Some(result) => (None, result, true),
+                None => (None, self.engine.add_order(req).await, false),
+            },
+            Some(order_id) => match self.engine.cancel_replace(order_id, req).await {
+                Ok((cancel, result)) => {
+                    self.publish_cancel(&cancel);
+                    (Some(cancel), result, false)
+                }
+                Err(e) => (None, Err(e), false),
+            },
+        };
+        let mut response = match result {
             Ok(resp) => resp,
+            Err(e) if cancelled.is_some() => {
+                self.reject(0, &trader_id, &e, "engine");
+                m::record_order_latency(start);
+                return Ok((cancelled, Err(e)));
+            }
             Err(e) => {
-                audit::order_rejected(0, &e.to_string(), "engine");
-                m::record_order_rejected("engine");
+                self.reject(0, &trader_id, &e, "engine");
                 m::record_order_latency(start);
                 return Err(e);
             }
@@ -95,15 +526,32 @@ impl OrderService {
             response.trades.len(),
             response.remaining_quantity,
         );
+        if let Some(outcome) = &response.stp_outcome {
+            audit::stp_triggered(
+                response.order_id,
+                &trader_id,
+                stp_mode.unwrap_or_default(),
+                outcome,
+            );
+        }
+        self.order_history
+            .record(response.order_id, "submitted", response.seq, submitted);
         for trade in &response.trades {
             audit::trade_executed(trade);
+            self.record_fill(trade, response.seq);
         }
         m::record_order_accepted(&side_str, &type_str);
         m::record_trades(response.trades.len() as u64);
 
-        // 7. Register this order for counterparty position tracking
+        // 7. Register this order for counterparty position tracking. Every
+        //    order collected for the auction waits there until the uncross.
+        let resting = if (rests && !response.remainder_cancelled) || collected {
+            response.remaining_quantity
+        } else {
+            0
+        };
         self.risk
-            .register_order(response.order_id, &trader_id, side);
+            .register_order(response.order_id, &trader_id, &symbol, side, resting);
 
         // 8. Update positions for both sides of each trade
         let trades: Vec<(u64, u64, i64)> = response
@@ -112,30 +560,79 @@ impl OrderService {
             .map(|t| (t.buy_order_id, t.sell_order_id, t.quantity))
             .collect();
         self.risk
-            .update_positions_from_trades(&trader_id, side, &trades);
+            .update_positions_from_trades(&trader_id, side, &symbol, &trades);
 
-        // 9. Unregister fully filled orders (remaining_quantity == 0)
-        if response.remaining_quantity == 0 {
+        // 9. Unregister orders with nothing resting (fully filled, or an
+        //    IOC/FOK/market remainder that was cancelled)
+        if resting == 0 {
             self.risk.unregister_order(response.order_id);
         }
 
-        // 10. Broadcast trades to WebSocket clients
-        for trade in &response.trades {
-            let msg = serde_json::json!({
-                "type": "trade",
-                "data": {
-                    "tradeId": trade.trade_id,
-                    "buyOrderId": trade.buy_order_id,
-                    "sellOrderId": trade.sell_order_id,
-                    "price": trade.price,
-                    "quantity": trade.quantity
-                }
+        // 10. Resting orders cancelled by self-trade prevention
+        for &order_id in &response.stp_cancelled {
+            self.publish_cancel(&CancelResponse {
+                order_id,
+                cancelled: true,
+                reason: CancelReason::Stp,
+                seq: response.seq,
             });
-            self.broadcast(&msg.to_string());
         }
 
+        // 11. Record trades in history and broadcast to WebSocket clients,
+        //     then charge fees. The submitter is always the taker and only
+        //     sees its own leg; the tape never carries fees.
+        let mut prints = Vec::with_capacity(response.trades.len());
+        let mut counterparties = Vec::with_capacity(response.trades.len());
+        for trade in &mut response.trades {
+            // The resting side's trader comes from the order registry
+            let (buyer, seller) = match side {
+                Side::Buy => (
+                    trader_id.clone(),
+                    self.risk.trader_for_order(trade.sell_order_id).unwrap_or_default(),
+                ),
+                Side::Sell => (
+                    self.risk.trader_for_order(trade.buy_order_id).unwrap_or_default(),
+                    trader_id.clone(),
+                ),
+            };
+            self.trade_history.record(trade, &buyer, &seller);
+            prints.push(trade_print(trade, response.seq));
+            let maker = match side {
+                Side::Buy => seller,
+                Side::Sell => buyer,
+            };
+            if let Some(schedule) = &self.fees {
+                let fees = schedule.trade_fees(trade, &maker, &trader_id);
+                audit::fees_charged(trade.trade_id, &maker, &trader_id, &fees);
+                trade.fee = Some(fees.taker);
+            }
+            counterparties.push(maker);
+        }
+        self.publish_trades(prints);
+        if self.log_order_fills {
+            let counterparties = counterparties.iter().map(String::as_str);
+            if let Some(summary) =
+                OrderFillSummary::new(response.order_id, &response.trades, counterparties)
+            {
+                audit::order_filled(&trader_id, &summary);
+            }
+        }
+
+        // 12. Feed trade prices to the circuit breaker
+        if let Some(cb) = &self.circuit_breaker {
+            let now = Instant::now();
+            for trade in &response.trades {
+                if let Some(trip) = cb.record_trade(&trade.symbol, trade.price, now) {
+                    self.publish_circuit_breaker(&trip);
+                }
+            }
+        }
+
+        // 13. Top-of-book imbalance alerts
+        self.check_imbalance().await;
+
         m::record_order_latency(start);
-        Ok(response)
+        Ok((cancelled, Ok(response)))
     }
 
     pub async fn modify_order(
@@ -143,11 +640,41 @@ impl OrderService {
         order_id: u64,
         req: ModifyRequest,
     ) -> Result<ModifyResponse, ApiError> {
+        let _gate = self.reset_gate.read().await;
+        // Apply the same price band a new order at this price would face
+        let symbol = self.engine.order_symbol(order_id);
+        let snapshot = self.risk_snapshot(symbol.as_deref()).await;
+        let symbol = symbol.as_deref().unwrap_or(self.engine.symbol());
+        if let Err(e) = self
+            .risk
+            .check_modify_allowed(order_id)
+            .and_then(|_| self.risk.check_price_band(symbol, req.new_price, &snapshot))
+        {
+            let trader_id = self.risk.trader_for_order(order_id).unwrap_or_default();
+            self.reject(order_id, &trader_id, &e, "risk");
+            return Err(e);
+        }
+
         let response = self.engine.modify_order(order_id, req).await?;
+        self.risk
+            .set_resting_quantity(order_id, response.new_quantity);
+        self.risk.record_modify(order_id);
         audit::order_modified(&response);
+        self.order_history.record(
+            order_id,
+            "modified",
+            response.seq,
+            serde_json::json!({
+                "oldPrice": response.old_price,
+                "newPrice": response.new_price,
+                "oldQuantity": response.old_quantity,
+                "newQuantity": response.new_quantity
+            }),
+        );
 
         let msg = serde_json::json!({
             "type": "orderModified",
+            "seq": response.seq,
             "data": {
                 "orderId": response.order_id,
                 "oldPrice": response.old_price,
@@ -157,134 +684,1211 @@ impl OrderService {
             }
         });
         self.broadcast(&msg.to_string());
+        self.check_imbalance().await;
 
         Ok(response)
     }
 
-    pub async fn cancel_order(&self, order_id: u64) -> Result<CancelResponse, ApiError> {
-        let response = self.engine.cancel_order(order_id).await?;
-        self.risk.unregister_order(order_id);
-        audit::order_cancelled(order_id);
+    pub async fn reduce_order(
+        &self,
+        order_id: u64,
+        req: ReduceRequest,
+    ) -> Result<ReduceResponse, ApiError> {
+        let _gate = self.reset_gate.read().await;
+        let response = self.engine.reduce_order(order_id, req).await?;
+        if response.remaining_quantity == 0 {
+            self.risk.unregister_order(order_id);
+        } else {
+            self.risk
+                .set_resting_quantity(order_id, response.remaining_quantity);
+        }
+        audit::order_reduced(&response);
+        self.order_history.record(
+            order_id,
+            "reduced",
+            response.seq,
+            serde_json::json!({
+                "oldQuantity": response.old_quantity,
+                "remainingQuantity": response.remaining_quantity
+            }),
+        );
 
         let msg = serde_json::json!({
-            "type": "orderCancelled",
-            "data": { "orderId": order_id }
+            "type": "orderReduced",
+            "seq": response.seq,
+            "data": {
+                "orderId": response.order_id,
+                "price": response.price,
+                "oldQuantity": response.old_quantity,
+                "remainingQuantity": response.remaining_quantity
+            }
         });
         self.broadcast(&msg.to_string());
+        self.check_imbalance().await;
 
         Ok(response)
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-    use crate::config::RiskConfig;
 
-    fn make_service() -> OrderService {
-        let engine = Arc::new(Engine::new());
-        let risk = Arc::new(RiskService::new(RiskConfig {
-            min_order_size: 1,
-            max_order_size: 10_000,
-            price_band_percent: 10.0,
-            max_position_per_trader: 1_000,
-            max_orders_per_second: 100,
-        }));
-        let rate_limiter = Arc::new(RateLimiterService::new(100));
-        let (ws_tx, _) = broadcast::channel(16);
-        OrderService::new(engine, risk, rate_limiter, ws_tx)
+    /// Collect `req` for the opening auction. `None` when no auction is
+    /// open, so the order goes straight to the engine.
+    fn collect_for_auction(&self, req: &OrderRequest) -> Option<Result<OrderResponse, ApiError>> {
+        let auction = self.auction.as_ref()?;
+        let result = auction.collect(req, |req| {
+            if req.time_in_force == TimeInForce::Fok {
+                return Err(ApiError::Validation(
+                    "FOK orders are not accepted during the opening auction".into(),
+                ));
+            }
+            if req.all_or_none {
+                return Err(ApiError::Validation(
+                    "All-or-none orders are not accepted during the opening auction".into(),
+                ));
+            }
+            self.engine.accept_for_auction(req)
+        })?;
+        Some(result.map(|order_id| OrderResponse {
+            order_id,
+            accepted: true,
+            reject_reason: None,
+            trades: Vec::new(),
+            remaining_quantity: req.quantity,
+            remainder_cancelled: false,
+            expires_at_ns: None,
+            trades_truncated: false,
+            trade_summary: None,
+            stp_cancelled: Vec::new(),
+            stp_outcome: None,
+            seq: 0,
+        }))
     }
 
-    fn limit_order(trader: &str, price: f64, qty: i64, side: Side) -> OrderRequest {
-        OrderRequest {
-            trader_id: trader.into(),
-            price: Some(price),
-            quantity: qty,
-            side,
-            order_type: OrderType::Limit,
-            time_in_force: TimeInForce::Gtc,
-            stp_mode: StpMode::Allow,
-        }
-    }
+    /// Close the opening auction and uncross the collected orders at the
+    /// price that maximises matched volume. Continuous trading takes over
+    /// from here. Returns the uncross, if anything crossed.
+    pub async fn run_opening_auction(&self) -> Option<Uncross> {
+        let _gate = self.reset_gate.read().await;
+        let orders = self.auction.as_ref()?.close();
+        let opening_price = self.risk.opening_price(self.engine.symbol());
+        let uncross = auction::uncross_price(&orders, opening_price);
+        let fills = match uncross {
+            Some(u) => auction::allocate(&orders, u),
+            None => vec![0; orders.len()],
+        };
+        let legs: Vec<UncrossLeg> = orders
+            .iter()
+            .zip(fills)
+            .map(|(o, matched)| UncrossLeg {
+                order_id: o.order_id,
+                req: &o.req,
+                matched,
+            })
+            .collect();
 
-    #[tokio::test]
-    async fn test_submit_through_service() {
-        let svc = make_service();
-        let resp = svc
-            .submit_order(limit_order("alice", 100.0, 100, Side::Buy))
-            .await
-            .unwrap();
-        assert!(resp.accepted);
-        assert_eq!(resp.remaining_quantity, 100);
-    }
+        let price = uncross.map_or(0.0, |u| u.price);
+        let execution = match self.engine.uncross(price, &legs).await {
+            Ok(execution) => execution,
+            Err(e) => {
+                tracing::error!(event = "OpeningAuctionFailed", error = %e);
+                return None;
+            }
+        };
 
-    #[tokio::test]
-    async fn test_risk_rejection_oversized() {
-        let svc = make_service();
-        let result = svc
-            .submit_order(limit_order("alice", 100.0, 10_001, Side::Buy))
-            .await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            ApiError::RiskRejection(msg) => assert!(msg.contains("exceeds maximum")),
-            e => panic!("Expected RiskRejection, got {:?}", e),
+        // Both sides of every trade are collected orders in the registry
+        let mut prints = Vec::with_capacity(execution.trades.len());
+        for trade in &execution.trades {
+            audit::trade_executed(trade);
+            self.record_fill(trade, execution.seq);
+            let buyer = self
+                .risk
+                .trader_for_order(trade.buy_order_id)
+                .unwrap_or_default();
+            let seller = self
+                .risk
+                .trader_for_order(trade.sell_order_id)
+                .unwrap_or_default();
+            self.risk.update_positions_from_trades(
+                &buyer,
+                Side::Buy,
+                &trade.symbol,
+                &[(trade.buy_order_id, trade.sell_order_id, trade.quantity)],
+            );
+            self.trade_history.record(trade, &buyer, &seller);
+            prints.push(trade_print(trade, execution.seq));
         }
-    }
+        m::record_trades(execution.trades.len() as u64);
+        for &(order_id, resting) in &execution.resting {
+            if resting == 0 {
+                self.risk.unregister_order(order_id);
+            } else {
+                self.risk.set_resting_quantity(order_id, resting);
+            }
+        }
+        self.publish_trades(prints);
 
-    #[tokio::test]
-    async fn test_position_tracking_after_fill() {
-        let svc = make_service();
+        tracing::info!(
+            event = "OpeningAuctionUncross",
+            orders = orders.len(),
+            price = ?uncross.map(|u| u.price),
+            volume = uncross.map_or(0, |u| u.volume),
+        );
+        let msg = serde_json::json!({
+            "type": "auctionUncross",
+            "seq": execution.seq,
+            "data": {
+                "price": uncross.map(|u| u.price),
+                "volume": uncross.map_or(0, |u| u.volume),
+                "orders": orders.len()
+            }
+        });
+        self.broadcast(&msg.to_string());
+        self.check_imbalance().await;
 
-        // Resting sell
-        svc.submit_order(limit_order("seller", 100.0, 50, Side::Sell))
-            .await
-            .unwrap();
+        uncross
+    }
 
-        // Crossing buy — fills 50
-        let resp = svc
-            .submit_order(limit_order("buyer", 100.0, 50, Side::Buy))
-            .await
-            .unwrap();
-        assert_eq!(resp.trades.len(), 1);
+    pub async fn cancel_order(&self, order_id: u64) -> Result<CancelResponse, ApiError> {
+        self.cancel_with_reason(order_id, CancelReason::User).await
+    }
 
-        // Buyer's position should be +50
-        assert_eq!(svc.risk.get_position("buyer"), 50);
-        // Seller's position should be -50
-        assert_eq!(svc.risk.get_position("seller"), -50);
+    /// Operator cancel of any trader's order, reported with reason `admin`.
+    pub async fn admin_cancel(&self, order_id: u64) -> Result<CancelResponse, ApiError> {
+        self.cancel_with_reason(order_id, CancelReason::Admin).await
     }
 
-    #[tokio::test]
-    async fn test_position_limit_blocks_order() {
-        let svc = make_service();
+    async fn cancel_with_reason(
+        &self,
+        order_id: u64,
+        reason: CancelReason,
+    ) -> Result<CancelResponse, ApiError> {
+        let _gate = self.reset_gate.read().await;
+        // Still waiting in the opening auction: never reached the book
+        if let Some(order) = self.auction.as_ref().and_then(|a| a.withdraw(order_id)) {
+            let response = CancelResponse {
+                order_id: order.order_id,
+                cancelled: true,
+                reason,
+                seq: 0,
+            };
+            self.publish_cancel(&response);
+            return Ok(response);
+        }
 
-        // Place resting sells within position limit (each seller ≤ 1000)
-        svc.submit_order(limit_order("seller1", 100.0, 1000, Side::Sell))
-            .await
-            .unwrap();
-        svc.submit_order(limit_order("seller2", 100.0, 1000, Side::Sell))
-            .await
-            .unwrap();
+        let response = self.engine.cancel_order(order_id, reason).await?;
+        self.publish_cancel(&response);
+        self.check_imbalance().await;
+        Ok(response)
+    }
 
-        // Buy 900 — fills against seller1, buyer position = 900
-        svc.submit_order(limit_order("buyer", 100.0, 900, Side::Buy))
-            .await
-            .unwrap();
-        assert_eq!(svc.risk.get_position("buyer"), 900);
+    /// Cancel every open order of `trader_id` after their session dropped.
+    /// Returns how many were cancelled.
+    pub async fn cancel_on_disconnect(&self, trader_id: &str) -> usize {
+        let mut cancelled = 0;
+        for order_id in self.risk.orders_for_trader(trader_id) {
+            if self
+                .cancel_with_reason(order_id, CancelReason::Disconnect)
+                .await
+                .is_ok()
+            {
+                cancelled += 1;
+            }
+        }
+        cancelled
+    }
 
-        // Try to buy 200 more — projected 1100 > limit 1000
-        let result = svc
-            .submit_order(limit_order("buyer", 100.0, 200, Side::Buy))
-            .await;
-        assert!(result.is_err());
-        match result.unwrap_err() {
-            ApiError::RiskRejection(msg) => assert!(msg.contains("Position limit")),
-            e => panic!("Expected RiskRejection, got {:?}", e),
+    /// Wipe every book along with the positions, orders and modify counts
+    /// the risk service tracks, order histories, the cached risk snapshot
+    /// and circuit breaker windows, and tell subscribers with a `bookReset`
+    /// message. Waits for in-flight order operations to finish and holds
+    /// new ones until everything is cleared.
+    pub async fn reset(&self) -> ResetResult {
+        let _gate = self.reset_gate.write().await;
+        let (orders_removed, seq) = self.engine.reset().await;
+        let positions_cleared = self.risk.reset();
+        self.order_history.clear();
+        if let Some(cache) = &self.snapshot_cache {
+            cache.invalidate();
+        }
+        if let Some(cb) = &self.circuit_breaker {
+            cb.clear();
+        }
+        tracing::warn!(event = "BookReset", orders_removed, positions_cleared, seq);
+        let msg = serde_json::json!({
+            "type": "bookReset",
+            "seq": seq,
+            "data": { "ordersRemoved": orders_removed }
+        });
+        self.broadcast(&msg.to_string());
+        ResetResult {
+            orders_removed,
+            positions_cleared,
+            seq,
         }
     }
 
-    #[tokio::test]
-    async fn test_price_band_rejection() {
-        let svc = make_service();
+    /// Cancel orders whose expiry has passed. Returns how many were swept.
+    pub async fn expire_orders(&self) -> usize {
+        let _gate = self.reset_gate.read().await;
+        let expired = self.engine.expire_due(unix_nanos()).await;
+        for response in &expired {
+            self.publish_cancel(response);
+        }
+        if !expired.is_empty() {
+            self.check_imbalance().await;
+        }
+        expired.len()
+    }
+
+    fn publish_circuit_breaker(&self, trip: &Trip) {
+        tracing::warn!(
+            event = "CircuitBreakerTripped",
+            symbol = %trip.symbol,
+            reference_price = trip.reference_price,
+            trigger_price = trip.trigger_price,
+            move_percent = trip.move_percent,
+            cooldown_ms = trip.cooldown.as_millis() as u64,
+        );
+        let msg = serde_json::json!({
+            "type": "circuitBreaker",
+            "data": {
+                "symbol": trip.symbol,
+                "referencePrice": trip.reference_price,
+                "triggerPrice": trip.trigger_price,
+                "movePercent": trip.move_percent,
+                "resumesInMs": trip.cooldown.as_millis() as u64
+            }
+        });
+        self.broadcast(&msg.to_string());
+    }
+
+    /// Re-read the top of book and broadcast an alert if its imbalance
+    /// crossed the configured threshold.
+    async fn check_imbalance(&self) {
+        let Some(monitor) = &self.imbalance else {
+            return;
+        };
+        let ticker = self.engine.get_ticker().await;
+        let Some(alert) = monitor.update(ticker.bid_quantity, ticker.ask_quantity) else {
+            return;
+        };
+        let msg = serde_json::json!({
+            "type": "imbalanceAlert",
+            "data": {
+                "state": alert.state,
+                "imbalance": alert.imbalance,
+                "bidQuantity": alert.bid_quantity,
+                "askQuantity": alert.ask_quantity
+            }
+        });
+        self.broadcast(&msg.to_string());
+    }
+
+    /// Add a trade to the lifecycle history of the orders on both sides.
+    fn record_fill(&self, trade: &TradeResponse, seq: u64) {
+        for (order_id, counterparty) in [
+            (trade.buy_order_id, trade.sell_order_id),
+            (trade.sell_order_id, trade.buy_order_id),
+        ] {
+            self.order_history.record(
+                order_id,
+                "filled",
+                seq,
+                serde_json::json!({
+                    "tradeId": trade.trade_id,
+                    "price": trade.price,
+                    "quantity": trade.quantity,
+                    "counterpartyOrderId": counterparty
+                }),
+            );
+        }
+    }
+
+    /// `order_id`'s lifecycle events, oldest first.
+    pub fn order_history(&self, order_id: u64) -> Result<OrderHistoryResponse, ApiError> {
+        self.order_history
+            .history(order_id)
+            .ok_or(ApiError::NotFound(order_id))
+    }
+
+    /// Sample the spread and broadcast an alert if it has been too wide for
+    /// the grace period, or has just recovered. Run on a timer: a book that
+    /// nobody trades against never triggers the post-order checks.
+    pub async fn check_market_quality(&self) {
+        let Some(monitor) = &self.market_quality else {
+            return;
+        };
+        let snapshot = self.engine.get_snapshot().await;
+        let Some(alert) = monitor.update(snapshot.spread, tokio::time::Instant::now()) else {
+            return;
+        };
+        let state = match alert.state {
+            SpreadState::Wide => "wide",
+            SpreadState::Normal => "normal",
+        };
+        m::record_market_quality_alert(state);
+        tracing::warn!(
+            event = "MarketQualityAlert",
+            state,
+            spread = ?alert.spread,
+            max_spread = alert.max_spread,
+            wide_for_ms = alert.wide_for.as_millis() as u64,
+        );
+        let msg = serde_json::json!({
+            "type": "marketQualityAlert",
+            "seq": snapshot.seq,
+            "data": {
+                "state": alert.state,
+                "spread": alert.spread,
+                "maxSpread": alert.max_spread,
+                "wideForMs": alert.wide_for.as_millis() as u64
+            }
+        });
+        self.broadcast(&msg.to_string());
+    }
+
+    /// Broadcast the default book's snapshot if the top of book or last
+    /// trade has changed since the previous call. Run on a timer for clients
+    /// that chart the market without polling it.
+    pub async fn broadcast_snapshot(&self) {
+        let snapshot = self.engine.get_snapshot().await;
+        {
+            let mut last = self.last_snapshot_broadcast.lock().unwrap();
+            // Book changes away from the touch bump `seq` without changing
+            // anything these clients see
+            let unchanged = last.as_ref().is_some_and(|prev| {
+                let at_prev_seq = MarketSnapshot {
+                    seq: prev.seq,
+                    ..snapshot.clone()
+                };
+                *prev == at_prev_seq
+            });
+            if unchanged {
+                return;
+            }
+            *last = Some(snapshot.clone());
+        }
+        let msg = serde_json::json!({
+            "type": "snapshot",
+            "seq": snapshot.seq,
+            "data": snapshot
+        });
+        self.broadcast(&msg.to_string());
+    }
+
+    /// Bookkeeping shared by every path that removes an order from the book.
+    fn publish_cancel(&self, response: &CancelResponse) {
+        self.risk.unregister_order(response.order_id);
+        audit::order_cancelled(response);
+        self.order_history.record(
+            response.order_id,
+            "cancelled",
+            response.seq,
+            serde_json::json!({ "reason": response.reason }),
+        );
+
+        // Expiries get their own type so clients needn't inspect the reason
+        let kind = match response.reason {
+            CancelReason::Expired => "orderExpired",
+            _ => "orderCancelled",
+        };
+        let msg = serde_json::json!({
+            "type": kind,
+            "seq": response.seq,
+            "data": { "orderId": response.order_id, "reason": response.reason }
+        });
+        self.broadcast(&msg.to_string());
+    }
+}
+
+fn trade_print(trade: &TradeResponse, seq: u64) -> String {
+    let mut msg = serde_json::json!({
+        "type": "trade",
+        "seq": seq,
+        "data": {
+            "tradeId": trade.trade_id,
+            "execSeq": trade.exec_seq,
+            "symbol": trade.symbol,
+            "buyOrderId": trade.buy_order_id,
+            "sellOrderId": trade.sell_order_id,
+            "price": trade.price,
+            "quantity": trade.quantity,
+            "timestampNs": trade.timestamp_ns
+        }
+    });
+    if let Some(engine_ts) = trade.engine_ts {
+        msg["data"]["engineTs"] = serde_json::json!(engine_ts);
+    }
+    if let Some(server_ts) = trade.server_ts {
+        msg["data"]["serverTs"] = serde_json::json!(server_ts);
+    }
+    if let Some(direction) = trade.tick_direction {
+        msg["data"]["tickDirection"] = serde_json::json!(direction);
+    }
+    msg.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{EngineConfig, FeeConfig, FeeTier, RiskConfig, TradeTimestampSource};
+
+    fn make_service() -> OrderService {
+        make_service_with(
+            Engine::new(),
+            RiskConfig {
+                min_order_size: 1,
+                max_order_size: 10_000,
+                price_band_percent: 10.0,
+                max_position_per_trader: 1_000,
+                max_orders_per_second: 100,
+                ..RiskConfig::default()
+            },
+        )
+    }
+
+    fn make_service_with(engine: Engine, risk: RiskConfig) -> OrderService {
+        let rate_limiter = Arc::new(RateLimiterService::new(100, 2));
+        let trade_history = Arc::new(TradeHistoryService::new());
+        let order_history = Arc::new(OrderHistoryService::new(1_000));
+        let rejections = Arc::new(RejectionSampler::new(1));
+        let (ws_tx, _) = broadcast::channel(16);
+        OrderService::new(
+            Arc::new(engine),
+            Arc::new(RiskService::new(risk)),
+            rate_limiter,
+            trade_history,
+            order_history,
+            rejections,
+            ws_tx,
+        )
+    }
+
+    fn limit_order(trader: &str, price: f64, qty: i64, side: Side) -> OrderRequest {
+        OrderRequest::limit(trader, price, qty, side)
+    }
+
+    #[tokio::test]
+    async fn test_submit_through_service() {
+        let svc = make_service();
+        let resp = svc
+            .submit_order(limit_order("alice", 100.0, 100, Side::Buy))
+            .await
+            .unwrap();
+        assert!(resp.accepted);
+        assert_eq!(resp.remaining_quantity, 100);
+    }
+
+    #[tokio::test]
+    async fn test_rebate_tier_maker_fee_on_trade() {
+        let mut fees = FeeConfig {
+            taker_fee_bps: 2.0,
+            ..Default::default()
+        };
+        fees.tiers.insert(
+            "market_maker".into(),
+            FeeTier {
+                maker_fee_bps: -1.0,
+                taker_fee_bps: 3.0,
+            },
+        );
+        fees.trader_tiers
+            .insert("mm1".into(), "market_maker".into());
+        let svc = make_service().with_fee_schedule(FeeSchedule::new(fees));
+        let capture = LogCapture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        svc.submit_order(limit_order("mm1", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let resp = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        // $1,000 notional: the maker earns 1 bp, the taker pays 2 bps
+        let fee = resp.trades[0].fee.as_ref().unwrap();
+        assert_eq!(fee.tier, "default");
+        assert!((fee.amount - 0.20).abs() < 1e-9, "{:?}", fee);
+        let charged = &capture.events("FeesCharged")[0];
+        assert_eq!(charged["maker_tier"], "market_maker");
+        assert!((charged["maker_fee"].as_f64().unwrap() - -0.10).abs() < 1e-9);
+
+        // Neither leg reaches the public tape
+        let tape = svc.trade_history.trades_page(None, None, 10);
+        assert!(tape[0].fee.is_none());
+        let json = serde_json::to_value(&tape[0]).unwrap();
+        assert!(json.get("fee").is_none(), "{}", json);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_limit_rejects_excess_concurrency() {
+        let svc = make_service();
+
+        // Two operations already in flight for alice (the configured limit)
+        let _a = svc.rate_limiter.acquire_in_flight("alice").unwrap();
+        let _b = svc.rate_limiter.acquire_in_flight("alice").unwrap();
+
+        let result = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RateLimited(_))));
+
+        // Bob is under the limit and proceeds
+        let resp = svc
+            .submit_order(limit_order("bob", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        assert!(resp.accepted);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_slot_released_after_submit() {
+        let svc = make_service();
+        let _held = svc.rate_limiter.acquire_in_flight("alice").unwrap();
+
+        // One slot left: sequential submits each acquire and release it
+        for _ in 0..3 {
+            svc.submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+                .await
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_order_cap_rejects_next_order() {
+        let engine = Engine::with_config(EngineConfig {
+            max_total_orders: 3,
+            ..EngineConfig::default()
+        });
+        let svc = make_service_with(engine, RiskConfig::default());
+
+        for _ in 0..3 {
+            svc.submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+                .await
+                .unwrap();
+        }
+        let result = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::EngineRejection(_))));
+    }
+
+    #[tokio::test]
+    async fn test_orders_rejected_during_warmup() {
+        let svc = make_service().with_warmup(tokio::time::Instant::now(), Duration::from_secs(60));
+        assert!(svc.warmup_remaining().is_some());
+
+        let result = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::Halted(_))));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_orders_accepted_after_warmup() {
+        let svc =
+            make_service().with_warmup(tokio::time::Instant::now(), Duration::from_millis(20));
+        tokio::time::advance(Duration::from_millis(19)).await;
+        assert!(svc.warmup_remaining().is_some());
+        tokio::time::advance(Duration::from_millis(1)).await;
+        assert!(svc.warmup_remaining().is_none());
+
+        assert!(svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .is_ok());
+    }
+
+    fn trading_hours(clock: fn() -> u64) -> TradingHours {
+        let config = crate::config::TradingHoursConfig {
+            open: Some("09:30".into()),
+            close: Some("16:00".into()),
+            utc_offset: "+00:00".into(),
+        };
+        TradingHours::from_config(&config)
+            .unwrap()
+            .unwrap()
+            .with_clock(clock)
+    }
+
+    #[tokio::test]
+    async fn test_trading_hours_gate_new_orders_only() {
+        // 2024-01-02 12:00 UTC
+        let open = make_service().with_trading_hours(trading_hours(|| 1_704_196_800_000_000_000));
+        assert!(open.market_open());
+        let resp = open
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        assert!(resp.accepted);
+
+        // The same day at 20:00 UTC, with an order left resting from the session
+        let closed = make_service().with_trading_hours(trading_hours(|| 1_704_225_600_000_000_000));
+        let resting = closed
+            .engine
+            .add_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        assert!(!closed.market_open());
+        let result = closed
+            .submit_order(limit_order("bob", 100.0, 10, Side::Sell))
+            .await;
+        assert!(matches!(result, Err(ApiError::Halted(msg)) if msg.contains("09:30-16:00")));
+        assert!(closed.cancel_order(resting.order_id).await.unwrap().cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_halted_engine_rejects_orders() {
+        let svc = make_service();
+        svc.engine.set_halted(true);
+
+        let result = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::Halted(_))));
+
+        svc.engine.set_halted(false);
+        assert!(svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .is_ok());
+    }
+
+    fn cancel_messages(rx: &mut broadcast::Receiver<String>) -> Vec<serde_json::Value> {
+        let mut out = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            let v: serde_json::Value = serde_json::from_str(&msg).unwrap();
+            if v["type"] == "orderCancelled" {
+                out.push(v["data"].clone());
+            }
+        }
+        out
+    }
+
+    #[tokio::test]
+    async fn test_user_cancel_reason() {
+        let svc = make_service();
+        let mut rx = svc.ws_broadcast.subscribe();
+        let resp = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        let cancel = svc.cancel_order(resp.order_id).await.unwrap();
+        assert_eq!(cancel.reason, CancelReason::User);
+        assert_eq!(cancel_messages(&mut rx)[0]["reason"], "user");
+    }
+
+    #[tokio::test]
+    async fn test_expired_gtd_order_broadcast_as_order_expired() {
+        let svc = make_service();
+        let mut rx = svc.ws_broadcast.subscribe();
+        let mut req = limit_order("alice", 100.0, 10, Side::Buy);
+        req.time_in_force = TimeInForce::Gtd;
+        req.expires_at_ns = Some(unix_nanos() + 20_000_000);
+        let resp = svc.submit_order(req).await.unwrap();
+        assert_eq!(svc.expire_orders().await, 0);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        assert_eq!(svc.expire_orders().await, 1);
+        let expired: Vec<_> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|msg| serde_json::from_str::<serde_json::Value>(&msg).unwrap())
+            .filter(|v| v["type"] == "orderExpired")
+            .collect();
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0]["data"]["orderId"], resp.order_id);
+        assert_eq!(expired[0]["data"]["reason"], "expired");
+    }
+
+    #[tokio::test]
+    async fn test_disconnect_cancel_reason() {
+        let svc = make_service();
+        let mut rx = svc.ws_broadcast.subscribe();
+        let resp = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("bob", 99.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        assert_eq!(svc.cancel_on_disconnect("alice").await, 1);
+        let cancels = cancel_messages(&mut rx);
+        assert_eq!(cancels.len(), 1);
+        assert_eq!(cancels[0]["orderId"], resp.order_id);
+        assert_eq!(cancels[0]["reason"], "disconnect");
+    }
+
+    #[tokio::test]
+    async fn test_stp_cancel_reason() {
+        let svc = make_service();
+        let mut rx = svc.ws_broadcast.subscribe();
+        let resting = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+
+        // Alice crosses her own offer; CANCEL_OLDEST removes the resting sell
+        let mut req = limit_order("alice", 100.0, 10, Side::Buy);
+        req.stp_mode = Some(StpMode::CancelOldest);
+        let resp = svc.submit_order(req).await.unwrap();
+        assert!(resp.trades.is_empty());
+        assert_eq!(resp.stp_cancelled, vec![resting.order_id]);
+
+        let cancels = cancel_messages(&mut rx);
+        assert_eq!(cancels.len(), 1);
+        assert_eq!(cancels[0]["orderId"], resting.order_id);
+        assert_eq!(cancels[0]["reason"], "stp");
+        assert!(svc.risk.trader_for_order(resting.order_id).is_none());
+    }
+
+    /// Audit log lines written while the guard is held on this thread.
+    #[derive(Clone, Default)]
+    struct LogCapture(Arc<std::sync::Mutex<Vec<u8>>>);
+
+    impl std::io::Write for LogCapture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl LogCapture {
+        fn events(&self, name: &str) -> Vec<serde_json::Value> {
+            let logs = String::from_utf8(self.0.lock().unwrap().clone()).unwrap();
+            logs.lines()
+                .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+                .filter(|line| line["fields"]["event"] == name)
+                .map(|line| line["fields"].clone())
+                .collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stp_triggered_audited() {
+        let capture = LogCapture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let svc = make_service();
+        let resting = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        assert!(capture.events("StpTriggered").is_empty());
+
+        let mut req = limit_order("alice", 100.0, 10, Side::Buy);
+        req.stp_mode = Some(StpMode::CancelBoth);
+        let resp = svc.submit_order(req).await.unwrap();
+
+        let events = capture.events("StpTriggered");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["order_id"], resp.order_id);
+        assert_eq!(events[0]["trader_id"], "alice");
+        assert_eq!(events[0]["stp_mode"], "CANCEL_BOTH");
+        let action = events[0]["action"].as_str().unwrap();
+        assert!(action.contains("Cancel Both"));
+        assert_eq!(
+            events[0]["cancelled_orders"],
+            format!("{:?}", [resp.order_id, resting.order_id])
+        );
+    }
+
+    #[tokio::test]
+    async fn test_order_filled_audited_with_aggregates() {
+        let capture = LogCapture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .json()
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let svc = make_service().with_order_fill_log(true);
+        svc.submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("alice", 101.0, 5, Side::Sell))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("carol", 101.0, 5, Side::Sell))
+            .await
+            .unwrap();
+        // Resting orders never log a fill summary
+        assert!(capture.events("OrderFilled").is_empty());
+
+        // Bob sweeps all three offers from two sellers
+        let resp = svc
+            .submit_order(limit_order("bob", 101.0, 20, Side::Buy))
+            .await
+            .unwrap();
+        let events = capture.events("OrderFilled");
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["order_id"], resp.order_id);
+        assert_eq!(events[0]["trader_id"], "bob");
+        assert_eq!(events[0]["filled_quantity"], 20);
+        assert_eq!(events[0]["trades"], 3);
+        assert_eq!(events[0]["counterparties"], 2);
+        assert_eq!(events[0]["vwap"], 100.5);
+
+        // An aggressive sell takes its counterparties from the buy side
+        svc.submit_order(limit_order("dave", 99.0, 5, Side::Buy))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("erin", 99.0, 5, Side::Buy))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("bob", 99.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let events = capture.events("OrderFilled");
+        assert_eq!(events.len(), 2);
+        assert_eq!(events[1]["trader_id"], "bob");
+        assert_eq!(events[1]["counterparties"], 2);
+        assert_eq!(events[1]["vwap"], 99.0);
+    }
+
+    #[tokio::test]
+    async fn test_trader_default_stp_mode_applied_when_omitted() {
+        let mut risk = RiskConfig {
+            max_order_size: 10_000,
+            max_position_per_trader: 1_000,
+            max_orders_per_second: 100,
+            default_stp_mode: Some(StpMode::CancelNewest),
+            ..RiskConfig::default()
+        };
+        risk.trader_stp_modes
+            .insert("alice".into(), StpMode::CancelOldest);
+        let svc = make_service_with(Engine::new(), risk);
+
+        // Alice's orders omit stpMode, so her CANCEL_OLDEST default applies
+        let resting = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let resp = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        assert!(resp.trades.is_empty());
+        assert_eq!(resp.stp_cancelled, vec![resting.order_id]);
+        assert_eq!(resp.remaining_quantity, 10);
+
+        // An explicit mode on the order wins over the configured default
+        let mut req = limit_order("alice", 100.0, 5, Side::Sell);
+        req.stp_mode = Some(StpMode::Allow);
+        let resp = svc.submit_order(req).await.unwrap();
+        assert_eq!(resp.trades.len(), 1);
+
+        // Bob has no entry and gets the global CANCEL_NEWEST
+        svc.submit_order(limit_order("bob", 101.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let resp = svc
+            .submit_order(limit_order("bob", 101.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        assert!(resp.trades.is_empty());
+        assert_eq!(resp.remaining_quantity, 0);
+    }
+
+    #[tokio::test]
+    async fn test_risk_rejection_oversized() {
+        let svc = make_service();
+        let result = svc
+            .submit_order(limit_order("alice", 100.0, 10_001, Side::Buy))
+            .await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            ApiError::RiskRejection(msg) => assert!(msg.reason.contains("exceeds maximum")),
+            e => panic!("Expected RiskRejection, got {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_position_tracking_after_fill() {
+        let svc = make_service();
+
+        // Resting sell
+        svc.submit_order(limit_order("seller", 100.0, 50, Side::Sell))
+            .await
+            .unwrap();
+
+        // Crossing buy — fills 50
+        let resp = svc
+            .submit_order(limit_order("buyer", 100.0, 50, Side::Buy))
+            .await
+            .unwrap();
+        assert_eq!(resp.trades.len(), 1);
+
+        // Buyer's position should be +50
+        assert_eq!(svc.risk.get_position("buyer", "DEFAULT"), 50);
+        // Seller's position should be -50
+        assert_eq!(svc.risk.get_position("seller", "DEFAULT"), -50);
+    }
+
+    #[tokio::test]
+    async fn test_fills_recorded_for_executions() {
+        let svc = make_service();
+
+        let s1 = svc
+            .submit_order(limit_order("seller1", 100.0, 20, Side::Sell))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("seller2", 101.0, 20, Side::Sell))
+            .await
+            .unwrap();
+        let buy = svc
+            .submit_order(limit_order("buyer", 101.0, 30, Side::Buy))
+            .await
+            .unwrap();
+
+        let report = svc.trade_history.executions(buy.order_id);
+        assert_eq!(report.trades.len(), 2);
+        assert_eq!(report.filled_quantity, 30);
+        // (100*20 + 101*10) / 30
+        assert!((report.avg_price.unwrap() - 3010.0 / 30.0).abs() < 1e-9);
+
+        // The resting side sees its own fill
+        assert_eq!(svc.trade_history.executions(s1.order_id).filled_quantity, 20);
+    }
+
+    #[tokio::test]
+    async fn test_position_limit_blocks_order() {
+        let svc = make_service();
+
+        // Place resting sells within position limit (each seller ≤ 1000)
+        svc.submit_order(limit_order("seller1", 100.0, 1000, Side::Sell))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("seller2", 100.0, 1000, Side::Sell))
+            .await
+            .unwrap();
+
+        // Buy 900 — fills against seller1, buyer position = 900
+        svc.submit_order(limit_order("buyer", 100.0, 900, Side::Buy))
+            .await
+            .unwrap();
+        assert_eq!(svc.risk.get_position("buyer", "DEFAULT"), 900);
+
+        // Try to buy 200 more — projected 1100 > limit 1000
+        let result = svc
+            .submit_order(limit_order("buyer", 100.0, 200, Side::Buy))
+            .await;
+        assert!(result.is_err());
+        match result.unwrap_err() {
+            ApiError::RiskRejection(msg) => assert!(msg.reason.contains("Position limit")),
+            e => panic!("Expected RiskRejection, got {:?}", e),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_opening_price_rejects_out_of_band_first_order() {
+        let svc = make_service();
+        svc.risk.set_opening_price("DEFAULT", 100.0).unwrap();
+        svc.risk.set_opening_price("BTC-USD", 150.0).unwrap();
+
+        let result = svc
+            .submit_order(limit_order("alice", 150.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+
+        // Each symbol bands around its own opening price
+        let btc = |price| OrderRequest {
+            symbol: Some("BTC-USD".into()),
+            ..limit_order("alice", price, 10, Side::Buy)
+        };
+        assert!(svc.submit_order(btc(150.0)).await.is_ok());
+        let result = svc.submit_order(btc(100.0)).await;
+        assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+
+        assert!(svc
+            .submit_order(limit_order("alice", 101.0, 10, Side::Buy))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_resting_orders_breach_worst_case_limit() {
+        let svc = make_service_with(
+            Engine::new(),
+            RiskConfig {
+                max_position_per_trader: 1_000,
+                include_resting_in_position_limit: true,
+                ..RiskConfig::default()
+            },
+        );
+
+        svc.submit_order(limit_order("alice", 100.0, 600, Side::Buy))
+            .await
+            .unwrap();
+        // 600 resting + 500 new would be long 1100 if both filled
+        let result = svc
+            .submit_order(limit_order("alice", 99.0, 500, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+
+        // Within the limit once the resting order is accounted for
+        assert!(svc
+            .submit_order(limit_order("alice", 99.0, 400, Side::Buy))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_opening_auction_uncrosses_at_volume_maximizing_price() {
+        let svc = make_service().with_opening_auction(AuctionService::new(
+            Instant::now() + Duration::from_secs(60),
+        ));
+        let mut ids = Vec::new();
+        for (trader, price, qty, side) in [
+            ("b1", 10.05, 100, Side::Buy),
+            ("b2", 10.00, 50, Side::Buy),
+            ("b3", 9.95, 50, Side::Buy),
+            ("s1", 9.90, 60, Side::Sell),
+            ("s2", 10.00, 60, Side::Sell),
+            ("s3", 10.10, 100, Side::Sell),
+            ("s4", 9.00, 500, Side::Sell),
+        ] {
+            let resp = svc
+                .submit_order(limit_order(trader, price, qty, side))
+                .await
+                .unwrap();
+            // Collected, not matched
+            assert!(resp.trades.is_empty());
+            assert_eq!(resp.remaining_quantity, qty);
+            ids.push(resp.order_id);
+        }
+        assert!(svc.engine.get_snapshot().await.best_bid.is_none());
+        svc.cancel_order(ids[6]).await.unwrap();
+
+        let uncross = svc.run_opening_auction().await.unwrap();
+        assert_eq!(uncross.price, 10.00);
+        assert_eq!(uncross.volume, 120);
+
+        let trades = svc.trade_history.trades_page(None, None, 100);
+        assert!(trades.iter().all(|t| t.price == 10.00));
+        assert_eq!(trades.iter().map(|t| t.quantity).sum::<i64>(), 120);
+        assert_eq!(svc.risk.get_position("b1", "DEFAULT"), 100);
+        assert_eq!(svc.risk.get_position("b2", "DEFAULT"), 20);
+        assert_eq!(svc.risk.get_position("s1", "DEFAULT"), -60);
+        assert_eq!(svc.risk.get_position("s2", "DEFAULT"), -60);
+        assert_eq!(svc.risk.get_position("s4", "DEFAULT"), 0);
+
+        // Remainders rest at their own limits, keeping what they filled
+        let snap = svc.engine.get_snapshot().await;
+        assert_eq!(snap.best_bid, Some(10.00));
+        assert_eq!(snap.best_ask, Some(10.10));
+        let b2 = svc.engine.get_order(ids[1]).await.unwrap();
+        assert_eq!(b2.original_quantity, 50);
+        assert_eq!(b2.filled_quantity, 20);
+        assert_eq!(b2.remaining_quantity, 30);
+
+        // Continuous trading from here on
+        let resp = svc
+            .submit_order(limit_order("s5", 10.00, 30, Side::Sell))
+            .await
+            .unwrap();
+        assert_eq!(resp.trades.len(), 1);
+        assert_eq!(resp.trades[0].buy_order_id, ids[1]);
+        assert_eq!(svc.risk.get_position("b2", "DEFAULT"), 50);
+    }
+
+    #[tokio::test]
+    async fn test_modify_beyond_cap_rejected() {
+        let svc = make_service_with(
+            Engine::new(),
+            RiskConfig {
+                max_modifies_per_order: 3,
+                ..RiskConfig::default()
+            },
+        );
+        let resp = svc
+            .submit_order(limit_order("a", 100.00, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        let modify = |price: f64| ModifyRequest {
+            new_price: price,
+            new_quantity: 10,
+        };
+        for price in [100.01, 100.02, 100.03] {
+            svc.modify_order(resp.order_id, modify(price))
+                .await
+                .unwrap();
+        }
+        let result = svc.modify_order(resp.order_id, modify(100.04)).await;
+        assert!(
+            matches!(result, Err(ApiError::RiskRejection(ref m)) if m.reason.contains("cancel and resubmit"))
+        );
+
+        // A fresh order id starts with a clean count
+        svc.cancel_order(resp.order_id).await.unwrap();
+        let resp = svc
+            .submit_order(limit_order("a", 100.00, 10, Side::Buy))
+            .await
+            .unwrap();
+        assert!(svc
+            .modify_order(resp.order_id, modify(100.01))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_modify_outside_price_band_rejected() {
+        let svc = make_service();
+
+        svc.submit_order(limit_order("a", 99.50, 100, Side::Buy))
+            .await
+            .unwrap();
+        svc.submit_order(limit_order("b", 100.50, 100, Side::Sell))
+            .await
+            .unwrap();
+        let resp = svc
+            .submit_order(limit_order("c", 95.00, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        // Band is [90, 110] around mid $100
+        let result = svc
+            .modify_order(
+                resp.order_id,
+                ModifyRequest {
+                    new_price: 85.00,
+                    new_quantity: 10,
+                },
+            )
+            .await;
+        assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+
+        let modify = svc
+            .modify_order(
+                resp.order_id,
+                ModifyRequest {
+                    new_price: 92.00,
+                    new_quantity: 10,
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(modify.new_price, 92.00);
+    }
+
+    #[tokio::test]
+    async fn test_price_band_rejection() {
+        let svc = make_service();
 
         // Establish a two-sided book around $100
         svc.submit_order(limit_order("a", 99.50, 100, Side::Buy))
@@ -301,8 +1905,536 @@ mod tests {
             .await;
         assert!(result.is_err());
         match result.unwrap_err() {
-            ApiError::RiskRejection(msg) => assert!(msg.contains("outside")),
+            ApiError::RiskRejection(msg) => assert!(msg.reason.contains("outside")),
             e => panic!("Expected RiskRejection, got {:?}", e),
         }
     }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_trade_print_delayed_but_fill_immediate() {
+        let svc = make_service().with_trade_print_delay(Duration::from_millis(40));
+        let mut rx = svc.ws_broadcast.subscribe();
+
+        svc.submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let resp = svc
+            .submit_order(limit_order("bob", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        // The submitter and the trade buffer see the fill right away
+        assert_eq!(resp.trades.len(), 1);
+        assert_eq!(svc.trade_history.executions(resp.order_id).filled_quantity, 10);
+        assert!(rx.try_recv().is_err());
+
+        // A later order's print waits its own full delay, behind the first
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        svc.submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let later = svc
+            .submit_order(limit_order("carol", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        let prints = |rx: &mut broadcast::Receiver<String>| {
+            std::iter::from_fn(|| rx.try_recv().ok())
+                .map(|raw| serde_json::from_str::<serde_json::Value>(&raw).unwrap())
+                .filter(|msg| msg["type"] == "trade")
+                .map(|msg| msg["data"]["tradeId"].as_u64().unwrap())
+                .collect::<Vec<_>>()
+        };
+        tokio::time::sleep(Duration::from_millis(29)).await;
+        assert!(prints(&mut rx).is_empty());
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        assert_eq!(prints(&mut rx), vec![resp.trades[0].trade_id]);
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        assert_eq!(prints(&mut rx), vec![later.trades[0].trade_id]);
+    }
+
+    #[tokio::test]
+    async fn test_trade_print_carries_both_clocks() {
+        let engine = Engine::with_config(EngineConfig {
+            trade_timestamp_source: TradeTimestampSource::Both,
+            ..EngineConfig::default()
+        });
+        let svc = make_service_with(engine, RiskConfig::default());
+        let mut rx = svc.ws_broadcast.subscribe();
+
+        svc.submit_order(limit_order("alice", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let resp = svc
+            .submit_order(limit_order("bob", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        let trade = &resp.trades[0];
+        let print = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|raw| serde_json::from_str::<serde_json::Value>(&raw).unwrap())
+            .find(|msg| msg["type"] == "trade")
+            .unwrap();
+        assert_eq!(print["data"]["engineTs"], trade.engine_ts.unwrap());
+        assert_eq!(print["data"]["serverTs"], trade.server_ts.unwrap());
+        assert_eq!(print["data"]["symbol"], "DEFAULT");
+    }
+
+    #[tokio::test]
+    async fn test_risk_snapshot_served_from_cache_within_ttl() {
+        let svc = make_service().with_snapshot_cache(SnapshotCache::new(
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+        ));
+        let first = svc.risk_snapshot(None).await;
+        assert!(first.best_bid.is_none());
+
+        svc.engine
+            .add_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        // Still the cached empty book
+        assert!(svc.risk_snapshot(None).await.best_bid.is_none());
+        assert_eq!(svc.engine.get_snapshot().await.best_bid, Some(100.0));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_cached_risk_checks_under_book_contention() {
+        let svc = Arc::new(make_service().with_snapshot_cache(SnapshotCache::new(
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+        )));
+        svc.publish_snapshot().await;
+
+        // With the book's write lock held, as during a long sweep, risk
+        // checks on every worker still get their snapshot from the cache
+        let book = svc.engine.default_book();
+        let guard = book.write().await;
+        let mut readers = tokio::task::JoinSet::new();
+        for _ in 0..8 {
+            let svc = Arc::clone(&svc);
+            readers.spawn(async move {
+                for _ in 0..100 {
+                    svc.risk_snapshot(None).await;
+                }
+            });
+        }
+        let drained = tokio::time::timeout(Duration::from_secs(5), async {
+            while let Some(reader) = readers.join_next().await {
+                reader.unwrap();
+            }
+        });
+        drained
+            .await
+            .expect("risk checks queued behind the book lock");
+        drop(guard);
+
+        // Submitters on every worker race the snapshot poller
+        let poller = {
+            let svc = Arc::clone(&svc);
+            tokio::spawn(async move {
+                loop {
+                    svc.publish_snapshot().await;
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+        let mut submitters = tokio::task::JoinSet::new();
+        for trader in 0..8 {
+            let svc = Arc::clone(&svc);
+            submitters.spawn(async move {
+                let trader_id = format!("trader{}", trader);
+                for i in 0..25 {
+                    let price = 90.0 + f64::from(i % 5);
+                    svc.submit_order(limit_order(&trader_id, price, 1, Side::Buy))
+                        .await
+                        .unwrap();
+                }
+            });
+        }
+        while let Some(submitter) = submitters.join_next().await {
+            submitter.unwrap();
+        }
+        poller.abort();
+
+        assert_eq!(svc.engine.total_orders(), 200);
+        svc.publish_snapshot().await;
+        let cached = svc.risk_snapshot(None).await;
+        assert_eq!(cached.seq, svc.engine.book_seq());
+        assert_eq!(cached.best_bid, Some(94.0));
+    }
+
+    #[tokio::test]
+    async fn test_published_snapshot_used_by_risk_checks() {
+        let svc = make_service().with_snapshot_cache(SnapshotCache::new(
+            Duration::from_secs(60),
+            Duration::from_secs(60),
+        ));
+        svc.publish_snapshot().await;
+        svc.engine
+            .add_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        // Risk checks see what the poller last published, not the book
+        assert!(svc.risk_snapshot(None).await.best_bid.is_none());
+
+        svc.publish_snapshot().await;
+        assert_eq!(svc.risk_snapshot(None).await.best_bid, Some(100.0));
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_cancel_replace_never_shows_both_orders() {
+        let svc = Arc::new(make_service());
+        let old = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        // Watch the book while the swap happens: the bid is always exactly
+        // one order, either the original at 100 or the replacement at 99
+        let watcher = {
+            let svc = Arc::clone(&svc);
+            tokio::spawn(async move {
+                for _ in 0..200 {
+                    let ticker = svc.engine.get_ticker().await;
+                    assert_eq!(ticker.bid_order_count, 1);
+                    assert_eq!(svc.engine.get_depth(10).await.bids.len(), 1);
+                    tokio::task::yield_now().await;
+                }
+            })
+        };
+
+        let swapped = svc
+            .cancel_replace(old.order_id, limit_order("alice", 99.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        watcher.await.unwrap();
+
+        assert_eq!(swapped.cancelled.order_id, old.order_id);
+        let OrderOutcome::Accepted(order) = swapped.order else {
+            panic!("replacement rejected");
+        };
+        assert_ne!(order.order_id, old.order_id);
+        let depth = svc.engine.get_depth(10).await;
+        assert_eq!(depth.bids.into_iter().collect::<Vec<_>>(), vec![(9900, 10)]);
+        assert!(svc.risk.trader_for_order(old.order_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_replace_reports_cancel_when_replacement_rejected() {
+        let svc = make_service();
+        svc.submit_order(limit_order("bob", 100.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        let old = svc
+            .submit_order(limit_order("alice", 99.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        // A post-only replacement that would cross is refused by the engine
+        // after the original has left the book
+        let swapped = svc
+            .cancel_replace(
+                old.order_id,
+                OrderRequest {
+                    post_only: true,
+                    ..limit_order("alice", 100.0, 10, Side::Buy)
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(swapped.cancelled.order_id, old.order_id);
+        assert!(swapped.cancelled.cancelled);
+        assert!(matches!(swapped.order, OrderOutcome::Rejected(_)));
+        assert_eq!(svc.engine.get_ticker().await.best_bid, None);
+        assert!(svc.risk.trader_for_order(old.order_id).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_replace_leaves_out_replaced_resting_quantity() {
+        let svc = make_service_with(
+            Engine::new(),
+            RiskConfig {
+                max_position_per_trader: 100,
+                include_resting_in_position_limit: true,
+                ..RiskConfig::default()
+            },
+        );
+        let old = svc
+            .submit_order(limit_order("alice", 100.0, 80, Side::Buy))
+            .await
+            .unwrap();
+
+        // Re-pricing keeps the worst case at 80, not 80 resting + 80 new
+        let swapped = svc
+            .cancel_replace(old.order_id, limit_order("alice", 99.0, 80, Side::Buy))
+            .await
+            .unwrap();
+        assert!(matches!(swapped.order, OrderOutcome::Accepted(_)));
+        assert_eq!(svc.risk.resting_quantity("alice", "DEFAULT", Side::Buy), 80);
+
+        // Other resting orders still count
+        let result = svc
+            .submit_order(limit_order("alice", 98.0, 30, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+    }
+
+    #[tokio::test]
+    async fn test_cancel_replace_rejects_other_traders_order() {
+        let svc = make_service();
+        let old = svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .unwrap();
+
+        let result = svc
+            .cancel_replace(old.order_id, limit_order("mallory", 99.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::Forbidden(_))));
+        assert_eq!(svc.engine.get_ticker().await.best_bid, Some(100.0));
+    }
+
+    #[tokio::test]
+    async fn test_reset_clears_cached_snapshot_and_breaker() {
+        let svc = make_service()
+            .with_snapshot_cache(SnapshotCache::new(
+                Duration::from_secs(60),
+                Duration::from_secs(60),
+            ))
+            .with_circuit_breaker(CircuitBreaker::new(
+                5.0,
+                Duration::from_secs(10),
+                Duration::from_secs(60),
+            ));
+        for (price, trader) in [(100.0, "alice"), (106.0, "carol")] {
+            svc.submit_order(limit_order(trader, price, 10, Side::Sell))
+                .await
+                .unwrap();
+            svc.submit_order(limit_order("bob", price, 10, Side::Buy))
+                .await
+                .unwrap();
+        }
+        svc.engine
+            .add_order(limit_order("dave", 105.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        svc.publish_snapshot().await;
+        assert!(svc.circuit_breaker_tripped("DEFAULT"));
+
+        svc.reset().await;
+        assert!(!svc.circuit_breaker_tripped("DEFAULT"));
+        assert!(svc.risk_snapshot(None).await.best_bid.is_none());
+        svc.submit_order(limit_order("alice", 106.0, 10, Side::Sell))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_fast_move_trips_circuit_breaker() {
+        let svc = make_service().with_circuit_breaker(CircuitBreaker::new(
+            5.0,
+            Duration::from_secs(10),
+            Duration::from_secs(60),
+        ));
+        let mut rx = svc.ws_broadcast.subscribe();
+
+        for (price, trader) in [(100.0, "alice"), (106.0, "carol")] {
+            svc.submit_order(limit_order(trader, price, 10, Side::Sell))
+                .await
+                .unwrap();
+            svc.submit_order(limit_order("bob", price, 10, Side::Buy))
+                .await
+                .unwrap();
+        }
+
+        let events: Vec<serde_json::Value> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|m| serde_json::from_str(&m).unwrap())
+            .filter(|m: &serde_json::Value| m["type"] == "circuitBreaker")
+            .collect();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0]["data"]["referencePrice"], 100.0);
+        assert_eq!(events[0]["data"]["triggerPrice"], 106.0);
+        assert_eq!(events[0]["data"]["symbol"], "DEFAULT");
+
+        let result = svc
+            .submit_order(limit_order("alice", 106.0, 10, Side::Sell))
+            .await;
+        assert!(matches!(result, Err(ApiError::Halted(msg)) if msg.contains("Circuit breaker")));
+
+        // Other symbols keep trading
+        let eth = OrderRequest {
+            symbol: Some("ETH-USD".into()),
+            ..limit_order("alice", 106.0, 10, Side::Sell)
+        };
+        svc.submit_order(eth).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_repeated_risk_rejections_put_trader_in_penalty_box() {
+        let svc = make_service_with(
+            Engine::new(),
+            RiskConfig {
+                max_order_size: 10_000,
+                rejection_penalty_threshold: 2,
+                rejection_penalty_base_ms: 60_000,
+                ..RiskConfig::default()
+            },
+        );
+
+        for _ in 0..2 {
+            let result = svc
+                .submit_order(limit_order("bot", 100.0, 50_000, Side::Buy))
+                .await;
+            assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+        }
+
+        // Even a valid order is refused during the cool-down
+        let result = svc
+            .submit_order(limit_order("bot", 100.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RateLimited(msg)) if msg.contains("cool-down")));
+        assert!(svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_orders_take_no_trader_slot() {
+        let svc = make_service_with(
+            Engine::new(),
+            RiskConfig {
+                max_order_size: 10_000,
+                max_traders: 1,
+                ..RiskConfig::default()
+            },
+        );
+
+        let result = svc
+            .submit_order(limit_order("bot", 100.0, 50_000, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RiskRejection(_))));
+
+        assert!(svc
+            .submit_order(limit_order("alice", 100.0, 10, Side::Buy))
+            .await
+            .is_ok());
+        let result = svc
+            .submit_order(limit_order("bob", 100.0, 10, Side::Buy))
+            .await;
+        assert!(matches!(result, Err(ApiError::RateLimited(msg)) if msg.contains("Trader limit")));
+    }
+
+    fn imbalance_alerts(rx: &mut broadcast::Receiver<String>) -> Vec<serde_json::Value> {
+        let mut alerts = Vec::new();
+        while let Ok(msg) = rx.try_recv() {
+            let v: serde_json::Value = serde_json::from_str(&msg).unwrap();
+            if v["type"] == "imbalanceAlert" {
+                alerts.push(v);
+            }
+        }
+        alerts
+    }
+
+    #[tokio::test]
+    async fn test_lopsided_book_broadcasts_imbalance_alert() {
+        let svc = make_service().with_imbalance_monitor(ImbalanceMonitor::new(0.6, 0.1));
+        let mut rx = svc.ws_broadcast.subscribe();
+
+        // Balanced: 100 bid vs 100 offered
+        svc.submit_order(limit_order("alice", 99.0, 100, Side::Buy)).await.unwrap();
+        svc.submit_order(limit_order("bob", 101.0, 100, Side::Sell)).await.unwrap();
+        let _ = imbalance_alerts(&mut rx);
+        svc.submit_order(limit_order("bob", 101.0, 20, Side::Sell)).await.unwrap();
+        assert!(imbalance_alerts(&mut rx).is_empty());
+
+        // 900 bid vs 120 offered at the touch
+        svc.submit_order(limit_order("alice", 99.0, 800, Side::Buy)).await.unwrap();
+        let alerts = imbalance_alerts(&mut rx);
+        assert_eq!(alerts.len(), 1);
+        assert_eq!(alerts[0]["data"]["state"], "bidHeavy");
+        assert_eq!(alerts[0]["data"]["bidQuantity"], 900);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wide_spread_broadcasts_market_quality_alert() {
+        let svc = make_service().with_market_quality_monitor(MarketQualityMonitor::new(
+            0.50,
+            Duration::from_millis(20),
+        ));
+        let mut rx = svc.ws_broadcast.subscribe();
+        let alerts = |rx: &mut broadcast::Receiver<String>| {
+            let mut alerts = Vec::new();
+            while let Ok(msg) = rx.try_recv() {
+                let v: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                if v["type"] == "marketQualityAlert" {
+                    alerts.push(v);
+                }
+            }
+            alerts
+        };
+
+        // $2.00 wide: first sample starts the grace period
+        svc.submit_order(limit_order("alice", 99.0, 10, Side::Buy)).await.unwrap();
+        svc.submit_order(limit_order("bob", 101.0, 10, Side::Sell)).await.unwrap();
+        svc.check_market_quality().await;
+        assert!(alerts(&mut rx).is_empty());
+
+        tokio::time::advance(Duration::from_millis(19)).await;
+        svc.check_market_quality().await;
+        assert!(alerts(&mut rx).is_empty());
+
+        tokio::time::advance(Duration::from_millis(2)).await;
+        svc.check_market_quality().await;
+        let wide = alerts(&mut rx);
+        assert_eq!(wide.len(), 1);
+        assert_eq!(wide[0]["data"]["state"], "wide");
+        assert_eq!(wide[0]["data"]["spread"], 2.0);
+
+        svc.submit_order(limit_order("bob", 99.25, 10, Side::Sell)).await.unwrap();
+        svc.check_market_quality().await;
+        assert_eq!(alerts(&mut rx)[0]["data"]["state"], "normal");
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_broadcast_only_when_changed() {
+        let svc = make_service();
+        let mut rx = svc.ws_broadcast.subscribe();
+        let snapshots = |rx: &mut broadcast::Receiver<String>| {
+            let mut snapshots = Vec::new();
+            while let Ok(msg) = rx.try_recv() {
+                let v: serde_json::Value = serde_json::from_str(&msg).unwrap();
+                if v["type"] == "snapshot" {
+                    snapshots.push(v);
+                }
+            }
+            snapshots
+        };
+
+        svc.submit_order(limit_order("alice", 99.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        svc.broadcast_snapshot().await;
+        let first = snapshots(&mut rx);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0]["data"]["bestBid"], 99.0);
+
+        // Idle market, and a change away from the touch, send nothing
+        svc.broadcast_snapshot().await;
+        svc.submit_order(limit_order("alice", 98.0, 10, Side::Buy))
+            .await
+            .unwrap();
+        svc.broadcast_snapshot().await;
+        assert!(snapshots(&mut rx).is_empty());
+
+        svc.submit_order(limit_order("bob", 101.0, 10, Side::Sell))
+            .await
+            .unwrap();
+        svc.broadcast_snapshot().await;
+        let next = snapshots(&mut rx);
+        assert_eq!(next.len(), 1);
+        assert_eq!(next[0]["data"]["bestAsk"], 101.0);
+        assert_eq!(next[0]["seq"], next[0]["data"]["seq"]);
+    }
 }
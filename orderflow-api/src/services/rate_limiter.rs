@@ -5,6 +5,7 @@ use dashmap::DashMap;
 use governor::clock::DefaultClock;
 use governor::state::{InMemoryState, NotKeyed};
 use governor::{Quota, RateLimiter as GovRateLimiter};
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 
 use crate::models::error::ApiError;
 
@@ -13,21 +14,80 @@ type Limiter = GovRateLimiter<NotKeyed, InMemoryState, DefaultClock>;
 pub struct RateLimiterService {
     limiters: DashMap<String, Arc<Limiter>>,
     max_per_second: NonZeroU32,
+    /// Per-trader cap on concurrently executing order operations. A trader's
+    /// entry is dropped once their last permit is released.
+    in_flight: Arc<DashMap<String, Arc<Semaphore>>>,
+    max_in_flight: u32,
+    /// Give each (trader, symbol) pair its own rate bucket
+    per_symbol: bool,
 }
 
 impl RateLimiterService {
-    pub fn new(max_orders_per_second: u32) -> Self {
+    pub fn new(max_orders_per_second: u32, max_in_flight_per_trader: u32) -> Self {
         let max = NonZeroU32::new(max_orders_per_second.max(1)).unwrap();
         Self {
             limiters: DashMap::new(),
             max_per_second: max,
+            in_flight: Arc::new(DashMap::new()),
+            max_in_flight: max_in_flight_per_trader.max(1),
+            per_symbol: false,
         }
     }
 
-    pub fn check_rate_limit(&self, trader_id: &str) -> Result<(), ApiError> {
+    /// Key rate buckets on (trader, symbol) instead of trader alone, so
+    /// flooding one symbol doesn't use up the budget for another.
+    pub fn with_symbol_scope(mut self, per_symbol: bool) -> Self {
+        self.per_symbol = per_symbol;
+        self
+    }
+
+    /// Reserve one in-flight slot for `trader_id`. The slot is released when the
+    /// returned permit is dropped.
+    pub fn acquire_in_flight(&self, trader_id: &str) -> Result<InFlightPermit, ApiError> {
+        let semaphore = self
+            .in_flight
+            .entry(trader_id.to_string())
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_in_flight as usize)))
+            .clone();
+
+        // A full semaphore has other holders, so it is never left idle here
+        let permit = semaphore.try_acquire_owned().map_err(|_| {
+            ApiError::RateLimited(format!(
+                "Too many in-flight orders for trader '{}' (max {})",
+                trader_id, self.max_in_flight
+            ))
+        })?;
+        Ok(InFlightPermit {
+            permit: Some(permit),
+            trader_id: trader_id.to_string(),
+            in_flight: Arc::clone(&self.in_flight),
+        })
+    }
+
+    /// Drop `trader_id`'s rate buckets and in-flight slots, unless an
+    /// operation of theirs is still running.
+    pub fn forget_trader(&self, trader_id: &str) {
+        let prefix = format!("{}/", trader_id);
+        self.limiters
+            .retain(|key, _| key != trader_id && !key.starts_with(&prefix));
+        remove_idle(&self.in_flight, trader_id);
+    }
+
+    /// Keys that currently have a rate-limit bucket, sorted.
+    pub fn bucket_keys(&self) -> Vec<String> {
+        let mut keys: Vec<String> = self.limiters.iter().map(|e| e.key().clone()).collect();
+        keys.sort();
+        keys
+    }
+
+    pub fn check_rate_limit(&self, trader_id: &str, symbol: Option<&str>) -> Result<(), ApiError> {
+        let key = match symbol {
+            Some(symbol) if self.per_symbol => format!("{}/{}", trader_id, symbol),
+            _ => trader_id.to_string(),
+        };
         let limiter = self
             .limiters
-            .entry(trader_id.to_string())
+            .entry(key)
             .or_insert_with(|| {
                 Arc::new(GovRateLimiter::direct(Quota::per_second(
                     self.max_per_second,
@@ -37,42 +97,143 @@ impl RateLimiterService {
 
         match limiter.check() {
             Ok(_) => Ok(()),
-            Err(_) => Err(ApiError::RateLimited(format!(
-                "Rate limit exceeded for trader '{}' (max {} orders/sec)",
-                trader_id, self.max_per_second
-            ))),
+            Err(_) => Err(ApiError::RateLimited(match symbol {
+                Some(symbol) if self.per_symbol => format!(
+                    "Rate limit exceeded for trader '{}' on {} (max {} orders/sec)",
+                    trader_id, symbol, self.max_per_second
+                ),
+                _ => format!(
+                    "Rate limit exceeded for trader '{}' (max {} orders/sec)",
+                    trader_id, self.max_per_second
+                ),
+            })),
         }
     }
 }
 
+/// One of a trader's in-flight slots, released on drop.
+pub struct InFlightPermit {
+    permit: Option<OwnedSemaphorePermit>,
+    trader_id: String,
+    in_flight: Arc<DashMap<String, Arc<Semaphore>>>,
+}
+
+impl Drop for InFlightPermit {
+    fn drop(&mut self) {
+        drop(self.permit.take());
+        remove_idle(&self.in_flight, &self.trader_id);
+    }
+}
+
+/// Drop `trader_id`'s semaphore unless a permit or an acquirer still holds it.
+fn remove_idle(in_flight: &DashMap<String, Arc<Semaphore>>, trader_id: &str) {
+    in_flight.remove_if(trader_id, |_, semaphore| Arc::strong_count(semaphore) == 1);
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
     fn test_rate_limiter_allows_within_limit() {
-        let rl = RateLimiterService::new(10);
+        let rl = RateLimiterService::new(10, 16);
         // First request should always pass
-        assert!(rl.check_rate_limit("alice").is_ok());
+        assert!(rl.check_rate_limit("alice", None).is_ok());
     }
 
     #[test]
     fn test_rate_limiter_blocks_burst() {
         // Allow only 1 per second
-        let rl = RateLimiterService::new(1);
+        let rl = RateLimiterService::new(1, 16);
         // First should pass
-        assert!(rl.check_rate_limit("alice").is_ok());
+        assert!(rl.check_rate_limit("alice", None).is_ok());
         // Second immediate request should be rate limited
-        assert!(rl.check_rate_limit("alice").is_err());
+        assert!(rl.check_rate_limit("alice", None).is_err());
     }
 
     #[test]
     fn test_rate_limiter_per_trader() {
-        let rl = RateLimiterService::new(1);
-        assert!(rl.check_rate_limit("alice").is_ok());
+        let rl = RateLimiterService::new(1, 16);
+        assert!(rl.check_rate_limit("alice", None).is_ok());
         // Different trader has their own bucket
-        assert!(rl.check_rate_limit("bob").is_ok());
+        assert!(rl.check_rate_limit("bob", None).is_ok());
         // Alice is still rate limited
-        assert!(rl.check_rate_limit("alice").is_err());
+        assert!(rl.check_rate_limit("alice", None).is_err());
+    }
+
+    #[test]
+    fn test_symbol_scoped_buckets() {
+        let rl = RateLimiterService::new(1, 16).with_symbol_scope(true);
+        assert!(rl.check_rate_limit("alice", Some("AAPL")).is_ok());
+        assert!(rl.check_rate_limit("alice", Some("AAPL")).is_err());
+        // Exhausting AAPL leaves MSFT untouched
+        assert!(rl.check_rate_limit("alice", Some("MSFT")).is_ok());
+    }
+
+    #[test]
+    fn test_symbol_ignored_without_scope() {
+        let rl = RateLimiterService::new(1, 16);
+        assert!(rl.check_rate_limit("alice", Some("AAPL")).is_ok());
+        // One bucket per trader, regardless of symbol
+        assert!(rl.check_rate_limit("alice", Some("MSFT")).is_err());
+    }
+
+    #[test]
+    fn test_forget_trader_drops_their_buckets() {
+        let rl = RateLimiterService::new(1, 16).with_symbol_scope(true);
+        rl.check_rate_limit("alice", Some("AAPL")).unwrap();
+        rl.check_rate_limit("alice2", Some("AAPL")).unwrap();
+        rl.forget_trader("alice");
+        assert_eq!(rl.bucket_keys(), vec!["alice2/AAPL"]);
+        assert!(rl.check_rate_limit("alice", Some("AAPL")).is_ok());
+    }
+
+    #[test]
+    fn test_in_flight_limit_per_trader() {
+        let rl = RateLimiterService::new(100, 2);
+        let p1 = rl.acquire_in_flight("alice").unwrap();
+        let _p2 = rl.acquire_in_flight("alice").unwrap();
+        // Third concurrent operation exceeds the limit
+        assert!(rl.acquire_in_flight("alice").is_err());
+        // Other traders are unaffected
+        assert!(rl.acquire_in_flight("bob").is_ok());
+        // Completing one operation frees a slot
+        drop(p1);
+        assert!(rl.acquire_in_flight("alice").is_ok());
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn test_in_flight_limit_under_concurrency() {
+        let rl = Arc::new(RateLimiterService::new(100, 3));
+        let tasks = 16;
+        // Nobody releases a slot until every task has tried for one
+        let barrier = Arc::new(tokio::sync::Barrier::new(tasks));
+        let mut set = tokio::task::JoinSet::new();
+        for _ in 0..tasks {
+            let (rl, barrier) = (Arc::clone(&rl), Arc::clone(&barrier));
+            set.spawn(async move {
+                let permit = rl.acquire_in_flight("alice");
+                barrier.wait().await;
+                permit.is_ok()
+            });
+        }
+        let mut admitted = 0;
+        while let Some(ok) = set.join_next().await {
+            admitted += ok.unwrap() as usize;
+        }
+        assert_eq!(admitted, 3);
+        // Every slot is back, and the idle entry is gone
+        assert!(rl.in_flight.is_empty());
+    }
+
+    #[test]
+    fn test_idle_in_flight_entry_evicted() {
+        let rl = RateLimiterService::new(100, 2);
+        let a = rl.acquire_in_flight("alice").unwrap();
+        let b = rl.acquire_in_flight("alice").unwrap();
+        drop(a);
+        assert!(rl.in_flight.contains_key("alice"));
+        drop(b);
+        assert!(!rl.in_flight.contains_key("alice"));
     }
 }
@@ -0,0 +1,136 @@
+use std::collections::HashSet;
+
+use crate::models::error::{ApiError, RiskRule};
+use crate::models::market::MarketSnapshot;
+use crate::models::order::{OrderType, Side};
+
+/// What a risk plugin sees of an incoming order.
+pub struct OrderContext<'a> {
+    pub trader_id: &'a str,
+    pub symbol: &'a str,
+    pub side: Side,
+    pub order_type: OrderType,
+    /// `None` for market orders
+    pub price: Option<f64>,
+    pub quantity: i64,
+    /// The trader's net position in `symbol` before this order
+    pub position: i64,
+    /// Quantity the trader already has resting on `side` in `symbol`
+    pub resting: i64,
+    pub snapshot: &'a MarketSnapshot,
+}
+
+/// A custom risk rule, run by `RiskService::check_order` after the built-in
+/// checks have passed. Register with `RiskService::with_plugin` and hand
+/// the service to `AppState::with_risk`.
+pub trait RiskPlugin: Send + Sync {
+    fn check(&self, ctx: &OrderContext) -> Result<(), ApiError>;
+}
+
+/// Lets the listed traders only reduce their position: no opening one, and
+/// no trading through flat to the other side. Orders already resting on the
+/// same side count against what is left to reduce, as if they all fill.
+pub struct ReduceOnlyTraders {
+    traders: HashSet<String>,
+}
+
+impl ReduceOnlyTraders {
+    pub fn new(traders: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            traders: traders.into_iter().collect(),
+        }
+    }
+}
+
+impl RiskPlugin for ReduceOnlyTraders {
+    fn check(&self, ctx: &OrderContext) -> Result<(), ApiError> {
+        if !self.traders.contains(ctx.trader_id) {
+            return Ok(());
+        }
+        let reducible = match ctx.side {
+            Side::Buy => -ctx.position,
+            Side::Sell => ctx.position,
+        } - ctx.resting;
+        if ctx.quantity > reducible {
+            return Err(ApiError::risk(
+                RiskRule::ReduceOnlyTraders,
+                format!(
+                    "Trader '{}' is reduce-only: {:?} {} against position {} with {} resting in {}",
+                    ctx.trader_id, ctx.side, ctx.quantity, ctx.position, ctx.resting, ctx.symbol
+                ),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx<'a>(
+        trader_id: &'a str,
+        side: Side,
+        quantity: i64,
+        position: i64,
+        resting: i64,
+    ) -> OrderContext<'a> {
+        static EMPTY: MarketSnapshot = MarketSnapshot {
+            best_bid: None,
+            best_ask: None,
+            spread: None,
+            mid_price: None,
+            last_trade_price: None,
+            last_trade_qty: None,
+            seq: 0,
+        };
+        OrderContext {
+            trader_id,
+            symbol: "DEFAULT",
+            side,
+            order_type: OrderType::Limit,
+            price: Some(100.0),
+            quantity,
+            position,
+            resting,
+            snapshot: &EMPTY,
+        }
+    }
+
+    #[test]
+    fn test_reduce_only_trader_can_only_shrink_position() {
+        let plugin = ReduceOnlyTraders::new(["wind_down".to_string()]);
+        for (side, quantity, position) in [
+            (Side::Sell, 40, 100),
+            (Side::Sell, 100, 100),
+            (Side::Buy, 30, -30),
+        ] {
+            let result = plugin.check(&ctx("wind_down", side, quantity, position, 0));
+            assert!(result.is_ok());
+        }
+
+        // Through flat, adding, or opening from flat
+        for (side, quantity, position) in [
+            (Side::Sell, 101, 100),
+            (Side::Buy, 10, 100),
+            (Side::Buy, 10, 0),
+        ] {
+            let result = plugin.check(&ctx("wind_down", side, quantity, position, 0));
+            assert!(matches!(
+                result,
+                Err(ApiError::RiskRejection(c)) if c.rule_id == RiskRule::ReduceOnlyTraders
+            ));
+        }
+
+        // Resting sells already cover part of the position
+        assert!(plugin
+            .check(&ctx("wind_down", Side::Sell, 40, 100, 60))
+            .is_ok());
+        assert!(plugin
+            .check(&ctx("wind_down", Side::Sell, 41, 100, 60))
+            .is_err());
+
+        // Everyone else is untouched
+        assert!(plugin.check(&ctx("alice", Side::Buy, 10, 0, 0)).is_ok());
+    }
+}
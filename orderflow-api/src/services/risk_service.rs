@@ -1,87 +1,445 @@
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
 use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
 
-use crate::config::RiskConfig;
-use crate::models::error::ApiError;
+use crate::config::{RiskConfig, RiskOverride, TraderCapPolicy};
+use crate::models::admin::Positions;
+use crate::models::debug::RegisteredOrder;
+use crate::models::error::{ApiError, RiskRule};
 use crate::models::market::MarketSnapshot;
-use crate::models::order::{OrderType, Side};
+use crate::models::order::{OrderType, Side, StpMode};
+
+use super::risk_plugin::{OrderContext, ReduceOnlyTraders, RiskPlugin};
 
 pub struct OrderRegistration {
     pub trader_id: String,
+    pub symbol: String,
     pub side: Side,
+    /// Quantity still resting on the book
+    pub resting: i64,
+    /// Accepted modifies so far, for `max_modifies_per_order`
+    pub modifies: u32,
+}
+
+/// Open quantity a trader has resting on each side of the book.
+#[derive(Default)]
+struct RestingExposure {
+    buy: i64,
+    sell: i64,
+}
+
+/// Risk state that must survive a restart. The book itself is not
+/// persisted, so neither are resting orders: only net positions, which
+/// trades have already settled, carry over.
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RiskState {
+    pub positions: Positions,
+}
+
+/// Recent rejections and cool-down history for one trader.
+#[derive(Default)]
+struct PenaltyState {
+    recent: VecDeque<Instant>,
+    /// Cool-downs served since the trader was last clean; doubles the next
+    level: u32,
+    boxed_until: Option<Instant>,
 }
 
 pub struct RiskService {
     config: RiskConfig,
-    positions: DashMap<String, i64>,
-    /// Maps order_id → (trader_id, side) so we can update counterparty positions after trades
+    allowed_traders: HashSet<String>,
+    /// symbol → band reference used before its book has a mid price or any
+    /// trades
+    opening_prices: DashMap<String, f64>,
+    /// trader_id → symbol → net position; each symbol is limited separately
+    positions: DashMap<String, BTreeMap<String, i64>>,
+    /// Maps order_id → (trader_id, symbol, side) so we can update counterparty positions after trades
     order_registry: DashMap<u64, OrderRegistration>,
+    /// trader_id → symbol → resting quantity per side, kept in step with the registry
+    resting: DashMap<String, BTreeMap<String, RestingExposure>>,
+    /// trader_id → rejection penalty box state
+    penalties: DashMap<String, PenaltyState>,
+    /// trader_id → when their last order arrived, for `max_traders`
+    traders: Mutex<HashMap<String, Instant>>,
+    /// Custom rules run after the built-in checks, in registration order
+    plugins: Vec<Box<dyn RiskPlugin>>,
 }
 
 impl RiskService {
     pub fn new(config: RiskConfig) -> Self {
+        let allowed_traders = config.allowed_traders.iter().cloned().collect();
+        let opening_prices = config.opening_prices.clone().into_iter().collect();
+        let mut plugins: Vec<Box<dyn RiskPlugin>> = Vec::new();
+        if !config.reduce_only_traders.is_empty() {
+            let traders = config.reduce_only_traders.iter().cloned();
+            plugins.push(Box::new(ReduceOnlyTraders::new(traders)));
+        }
         Self {
             config,
+            allowed_traders,
+            opening_prices,
             positions: DashMap::new(),
             order_registry: DashMap::new(),
+            resting: DashMap::new(),
+            penalties: DashMap::new(),
+            traders: Mutex::new(HashMap::new()),
+            plugins,
+        }
+    }
+
+    /// Run `plugin` on every order that passes the built-in checks.
+    pub fn with_plugin(mut self, plugin: impl RiskPlugin + 'static) -> Self {
+        self.plugins.push(Box::new(plugin));
+        self
+    }
+
+    pub fn config(&self) -> &RiskConfig {
+        &self.config
+    }
+
+    /// STP mode for `trader_id`'s orders that don't set one: the trader's
+    /// own entry in `trader_stp_modes`, else `default_stp_mode`.
+    pub fn default_stp_mode(&self, trader_id: &str) -> Option<StpMode> {
+        self.config
+            .trader_stp_modes
+            .get(trader_id)
+            .copied()
+            .or(self.config.default_stp_mode)
+    }
+
+    pub fn opening_price(&self, symbol: &str) -> Option<f64> {
+        self.opening_prices.get(symbol).map(|p| *p)
+    }
+
+    pub fn set_opening_price(&self, symbol: &str, price: f64) -> Result<(), ApiError> {
+        if !(price > 0.0 && price.is_finite()) {
+            return Err(ApiError::Validation(format!(
+                "Opening price must be positive, got {}",
+                price
+            )));
+        }
+        self.opening_prices.insert(symbol.to_string(), price);
+        Ok(())
+    }
+
+    /// Order-entry allow-list. An empty list admits every trader.
+    pub fn check_trader_allowed(&self, trader_id: &str) -> Result<(), ApiError> {
+        if self.allowed_traders.is_empty() || self.allowed_traders.contains(trader_id) {
+            return Ok(());
+        }
+        Err(ApiError::Forbidden(format!(
+            "Trader '{}' is not permitted to submit orders",
+            trader_id
+        )))
+    }
+
+    /// Track `trader_id` against `max_traders`. Once the cap is reached a new
+    /// trader is refused, or under `evict_idle` takes the place of the least
+    /// recently active trader with nothing resting, a flat position and no
+    /// cool-down. Rejection history is kept through eviction and ages out in
+    /// `prune_penalties`. Returns the evicted trader so callers can drop
+    /// their own state for it.
+    pub fn admit_trader(&self, trader_id: &str, now: Instant) -> Result<Option<String>, ApiError> {
+        let max = self.config.max_traders;
+        if max == 0 {
+            return Ok(None);
+        }
+        let mut traders = self.traders.lock().unwrap();
+        if let Some(last_seen) = traders.get_mut(trader_id) {
+            *last_seen = now;
+            return Ok(None);
+        }
+        let mut evicted = None;
+        if traders.len() >= max {
+            if self.config.trader_cap_policy == TraderCapPolicy::EvictIdle {
+                evicted = traders
+                    .iter()
+                    .filter(|(id, _)| self.is_idle(id, now))
+                    .min_by_key(|(_, last_seen)| **last_seen)
+                    .map(|(id, _)| id.clone());
+            }
+            let Some(idle) = &evicted else {
+                return Err(ApiError::RateLimited(format!(
+                    "Trader limit of {} reached; new trader '{}' not accepted",
+                    max, trader_id
+                )));
+            };
+            traders.remove(idle);
+            self.positions.remove(idle);
+            self.resting.remove(idle);
+        }
+        traders.insert(trader_id.to_string(), now);
+        Ok(evicted)
+    }
+
+    fn is_idle(&self, trader_id: &str, now: Instant) -> bool {
+        let flat = self
+            .positions
+            .get(trader_id)
+            .is_none_or(|p| p.values().all(|&position| position == 0));
+        let nothing_resting = self
+            .resting
+            .get(trader_id)
+            .is_none_or(|r| r.values().all(|e| e.buy == 0 && e.sell == 0));
+        flat && nothing_resting && self.check_penalty_box(trader_id, now).is_ok()
+    }
+
+    /// Refuse a trader still serving a rejection cool-down.
+    pub fn check_penalty_box(&self, trader_id: &str, now: Instant) -> Result<(), ApiError> {
+        let Some(until) = self.penalties.get(trader_id).and_then(|p| p.boxed_until) else {
+            return Ok(());
+        };
+        if until <= now {
+            return Ok(());
+        }
+        Err(ApiError::RateLimited(format!(
+            "Trader '{}' is in a {}ms cool-down after repeated rejections",
+            trader_id,
+            (until - now).as_millis()
+        )))
+    }
+
+    /// Count a rejection against `trader_id`. Reaching the threshold within
+    /// the window starts a cool-down, twice as long as the previous one
+    /// unless the trader has been clean for the reset period. Returns the
+    /// cool-down when one starts.
+    pub fn record_rejection(&self, trader_id: &str, now: Instant) -> Option<Duration> {
+        let threshold = self.config.rejection_penalty_threshold;
+        if threshold == 0 {
+            return None;
+        }
+        let window = Duration::from_millis(self.config.rejection_penalty_window_ms);
+        let base = Duration::from_millis(self.config.rejection_penalty_base_ms);
+        let max = Duration::from_millis(self.config.rejection_penalty_max_ms);
+        let reset = Duration::from_millis(self.config.rejection_penalty_reset_ms);
+
+        let mut state = self.penalties.entry(trader_id.to_string()).or_default();
+        if state.boxed_until.is_some_and(|until| now < until) {
+            return None;
+        }
+        while state
+            .recent
+            .front()
+            .is_some_and(|&t| now.duration_since(t) >= window)
+        {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(now);
+        if state.recent.len() < threshold as usize {
+            return None;
+        }
+
+        if state
+            .boxed_until
+            .is_some_and(|until| now.duration_since(until) >= reset)
+        {
+            state.level = 0;
         }
+        let cooldown = 2u32
+            .checked_pow(state.level)
+            .and_then(|factor| base.checked_mul(factor))
+            .map_or(max, |d| d.min(max));
+        state.level = state.level.saturating_add(1);
+        state.boxed_until = Some(now + cooldown);
+        state.recent.clear();
+        Some(cooldown)
+    }
+
+    /// Forget traders whose rejections have all aged out of the window and
+    /// who have been clean long enough for their cool-down level to reset;
+    /// their entry would behave like a fresh one.
+    pub fn prune_penalties(&self, now: Instant) {
+        let window = Duration::from_millis(self.config.rejection_penalty_window_ms);
+        let reset = Duration::from_millis(self.config.rejection_penalty_reset_ms);
+        self.penalties.retain(|_, state| {
+            let recent = state
+                .recent
+                .back()
+                .is_some_and(|&t| now.duration_since(t) < window);
+            let remembered = state
+                .boxed_until
+                .is_some_and(|until| now.saturating_duration_since(until) < reset);
+            recent || remembered
+        });
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub fn check_order(
         &self,
         trader_id: &str,
+        symbol: &str,
         quantity: i64,
         side: Side,
         order_type: OrderType,
         price: Option<f64>,
         snapshot: &MarketSnapshot,
+        replaces: Option<u64>,
     ) -> Result<(), ApiError> {
-        self.check_order_size(quantity)?;
+        self.check_order_size(trader_id, quantity)?;
         if order_type == OrderType::Limit {
             if let Some(p) = price {
-                self.check_price_band(p, snapshot)?;
+                self.check_price_band(symbol, p, snapshot)?;
             }
         }
-        self.check_position_limit(trader_id, quantity, side)?;
+        self.check_position_limit(trader_id, symbol, quantity, side, replaces)?;
+        self.check_notional(trader_id, quantity, side, order_type, price, snapshot)?;
+        if self.plugins.is_empty() {
+            return Ok(());
+        }
+        let ctx = OrderContext {
+            trader_id,
+            symbol,
+            side,
+            order_type,
+            price,
+            quantity,
+            position: self.get_position(trader_id, symbol),
+            resting: self.resting_besides(trader_id, symbol, side, replaces),
+            snapshot,
+        };
+        for plugin in &self.plugins {
+            plugin.check(&ctx)?;
+        }
         Ok(())
     }
 
-    fn check_order_size(&self, quantity: i64) -> Result<(), ApiError> {
-        if quantity < self.config.min_order_size {
-            return Err(ApiError::RiskRejection(format!(
-                "Order size {} below minimum {}",
-                quantity, self.config.min_order_size
-            )));
+    /// `trader_id`'s entry in `risk.overrides`, if any.
+    fn overrides(&self, trader_id: &str) -> Option<&RiskOverride> {
+        self.config.overrides.get(trader_id)
+    }
+
+    fn max_position(&self, trader_id: &str) -> i64 {
+        self.overrides(trader_id)
+            .and_then(|o| o.max_position_per_trader)
+            .unwrap_or(self.config.max_position_per_trader)
+    }
+
+    /// Dollar value of an order. Market orders have no price of their own, so
+    /// they are valued at the best opposite price; with nothing on the other
+    /// side the notional is undefined and the order is rejected.
+    pub fn order_notional(
+        &self,
+        quantity: i64,
+        side: Side,
+        order_type: OrderType,
+        price: Option<f64>,
+        snapshot: &MarketSnapshot,
+    ) -> Result<f64, ApiError> {
+        let reference = match order_type {
+            OrderType::Limit => price,
+            OrderType::Market => match side {
+                Side::Buy => snapshot.best_ask,
+                Side::Sell => snapshot.best_bid,
+            },
+        };
+        let reference = reference.ok_or_else(|| {
+            ApiError::risk(
+                RiskRule::MaxOrderNotional,
+                "Cannot value market order: no opposite-side liquidity",
+            )
+        })?;
+        Ok(reference * quantity as f64)
+    }
+
+    fn check_notional(
+        &self,
+        trader_id: &str,
+        quantity: i64,
+        side: Side,
+        order_type: OrderType,
+        price: Option<f64>,
+        snapshot: &MarketSnapshot,
+    ) -> Result<(), ApiError> {
+        let max = self
+            .overrides(trader_id)
+            .and_then(|o| o.max_order_notional)
+            .or(self.config.max_order_notional);
+        let Some(max) = max else {
+            return Ok(());
+        };
+        let notional = self.order_notional(quantity, side, order_type, price, snapshot)?;
+        if notional > max {
+            return Err(ApiError::risk(
+                RiskRule::MaxOrderNotional,
+                format!("Order notional {:.2} exceeds maximum {:.2}", notional, max),
+            ));
         }
-        if quantity > self.config.max_order_size {
-            return Err(ApiError::RiskRejection(format!(
-                "Order size {} exceeds maximum {}",
-                quantity, self.config.max_order_size
-            )));
+        Ok(())
+    }
+
+    fn check_order_size(&self, trader_id: &str, quantity: i64) -> Result<(), ApiError> {
+        let limits = self.overrides(trader_id);
+        let min = limits
+            .and_then(|o| o.min_order_size)
+            .unwrap_or(self.config.min_order_size);
+        let max = limits
+            .and_then(|o| o.max_order_size)
+            .unwrap_or(self.config.max_order_size);
+        if quantity < min {
+            return Err(ApiError::risk(
+                RiskRule::MinOrderSize,
+                format!("Order size {} below minimum {}", quantity, min),
+            ));
+        }
+        if quantity > max {
+            return Err(ApiError::risk(
+                RiskRule::MaxOrderSize,
+                format!("Order size {} exceeds maximum {}", quantity, max),
+            ));
         }
         Ok(())
     }
 
-    fn check_price_band(&self, price: f64, snapshot: &MarketSnapshot) -> Result<(), ApiError> {
-        // Use mid price as reference, fall back to last trade price
+    /// Band `price` against `snapshot`, the market on `symbol`'s book.
+    pub fn check_price_band(
+        &self,
+        symbol: &str,
+        price: f64,
+        snapshot: &MarketSnapshot,
+    ) -> Result<(), ApiError> {
+        // Use mid price as reference, fall back to last trade price, then to
+        // the opening price until the session has traded, and finally to the
+        // best price of a one-sided book under the wider one-sided band
         let reference = snapshot
             .mid_price
-            .or(snapshot.last_trade_price);
+            .or(snapshot.last_trade_price)
+            .or_else(|| self.opening_price(symbol));
 
-        // If no reference price exists (empty book, no trades), skip band check
-        let reference = match reference {
-            Some(r) if r > 0.0 => r,
-            _ => return Ok(()),
+        let (reference, band_percent, rule) = match reference {
+            Some(r) if r > 0.0 => (
+                r,
+                self.config.price_band_percent,
+                RiskRule::PriceBandPercent,
+            ),
+            // One-sided book: band around the best price that does exist
+            _ => match snapshot.best_bid.or(snapshot.best_ask) {
+                Some(best) if best > 0.0 && self.config.one_sided_price_band_percent > 0.0 => (
+                    best,
+                    self.config.one_sided_price_band_percent,
+                    RiskRule::OneSidedPriceBandPercent,
+                ),
+                // Empty book with no trades: nothing to band against
+                _ => return Ok(()),
+            },
         };
 
-        let band = self.config.price_band_percent / 100.0;
+        let band = band_percent / 100.0;
         let lower = reference * (1.0 - band);
         let upper = reference * (1.0 + band);
 
         if price < lower || price > upper {
-            return Err(ApiError::RiskRejection(format!(
-                "Price {:.2} outside {:.1}% band [{:.2}, {:.2}] around reference {:.2}",
-                price, self.config.price_band_percent, lower, upper, reference
-            )));
+            return Err(ApiError::risk(
+                rule,
+                format!(
+                    "Price {:.2} outside {:.1}% band [{:.2}, {:.2}] around reference {:.2}",
+                    price, band_percent, lower, upper, reference
+                ),
+            ));
         }
         Ok(())
     }
@@ -89,47 +447,175 @@ impl RiskService {
     fn check_position_limit(
         &self,
         trader_id: &str,
+        symbol: &str,
         quantity: i64,
         side: Side,
+        replaces: Option<u64>,
     ) -> Result<(), ApiError> {
-        let current = self.positions.get(trader_id).map(|v| *v).unwrap_or(0);
+        let current = self.get_position(trader_id, symbol);
+        // Worst case: every resting order on the same side fills as well
+        let resting = if self.config.include_resting_in_position_limit {
+            self.resting_besides(trader_id, symbol, side, replaces)
+        } else {
+            0
+        };
         let delta = match side {
-            Side::Buy => quantity,
-            Side::Sell => -quantity,
+            Side::Buy => quantity + resting,
+            Side::Sell => -(quantity + resting),
         };
         let projected = current + delta;
 
-        if projected.abs() > self.config.max_position_per_trader {
-            return Err(ApiError::RiskRejection(format!(
-                "Position limit exceeded: current {}, projected {} (limit ±{})",
-                current, projected, self.config.max_position_per_trader
-            )));
+        let limit = self.max_position(trader_id);
+        let bound = if self.config.position_limit_inclusive {
+            ""
+        } else {
+            ", exclusive"
+        };
+        if self.exceeds_position_limit(trader_id, projected) {
+            return Err(ApiError::risk(
+                RiskRule::MaxPositionPerTrader,
+                format!(
+                    "Position limit exceeded in {}: current {}, projected {} (limit ±{}{})",
+                    symbol, current, projected, limit, bound
+                ),
+            ));
         }
         Ok(())
     }
 
+    fn exceeds_position_limit(&self, trader_id: &str, position: i64) -> bool {
+        let limit = self.max_position(trader_id);
+        if self.config.position_limit_inclusive {
+            position.abs() > limit
+        } else {
+            position.abs() >= limit
+        }
+    }
+
     /// Register an order so we can look up the trader for counterparty position updates.
-    pub fn register_order(&self, order_id: u64, trader_id: &str, side: Side) {
-        self.order_registry.insert(
+    /// `resting` is the quantity left on the book after any immediate fills.
+    pub fn register_order(
+        &self,
+        order_id: u64,
+        trader_id: &str,
+        symbol: &str,
+        side: Side,
+        resting: i64,
+    ) {
+        self.adjust_resting(trader_id, symbol, side, resting);
+        let previous = self.order_registry.insert(
             order_id,
             OrderRegistration {
                 trader_id: trader_id.to_string(),
+                symbol: symbol.to_string(),
                 side,
+                resting,
+                modifies: 0,
             },
         );
+        if let Some(prev) = previous {
+            self.adjust_resting(&prev.trader_id, &prev.symbol, prev.side, -prev.resting);
+        }
+    }
+
+    /// Record a new resting quantity for a registered order (modify or reduce).
+    pub fn set_resting_quantity(&self, order_id: u64, quantity: i64) {
+        let Some(mut reg) = self.order_registry.get_mut(&order_id) else {
+            return;
+        };
+        let delta = quantity - reg.resting;
+        reg.resting = quantity;
+        let (trader_id, symbol, side) = (reg.trader_id.clone(), reg.symbol.clone(), reg.side);
+        drop(reg);
+        self.adjust_resting(&trader_id, &symbol, side, delta);
+    }
+
+    /// Refuse another modify once the order has used up
+    /// `max_modifies_per_order`. Unknown orders are left to the engine.
+    pub fn check_modify_allowed(&self, order_id: u64) -> Result<(), ApiError> {
+        let max = self.config.max_modifies_per_order;
+        if max == 0 {
+            return Ok(());
+        }
+        match self.order_registry.get(&order_id) {
+            Some(reg) if reg.modifies >= max => Err(ApiError::risk(
+                RiskRule::MaxModifiesPerOrder,
+                format!(
+                    "Order {} has reached the limit of {} modifies; cancel and resubmit",
+                    order_id, max
+                ),
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    /// Count an accepted modify against the order.
+    pub fn record_modify(&self, order_id: u64) {
+        if let Some(mut reg) = self.order_registry.get_mut(&order_id) {
+            reg.modifies += 1;
+        }
+    }
+
+    /// Total quantity `trader_id` has resting on `side` of `symbol`'s book.
+    pub fn resting_quantity(&self, trader_id: &str, symbol: &str, side: Side) -> i64 {
+        self.resting
+            .get(trader_id)
+            .and_then(|r| {
+                r.get(symbol).map(|e| match side {
+                    Side::Buy => e.buy,
+                    Side::Sell => e.sell,
+                })
+            })
+            .unwrap_or(0)
+    }
+
+    /// `resting_quantity` without the order being cancel/replaced, which
+    /// leaves the book as its replacement arrives.
+    fn resting_besides(
+        &self,
+        trader_id: &str,
+        symbol: &str,
+        side: Side,
+        replaces: Option<u64>,
+    ) -> i64 {
+        let replaced = replaces
+            .and_then(|id| self.order_registry.get(&id))
+            .filter(|reg| reg.symbol == symbol && reg.side == side)
+            .map_or(0, |reg| reg.resting);
+        self.resting_quantity(trader_id, symbol, side) - replaced
+    }
+
+    fn adjust_resting(&self, trader_id: &str, symbol: &str, side: Side, delta: i64) {
+        if delta == 0 {
+            return;
+        }
+        let mut by_symbol = self.resting.entry(trader_id.to_string()).or_default();
+        let entry = by_symbol.entry(symbol.to_string()).or_default();
+        match side {
+            Side::Buy => entry.buy += delta,
+            Side::Sell => entry.sell += delta,
+        }
+    }
+
+    /// Trader who owns a registered order, if known.
+    pub fn trader_for_order(&self, order_id: u64) -> Option<String> {
+        self.order_registry.get(&order_id).map(|r| r.trader_id.clone())
     }
 
     /// Unregister an order (on cancel or full fill).
     pub fn unregister_order(&self, order_id: u64) {
-        self.order_registry.remove(&order_id);
+        if let Some((_, reg)) = self.order_registry.remove(&order_id) {
+            self.adjust_resting(&reg.trader_id, &reg.symbol, reg.side, -reg.resting);
+        }
     }
 
-    /// Update positions for both sides of each trade.
+    /// Update positions for both sides of each trade on `symbol`'s book.
     /// `trades` contains (buy_order_id, sell_order_id, quantity).
     pub fn update_positions_from_trades(
         &self,
         submitting_trader: &str,
         submitting_side: Side,
+        symbol: &str,
         trades: &[(u64, u64, i64)],
     ) {
         for &(buy_order_id, sell_order_id, qty) in trades {
@@ -143,7 +629,7 @@ impl RiskService {
                     .unwrap_or_default()
             };
             if !buyer.is_empty() {
-                self.apply_delta(&buyer, qty);
+                self.apply_delta(&buyer, symbol, qty);
             }
 
             // Update seller position (-qty)
@@ -156,20 +642,159 @@ impl RiskService {
                     .unwrap_or_default()
             };
             if !seller.is_empty() {
-                self.apply_delta(&seller, -qty);
+                self.apply_delta(&seller, symbol, -qty);
             }
+
+            // The counterparty order was resting; it now has less on the book
+            let resting_id = match submitting_side {
+                Side::Buy => sell_order_id,
+                Side::Sell => buy_order_id,
+            };
+            self.consume_resting(resting_id, qty);
         }
     }
 
-    fn apply_delta(&self, trader_id: &str, delta: i64) {
-        self.positions
+    fn consume_resting(&self, order_id: u64, qty: i64) {
+        let remaining = match self.order_registry.get(&order_id) {
+            Some(reg) => (reg.resting - qty).max(0),
+            None => return,
+        };
+        self.set_resting_quantity(order_id, remaining);
+    }
+
+    fn apply_delta(&self, trader_id: &str, symbol: &str, delta: i64) {
+        *self
+            .positions
             .entry(trader_id.to_string())
-            .and_modify(|pos| *pos += delta)
-            .or_insert(delta);
+            .or_default()
+            .entry(symbol.to_string())
+            .or_default() += delta;
     }
 
-    pub fn get_position(&self, trader_id: &str) -> i64 {
-        self.positions.get(trader_id).map(|v| *v).unwrap_or(0)
+    pub fn get_position(&self, trader_id: &str, symbol: &str) -> i64 {
+        self.positions
+            .get(trader_id)
+            .and_then(|p| p.get(symbol).copied())
+            .unwrap_or(0)
+    }
+
+    /// Copy of every trader's positions, for debugging.
+    pub fn positions(&self) -> Positions {
+        self.positions
+            .iter()
+            .map(|e| (e.key().clone(), e.value().clone()))
+            .collect()
+    }
+
+    /// Forget every position and registered order, after the book has been
+    /// wiped. Returns how many positions there were, one per trader and
+    /// symbol.
+    pub fn reset(&self) -> usize {
+        let positions = self.positions.iter().map(|e| e.value().len()).sum();
+        self.positions.clear();
+        self.order_registry.clear();
+        self.resting.clear();
+        positions
+    }
+
+    pub fn export_state(&self) -> RiskState {
+        RiskState {
+            positions: self.positions(),
+        }
+    }
+
+    /// Replace all positions with those in `state`.
+    pub fn import_state(&self, state: RiskState) {
+        self.positions.clear();
+        for (trader_id, position) in state.positions {
+            self.positions.insert(trader_id, position);
+        }
+    }
+
+    /// Set the listed positions, leaving every other trader and symbol
+    /// alone. Positions already at or past the position limit are still
+    /// taken, as they are real exposure, but logged; the traders holding
+    /// them are returned, sorted. Any order that would leave them over the
+    /// limit is then refused.
+    pub fn import_positions(&self, positions: &Positions) -> Vec<String> {
+        let mut over_limit = Vec::new();
+        for (trader_id, by_symbol) in positions {
+            for (symbol, &position) in by_symbol {
+                if self.exceeds_position_limit(trader_id, position) {
+                    tracing::warn!(
+                        event = "ImportedPositionOverLimit",
+                        trader_id = %trader_id,
+                        symbol = %symbol,
+                        position,
+                        limit = self.max_position(trader_id),
+                    );
+                    if over_limit.last() != Some(trader_id) {
+                        over_limit.push(trader_id.clone());
+                    }
+                }
+                self.positions
+                    .entry(trader_id.clone())
+                    .or_default()
+                    .insert(symbol.clone(), position);
+            }
+        }
+        over_limit
+    }
+
+    /// Import a JSON map of trader → symbol → position with
+    /// `import_positions`.
+    pub fn load_positions(&self, path: &Path) -> std::io::Result<Vec<String>> {
+        let positions: Positions = serde_json::from_slice(&std::fs::read(path)?)?;
+        Ok(self.import_positions(&positions))
+    }
+
+    /// Write the exported state to `path` as JSON. Goes through a temporary
+    /// file and a rename, so a crash mid-write leaves the previous file.
+    pub fn save_state(&self, path: &Path) -> std::io::Result<()> {
+        let json = serde_json::to_vec_pretty(&self.export_state())?;
+        let tmp = path.with_extension("tmp");
+        std::fs::write(&tmp, json)?;
+        std::fs::rename(&tmp, path)
+    }
+
+    /// Import state saved by `save_state`. A missing file is not an error:
+    /// returns false and leaves positions untouched.
+    pub fn load_state(&self, path: &Path) -> std::io::Result<bool> {
+        let json = match std::fs::read(path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        self.import_state(serde_json::from_slice(&json)?);
+        Ok(true)
+    }
+
+    /// Ids of `trader_id`'s registered orders, ascending.
+    pub fn orders_for_trader(&self, trader_id: &str) -> Vec<u64> {
+        let mut orders: Vec<u64> = self
+            .order_registry
+            .iter()
+            .filter(|e| e.trader_id == trader_id)
+            .map(|e| *e.key())
+            .collect();
+        orders.sort_unstable();
+        orders
+    }
+
+    /// Copy of the order registry sorted by order id, for debugging.
+    pub fn registered_orders(&self) -> Vec<RegisteredOrder> {
+        let mut orders: Vec<RegisteredOrder> = self
+            .order_registry
+            .iter()
+            .map(|e| RegisteredOrder {
+                order_id: *e.key(),
+                trader_id: e.trader_id.clone(),
+                side: e.side,
+                resting_quantity: e.resting,
+            })
+            .collect();
+        orders.sort_by_key(|o| o.order_id);
+        orders
     }
 }
 
@@ -184,6 +809,7 @@ mod tests {
             price_band_percent: 10.0,
             max_position_per_trader: 1_000,
             max_orders_per_second: 100,
+            ..RiskConfig::default()
         }
     }
 
@@ -195,6 +821,7 @@ mod tests {
             mid_price: None,
             last_trade_price: None,
             last_trade_qty: None,
+            seq: 0,
         }
     }
 
@@ -206,29 +833,30 @@ mod tests {
             mid_price: Some(mid),
             last_trade_price: Some(mid),
             last_trade_qty: Some(10),
+            seq: 0,
         }
     }
 
     #[test]
     fn test_order_size_valid() {
         let svc = RiskService::new(default_config());
-        assert!(svc.check_order_size(100).is_ok());
-        assert!(svc.check_order_size(1).is_ok());
-        assert!(svc.check_order_size(10_000).is_ok());
+        assert!(svc.check_order_size("alice", 100).is_ok());
+        assert!(svc.check_order_size("alice", 1).is_ok());
+        assert!(svc.check_order_size("alice", 10_000).is_ok());
     }
 
     #[test]
     fn test_order_size_too_small() {
         let svc = RiskService::new(default_config());
-        assert!(svc.check_order_size(0).is_err());
-        assert!(svc.check_order_size(-1).is_err());
+        assert!(svc.check_order_size("alice", 0).is_err());
+        assert!(svc.check_order_size("alice", -1).is_err());
     }
 
     #[test]
     fn test_order_size_too_large() {
         let svc = RiskService::new(default_config());
-        assert!(svc.check_order_size(10_001).is_err());
-        assert!(svc.check_order_size(100_000).is_err());
+        assert!(svc.check_order_size("alice", 10_001).is_err());
+        assert!(svc.check_order_size("alice", 100_000).is_err());
     }
 
     #[test]
@@ -236,17 +864,17 @@ mod tests {
         let svc = RiskService::new(default_config());
         let snap = snapshot_with_mid(100.0);
         // 10% band: [90.0, 110.0]
-        assert!(svc.check_price_band(100.0, &snap).is_ok());
-        assert!(svc.check_price_band(90.0, &snap).is_ok());
-        assert!(svc.check_price_band(110.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 100.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 90.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 110.0, &snap).is_ok());
     }
 
     #[test]
     fn test_price_band_breach() {
         let svc = RiskService::new(default_config());
         let snap = snapshot_with_mid(100.0);
-        assert!(svc.check_price_band(89.99, &snap).is_err());
-        assert!(svc.check_price_band(110.01, &snap).is_err());
+        assert!(svc.check_price_band("DEFAULT", 89.99, &snap).is_err());
+        assert!(svc.check_price_band("DEFAULT", 110.01, &snap).is_err());
     }
 
     #[test]
@@ -254,17 +882,122 @@ mod tests {
         let svc = RiskService::new(default_config());
         let snap = empty_snapshot();
         // No reference price, so band check is skipped
-        assert!(svc.check_price_band(999.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 999.0, &snap).is_ok());
+    }
+
+    fn one_sided_snapshot(best_bid: Option<f64>, best_ask: Option<f64>) -> MarketSnapshot {
+        MarketSnapshot {
+            best_bid,
+            best_ask,
+            ..empty_snapshot()
+        }
+    }
+
+    #[test]
+    fn test_modify_count_resets_with_registration() {
+        let svc = RiskService::new(RiskConfig {
+            max_modifies_per_order: 2,
+            ..default_config()
+        });
+        svc.register_order(1, "alice", "DEFAULT", Side::Buy, 10);
+        svc.record_modify(1);
+        assert!(svc.check_modify_allowed(1).is_ok());
+        svc.record_modify(1);
+        assert!(matches!(
+            svc.check_modify_allowed(1),
+            Err(ApiError::RiskRejection(_))
+        ));
+
+        // Gone once cancelled or filled
+        svc.unregister_order(1);
+        assert!(svc.check_modify_allowed(1).is_ok());
+    }
+
+    #[test]
+    fn test_one_sided_book_bands_around_best_price() {
+        let svc = RiskService::new(RiskConfig {
+            one_sided_price_band_percent: 20.0,
+            ..default_config()
+        });
+        // Bids only at 100: 20% band is [80, 120]
+        let snap = one_sided_snapshot(Some(100.0), None);
+        assert!(svc.check_price_band("DEFAULT", 115.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 80.0, &snap).is_ok());
+        let err = svc.check_price_band("DEFAULT", 125.0, &snap).unwrap_err();
+        assert!(matches!(err, ApiError::RiskRejection(ref m) if m.reason.contains("20.0%")));
+
+        // Asks only at 50: [40, 60]
+        let snap = one_sided_snapshot(None, Some(50.0));
+        assert!(svc.check_price_band("DEFAULT", 59.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 39.0, &snap).is_err());
+    }
+
+    #[test]
+    fn test_one_sided_fallback_disabled() {
+        let svc = RiskService::new(RiskConfig {
+            one_sided_price_band_percent: 0.0,
+            ..default_config()
+        });
+        let snap = one_sided_snapshot(Some(100.0), None);
+        assert!(svc.check_price_band("DEFAULT", 999.0, &snap).is_ok());
+    }
+
+    #[test]
+    fn test_opening_price_preferred_over_one_sided_best() {
+        let svc = RiskService::new(default_config());
+        svc.set_opening_price("DEFAULT", 50.0).unwrap();
+        let snap = one_sided_snapshot(Some(100.0), None);
+        // Normal 10% band around the opening price, not 20% around the bid
+        assert!(svc.check_price_band("DEFAULT", 54.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 100.0, &snap).is_err());
+    }
+
+    #[test]
+    fn test_opening_price_bands_empty_book() {
+        let svc = RiskService::new(default_config());
+        svc.set_opening_price("DEFAULT", 100.0).unwrap();
+        let snap = empty_snapshot();
+        assert!(svc.check_price_band("DEFAULT", 105.0, &snap).is_ok());
+        assert!(svc.check_price_band("DEFAULT", 999.0, &snap).is_err());
+    }
+
+    #[test]
+    fn test_opening_price_from_config() {
+        let svc = RiskService::new(RiskConfig {
+            opening_prices: HashMap::from([("DEFAULT".into(), 50.0)]),
+            ..default_config()
+        });
+        assert!(svc
+            .check_price_band("DEFAULT", 60.0, &empty_snapshot())
+            .is_err());
+    }
+
+    #[test]
+    fn test_market_reference_overrides_opening_price() {
+        let svc = RiskService::new(default_config());
+        svc.set_opening_price("DEFAULT", 50.0).unwrap();
+        // Once the book has a mid, the opening price no longer applies
+        assert!(svc
+            .check_price_band("DEFAULT", 100.0, &snapshot_with_mid(100.0))
+            .is_ok());
+    }
+
+    #[test]
+    fn test_opening_price_must_be_positive() {
+        let svc = RiskService::new(default_config());
+        assert!(svc.set_opening_price("DEFAULT", 0.0).is_err());
+        assert!(svc.set_opening_price("DEFAULT", -1.0).is_err());
+        assert_eq!(svc.opening_price("DEFAULT"), None);
     }
 
     #[test]
     fn test_position_limit_valid() {
         let svc = RiskService::new(default_config());
         assert!(svc
-            .check_position_limit("alice", 1000, Side::Buy)
+            .check_position_limit("alice", "DEFAULT", 1000, Side::Buy, None)
             .is_ok());
         assert!(svc
-            .check_position_limit("alice", 1000, Side::Sell)
+            .check_position_limit("alice", "DEFAULT", 1000, Side::Sell, None)
             .is_ok());
     }
 
@@ -272,7 +1005,39 @@ mod tests {
     fn test_position_limit_exceeded() {
         let svc = RiskService::new(default_config());
         assert!(svc
-            .check_position_limit("alice", 1001, Side::Buy)
+            .check_position_limit("alice", "DEFAULT", 1001, Side::Buy, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_inclusive_position_limit_allows_boundary() {
+        let svc = RiskService::new(default_config());
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 1_000, Side::Buy, None)
+            .is_ok());
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 1_000, Side::Sell, None)
+            .is_ok());
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 1_001, Side::Buy, None)
+            .is_err());
+    }
+
+    #[test]
+    fn test_exclusive_position_limit_rejects_boundary() {
+        let svc = RiskService::new(RiskConfig {
+            position_limit_inclusive: false,
+            ..default_config()
+        });
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 999, Side::Buy, None)
+            .is_ok());
+        let err = svc
+            .check_position_limit("bob", "DEFAULT", 1_000, Side::Buy, None)
+            .unwrap_err();
+        assert!(matches!(err, ApiError::RiskRejection(ref m) if m.reason.contains("exclusive")));
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 1_000, Side::Sell, None)
             .is_err());
     }
 
@@ -281,30 +1046,99 @@ mod tests {
         let svc = RiskService::new(default_config());
 
         // Register resting sell from alice (order 1)
-        svc.register_order(1, "alice", Side::Sell);
+        svc.register_order(1, "alice", "DEFAULT", Side::Sell, 500);
 
         // Bob buys, trade fills: buy_order_id=2, sell_order_id=1, qty=500
-        svc.update_positions_from_trades("bob", Side::Buy, &[(2, 1, 500)]);
-        assert_eq!(svc.get_position("bob"), 500);
-        assert_eq!(svc.get_position("alice"), -500);
+        svc.update_positions_from_trades("bob", Side::Buy, "DEFAULT", &[(2, 1, 500)]);
+        assert_eq!(svc.get_position("bob", "DEFAULT"), 500);
+        assert_eq!(svc.get_position("alice", "DEFAULT"), -500);
 
         // Another trade: bob sells 200 back
-        svc.register_order(2, "bob", Side::Buy); // bob's resting buy
-        svc.update_positions_from_trades("alice", Side::Sell, &[(2, 3, 200)]);
+        svc.register_order(2, "bob", "DEFAULT", Side::Buy, 200); // bob's resting buy
+        svc.update_positions_from_trades("alice", Side::Sell, "DEFAULT", &[(2, 3, 200)]);
         // alice sold 200 more: -500 + (-200) = -700
         // bob: counterparty on buy side: 500 + 200 = 700
-        assert_eq!(svc.get_position("alice"), -700);
-        assert_eq!(svc.get_position("bob"), 700);
+        assert_eq!(svc.get_position("alice", "DEFAULT"), -700);
+        assert_eq!(svc.get_position("bob", "DEFAULT"), 700);
 
         // Now check limit with existing position for bob
         // Current: 700, buying 301 would make 1001 > 1000
         assert!(svc
-            .check_position_limit("bob", 301, Side::Buy)
+            .check_position_limit("bob", "DEFAULT", 301, Side::Buy, None)
             .is_err());
         // Current: 700, buying 300 would make 1000 <= 1000
         assert!(svc
-            .check_position_limit("bob", 300, Side::Buy)
+            .check_position_limit("bob", "DEFAULT", 300, Side::Buy, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_resting_orders_count_toward_worst_case_limit() {
+        let svc = RiskService::new(RiskConfig {
+            include_resting_in_position_limit: true,
+            ..default_config()
+        });
+        svc.register_order(1, "alice", "DEFAULT", Side::Buy, 400);
+        svc.register_order(2, "alice", "DEFAULT", Side::Buy, 400);
+
+        // 800 resting + 300 new = 1100 if everything fills
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 300, Side::Buy, None)
+            .is_err());
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 200, Side::Buy, None)
             .is_ok());
+        // Resting buys don't add to worst-case short exposure
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 1000, Side::Sell, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_resting_orders_ignored_when_disabled() {
+        let svc = RiskService::new(default_config());
+        svc.register_order(1, "alice", "DEFAULT", Side::Buy, 900);
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 300, Side::Buy, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_resting_quantity_tracks_fills_and_cancels() {
+        let svc = RiskService::new(default_config());
+        svc.register_order(1, "alice", "DEFAULT", Side::Sell, 500);
+        svc.register_order(2, "alice", "DEFAULT", Side::Sell, 100);
+        assert_eq!(svc.resting_quantity("alice", "DEFAULT", Side::Sell), 600);
+
+        // Bob's buy lifts 300 of order 1
+        svc.update_positions_from_trades("bob", Side::Buy, "DEFAULT", &[(3, 1, 300)]);
+        assert_eq!(svc.resting_quantity("alice", "DEFAULT", Side::Sell), 300);
+
+        svc.set_resting_quantity(1, 50);
+        assert_eq!(svc.resting_quantity("alice", "DEFAULT", Side::Sell), 150);
+
+        svc.unregister_order(2);
+        assert_eq!(svc.resting_quantity("alice", "DEFAULT", Side::Sell), 50);
+    }
+
+    #[test]
+    fn test_allow_list_empty_allows_all() {
+        let svc = RiskService::new(default_config());
+        assert!(svc.check_trader_allowed("anyone").is_ok());
+    }
+
+    #[test]
+    fn test_allow_list_restricts_traders() {
+        let svc = RiskService::new(RiskConfig {
+            allowed_traders: vec!["pilot1".into(), "pilot2".into()],
+            ..default_config()
+        });
+        assert!(svc.check_trader_allowed("pilot1").is_ok());
+        assert!(svc.check_trader_allowed("pilot2").is_ok());
+        assert!(matches!(
+            svc.check_trader_allowed("outsider"),
+            Err(ApiError::Forbidden(_))
+        ));
     }
 
     #[test]
@@ -312,17 +1146,508 @@ mod tests {
         let svc = RiskService::new(default_config());
         let snap = snapshot_with_mid(100.0);
         assert!(svc
-            .check_order("alice", 100, Side::Buy, OrderType::Limit, Some(100.0), &snap)
+            .check_order(
+                "alice",
+                "DEFAULT",
+                100,
+                Side::Buy,
+                OrderType::Limit,
+                Some(100.0),
+                &snap,
+                None
+            )
             .is_ok());
     }
 
+    /// Bespoke rule: limit orders must not cross the touch
+    struct PassiveOnly;
+
+    impl RiskPlugin for PassiveOnly {
+        fn check(&self, ctx: &OrderContext) -> Result<(), ApiError> {
+            let crosses = match (ctx.side, ctx.price) {
+                (Side::Buy, Some(p)) => ctx.snapshot.best_ask.is_some_and(|ask| p >= ask),
+                (Side::Sell, Some(p)) => ctx.snapshot.best_bid.is_some_and(|bid| p <= bid),
+                (_, None) => true,
+            };
+            if crosses {
+                return Err(ApiError::risk(RiskRule::Custom, "passive orders only"));
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_registered_plugin_runs_after_built_in_checks() {
+        let svc = RiskService::new(default_config()).with_plugin(PassiveOnly);
+        let snap = snapshot_with_mid(100.0);
+        let check = |qty, price| {
+            svc.check_order(
+                "alice",
+                "DEFAULT",
+                qty,
+                Side::Buy,
+                OrderType::Limit,
+                price,
+                &snap,
+                None,
+            )
+        };
+
+        assert!(check(100, Some(100.0)).is_ok());
+        assert!(matches!(
+            check(100, Some(100.5)),
+            Err(ApiError::RiskRejection(c)) if c.rule_id == RiskRule::Custom
+        ));
+        // Built-in rules still report first
+        assert!(matches!(
+            check(20_000, Some(100.5)),
+            Err(ApiError::RiskRejection(c)) if c.rule_id == RiskRule::MaxOrderSize
+        ));
+    }
+
+    #[test]
+    fn test_reduce_only_traders_from_config() {
+        let svc = RiskService::new(RiskConfig {
+            reduce_only_traders: vec!["wind_down".into()],
+            ..default_config()
+        });
+        let snap = snapshot_with_mid(100.0);
+        svc.register_order(1, "other", "DEFAULT", Side::Sell, 100);
+        svc.update_positions_from_trades("wind_down", Side::Buy, "DEFAULT", &[(2, 1, 100)]);
+
+        let check = |side| {
+            svc.check_order(
+                "wind_down",
+                "DEFAULT",
+                50,
+                side,
+                OrderType::Limit,
+                Some(100.0),
+                &snap,
+                None,
+            )
+        };
+        assert!(check(Side::Sell).is_ok());
+        assert!(matches!(
+            check(Side::Buy),
+            Err(ApiError::RiskRejection(c)) if c.rule_id == RiskRule::ReduceOnlyTraders
+        ));
+
+        // A resting sell of 60 leaves only 40 to reduce
+        svc.register_order(3, "wind_down", "DEFAULT", Side::Sell, 60);
+        assert!(matches!(
+            check(Side::Sell),
+            Err(ApiError::RiskRejection(c)) if c.rule_id == RiskRule::ReduceOnlyTraders
+        ));
+    }
+
     #[test]
     fn test_full_check_market_order_skips_price_band() {
         let svc = RiskService::new(default_config());
         let snap = snapshot_with_mid(100.0);
         // Market order with no price — price band check is skipped
         assert!(svc
-            .check_order("alice", 100, Side::Buy, OrderType::Market, None, &snap)
+            .check_order(
+                "alice",
+                "DEFAULT",
+                100,
+                Side::Buy,
+                OrderType::Market,
+                None,
+                &snap,
+                None
+            )
             .is_ok());
     }
+
+    #[test]
+    fn test_market_order_valued_at_best_opposite_price() {
+        let svc = RiskService::new(RiskConfig {
+            max_order_notional: Some(5_000.0),
+            ..default_config()
+        });
+        let snap = snapshot_with_mid(100.0);
+
+        // Buy is valued at the ask (100.50), sell at the bid (99.50)
+        let buy = svc.order_notional(40, Side::Buy, OrderType::Market, None, &snap);
+        assert_eq!(buy.unwrap(), 4_020.0);
+        let sell = svc.order_notional(40, Side::Sell, OrderType::Market, None, &snap);
+        assert_eq!(sell.unwrap(), 3_980.0);
+
+        assert!(svc
+            .check_order(
+                "alice",
+                "DEFAULT",
+                40,
+                Side::Buy,
+                OrderType::Market,
+                None,
+                &snap,
+                None
+            )
+            .is_ok());
+        assert!(matches!(
+            svc.check_order(
+                "alice",
+                "DEFAULT",
+                60,
+                Side::Buy,
+                OrderType::Market,
+                None,
+                &snap,
+                None
+            ),
+            Err(ApiError::RiskRejection(_))
+        ));
+    }
+
+    #[test]
+    fn test_market_order_without_opposite_liquidity_rejected() {
+        let svc = RiskService::new(RiskConfig {
+            max_order_notional: Some(5_000.0),
+            ..default_config()
+        });
+        let bids_only = MarketSnapshot {
+            best_bid: Some(99.50),
+            ..empty_snapshot()
+        };
+
+        let result = svc.check_order(
+            "alice",
+            "DEFAULT",
+            10,
+            Side::Buy,
+            OrderType::Market,
+            None,
+            &bids_only,
+            None,
+        );
+        assert!(
+            matches!(result, Err(ApiError::RiskRejection(msg)) if msg.reason.contains("no opposite-side liquidity"))
+        );
+        // The sell side can still be valued against the bid
+        assert!(svc
+            .check_order(
+                "alice",
+                "DEFAULT",
+                10,
+                Side::Sell,
+                OrderType::Market,
+                None,
+                &bids_only,
+                None
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn test_repeated_rejection_bursts_double_cooldown() {
+        let svc = RiskService::new(RiskConfig {
+            rejection_penalty_threshold: 3,
+            rejection_penalty_window_ms: 1_000,
+            rejection_penalty_base_ms: 1_000,
+            rejection_penalty_max_ms: 3_000,
+            rejection_penalty_reset_ms: 10_000,
+            ..default_config()
+        });
+        let mut now = Instant::now();
+        let burst = |now: Instant| {
+            (0..3).filter_map(|_| svc.record_rejection("bot", now)).last()
+        };
+
+        assert_eq!(burst(now), Some(Duration::from_secs(1)));
+        assert!(svc.check_penalty_box("bot", now).is_err());
+        assert!(svc.check_penalty_box("other", now).is_ok());
+
+        now += Duration::from_secs(1);
+        assert!(svc.check_penalty_box("bot", now).is_ok());
+        assert_eq!(burst(now), Some(Duration::from_secs(2)));
+
+        // Capped at the maximum
+        now += Duration::from_secs(2);
+        assert_eq!(burst(now), Some(Duration::from_secs(3)));
+
+        // A clean period resets to the base cool-down
+        now += Duration::from_secs(3 + 10);
+        assert_eq!(burst(now), Some(Duration::from_secs(1)));
+    }
+
+    #[test]
+    fn test_expired_penalties_pruned() {
+        let svc = RiskService::new(RiskConfig {
+            rejection_penalty_threshold: 3,
+            rejection_penalty_window_ms: 1_000,
+            rejection_penalty_base_ms: 1_000,
+            rejection_penalty_reset_ms: 10_000,
+            ..default_config()
+        });
+        let now = Instant::now();
+        svc.record_rejection("noisy", now);
+        for _ in 0..3 {
+            svc.record_rejection("bot", now);
+        }
+
+        // One rejection, still inside the window
+        svc.prune_penalties(now + Duration::from_millis(999));
+        assert_eq!(svc.penalties.len(), 2);
+        // Its window has passed; the boxed trader's reset period has not
+        svc.prune_penalties(now + Duration::from_secs(1));
+        assert!(!svc.penalties.contains_key("noisy"));
+        svc.prune_penalties(now + Duration::from_secs(10));
+        assert!(svc.penalties.contains_key("bot"));
+        // Cool-down of 1s plus the 10s reset
+        svc.prune_penalties(now + Duration::from_secs(11));
+        assert!(svc.penalties.is_empty());
+    }
+
+    #[test]
+    fn test_positions_round_trip_through_export_import() {
+        let svc = RiskService::new(default_config());
+        svc.register_order(1, "alice", "DEFAULT", Side::Sell, 40);
+        svc.update_positions_from_trades("bob", Side::Buy, "DEFAULT", &[(2, 1, 40)]);
+        svc.update_positions_from_trades("carol", Side::Sell, "DEFAULT", &[(3, 4, 15)]);
+
+        let exported = svc.export_state();
+        let json = serde_json::to_string(&exported).unwrap();
+
+        let restored = RiskService::new(default_config());
+        restored.import_state(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.get_position("alice", "DEFAULT"), -40);
+        assert_eq!(restored.get_position("bob", "DEFAULT"), 40);
+        assert_eq!(restored.get_position("carol", "DEFAULT"), -15);
+        assert_eq!(restored.export_state(), exported);
+    }
+
+    #[test]
+    fn test_imported_positions_count_toward_limit() {
+        let svc = RiskService::new(default_config());
+        svc.update_positions_from_trades("carol", Side::Buy, "DEFAULT", &[(2, 1, 25)]);
+        let held = |position| BTreeMap::from([("DEFAULT".to_string(), position)]);
+        let positions = BTreeMap::from([
+            ("alice".to_string(), held(900)),
+            ("bob".to_string(), held(-1_200)),
+        ]);
+
+        assert_eq!(svc.import_positions(&positions), ["bob"]);
+        assert_eq!(svc.get_position("alice", "DEFAULT"), 900);
+        // Unlisted traders keep their positions
+        assert_eq!(svc.get_position("carol", "DEFAULT"), 25);
+
+        // alice has 100 of headroom left on the buy side
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 100, Side::Buy, None)
+            .is_ok());
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 101, Side::Buy, None)
+            .is_err());
+        assert!(svc
+            .check_position_limit("alice", "DEFAULT", 1_000, Side::Sell, None)
+            .is_ok());
+        // bob's orders are refused unless they bring the position back inside
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 100, Side::Buy, None)
+            .is_err());
+        assert!(svc
+            .check_position_limit("bob", "DEFAULT", 200, Side::Buy, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_positions_limited_per_symbol() {
+        let svc = RiskService::new(default_config());
+        svc.update_positions_from_trades("bob", Side::Buy, "BTC-USD", &[(2, 1, 900)]);
+        assert_eq!(svc.get_position("bob", "BTC-USD"), 900);
+        assert_eq!(svc.get_position("bob", "DEFAULT"), 0);
+
+        // A long in one symbol leaves the full limit in another
+        assert!(svc
+            .check_position_limit("bob", "BTC-USD", 101, Side::Buy, None)
+            .is_err());
+        assert!(svc
+            .check_position_limit("bob", "ETH-USD", 1_000, Side::Buy, None)
+            .is_ok());
+
+        // Resting orders count only against their own book
+        let svc = RiskService::new(RiskConfig {
+            include_resting_in_position_limit: true,
+            ..default_config()
+        });
+        svc.register_order(1, "alice", "BTC-USD", Side::Buy, 800);
+        assert_eq!(svc.resting_quantity("alice", "BTC-USD", Side::Buy), 800);
+        assert!(svc
+            .check_position_limit("alice", "BTC-USD", 201, Side::Buy, None)
+            .is_err());
+        assert!(svc
+            .check_position_limit("alice", "ETH-USD", 1_000, Side::Buy, None)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_state_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("risk-state-{}.json", std::process::id()));
+        let svc = RiskService::new(default_config());
+        assert!(!svc.load_state(&path).unwrap());
+
+        svc.update_positions_from_trades("bob", Side::Buy, "DEFAULT", &[(2, 1, 25)]);
+        svc.save_state(&path).unwrap();
+
+        let restored = RiskService::new(default_config());
+        assert!(restored.load_state(&path).unwrap());
+        assert_eq!(restored.get_position("bob", "DEFAULT"), 25);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_each_check_reports_its_rule_id() {
+        let svc = RiskService::new(RiskConfig {
+            one_sided_price_band_percent: 20.0,
+            max_order_notional: Some(50_000.0),
+            max_modifies_per_order: 1,
+            ..default_config()
+        });
+        let snap = snapshot_with_mid(100.0);
+        let rule = |result: Result<(), ApiError>| match result {
+            Err(ApiError::RiskRejection(cause)) => cause.rule_id,
+            other => panic!("Expected RiskRejection, got {:?}", other),
+        };
+        let limit = |qty, price| {
+            svc.check_order(
+                "alice",
+                "DEFAULT",
+                qty,
+                Side::Buy,
+                OrderType::Limit,
+                Some(price),
+                &snap,
+                None,
+            )
+        };
+
+        assert_eq!(rule(limit(0, 100.0)), RiskRule::MinOrderSize);
+        assert_eq!(rule(limit(10_001, 100.0)), RiskRule::MaxOrderSize);
+        assert_eq!(rule(limit(10, 150.0)), RiskRule::PriceBandPercent);
+        assert_eq!(
+            rule(svc.check_price_band("DEFAULT", 150.0, &one_sided_snapshot(Some(100.0), None))),
+            RiskRule::OneSidedPriceBandPercent
+        );
+        assert_eq!(rule(limit(1_001, 100.0)), RiskRule::MaxPositionPerTrader);
+        assert_eq!(rule(limit(600, 100.0)), RiskRule::MaxOrderNotional);
+        assert_eq!(
+            rule(svc.check_order(
+                "alice",
+                "DEFAULT",
+                10,
+                Side::Buy,
+                OrderType::Market,
+                None,
+                &empty_snapshot(),
+                None
+            )),
+            RiskRule::MaxOrderNotional
+        );
+
+        svc.register_order(1, "alice", "DEFAULT", Side::Buy, 10);
+        svc.record_modify(1);
+        assert_eq!(rule(svc.check_modify_allowed(1)), RiskRule::MaxModifiesPerOrder);
+    }
+
+    fn capped(policy: TraderCapPolicy) -> RiskService {
+        RiskService::new(RiskConfig {
+            max_traders: 2,
+            trader_cap_policy: policy,
+            ..default_config()
+        })
+    }
+
+    #[test]
+    fn test_trader_cap_rejects_new_traders() {
+        let svc = capped(TraderCapPolicy::RejectNew);
+        let now = Instant::now();
+        assert_eq!(svc.admit_trader("alice", now).unwrap(), None);
+        assert_eq!(svc.admit_trader("bob", now).unwrap(), None);
+        assert!(matches!(
+            svc.admit_trader("carol", now),
+            Err(ApiError::RateLimited(_))
+        ));
+        // Known traders keep trading
+        assert_eq!(svc.admit_trader("alice", now).unwrap(), None);
+    }
+
+    #[test]
+    fn test_trader_cap_evicts_least_recent_idle_trader() {
+        let svc = capped(TraderCapPolicy::EvictIdle);
+        let start = Instant::now();
+        let at = |secs| start + Duration::from_secs(secs);
+        svc.admit_trader("alice", at(0)).unwrap();
+        svc.admit_trader("bob", at(1)).unwrap();
+        svc.admit_trader("alice", at(2)).unwrap();
+
+        // Bob is now the least recently active
+        let evicted = svc.admit_trader("carol", at(3)).unwrap();
+        assert_eq!(evicted.as_deref(), Some("bob"));
+
+        // Neither remaining trader is idle: one has a position, one resting
+        svc.apply_delta("alice", "DEFAULT", 10);
+        svc.register_order(1, "carol", "DEFAULT", Side::Buy, 5);
+        assert!(matches!(
+            svc.admit_trader("dave", at(4)),
+            Err(ApiError::RateLimited(_))
+        ));
+        assert_eq!(svc.get_position("alice", "DEFAULT"), 10);
+    }
+
+    #[test]
+    fn test_trader_cap_eviction_keeps_rejection_history() {
+        let svc = RiskService::new(RiskConfig {
+            max_traders: 1,
+            trader_cap_policy: TraderCapPolicy::EvictIdle,
+            rejection_penalty_threshold: 2,
+            ..default_config()
+        });
+        let now = Instant::now();
+        svc.admit_trader("bot", now).unwrap();
+        svc.record_rejection("bot", now);
+
+        let evicted = svc.admit_trader("alice", now).unwrap();
+        assert_eq!(evicted.as_deref(), Some("bot"));
+
+        // The strike from before eviction still counts
+        assert!(svc.record_rejection("bot", now).is_some());
+    }
+
+    #[test]
+    fn test_trader_override_replaces_global_position_limit() {
+        let mut config = default_config();
+        config.max_order_size = 1_000_000;
+        config.overrides.insert(
+            "whale".into(),
+            RiskOverride {
+                max_position_per_trader: Some(1_000_000),
+                ..RiskOverride::default()
+            },
+        );
+        let svc = RiskService::new(config);
+        let order = |trader_id| {
+            svc.check_order(
+                trader_id,
+                "DEFAULT",
+                500_000,
+                Side::Buy,
+                OrderType::Limit,
+                Some(100.0),
+                &empty_snapshot(),
+                None,
+            )
+        };
+
+        assert!(order("whale").is_ok());
+        match order("retail") {
+            Err(ApiError::RiskRejection(cause)) => {
+                assert_eq!(cause.rule_id, RiskRule::MaxPositionPerTrader)
+            }
+            other => panic!("expected a position limit rejection, got {:?}", other),
+        }
+        // Fields the override leaves unset still apply
+        assert!(svc.check_order_size("whale", 0).is_err());
+    }
 }
@@ -0,0 +1,125 @@
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use crate::models::market::MarketSnapshot;
+
+pub type Clock = Arc<dyn Fn() -> Instant + Send + Sync>;
+
+/// What the cache can offer for a risk check.
+#[derive(Debug)]
+pub enum CacheLookup {
+    /// Within the soft TTL; use as-is
+    Fresh(MarketSnapshot),
+    /// Past the soft TTL but under the hard ceiling: refresh if the book is
+    /// free, otherwise this is still acceptable
+    Stale(MarketSnapshot),
+    /// Empty, or older than the hard ceiling; the caller must read the book
+    Miss,
+}
+
+/// Market snapshot cached for the submit path's risk checks, so they don't
+/// queue behind the book's write lock on every order. The hard `max_age`
+/// bounds how stale the price-band reference can get in a fast market.
+pub struct SnapshotCache {
+    ttl: Duration,
+    max_age: Duration,
+    clock: Clock,
+    cached: Mutex<Option<(Instant, MarketSnapshot)>>,
+}
+
+impl SnapshotCache {
+    pub fn new(ttl: Duration, max_age: Duration) -> Self {
+        Self::with_clock(ttl, max_age, Arc::new(Instant::now))
+    }
+
+    pub fn with_clock(ttl: Duration, max_age: Duration, clock: Clock) -> Self {
+        Self {
+            ttl,
+            // A ceiling below the soft TTL would make the TTL meaningless
+            max_age: max_age.max(ttl),
+            clock,
+            cached: Mutex::new(None),
+        }
+    }
+
+    pub fn lookup(&self) -> CacheLookup {
+        let cached = self.cached.lock().unwrap();
+        let Some((taken, snapshot)) = cached.as_ref() else {
+            return CacheLookup::Miss;
+        };
+        let age = (self.clock)().saturating_duration_since(*taken);
+        if age < self.ttl {
+            CacheLookup::Fresh(snapshot.clone())
+        } else if age < self.max_age {
+            CacheLookup::Stale(snapshot.clone())
+        } else {
+            CacheLookup::Miss
+        }
+    }
+
+    pub fn store(&self, snapshot: MarketSnapshot) {
+        *self.cached.lock().unwrap() = Some(((self.clock)(), snapshot));
+    }
+
+    /// Drop the cached snapshot, so the next lookup reads the book.
+    pub fn invalidate(&self) {
+        *self.cached.lock().unwrap() = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(mid: f64) -> MarketSnapshot {
+        MarketSnapshot {
+            best_bid: Some(mid - 0.5),
+            best_ask: Some(mid + 0.5),
+            spread: Some(1.0),
+            mid_price: Some(mid),
+            last_trade_price: None,
+            last_trade_qty: None,
+            seq: 0,
+        }
+    }
+
+    /// Cache whose clock only moves when the returned handle is advanced.
+    fn mock_cache() -> (SnapshotCache, Arc<Mutex<Instant>>) {
+        let now = Arc::new(Mutex::new(Instant::now()));
+        let clock_now = Arc::clone(&now);
+        let cache = SnapshotCache::with_clock(
+            Duration::from_millis(10),
+            Duration::from_millis(50),
+            Arc::new(move || *clock_now.lock().unwrap()),
+        );
+        (cache, now)
+    }
+
+    #[test]
+    fn test_empty_cache_misses() {
+        let (cache, _) = mock_cache();
+        assert!(matches!(cache.lookup(), CacheLookup::Miss));
+    }
+
+    #[test]
+    fn test_soft_ttl_then_stale() {
+        let (cache, now) = mock_cache();
+        cache.store(snapshot(100.0));
+        assert!(matches!(cache.lookup(), CacheLookup::Fresh(s) if s.mid_price == Some(100.0)));
+
+        *now.lock().unwrap() += Duration::from_millis(20);
+        assert!(matches!(cache.lookup(), CacheLookup::Stale(_)));
+    }
+
+    #[test]
+    fn test_too_old_cache_forces_refresh() {
+        let (cache, now) = mock_cache();
+        cache.store(snapshot(100.0));
+
+        *now.lock().unwrap() += Duration::from_millis(50);
+        assert!(matches!(cache.lookup(), CacheLookup::Miss));
+
+        cache.store(snapshot(101.0));
+        assert!(matches!(cache.lookup(), CacheLookup::Fresh(s) if s.mid_price == Some(101.0)));
+    }
+}
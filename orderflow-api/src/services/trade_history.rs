@@ -0,0 +1,341 @@
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
+
+use crate::models::trade::{ActivityStats, ExecutionsResponse, TradeResponse, WindowStats};
+
+const TRADE_HISTORY_CAPACITY: usize = 10_000;
+
+struct RecordedTrade {
+    trade: TradeResponse,
+    buyer: String,
+    seller: String,
+}
+
+struct HistoryInner {
+    /// Trades in execution order (ascending trade_id)
+    trades: VecDeque<RecordedTrade>,
+    /// order_id → trade_ids that order participated in, as buyer or seller
+    by_order: HashMap<u64, Vec<u64>>,
+    /// trader_id → trade_ids that trader participated in, as buyer or seller
+    by_trader: HashMap<String, Vec<u64>>,
+}
+
+/// Remove `trade_id` from `key`'s index entry, dropping the entry once empty.
+fn unindex<K: std::hash::Hash + Eq>(index: &mut HashMap<K, Vec<u64>>, key: &K, trade_id: u64) {
+    if let Some(ids) = index.get_mut(key) {
+        ids.retain(|&id| id != trade_id);
+        if ids.is_empty() {
+            index.remove(key);
+        }
+    }
+}
+
+/// Bounded in-memory buffer of recent trades, indexed by order id and by the
+/// traders on each side.
+pub struct TradeHistoryService {
+    inner: Mutex<HistoryInner>,
+    capacity: usize,
+}
+
+impl HistoryInner {
+    fn find(&self, trade_id: u64) -> Option<TradeResponse> {
+        self.trades
+            .binary_search_by_key(&trade_id, |r| r.trade.trade_id)
+            .ok()
+            .map(|idx| self.trades[idx].trade.clone())
+    }
+}
+
+impl Default for TradeHistoryService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TradeHistoryService {
+    pub fn new() -> Self {
+        Self::with_capacity(TRADE_HISTORY_CAPACITY)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            inner: Mutex::new(HistoryInner {
+                trades: VecDeque::with_capacity(capacity),
+                by_order: HashMap::new(),
+                by_trader: HashMap::new(),
+            }),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Buffer a trade between `buyer` and `seller` (trader ids). An empty
+    /// id, for a side whose trader is unknown, is not indexed.
+    pub fn record(&self, trade: &TradeResponse, buyer: &str, seller: &str) {
+        let mut inner = self.inner.lock().unwrap();
+
+        if inner.trades.len() >= self.capacity {
+            if let Some(evicted) = inner.trades.pop_front() {
+                let trade_id = evicted.trade.trade_id;
+                for order_id in [evicted.trade.buy_order_id, evicted.trade.sell_order_id] {
+                    unindex(&mut inner.by_order, &order_id, trade_id);
+                }
+                for trader_id in [&evicted.buyer, &evicted.seller] {
+                    unindex(&mut inner.by_trader, trader_id, trade_id);
+                }
+            }
+        }
+
+        for order_id in [trade.buy_order_id, trade.sell_order_id] {
+            inner
+                .by_order
+                .entry(order_id)
+                .or_default()
+                .push(trade.trade_id);
+        }
+        for trader_id in [buyer, seller] {
+            if trader_id.is_empty() {
+                continue;
+            }
+            let ids = inner.by_trader.entry(trader_id.to_string()).or_default();
+            // A self-trade is listed once
+            if ids.last() != Some(&trade.trade_id) {
+                ids.push(trade.trade_id);
+            }
+        }
+        inner.trades.push_back(RecordedTrade {
+            trade: trade.clone(),
+            buyer: buyer.to_string(),
+            seller: seller.to_string(),
+        });
+    }
+
+    /// Newest-first page of buffered trades with `trade_id` below `before`,
+    /// optionally only those `trader_id` took part in.
+    pub fn trades_page(
+        &self,
+        trader_id: Option<&str>,
+        before: Option<u64>,
+        limit: usize,
+    ) -> Vec<TradeResponse> {
+        let inner = self.inner.lock().unwrap();
+        let before = before.unwrap_or(u64::MAX);
+        match trader_id {
+            Some(trader_id) => {
+                let Some(ids) = inner.by_trader.get(trader_id) else {
+                    return Vec::new();
+                };
+                ids.iter()
+                    .rev()
+                    .filter(|&&id| id < before)
+                    .take(limit)
+                    .filter_map(|id| inner.find(*id))
+                    .collect()
+            }
+            None => inner
+                .trades
+                .iter()
+                .rev()
+                .map(|r| &r.trade)
+                .filter(|t| t.trade_id < before)
+                .take(limit)
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Oldest-first page of buffered trades with `trade_id` above `since`,
+    /// optionally only those `trader_id` took part in. Trades evicted from
+    /// the buffer before they were read are skipped.
+    pub fn trades_since(
+        &self,
+        trader_id: Option<&str>,
+        since: u64,
+        limit: usize,
+    ) -> Vec<TradeResponse> {
+        let inner = self.inner.lock().unwrap();
+        match trader_id {
+            Some(trader_id) => {
+                let Some(ids) = inner.by_trader.get(trader_id) else {
+                    return Vec::new();
+                };
+                let start = ids.partition_point(|&id| id <= since);
+                ids[start..]
+                    .iter()
+                    .take(limit)
+                    .filter_map(|id| inner.find(*id))
+                    .collect()
+            }
+            None => {
+                let start = inner.trades.partition_point(|r| r.trade.trade_id <= since);
+                inner
+                    .trades
+                    .range(start..)
+                    .take(limit)
+                    .map(|r| r.trade.clone())
+                    .collect()
+            }
+        }
+    }
+
+    /// All buffered trades for `order_id`, in execution order.
+    pub fn trades_for_order(&self, order_id: u64) -> Vec<TradeResponse> {
+        let inner = self.inner.lock().unwrap();
+        let Some(ids) = inner.by_order.get(&order_id) else {
+            return Vec::new();
+        };
+        ids.iter().filter_map(|id| inner.find(*id)).collect()
+    }
+
+    /// Trade count and volume over the last 1m, 5m and 1h before `now_ns`.
+    /// Only buffered trades count, so a busy hour can be undercounted once
+    /// the buffer wraps.
+    pub fn activity(&self, now_ns: u64) -> ActivityStats {
+        const MINUTE_NS: u64 = 60 * 1_000_000_000;
+        let windows = [MINUTE_NS, 5 * MINUTE_NS, 60 * MINUTE_NS];
+        let mut stats = [WindowStats::default(); 3];
+
+        let inner = self.inner.lock().unwrap();
+        for trade in inner.trades.iter().map(|r| &r.trade) {
+            // Windows are wall-clock, whichever clock `timestamp_ns` is on
+            let at = trade.server_ts.unwrap_or(trade.timestamp_ns);
+            let age = now_ns.saturating_sub(at);
+            for (window, stat) in windows.iter().zip(stats.iter_mut()) {
+                if age < *window {
+                    stat.trades += 1;
+                    stat.volume += trade.quantity;
+                }
+            }
+        }
+
+        let [last_1m, last_5m, last_1h] = stats;
+        ActivityStats {
+            last_1m,
+            last_5m,
+            last_1h,
+        }
+    }
+
+    /// Aggregate an order's fills into a single execution report.
+    pub fn executions(&self, order_id: u64) -> ExecutionsResponse {
+        let trades = self.trades_for_order(order_id);
+        let filled_quantity: i64 = trades.iter().map(|t| t.quantity).sum();
+        let avg_price = if filled_quantity > 0 {
+            let notional: f64 = trades.iter().map(|t| t.price * t.quantity as f64).sum();
+            Some(notional / filled_quantity as f64)
+        } else {
+            None
+        };
+
+        ExecutionsResponse {
+            order_id,
+            filled_quantity,
+            avg_price,
+            trades,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn trade(trade_id: u64, buy: u64, sell: u64, price: f64, quantity: i64) -> TradeResponse {
+        TradeResponse {
+            trade_id,
+            exec_seq: trade_id,
+            symbol: "DEFAULT".into(),
+            buy_order_id: buy,
+            sell_order_id: sell,
+            price,
+            quantity,
+            timestamp_ns: 0,
+            engine_ts: None,
+            server_ts: None,
+            fee: None,
+            tick_direction: None,
+        }
+    }
+
+    #[test]
+    fn test_executions_aggregated_per_order() {
+        let history = TradeHistoryService::new();
+        // Order 10 buys from two sellers, then sells some back as a resting ask
+        history.record(&trade(1, 10, 1, 100.00, 30), "buyer", "seller");
+        history.record(&trade(2, 10, 2, 101.00, 10), "buyer", "seller");
+        history.record(&trade(3, 3, 4, 99.00, 5), "buyer", "seller");
+        history.record(&trade(4, 5, 10, 102.00, 20), "buyer", "seller");
+
+        let report = history.executions(10);
+        assert_eq!(report.order_id, 10);
+        assert_eq!(report.trades.len(), 3);
+        assert_eq!(report.filled_quantity, 60);
+        // (100*30 + 101*10 + 102*20) / 60
+        let expected = (3000.0 + 1010.0 + 2040.0) / 60.0;
+        assert!((report.avg_price.unwrap() - expected).abs() < 1e-9);
+        let ids: Vec<u64> = report.trades.iter().map(|t| t.trade_id).collect();
+        assert_eq!(ids, vec![1, 2, 4]);
+    }
+
+    #[test]
+    fn test_executions_empty_for_unknown_order() {
+        let history = TradeHistoryService::new();
+        history.record(&trade(1, 1, 2, 100.00, 10), "buyer", "seller");
+
+        let report = history.executions(99);
+        assert!(report.trades.is_empty());
+        assert_eq!(report.filled_quantity, 0);
+        assert_eq!(report.avg_price, None);
+    }
+
+    #[test]
+    fn test_buffer_evicts_oldest() {
+        let history = TradeHistoryService::with_capacity(2);
+        history.record(&trade(1, 10, 20, 100.00, 1), "buyer", "seller");
+        history.record(&trade(2, 10, 21, 100.00, 2), "buyer", "seller");
+        history.record(&trade(3, 11, 21, 100.00, 3), "buyer", "seller");
+
+        // Trade 1 fell out of the buffer, and with it order 20's only fill
+        assert!(history.trades_for_order(20).is_empty());
+        assert_eq!(history.executions(10).filled_quantity, 2);
+        assert_eq!(history.executions(21).filled_quantity, 5);
+    }
+
+    #[test]
+    fn test_activity_windows() {
+        const SEC: u64 = 1_000_000_000;
+        let now = 10_000 * SEC;
+        let history = TradeHistoryService::new();
+        let at = |id, ago: u64, quantity| TradeResponse {
+            timestamp_ns: now - ago,
+            ..trade(id, id, id + 100, 100.00, quantity)
+        };
+        history.record(&at(1, 2 * 3600 * SEC, 1000), "buyer", "seller"); // outside every window
+        history.record(&at(2, 30 * 60 * SEC, 40), "buyer", "seller"); // 1h only
+        history.record(&at(3, 3 * 60 * SEC, 20), "buyer", "seller"); // 5m and 1h
+        history.record(&at(4, 10 * SEC, 5), "buyer", "seller"); // all
+        history.record(&at(5, 0, 7), "buyer", "seller"); // all
+
+        let stats = history.activity(now);
+        assert_eq!(stats.last_1m, WindowStats { trades: 2, volume: 12 });
+        assert_eq!(stats.last_5m, WindowStats { trades: 3, volume: 32 });
+        assert_eq!(stats.last_1h, WindowStats { trades: 4, volume: 72 });
+    }
+
+    #[test]
+    fn test_trades_by_trader_paginated_newest_first() {
+        let history = TradeHistoryService::new();
+        history.record(&trade(1, 10, 20, 100.00, 1), "alice", "bob");
+        history.record(&trade(2, 11, 21, 100.00, 2), "carol", "alice");
+        history.record(&trade(3, 12, 22, 100.00, 3), "carol", "bob");
+        history.record(&trade(4, 13, 23, 100.00, 4), "alice", "alice");
+
+        let ids = |trades: Vec<TradeResponse>| -> Vec<u64> {
+            trades.iter().map(|t| t.trade_id).collect()
+        };
+        assert_eq!(ids(history.trades_page(Some("alice"), None, 10)), vec![4, 2, 1]);
+        assert_eq!(ids(history.trades_page(Some("alice"), None, 2)), vec![4, 2]);
+        assert_eq!(ids(history.trades_page(Some("alice"), Some(2), 2)), vec![1]);
+        assert!(history.trades_page(Some("dave"), None, 10).is_empty());
+        assert_eq!(ids(history.trades_page(None, Some(4), 2)), vec![3, 2]);
+    }
+}
@@ -0,0 +1,172 @@
+use crate::config::TradingHoursConfig;
+use crate::engine::orderbook::unix_nanos;
+
+const SECS_PER_DAY: i64 = 86_400;
+
+/// Daily order-entry window at a fixed offset from UTC. A window whose
+/// close is earlier than its open runs past midnight.
+pub struct TradingHours {
+    /// Seconds after local midnight
+    open: i64,
+    close: i64,
+    utc_offset_secs: i64,
+    /// As configured, for rejection messages
+    window: String,
+    /// Unix nanoseconds; the system clock outside tests
+    clock: fn() -> u64,
+}
+
+impl TradingHours {
+    /// `None` when no window is configured.
+    pub fn from_config(config: &TradingHoursConfig) -> Result<Option<Self>, String> {
+        let (open, close) = match (&config.open, &config.close) {
+            (None, None) => return Ok(None),
+            (Some(open), Some(close)) => (open, close),
+            _ => {
+                return Err(
+                    "trading_hours.open and trading_hours.close must be set together".into(),
+                )
+            }
+        };
+        let window = format!("{}-{} UTC{}", open, close, config.utc_offset);
+        let (open, close) = (parse_time("open", open)?, parse_time("close", close)?);
+        if open == close {
+            return Err(format!("trading_hours window {} is empty", window));
+        }
+        Ok(Some(Self {
+            open,
+            close,
+            utc_offset_secs: parse_offset(&config.utc_offset)?,
+            window,
+            clock: unix_nanos,
+        }))
+    }
+
+    #[cfg(test)]
+    pub fn with_clock(mut self, clock: fn() -> u64) -> Self {
+        self.clock = clock;
+        self
+    }
+
+    pub fn window(&self) -> &str {
+        &self.window
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.is_open_at((self.clock)())
+    }
+
+    /// Whether order entry is open at `now_ns` (Unix nanoseconds).
+    pub fn is_open_at(&self, now_ns: u64) -> bool {
+        let local_secs = (now_ns / 1_000_000_000) as i64 + self.utc_offset_secs;
+        let time_of_day = local_secs.rem_euclid(SECS_PER_DAY);
+        if self.open < self.close {
+            (self.open..self.close).contains(&time_of_day)
+        } else {
+            time_of_day >= self.open || time_of_day < self.close
+        }
+    }
+}
+
+/// "HH:MM" → seconds after midnight.
+fn parse_time(field: &str, value: &str) -> Result<i64, String> {
+    let invalid = || format!("trading_hours.{} must be HH:MM, got '{}'", field, value);
+    let (hours, minutes) = value.split_once(':').ok_or_else(invalid)?;
+    let hours: u8 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u8 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 23 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(i64::from(hours) * 3_600 + i64::from(minutes) * 60)
+}
+
+/// "+HH:MM" / "-HH:MM" → seconds east of UTC.
+fn parse_offset(value: &str) -> Result<i64, String> {
+    let invalid = || format!("trading_hours.utc_offset must be ±HH:MM, got '{}'", value);
+    let (sign, rest) = match value.split_at_checked(1) {
+        Some(("+", rest)) => (1, rest),
+        Some(("-", rest)) => (-1, rest),
+        _ => return Err(invalid()),
+    };
+    let (hours, minutes) = rest.split_once(':').ok_or_else(invalid)?;
+    let hours: u8 = hours.parse().map_err(|_| invalid())?;
+    let minutes: u8 = minutes.parse().map_err(|_| invalid())?;
+    if hours > 14 || minutes > 59 {
+        return Err(invalid());
+    }
+    Ok(sign * (i64::from(hours) * 3_600 + i64::from(minutes) * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// 2024-01-02 at the given UTC time, in Unix nanoseconds
+    const fn utc(hours: u64, minutes: u64) -> u64 {
+        (1_704_153_600 + hours * 3_600 + minutes * 60) * 1_000_000_000
+    }
+
+    fn hours(open: &str, close: &str, utc_offset: &str) -> TradingHours {
+        TradingHours::from_config(&TradingHoursConfig {
+            open: Some(open.into()),
+            close: Some(close.into()),
+            utc_offset: utc_offset.into(),
+        })
+        .unwrap()
+        .unwrap()
+    }
+
+    #[test]
+    fn test_daily_window() {
+        let schedule = hours("09:30", "16:00", "+00:00");
+        assert!(schedule.is_open_at(utc(9, 30)));
+        assert!(schedule.is_open_at(utc(15, 59)));
+        assert!(!schedule.is_open_at(utc(9, 29)));
+        // Close is exclusive
+        assert!(!schedule.is_open_at(utc(16, 0)));
+    }
+
+    #[test]
+    fn test_offset_and_overnight_windows() {
+        // 14:30 UTC is 09:30 at UTC-05:00
+        let schedule = hours("09:30", "16:00", "-05:00");
+        assert!(schedule.is_open_at(utc(14, 30)));
+        assert!(!schedule.is_open_at(utc(9, 30)));
+
+        let overnight = hours("22:00", "02:00", "+00:00");
+        assert!(overnight.is_open_at(utc(23, 0)));
+        assert!(overnight.is_open_at(utc(1, 0)));
+        assert!(!overnight.is_open_at(utc(12, 0)));
+    }
+
+    #[test]
+    fn test_injected_clock() {
+        let schedule = hours("09:30", "16:00", "+00:00");
+        assert!(schedule.with_clock(|| utc(12, 0)).is_open());
+        let schedule = hours("09:30", "16:00", "+00:00");
+        assert!(!schedule.with_clock(|| utc(20, 0)).is_open());
+    }
+
+    #[test]
+    fn test_invalid_schedules_rejected() {
+        let config =
+            |open: Option<&str>, close: Option<&str>, utc_offset: &str| TradingHoursConfig {
+                open: open.map(Into::into),
+                close: close.map(Into::into),
+                utc_offset: utc_offset.into(),
+            };
+        assert!(TradingHours::from_config(&config(None, None, "+00:00"))
+            .unwrap()
+            .is_none());
+        for bad in [
+            config(Some("09:30"), None, "+00:00"),
+            config(Some("24:00"), Some("16:00"), "+00:00"),
+            config(Some("9h30"), Some("16:00"), "+00:00"),
+            config(Some("09:30"), Some("09:30"), "+00:00"),
+            config(Some("09:30"), Some("16:00"), "05:00"),
+            config(Some("09:30"), Some("16:00"), "+15:00"),
+        ] {
+            assert!(TradingHours::from_config(&bad).is_err(), "{:?}", bad);
+        }
+    }
+}
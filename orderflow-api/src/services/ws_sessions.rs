@@ -0,0 +1,114 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use dashmap::DashMap;
+use tokio::time::Instant;
+
+/// What a WebSocket connection is subscribed to, carried across a reconnect.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WsSession {
+    /// MessagePack depth frames alongside the JSON event stream
+    pub binary_depth: bool,
+    /// Trader whose resting orders are cancelled once the connection is
+    /// gone for good
+    pub cancel_on_disconnect: Option<String>,
+    /// Broadcast channels from the client's last `subscribe`; `None` until
+    /// it sends one, which means every channel
+    pub channels: Option<HashSet<String>>,
+}
+
+struct PendingSession {
+    session: WsSession,
+    disconnected: Instant,
+}
+
+/// Sessions of dropped WebSocket connections, keyed by resume token. A
+/// client that reconnects with its token within the grace window gets the
+/// session back; otherwise it lapses and its cancel-on-disconnect fires.
+pub struct WsSessionStore {
+    pending: DashMap<String, PendingSession>,
+    grace: Duration,
+}
+
+impl WsSessionStore {
+    pub fn new(grace: Duration) -> Self {
+        Self {
+            pending: DashMap::new(),
+            grace,
+        }
+    }
+
+    pub fn grace(&self) -> Duration {
+        self.grace
+    }
+
+    /// A fresh token for a new connection.
+    pub fn issue_token(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+
+    /// Hold `session` for resumption after its connection dropped.
+    pub fn park(&self, token: &str, session: WsSession) {
+        self.pending.insert(
+            token.to_string(),
+            PendingSession {
+                session,
+                disconnected: Instant::now(),
+            },
+        );
+    }
+
+    /// Reclaim a parked session. `None` for an unknown token, one already
+    /// resumed, or one whose grace window has passed; a lapsed session is
+    /// left for `expire` so its cancel-on-disconnect still fires.
+    pub fn resume(&self, token: &str) -> Option<WsSession> {
+        self.pending
+            .remove_if(token, |_, pending| {
+                pending.disconnected.elapsed() < self.grace
+            })
+            .map(|(_, pending)| pending.session)
+    }
+
+    /// Drop a parked session once its grace window is over. Returns it if
+    /// nobody resumed it in the meantime.
+    pub fn expire(&self, token: &str) -> Option<WsSession> {
+        self.pending
+            .remove(token)
+            .map(|(_, pending)| pending.session)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn session() -> WsSession {
+        WsSession {
+            binary_depth: true,
+            cancel_on_disconnect: Some("alice".into()),
+            channels: Some(HashSet::from(["trades".to_string()])),
+        }
+    }
+
+    #[test]
+    fn test_resume_within_grace_returns_session_once() {
+        let store = WsSessionStore::new(Duration::from_secs(60));
+        let token = store.issue_token();
+        store.park(&token, session());
+
+        assert_eq!(store.resume(&token), Some(session()));
+        assert!(store.resume(&token).is_none());
+        // Resumed sessions are not expired from under the new connection
+        assert!(store.expire(&token).is_none());
+    }
+
+    #[test]
+    fn test_lapsed_session_cannot_be_resumed() {
+        let store = WsSessionStore::new(Duration::ZERO);
+        store.park("t", session());
+        assert!(store.resume("t").is_none());
+        assert!(store.resume("unknown").is_none());
+        // Still there for the expiry to act on
+        assert_eq!(store.expire("t"), Some(session()));
+    }
+}
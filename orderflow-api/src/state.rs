@@ -1,19 +1,64 @@
+use std::collections::HashMap;
 use std::sync::atomic::AtomicU64;
 use std::sync::Arc;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use tokio::sync::broadcast;
 
 use crate::config::Config;
 use crate::engine::orderbook::Engine;
+use crate::middleware::http_rate_limit::HttpRateLimiter;
+use crate::services::admin_audit::AdminAuditLog;
+use crate::services::auction_service::AuctionService;
+use crate::services::audit_service::RejectionSampler;
+use crate::services::circuit_breaker::CircuitBreaker;
+use crate::services::fees::FeeSchedule;
+use crate::services::idempotency::IdempotencyService;
+use crate::services::imbalance::ImbalanceMonitor;
+use crate::services::market_quality::MarketQualityMonitor;
+use crate::services::order_history::OrderHistoryService;
 use crate::services::order_service::OrderService;
 use crate::services::rate_limiter::RateLimiterService;
 use crate::services::risk_service::RiskService;
+use crate::services::snapshot_cache::SnapshotCache;
+use crate::services::trade_history::TradeHistoryService;
+use crate::services::trading_hours::TradingHours;
+use crate::services::ws_sessions::WsSessionStore;
 
 #[derive(Clone)]
 pub struct AppState {
     pub order_service: Arc<OrderService>,
     pub engine: Arc<Engine>,
+    pub risk: Arc<RiskService>,
+    pub rate_limiter: Arc<RateLimiterService>,
+    /// Per-route, per-IP HTTP request limits
+    pub http_rate_limiter: Arc<HttpRateLimiter>,
+    pub trade_history: Arc<TradeHistoryService>,
+    pub rejections: Arc<RejectionSampler>,
+    pub idempotency: Arc<IdempotencyService>,
+    pub admin_audit: Arc<AdminAuditLog>,
+    /// Admin API key → principal; see `AdminActor`
+    pub admin_keys: Arc<HashMap<String, String>>,
+    pub max_inline_trades: usize,
+    /// Cap on `levels` for `GET /api/v1/market/depth`
+    pub max_depth_levels: usize,
+    /// Cap on orders in one `POST /api/v1/orders/batch`
+    pub max_batch_orders: usize,
+    /// Route duplicate Idempotency-Key submits to a modify
+    pub idempotency_key_upsert: bool,
+    /// Refuse mutating requests; see `middleware::read_only`
+    pub read_only: bool,
+    /// Require a JSON content type on order bodies; see `JsonBody`
+    pub strict_content_type: bool,
+    /// Stringify quantities and ids in every JSON response
+    pub numbers_as_strings: bool,
+    /// Wrap successful responses by default; see `middleware::envelope`
+    pub envelope_responses: bool,
+    pub ws_batch_interval: Option<Duration>,
+    /// Inbound WebSocket message size limit in bytes
+    pub ws_max_message_bytes: usize,
+    /// Dropped WebSocket sessions awaiting resumption
+    pub ws_sessions: Arc<WsSessionStore>,
     pub start_time: Instant,
     pub ws_broadcast: broadcast::Sender<String>,
     pub ws_connections: Arc<AtomicU64>,
@@ -21,23 +66,120 @@ pub struct AppState {
 
 impl AppState {
     pub fn new(config: &Config) -> Self {
-        let engine = Arc::new(Engine::new());
-        let risk = Arc::new(RiskService::new(config.risk.clone()));
-        let rate_limiter = Arc::new(RateLimiterService::new(config.risk.max_orders_per_second));
+        Self::with_risk(config, RiskService::new(config.risk.clone()))
+    }
+
+    /// Like `new`, with a risk service built by the caller, e.g. one with
+    /// custom `RiskPlugin`s registered.
+    pub fn with_risk(config: &Config, risk: RiskService) -> Self {
+        let engine = Arc::new(
+            Engine::with_config(config.engine.clone())
+                .with_tick_size(config.risk.tick_size)
+                .with_lot_size(config.risk.lot_size),
+        );
+        let risk = Arc::new(risk);
+        let rate_limiter = Arc::new(
+            RateLimiterService::new(
+                config.risk.max_orders_per_second,
+                config.risk.max_in_flight_per_trader,
+            )
+            .with_symbol_scope(config.risk.rate_limit_per_symbol),
+        );
+
+        let trade_history = Arc::new(TradeHistoryService::new());
+        let order_history = Arc::new(OrderHistoryService::new(
+            config.audit.order_history_capacity,
+        ));
+        let rejections = Arc::new(RejectionSampler::new(config.audit.rejection_log_every));
 
         let (ws_broadcast, _) = broadcast::channel(1024);
 
-        let order_service = Arc::new(OrderService::new(
+        let start_time = tokio::time::Instant::now();
+        let mut order_service = OrderService::new(
             Arc::clone(&engine),
-            risk,
-            rate_limiter,
+            Arc::clone(&risk),
+            Arc::clone(&rate_limiter),
+            Arc::clone(&trade_history),
+            order_history,
+            Arc::clone(&rejections),
             ws_broadcast.clone(),
-        ));
+        )
+        .with_warmup(start_time, Duration::from_millis(config.server.warmup_ms))
+        .with_trade_print_delay(Duration::from_millis(config.server.trade_print_delay_ms))
+        .with_order_fill_log(config.audit.log_order_fills);
+        if config.risk.snapshot_poll_interval_ms > 0 {
+            // The poller keeps the cache current; anything it has published
+            // is good until the hard ceiling
+            let max_age = Duration::from_millis(config.risk.snapshot_max_age_ms);
+            order_service = order_service.with_snapshot_cache(SnapshotCache::new(max_age, max_age));
+        } else if config.risk.snapshot_cache_ttl_ms > 0 {
+            order_service = order_service.with_snapshot_cache(SnapshotCache::new(
+                Duration::from_millis(config.risk.snapshot_cache_ttl_ms),
+                Duration::from_millis(config.risk.snapshot_max_age_ms),
+            ));
+        }
+        if let Some(percent) = config.risk.circuit_breaker_percent {
+            order_service = order_service.with_circuit_breaker(CircuitBreaker::new(
+                percent,
+                Duration::from_millis(config.risk.circuit_breaker_window_ms),
+                Duration::from_millis(config.risk.circuit_breaker_cooldown_ms),
+            ));
+        }
+        if let Some(threshold) = config.server.imbalance_alert_threshold {
+            order_service = order_service.with_imbalance_monitor(ImbalanceMonitor::new(
+                threshold,
+                config.server.imbalance_alert_hysteresis,
+            ));
+        }
+        if let Some(max_spread) = config.server.market_quality_max_spread {
+            order_service = order_service.with_market_quality_monitor(MarketQualityMonitor::new(
+                max_spread,
+                Duration::from_millis(config.server.market_quality_grace_ms),
+            ));
+        }
+        if config.server.opening_auction_ms > 0 {
+            let closes_at = start_time.into_std()
+                + Duration::from_millis(config.server.warmup_ms)
+                + Duration::from_millis(config.server.opening_auction_ms);
+            order_service = order_service.with_opening_auction(AuctionService::new(closes_at));
+        }
+        if let Ok(Some(hours)) = TradingHours::from_config(&config.trading_hours) {
+            order_service = order_service.with_trading_hours(hours);
+        }
+        let fees = FeeSchedule::new(config.fees.clone());
+        if fees.is_active() {
+            order_service = order_service.with_fee_schedule(fees);
+        }
+        let order_service = Arc::new(order_service);
 
         Self {
             order_service,
             engine,
-            start_time: Instant::now(),
+            risk,
+            rate_limiter,
+            http_rate_limiter: Arc::new(HttpRateLimiter::new(&config.server.http_rate_limits)),
+            trade_history,
+            rejections,
+            idempotency: Arc::new(IdempotencyService::new(Duration::from_secs(
+                config.server.idempotency_ttl_secs,
+            ))),
+            admin_audit: Arc::new(AdminAuditLog::new(config.audit.admin_log_capacity)),
+            admin_keys: Arc::new(config.server.admin_api_keys.clone()),
+            max_inline_trades: config.server.max_inline_trades,
+            max_depth_levels: config.server.max_depth_levels,
+            max_batch_orders: config.server.max_batch_orders,
+            idempotency_key_upsert: config.server.idempotency_key_upsert,
+            read_only: config.server.read_only,
+            strict_content_type: config.server.strict_content_type,
+            numbers_as_strings: config.server.numbers_as_strings,
+            envelope_responses: config.server.envelope_responses,
+            ws_batch_interval: (config.server.ws_batch_interval_ms > 0)
+                .then(|| Duration::from_millis(config.server.ws_batch_interval_ms)),
+            ws_max_message_bytes: config.server.ws_max_message_bytes,
+            ws_sessions: Arc::new(WsSessionStore::new(Duration::from_millis(
+                config.server.ws_resume_grace_ms,
+            ))),
+            start_time: start_time.into_std(),
             ws_broadcast,
             ws_connections: Arc::new(AtomicU64::new(0)),
         }
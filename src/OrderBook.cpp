@@ -48,6 +48,59 @@ namespace orderbook {
         return total;
     }
 
+    // Walk the book for order the way matching would, without touching it:
+    // all-or-none resting orders larger than what is still needed are passed
+    // over, and self-trade conflicts count as no liquidity, or end the walk
+    // when the STP mode cancels the incoming order
+    OrderBook::MatchPreview OrderBook::previewMatch(const Order& order) const {
+        MatchPreview preview;
+        preview.unfilled = order.quantity;
+
+        auto walk = [&](const auto& book, auto beyondLimit) {
+            for (const auto& [price, level] : book) {
+                if (order.price.has_value() && beyondLimit(price, order.price.value())) return;
+                for (const Order& resting : level.orders) {
+                    if (resting.allOrNone && resting.quantity > preview.unfilled) {
+                        preview.passedAllOrNone = true;
+                        continue;
+                    }
+                    if (!order.traderId.empty() && order.traderId == resting.traderId &&
+                        order.stpMode != STPMode::ALLOW) {
+                        if (order.stpMode == STPMode::CANCEL_NEWEST ||
+                            order.stpMode == STPMode::CANCEL_BOTH) {
+                            preview.cancelledBySelfTrade = true;
+                            return;
+                        }
+                        continue;
+                    }
+                    preview.unfilled -= std::min(preview.unfilled, resting.quantity);
+                    if (preview.unfilled == 0) return;
+                }
+            }
+        };
+
+        if (order.side == Side::BUY) {
+            walk(asks, [](Price best, Price limit) { return best > limit; });
+        } else {
+            walk(bids, [](Price best, Price limit) { return best < limit; });
+        }
+        return preview;
+    }
+
+    // Whether order could fill its whole quantity right now
+    bool OrderBook::canFillInFull(const Order& order) const {
+        return previewMatch(order).unfilled == 0;
+    }
+
+    // Whether a limit order priced where order is would trade on arrival
+    bool OrderBook::crossesBook(const Order& order) const {
+        if (!order.price.has_value()) return true;
+        if (order.side == Side::BUY) {
+            return !asks.empty() && order.price.value() >= asks.begin()->first;
+        }
+        return !bids.empty() && order.price.value() <= bids.begin()->first;
+    }
+
     STPResult OrderBook::checkSelfTrade(const Order& incomingOrder, const Order& restingOrder) {
         STPResult result; 
 
@@ -68,6 +121,7 @@ namespace orderbook {
         if (orderIndex.find(order.id) != orderIndex.end()) {
             result.accepted = false;
             result.rejectReason = "Duplicate order ID";
+            result.rejectCode = RejectCode::DUPLICATE_ID;
             return false;
         }
 
@@ -75,6 +129,7 @@ namespace orderbook {
         if (order.quantity <= 0) {
             result.accepted = false;
             result.rejectReason = "Invalid quantity: must be positive";
+            result.rejectCode = RejectCode::INVALID_QUANTITY;
             return false;
         }
 
@@ -82,6 +137,7 @@ namespace orderbook {
         if (order.orderType == OrderType::LIMIT && !order.price.has_value()) {
             result.accepted = false;
             result.rejectReason = "Limit order requires price";
+            result.rejectCode = RejectCode::MISSING_PRICE;
             return false;
         }
 
@@ -89,6 +145,7 @@ namespace orderbook {
         if (order.price.has_value() && order.price.value() <= 0) {
             result.accepted = false;
             result.rejectReason = "Price must be positive";
+            result.rejectCode = RejectCode::INVALID_PRICE;
             return false;
         }
 
@@ -96,15 +153,39 @@ namespace orderbook {
         if (order.orderType == OrderType::MARKET && order.timeInForce == TimeInForce::GTC) {
             result.accepted = false;
             result.rejectReason = "Invalid: MARKET orders cannot be GTC";
+            result.rejectCode = RejectCode::MARKET_ORDER_GTC;
             return false;
         }
 
-        // 6. FOK liquidity check - required for FOK semantics
+        // 6. All-or-none orders rest until they can fill in full, so only
+        //    GTC limit orders make sense
+        if (order.allOrNone &&
+            (order.orderType != OrderType::LIMIT || order.timeInForce != TimeInForce::GTC)) {
+            result.accepted = false;
+            result.rejectReason = "AON: only GTC limit orders can be all-or-none";
+            result.rejectCode = RejectCode::INVALID_ALL_OR_NONE;
+            return false;
+        }
+
+        // 7. Post-only orders exist to rest on the book, so likewise only
+        //    GTC limit orders
+        if (order.postOnly &&
+            (order.orderType != OrderType::LIMIT || order.timeInForce != TimeInForce::GTC)) {
+            result.accepted = false;
+            result.rejectReason = "Post-only: only GTC limit orders can be post-only";
+            result.rejectCode = RejectCode::INVALID_POST_ONLY;
+            return false;
+        }
+
+        // 8. FOK liquidity check - required for FOK semantics. All-or-none
+        //    orders on the book may not be fillable, so walk the book when
+        //    plain liquidity looks sufficient.
         if (order.timeInForce == TimeInForce::FOK) {
             Quantity available = getAvailableLiquidity(order.side, order.price);
-            if (available < order.quantity) {
+            if (available < order.quantity || !canFillInFull(order)) {
                 result.accepted = false;
                 result.rejectReason = "FOK: insufficient liquidity for full fill";
+                result.rejectCode = RejectCode::FOK_UNFILLABLE;
                 return false;
             }
         }
@@ -113,12 +194,19 @@ namespace orderbook {
     }
 
     // Match against all orders at that price level, FIFO
-        void OrderBook::fillOrderAtPriceLevel(Order& incomingOrder, PriceLevel& level, std::vector<Trade>& trades) {
+        void OrderBook::fillOrderAtPriceLevel(Order& incomingOrder, PriceLevel& level, std::vector<Trade>& trades, STPResult& stpResult) {
         bool isBuy = (incomingOrder.side == Side::BUY);
         auto it = level.orders.begin();
         
         while (incomingOrder.quantity > 0 && it != level.orders.end()) {
             Order& restingOrder = *it;
+
+            // An all-or-none order only trades against something that takes
+            // all of it
+            if (restingOrder.allOrNone && restingOrder.quantity > incomingOrder.quantity) {
+                ++it;
+                continue;
+            }
             
             // Check for self-trade
             STPResult stp = checkSelfTrade(incomingOrder, restingOrder);
@@ -128,6 +216,11 @@ namespace orderbook {
                     continue;
                 }
                 handleSelfTrade(incomingOrder, restingOrder, level, stp);
+                // Report every STP action taken while matching this order
+                stpResult.selfTrade = true;
+                stpResult.action = stp.action;
+                stpResult.cancelledOrders.insert(stpResult.cancelledOrders.end(),
+                                                 stp.cancelledOrders.begin(), stp.cancelledOrders.end());
                 if (incomingOrder.quantity == 0) return;  // CANCEL_NEWEST or CANCEL_BOTH
                 // CANCEL_OLDEST or CANCEL_BOTH removed resting, get next iterator
                 it = level.orders.erase(it);
@@ -151,6 +244,8 @@ namespace orderbook {
             lastTradeQty = fillQty;
             incomingOrder.quantity -= fillQty;
             restingOrder.quantity -= fillQty;
+            incomingOrder.filledQuantity += fillQty;
+            restingOrder.filledQuantity += fillQty;
             level.totalQuantity -= fillQty;
 
             if (restingOrder.quantity == 0) {
@@ -163,17 +258,19 @@ namespace orderbook {
     }
 
 
-    std::vector<Trade> OrderBook::fillLimitOrder(Order& order) {
+    std::vector<Trade> OrderBook::fillLimitOrder(Order& order, STPResult& stpResult) {
         std::vector<Trade> trades; 
 
+        // Levels can survive a pass with quantity still to fill (skipped
+        // all-or-none or self-trade orders), so step through them in turn
         auto matchBook = [&](auto& book, auto priceCheckFn) {
-            while (order.quantity > 0 && !book.empty()) {
-                auto it = book.begin();
+            auto it = book.begin();
+            while (order.quantity > 0 && it != book.end()) {
                 if (priceCheckFn(it->first, order.price.value())) break;  // Use the lambda!
 
                 PriceLevel& level = it->second;
-                fillOrderAtPriceLevel(order, level, trades);
-                if (level.orders.empty()) book.erase(it);
+                fillOrderAtPriceLevel(order, level, trades, stpResult);
+                it = level.orders.empty() ? book.erase(it) : std::next(it);
             }
         };
 
@@ -186,15 +283,15 @@ namespace orderbook {
         return trades; 
     }
 
-    std::vector<Trade> OrderBook::fillMarketOrder(Order& order) {
+    std::vector<Trade> OrderBook::fillMarketOrder(Order& order, STPResult& stpResult) {
         std::vector<Trade> trades; 
 
         auto matchBook = [&](auto& book) {
-            while (order.quantity > 0 && !book.empty()) {
-                auto it = book.begin();
+            auto it = book.begin();
+            while (order.quantity > 0 && it != book.end()) {
                 PriceLevel& level = it->second;
-                fillOrderAtPriceLevel(order, level, trades);
-                if (level.orders.empty()) book.erase(it);
+                fillOrderAtPriceLevel(order, level, trades, stpResult);
+                it = level.orders.empty() ? book.erase(it) : std::next(it);
             }
         };
 
@@ -214,16 +311,18 @@ namespace orderbook {
         if (order.side == Side::BUY && asks.empty()) {
             result.accepted = false;
             result.rejectReason = "No liquidity: ask side empty";
+            result.rejectCode = RejectCode::NO_LIQUIDITY;
             return result;
         }
         if (order.side == Side::SELL && bids.empty()) {
             result.accepted = false;
             result.rejectReason = "No liquidity: bid side empty";
+            result.rejectCode = RejectCode::NO_LIQUIDITY;
             return result;
         }
 
         result.accepted = true;
-        result.trades = fillMarketOrder(order);
+        result.trades = fillMarketOrder(order, result.stpResult);
         result.remainingQuantity = order.quantity;
 
         return result;
@@ -232,12 +331,44 @@ namespace orderbook {
     OrderResult OrderBook::handleLimitOrder(Order order) {
         OrderResult result;
         result.remainingQuantity = order.quantity;
+
+        // A post-only order must add liquidity, never take it
+        if (order.postOnly && crossesBook(order)) {
+            result.rejectReason = "post-only would cross";
+            result.rejectCode = RejectCode::POST_ONLY_WOULD_MATCH;
+            return result;
+        }
+
+        // An all-or-none order trades only if it fills completely. Otherwise
+        // it rests untouched, unless resting would cross the book.
+        bool matches = true;
+        if (order.allOrNone && !canFillInFull(order)) {
+            if (crossesBook(order)) {
+                result.rejectReason = "AON: insufficient liquidity for full fill";
+                result.rejectCode = RejectCode::AON_UNFILLABLE;
+                return result;
+            }
+            matches = false;
+        } else if (!order.allOrNone && order.timeInForce == TimeInForce::GTC) {
+            // A remainder left facing all-or-none orders it is too small to
+            // fill would rest crossed against them, so refuse the order
+            // before anything trades
+            MatchPreview preview = previewMatch(order);
+            if (preview.unfilled > 0 && preview.passedAllOrNone && !preview.cancelledBySelfTrade) {
+                result.rejectReason = "AON: remainder would rest through all-or-none orders too large to fill";
+                result.rejectCode = RejectCode::AON_UNFILLABLE;
+                return result;
+            }
+        }
+
         result.accepted = true;
-        result.trades = fillLimitOrder(order);
+        if (matches) result.trades = fillLimitOrder(order, result.stpResult);
         result.remainingQuantity = order.quantity;
 
-        // Add to book if not fully filled and not IOC Order
-        if (order.quantity > 0 && order.timeInForce == TimeInForce::GTC) {
+        // Add to book if not fully filled and not IOC Order. A remainder that
+        // still crosses (own orders passed over by DECREMENT_AND_CANCEL) is
+        // cancelled instead.
+        if (order.quantity > 0 && order.timeInForce == TimeInForce::GTC && !crossesBook(order)) {
             auto addToBook = [&](auto& book) {
                 PriceLevel& level = book[order.price.value()];
                 level.orders.push_back(order);
@@ -319,6 +450,16 @@ namespace orderbook {
         return true; 
     }
 
+    size_t OrderBook::clear() {
+        size_t removed = orderIndex.size();
+        bids.clear();
+        asks.clear();
+        orderIndex.clear();
+        lastTradePrice = 0;
+        lastTradeQty = 0;
+        return removed;
+    }
+
     ModifyResult OrderBook::modifyOrder(OrderId id, Price newPrice, Quantity newQuantity) {
         ModifyResult result;
 
@@ -326,18 +467,21 @@ namespace orderbook {
         auto indexIt = orderIndex.find(id);
         if (indexIt == orderIndex.end()) {
             result.rejectReason = "Order not found";
+            result.rejectCode = RejectCode::NOT_FOUND;
             return result;
         }
 
         // 2. New quantity must be positive
         if (newQuantity <= 0) {
             result.rejectReason = "Quantity must be positive";
+            result.rejectCode = RejectCode::INVALID_QUANTITY;
             return result;
         }
 
         // 3. New price must be positive
         if (newPrice <= 0) {
             result.rejectReason = "Price must be positive";
+            result.rejectCode = RejectCode::INVALID_PRICE;
             return result;
         }
 
@@ -345,10 +489,12 @@ namespace orderbook {
         auto& loc = indexIt->second;
         if (loc.side == Side::BUY && !asks.empty() && newPrice >= asks.begin()->first) {
             result.rejectReason = "Buy price would cross spread (>= best ask)";
+            result.rejectCode = RejectCode::CROSSED_SPREAD;
             return result;
         }
         if (loc.side == Side::SELL && !bids.empty() && newPrice <= bids.begin()->first) {
             result.rejectReason = "Sell price would cross spread (<= best bid)";
+            result.rejectCode = RejectCode::CROSSED_SPREAD;
             return result;
         }
 
@@ -362,6 +508,9 @@ namespace orderbook {
         result.newPrice = newPrice;
         result.newQuantity = newQuantity;
 
+        // The order now stands for what it filled plus its new size
+        order.originalQuantity = order.filledQuantity + newQuantity;
+
         // Generic lambda that works with either book type (bids or asks)
         auto doModify = [&](auto& book) {
             // --- SAME PRICE: quantity-only change ---
@@ -412,4 +561,212 @@ namespace orderbook {
         return result;
     }
 
-}
\ No newline at end of file
+    ModifyResult OrderBook::reduceOrder(OrderId id, Quantity reduceBy) {
+        ModifyResult result;
+
+        // 1. Order must exist
+        auto indexIt = orderIndex.find(id);
+        if (indexIt == orderIndex.end()) {
+            result.rejectReason = "Order not found";
+            result.rejectCode = RejectCode::NOT_FOUND;
+            return result;
+        }
+
+        // 2. Reduction must be positive
+        if (reduceBy <= 0) {
+            result.rejectReason = "Reduce quantity must be positive";
+            result.rejectCode = RejectCode::INVALID_QUANTITY;
+            return result;
+        }
+
+        // 3. Cannot reduce below zero
+        auto& loc = indexIt->second;
+        Order& order = *(loc.position);
+        if (reduceBy > order.quantity) {
+            result.rejectReason = "Reduce quantity exceeds remaining quantity";
+            result.rejectCode = RejectCode::REDUCE_EXCEEDS_REMAINING;
+            return result;
+        }
+
+        result.oldPrice = order.price.value();
+        result.newPrice = order.price.value();
+        result.oldQuantity = order.quantity;
+        result.newQuantity = order.quantity - reduceBy;
+        result.accepted = true;
+
+        // Reducing to zero removes the order entirely
+        if (result.newQuantity == 0) {
+            cancelOrder(id);
+            return result;
+        }
+
+        // Reduce in place - the order keeps its position in the queue
+        auto doReduce = [&](auto& book) {
+            order.quantity -= reduceBy;
+            order.originalQuantity -= reduceBy;
+            book[loc.price].totalQuantity -= reduceBy;
+        };
+
+        if (loc.side == Side::BUY) {
+            doReduce(bids);
+        } else {
+            doReduce(asks);
+        }
+
+        return result;
+    }
+
+    bool OrderBook::creditFill(OrderId id, Quantity filled) {
+        auto indexIt = orderIndex.find(id);
+        if (indexIt == orderIndex.end() || filled <= 0) return false;
+
+        Order& order = *(indexIt->second.position);
+        order.filledQuantity += filled;
+        order.originalQuantity += filled;
+        return true;
+    }
+
+    QueuePosition OrderBook::getQueuePosition(OrderId id) const {
+        QueuePosition result;
+
+        auto indexIt = orderIndex.find(id);
+        if (indexIt == orderIndex.end()) return result;
+        const auto& loc = indexIt->second;
+
+        // Walk the level from the front up to (not including) this order
+        auto countAhead = [&](const auto& book) {
+            const PriceLevel& level = book.at(loc.price);
+            for (auto it = level.orders.begin(); it != loc.position; ++it) {
+                result.ordersAhead++;
+                result.quantityAhead += it->quantity;
+            }
+        };
+
+        if (loc.side == Side::BUY) {
+            countAhead(bids);
+        } else {
+            countAhead(asks);
+        }
+
+        result.found = true;
+        return result;
+    }
+
+    OrderInfo OrderBook::getOrder(OrderId id) const {
+        OrderInfo info;
+
+        auto indexIt = orderIndex.find(id);
+        if (indexIt == orderIndex.end()) return info;
+        const auto& loc = indexIt->second;
+
+        info.found = true;
+        info.side = loc.side;
+        info.price = loc.price;
+        info.quantity = loc.position->quantity;
+        info.originalQuantity = loc.position->originalQuantity;
+        info.filledQuantity = loc.position->filledQuantity;
+        return info;
+    }
+
+    std::vector<DepthLevel> OrderBook::getDepth(Side side, size_t maxLevels) const {
+        std::vector<DepthLevel> levels;
+
+        // Best price first on both sides
+        auto collect = [&](const auto& book) {
+            for (const auto& [price, level] : book) {
+                if (levels.size() >= maxLevels) break;
+                levels.push_back({price, level.totalQuantity, level.orders.size()});
+            }
+        };
+
+        if (side == Side::BUY) {
+            collect(bids);
+        } else {
+            collect(asks);
+        }
+
+        return levels;
+    }
+
+    BookStats OrderBook::getBookStats() const {
+        BookStats stats;
+
+        for (const auto& [price, level] : bids) {
+            stats.bidOrders += level.orders.size();
+            stats.bidQuantity += level.totalQuantity;
+        }
+        for (const auto& [price, level] : asks) {
+            stats.askOrders += level.orders.size();
+            stats.askQuantity += level.totalQuantity;
+        }
+
+        return stats;
+    }
+
+    // Quantity an order of quantity could take from the first maxOrders
+    // resting orders on one side, in matching order (best price first, FIFO
+    // within a level). All-or-none orders larger than what is still needed
+    // are passed over as matching would, and don't count toward maxOrders.
+    // Levels past limitPrice are not counted; 0 means no limit.
+    Quantity OrderBook::getSweepableQuantity(Side side, size_t maxOrders, Price limitPrice, Quantity quantity) const {
+        Quantity total = 0;
+        size_t counted = 0;
+
+        auto walk = [&](const auto& book, auto withinLimit) {
+            for (const auto& [price, level] : book) {
+                if (limitPrice != 0 && !withinLimit(price)) return;
+                for (const auto& order : level.orders) {
+                    if (counted >= maxOrders || total >= quantity) return;
+                    if (order.allOrNone && order.quantity > quantity - total) continue;
+                    total += order.quantity;
+                    counted++;
+                }
+            }
+        };
+
+        if (side == Side::BUY) {
+            walk(bids, [&](Price p) { return p >= limitPrice; });
+        } else {
+            walk(asks, [&](Price p) { return p <= limitPrice; });
+        }
+
+        return total;
+    }
+
+    // What an order of quantity on side would take from the opposite side,
+    // best price first, ignoring price limits and self-trade prevention.
+    // All-or-none orders larger than what is still needed are passed over,
+    // as matching would.
+    FillEstimate OrderBook::estimateFill(Side side, Quantity quantity) const {
+        FillEstimate estimate;
+
+        auto walk = [&](const auto& book) {
+            for (const auto& [price, level] : book) {
+                bool reached = false;
+                for (const auto& order : level.orders) {
+                    Quantity needed = quantity - estimate.filledQuantity;
+                    if (needed <= 0) break;
+                    if (order.allOrNone && order.quantity > needed) continue;
+                    Quantity take = std::min(needed, order.quantity);
+                    estimate.filledQuantity += take;
+                    estimate.cost += take * price;
+                    reached = true;
+                }
+                if (reached) {
+                    estimate.worstPrice = price;
+                    estimate.levels++;
+                }
+                if (estimate.filledQuantity >= quantity) return;
+            }
+        };
+
+        if (side == Side::BUY) {
+            walk(asks);
+        } else {
+            walk(bids);
+        }
+
+        return estimate;
+    }
+
+}